@@ -141,6 +141,7 @@ fn main() {
         if let Event::Render(args) = event {
             gl.draw([0, 0, args.width as i32, args.height as i32], |_, gl| {
                 draw_ui(gl, &mut ui, &mut demo);
+                ui.draw(gl);
             });
         }
     }
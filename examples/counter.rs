@@ -56,8 +56,10 @@ fn main() {
                 Background::new().rgba(0.2, 0.25, 0.4, 1.0).draw(ui, gl);
 
                 // Draw the counter.
-                counter(gl, ui, &mut count)
+                counter(gl, ui, &mut count);
 
+                // Finish the frame, drawing anything that goes above the widgets.
+                ui.draw(gl);
             });
         }
     }
@@ -0,0 +1,146 @@
+use color::Color;
+use theme::{ Theme, WidgetStyle };
+use utils::clamp;
+
+/// Return the progress (0.0 to 1.0) of an animation that began at
+/// `start` and lasts `duration` seconds at the given `time`.
+pub fn progress(start: f64, duration: f64, time: f64) -> f64 {
+    if duration <= 0.0 { 1.0 }
+    else { clamp((time - start) / duration, 0.0, 1.0) }
+}
+
+/// Ease a linear progress value so that it starts and ends smoothly.
+pub fn ease_in_out(perc: f64) -> f64 {
+    perc * perc * (3.0 - 2.0 * perc)
+}
+
+/// A transition from one color towards a target color over a
+/// given duration. Used for smoothly animating widget colors
+/// between their Normal, Highlighted and Clicked states.
+#[derive(Copy, Clone, Debug)]
+pub struct ColorTransition {
+    from: Color,
+    to: Color,
+    start: f64,
+    duration: f64,
+}
+
+impl ColorTransition {
+
+    /// Construct a transition that has already settled on the given color.
+    pub fn new(color: Color, time: f64) -> ColorTransition {
+        ColorTransition { from: color, to: color, start: time, duration: 0.0 }
+    }
+
+    /// Return the color of the transition at the given time.
+    pub fn color_at(&self, time: f64) -> Color {
+        let perc = ease_in_out(progress(self.start, self.duration, time));
+        self.from.interpolate(self.to, perc as f32)
+    }
+
+    /// Return the color towards which the transition is heading.
+    pub fn target(&self) -> Color { self.to }
+
+    /// Whether or not the transition has reached its target at the given time.
+    pub fn is_finished(&self, time: f64) -> bool {
+        progress(self.start, self.duration, time) >= 1.0
+    }
+
+    /// Begin transitioning towards a new target color from wherever
+    /// the transition currently is. Does nothing if the target is unchanged.
+    pub fn retarget(&mut self, to: Color, time: f64, duration: f64) {
+        if self.to.0 != to.0 {
+            self.from = self.color_at(time);
+            self.to = to;
+            self.start = time;
+            self.duration = duration;
+        }
+    }
+
+}
+
+/// A crossfade from one `Theme` to another. Colors are interpolated over the
+/// duration while all other values are taken from the target immediately.
+#[derive(Clone, Debug)]
+pub struct ThemeTransition {
+    from: Theme,
+    to: Theme,
+    start: f64,
+    duration: f64,
+}
+
+impl ThemeTransition {
+
+    /// Construct a transition from one theme to another beginning at the given time.
+    pub fn new(from: Theme, to: Theme, start: f64, duration: f64) -> ThemeTransition {
+        ThemeTransition { from: from, to: to, start: start, duration: duration }
+    }
+
+    /// Return the theme at the given time.
+    pub fn theme_at(&self, time: f64) -> Theme {
+        let amt = ease_in_out(progress(self.start, self.duration, time)) as f32;
+        let (from, to) = (&self.from, &self.to);
+        let lerp = |a: Color, b: Color| a.interpolate(b, amt);
+        let style = |a: Option<WidgetStyle>, b: Option<WidgetStyle>| lerp_style(a, b, amt);
+        Theme {
+            background_color: lerp(from.background_color, to.background_color),
+            shape_color: lerp(from.shape_color, to.shape_color),
+            frame_color: lerp(from.frame_color, to.frame_color),
+            label_color: lerp(from.label_color, to.label_color),
+            error_color: lerp(from.error_color, to.error_color),
+            link_color: lerp(from.link_color, to.link_color),
+            maybe_button: style(from.maybe_button, to.maybe_button),
+            maybe_console: style(from.maybe_console, to.maybe_console),
+            maybe_curve_editor: style(from.maybe_curve_editor, to.maybe_curve_editor),
+            maybe_dock: style(from.maybe_dock, to.maybe_dock),
+            maybe_drop_down_list: style(from.maybe_drop_down_list, to.maybe_drop_down_list),
+            maybe_envelope_editor: style(from.maybe_envelope_editor, to.maybe_envelope_editor),
+            maybe_gallery: style(from.maybe_gallery, to.maybe_gallery),
+            maybe_heatmap: style(from.maybe_heatmap, to.maybe_heatmap),
+            maybe_label: style(from.maybe_label, to.maybe_label),
+            maybe_link: style(from.maybe_link, to.maybe_link),
+            maybe_node_editor: style(from.maybe_node_editor, to.maybe_node_editor),
+            maybe_num_pad: style(from.maybe_num_pad, to.maybe_num_pad),
+            maybe_number_dialer: style(from.maybe_number_dialer, to.maybe_number_dialer),
+            maybe_rating: style(from.maybe_rating, to.maybe_rating),
+            maybe_reorderable_list: style(from.maybe_reorderable_list, to.maybe_reorderable_list),
+            maybe_ruler: style(from.maybe_ruler, to.maybe_ruler),
+            maybe_slider: style(from.maybe_slider, to.maybe_slider),
+            maybe_sparkline: style(from.maybe_sparkline, to.maybe_sparkline),
+            maybe_text_box: style(from.maybe_text_box, to.maybe_text_box),
+            maybe_timeline: style(from.maybe_timeline, to.maybe_timeline),
+            maybe_toggle: style(from.maybe_toggle, to.maybe_toggle),
+            maybe_viewport: style(from.maybe_viewport, to.maybe_viewport),
+            maybe_xy_pad: style(from.maybe_xy_pad, to.maybe_xy_pad),
+            ..to.clone()
+        }
+    }
+
+    /// The theme towards which the transition is heading.
+    pub fn target(&self) -> &Theme { &self.to }
+
+    /// Whether or not the transition has reached its target at the given time.
+    pub fn is_finished(&self, time: f64) -> bool {
+        progress(self.start, self.duration, time) >= 1.0
+    }
+
+}
+
+/// Interpolate the colors of two widget styles, where both styles specify them.
+fn lerp_style(a: Option<WidgetStyle>, b: Option<WidgetStyle>, amt: f32) -> Option<WidgetStyle> {
+    fn lerp_maybe(a: Option<Color>, b: Option<Color>, amt: f32) -> Option<Color> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.interpolate(b, amt)),
+            _ => b,
+        }
+    }
+    match (a, b) {
+        (Some(a), Some(b)) => Some(WidgetStyle {
+            maybe_color: lerp_maybe(a.maybe_color, b.maybe_color, amt),
+            maybe_frame_color: lerp_maybe(a.maybe_frame_color, b.maybe_frame_color, amt),
+            maybe_label_color: lerp_maybe(a.maybe_label_color, b.maybe_label_color, amt),
+            ..b
+        }),
+        _ => b,
+    }
+}
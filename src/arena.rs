@@ -0,0 +1,94 @@
+//! Scratch buffers for the temporary strings and point lists that widgets
+//! build while drawing, so that drawing a frame doesn't allocate them anew.
+//!
+//! A widget takes a buffer from the `Ui` (see `Ui::scratch_string`), fills
+//! and uses it, then gives it back so that the next widget may reuse its
+//! allocation. At the end of each frame the arena lets go of any buffers
+//! beyond the most that were in use at once during the frame.
+
+use point::Point;
+
+/// Buffers that grew larger than this many elements aren't kept for reuse,
+/// so that a single large string doesn't stay allocated forever.
+const MAX_RETAINED_CAPACITY: usize = 4096;
+
+/// A pool of a single kind of buffer.
+struct Pool<T> {
+    free: Vec<T>,
+    /// The number of buffers taken and not yet given back.
+    in_use: usize,
+    /// The most buffers in use at once during the current frame.
+    peak: usize,
+}
+
+impl<T> Pool<T> {
+
+    fn new() -> Pool<T> {
+        Pool { free: Vec::new(), in_use: 0, peak: 0 }
+    }
+
+    fn take<F>(&mut self, new: F) -> T where F: FnOnce() -> T {
+        self.in_use += 1;
+        self.peak = ::std::cmp::max(self.peak, self.in_use);
+        self.free.pop().unwrap_or_else(new)
+    }
+
+    fn give(&mut self, buffer: T, capacity: usize) {
+        self.in_use = self.in_use.saturating_sub(1);
+        if capacity <= MAX_RETAINED_CAPACITY {
+            self.free.push(buffer);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.free.truncate(self.peak);
+        self.peak = self.in_use;
+    }
+
+}
+
+/// The scratch buffers of a `Ui`, reset at the end of each frame.
+pub struct ScratchArena {
+    strings: Pool<String>,
+    point_lists: Pool<Vec<Point>>,
+}
+
+impl ScratchArena {
+
+    /// Construct an empty arena.
+    pub fn new() -> ScratchArena {
+        ScratchArena { strings: Pool::new(), point_lists: Pool::new() }
+    }
+
+    /// An empty string, reusing the allocation of one given back earlier.
+    pub fn string(&mut self) -> String {
+        self.strings.take(String::new)
+    }
+
+    /// Give back a string taken via `string` so that it may be reused.
+    pub fn give_string(&mut self, mut string: String) {
+        string.clear();
+        let capacity = string.capacity();
+        self.strings.give(string, capacity);
+    }
+
+    /// An empty list of points, reusing the allocation of one given back earlier.
+    pub fn points(&mut self) -> Vec<Point> {
+        self.point_lists.take(Vec::new)
+    }
+
+    /// Give back a list of points taken via `points` so that it may be reused.
+    pub fn give_points(&mut self, mut points: Vec<Point>) {
+        points.clear();
+        let capacity = points.capacity();
+        self.point_lists.give(points, capacity);
+    }
+
+    /// Let go of the buffers beyond the most that were in use at once during
+    /// the frame, which are unlikely to be needed again.
+    pub fn reset(&mut self) {
+        self.strings.reset();
+        self.point_lists.reset();
+    }
+
+}
@@ -4,25 +4,26 @@ use draw::Drawable;
 use graphics;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use theme::WidgetStyle;
 use ui::Ui;
 
 /// The context from which we'll draw the background.
 #[derive(Copy, Clone)]
 pub struct Background {
-    maybe_color: Option<Color>,
+    style: WidgetStyle,
 }
 
 impl Background {
     pub fn new() -> Background {
         Background {
-            maybe_color: None,
+            style: WidgetStyle::new(),
         }
     }
 }
 
 impl Colorable for Background {
     fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
+        self.style.maybe_color = Some(color);
         self
     }
 }
@@ -33,8 +34,10 @@ impl Drawable for Background {
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-        let Color(col) = self.maybe_color
-            .unwrap_or(ui.theme.background_color);
+        // The background is the theme's background color unless given another.
+        let defaults = WidgetStyle { maybe_color: Some(ui.theme.background_color), ..WidgetStyle::new() };
+        let style = self.style.resolve_with(None, defaults, &ui.theme);
+        let Color(col) = style.color;
         graphics::clear(col, graphics);
     }
 }
@@ -0,0 +1,103 @@
+
+use color::Color;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use ui::Ui;
+
+/// The default maximum distance (in DPI-independent units) between a curve
+/// and the line segments with which it is drawn.
+pub const TOLERANCE: f64 = 0.25;
+
+/// The maximum depth to which curves are subdivided.
+const MAX_DEPTH: u32 = 16;
+
+/// Draw a quadratic bezier curve from `p0` to `p2` with control point `p1`.
+pub fn draw_quadratic<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    p0: Point, p1: Point, p2: Point,
+    thickness: f64,
+    color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let tolerance = TOLERANCE / ui.pixel_factor();
+    draw_points(ui, graphics, &flatten_quadratic(p0, p1, p2, tolerance), thickness, color);
+}
+
+/// Draw a cubic bezier curve from `p0` to `p3` with control points `p1` and `p2`.
+pub fn draw_cubic<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    p0: Point, p1: Point, p2: Point, p3: Point,
+    thickness: f64,
+    color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let tolerance = TOLERANCE / ui.pixel_factor();
+    draw_points(ui, graphics, &flatten_cubic(p0, p1, p2, p3, tolerance), thickness, color);
+}
+
+/// Draw lines between the flattened points. Round caps hide the joins.
+fn draw_points<B, C>(ui: &mut Ui<C>, graphics: &mut B, points: &[Point], thickness: f64, color: Color)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    for w in points.windows(2) {
+        ui.draw_line(graphics, [w[0][0], w[0][1], w[1][0], w[1][1]], thickness, true, color);
+    }
+}
+
+/// Return points along the quadratic bezier curve such that no part of the
+/// curve is further than `tolerance` from the lines between them.
+pub fn flatten_quadratic(p0: Point, p1: Point, p2: Point, tolerance: f64) -> Vec<Point> {
+    // A quadratic curve is the cubic curve with control points 2/3 of the way to `p1`.
+    let c1 = [p0[0] + 2.0 / 3.0 * (p1[0] - p0[0]), p0[1] + 2.0 / 3.0 * (p1[1] - p0[1])];
+    let c2 = [p2[0] + 2.0 / 3.0 * (p1[0] - p2[0]), p2[1] + 2.0 / 3.0 * (p1[1] - p2[1])];
+    flatten_cubic(p0, c1, c2, p2, tolerance)
+}
+
+/// Return points along the cubic bezier curve such that no part of the
+/// curve is further than `tolerance` from the lines between them.
+pub fn flatten_cubic(p0: Point, p1: Point, p2: Point, p3: Point, tolerance: f64) -> Vec<Point> {
+    let mut points = vec![p0];
+    subdivide(p0, p1, p2, p3, tolerance, 0, &mut points);
+    points
+}
+
+/// Recursively split the curve in half (de Casteljau) until it is flat
+/// enough, pushing the end point of each flat piece.
+fn subdivide(p0: Point, p1: Point, p2: Point, p3: Point,
+             tolerance: f64, depth: u32, points: &mut Vec<Point>) {
+    if depth >= MAX_DEPTH || is_flat(p0, p1, p2, p3, tolerance) {
+        points.push(p3);
+        return
+    }
+    let mid = |a: Point, b: Point| [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0];
+    let (p01, p12, p23) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+    let (p012, p123) = (mid(p01, p12), mid(p12, p23));
+    let p0123 = mid(p012, p123);
+    subdivide(p0, p01, p012, p0123, tolerance, depth + 1, points);
+    subdivide(p0123, p123, p23, p3, tolerance, depth + 1, points);
+}
+
+/// Whether or not both control points lie within `tolerance` of the chord.
+fn is_flat(p0: Point, p1: Point, p2: Point, p3: Point, tolerance: f64) -> bool {
+    let (dx, dy) = (p3[0] - p0[0], p3[1] - p0[1]);
+    let chord = (dx * dx + dy * dy).sqrt();
+    let distance = |p: Point| {
+        if chord == 0.0 {
+            ((p[0] - p0[0]).powi(2) + (p[1] - p0[1]).powi(2)).sqrt()
+        } else {
+            ((p[0] - p0[0]) * dy - (p[1] - p0[1]) * dx).abs() / chord
+        }
+    };
+    distance(p1) <= tolerance && distance(p2) <= tolerance
+}
@@ -0,0 +1,219 @@
+//! A simplified implementation of the Unicode bidirectional algorithm,
+//! sufficient for displaying single lines of mixed left-to-right and
+//! right-to-left (i.e. Arabic or Hebrew) text.
+//!
+//! Text is always stored and edited in logical order; these functions
+//! determine the order in which its characters should be displayed.
+
+/// The direction in which a run of text is read.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// The strong direction of the given character, or `None` if it is weak or
+/// neutral (i.e. a digit, whitespace or punctuation) and so is placed
+/// according to the text surrounding it.
+pub fn char_direction(ch: char) -> Option<Direction> {
+    match ch as u32 {
+        _ if is_digit(ch) => None,
+        // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan and Mandaic.
+        0x0590...0x08FF |
+        // Hebrew and Arabic presentation forms.
+        0xFB1D...0xFDFF | 0xFE70...0xFEFF => Some(Direction::RightToLeft),
+        _ if ch.is_alphabetic() => Some(Direction::LeftToRight),
+        _ => None,
+    }
+}
+
+/// Whether or not the given character is a digit, including the Arabic-Indic
+/// digits. Runs of digits are always displayed left-to-right.
+fn is_digit(ch: char) -> bool {
+    match ch as u32 {
+        0x0660...0x0669 | 0x06F0...0x06F9 => true,
+        _ => ch.is_digit(10),
+    }
+}
+
+/// Whether or not the given character joins the digits either side of it into
+/// a single number, i.e. a decimal point or a thousands separator.
+fn is_number_separator(ch: char) -> bool {
+    match ch {
+        '.' | ',' | ':' | '/' | '+' | '-' | '\u{066B}' | '\u{066C}' => true,
+        _ => false,
+    }
+}
+
+/// The class of a character when resolving its embedding level.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Class {
+    Strong(Direction),
+    Number,
+    Neutral,
+}
+
+/// Whether or not the given text contains any right-to-left characters.
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(|ch| char_direction(ch) == Some(Direction::RightToLeft))
+}
+
+/// The base direction of the text, given by its first strongly directional
+/// character. Text without any is left-to-right.
+pub fn base_direction(text: &str) -> Direction {
+    text.chars().filter_map(char_direction).next().unwrap_or(Direction::LeftToRight)
+}
+
+/// A line of text along with the order in which its characters are displayed.
+#[derive(Clone, Debug)]
+pub struct Line {
+    base: Direction,
+    /// The logical index of the character displayed at each position.
+    visual_to_logical: Vec<usize>,
+    /// The display position of the character at each logical index.
+    logical_to_visual: Vec<usize>,
+    /// Whether or not the character at each logical index is displayed right-to-left.
+    rtl: Vec<bool>,
+    /// The characters in display order.
+    visual: String,
+}
+
+impl Line {
+
+    /// Resolve the display order of the given text.
+    pub fn new(text: &str) -> Line {
+        let chars: Vec<char> = text.chars().collect();
+        let base = base_direction(text);
+        let base_level = match base { Direction::LeftToRight => 0, Direction::RightToLeft => 1 };
+
+        // Classify each character. A separator between two digits is part
+        // of their number, and a number following left-to-right text (or
+        // beginning left-to-right text) is simply left-to-right.
+        let mut classes: Vec<Class> = chars.iter().map(|&ch| match char_direction(ch) {
+            Some(direction) => Class::Strong(direction),
+            None if is_digit(ch) => Class::Number,
+            None => Class::Neutral,
+        }).collect();
+        for i in 1..chars.len().saturating_sub(1) {
+            if is_number_separator(chars[i])
+            && classes[i - 1] == Class::Number && classes[i + 1] == Class::Number {
+                classes[i] = Class::Number;
+            }
+        }
+        let mut prev_strong = base;
+        for class in classes.iter_mut() {
+            match *class {
+                Class::Strong(direction) => prev_strong = direction,
+                Class::Number if prev_strong == Direction::LeftToRight =>
+                    *class = Class::Strong(Direction::LeftToRight),
+                _ => (),
+            }
+        }
+
+        // Resolve each character's embedding level. Neutrals between two
+        // characters of the same direction take that direction (numbers
+        // counting as right-to-left), otherwise they take the base direction.
+        // Numbers are a level above right-to-left text so that their digits
+        // keep their order.
+        let direction_of = |class: &Class| match *class {
+            Class::Strong(direction) => Some(direction),
+            Class::Number => Some(Direction::RightToLeft),
+            Class::Neutral => None,
+        };
+        let mut levels: Vec<u8> = Vec::with_capacity(chars.len());
+        for i in 0..chars.len() {
+            let direction = match classes[i] {
+                Class::Strong(direction) => direction,
+                Class::Number => {
+                    levels.push(2);
+                    continue;
+                },
+                Class::Neutral => {
+                    let before = classes[..i].iter().rev().filter_map(&direction_of).next();
+                    let after = classes[i+1..].iter().filter_map(&direction_of).next();
+                    match (before, after) {
+                        (Some(a), Some(b)) if a == b => a,
+                        _ => base,
+                    }
+                },
+            };
+            levels.push(match (base_level, direction) {
+                (0, Direction::LeftToRight) => 0,
+                (0, Direction::RightToLeft) => 1,
+                (_, Direction::RightToLeft) => 1,
+                (_, Direction::LeftToRight) => 2,
+            });
+        }
+
+        // From the highest level down to the lowest odd level, reverse every
+        // run of characters at that level or higher.
+        let mut visual_to_logical: Vec<usize> = (0..chars.len()).collect();
+        let max_level = levels.iter().cloned().max().unwrap_or(0);
+        let mut level = max_level;
+        while level >= 1 {
+            let mut i = 0;
+            while i < visual_to_logical.len() {
+                if levels[visual_to_logical[i]] >= level {
+                    let start = i;
+                    while i < visual_to_logical.len() && levels[visual_to_logical[i]] >= level {
+                        i += 1;
+                    }
+                    visual_to_logical[start..i].reverse();
+                } else {
+                    i += 1;
+                }
+            }
+            level -= 1;
+        }
+
+        let mut logical_to_visual = vec![0; chars.len()];
+        for (visual_idx, &logical_idx) in visual_to_logical.iter().enumerate() {
+            logical_to_visual[logical_idx] = visual_idx;
+        }
+        let visual = visual_to_logical.iter().map(|&i| chars[i]).collect();
+        Line {
+            base: base,
+            visual_to_logical: visual_to_logical,
+            logical_to_visual: logical_to_visual,
+            rtl: levels.iter().map(|&level| level % 2 == 1).collect(),
+            visual: visual,
+        }
+    }
+
+    /// The base direction of the line.
+    pub fn base_direction(&self) -> Direction {
+        self.base
+    }
+
+    /// The number of characters in the line.
+    pub fn len(&self) -> usize {
+        self.rtl.len()
+    }
+
+    /// The line's characters in the order in which they are displayed.
+    pub fn visual_text(&self) -> &str {
+        &self.visual
+    }
+
+    /// The display position of the character at the given logical index.
+    pub fn visual_index(&self, logical_idx: usize) -> usize {
+        self.logical_to_visual[logical_idx]
+    }
+
+    /// The logical index of the character displayed at the given position.
+    pub fn logical_index(&self, visual_idx: usize) -> usize {
+        self.visual_to_logical[visual_idx]
+    }
+
+    /// Whether or not the character at the given logical index is displayed right-to-left.
+    pub fn is_rtl(&self, logical_idx: usize) -> bool {
+        self.rtl[logical_idx]
+    }
+
+}
+
+/// Return the given text with its characters in display order.
+pub fn reorder(text: &str) -> String {
+    if !has_rtl(text) { return text.to_string() }
+    Line::new(text).visual
+}
@@ -1,12 +1,13 @@
-use callback::Callable;
-use frame::Frameable;
-use color::{ Color, Colorable };
-use label::{ FontSize, Labelable };
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use label;
+use label::{ FontId, FontSize, Labelable };
 use dimensions::Dimensions;
+use mouse;
 use mouse::Mouse;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use theme::WidgetStyle;
+use icon;
 use rectangle;
 use ui::{ UIID, Ui };
 use widget::Widget;
@@ -14,11 +15,13 @@ use graphics::Graphics;
 use graphics::character::CharacterCache;
 
 /// Represents the state of the Button widget.
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum State {
     Normal,
     Highlighted,
     Clicked,
+    /// Pressed with the right or middle button.
+    AltClicked(mouse::Button),
 }
 
 impl State {
@@ -27,25 +30,41 @@ impl State {
         match self {
             &State::Normal => rectangle::State::Normal,
             &State::Highlighted => rectangle::State::Highlighted,
-            &State::Clicked => rectangle::State::Clicked,
+            &State::Clicked | &State::AltClicked(_) => rectangle::State::Clicked,
         }
     }
 }
 
 widget_fns!(Button, State, Widget::Button(State::Normal));
 
+/// What happened to the Button, returned by `Button::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The mouse was pressed over the button.
+    Pressed,
+    /// The mouse was released over the button after pressing it.
+    Clicked,
+    /// The right or middle button was released over the button after
+    /// pressing it with the same, i.e. to open a context menu.
+    AltClicked(mouse::Button),
+}
+
 /// Check the current state of the button.
 fn get_new_state(is_over: bool,
                  prev: State,
                  mouse: Mouse) -> State {
     use mouse::ButtonState::{Down, Up};
-    use self::State::{Normal, Highlighted, Clicked};
-    match (is_over, prev, mouse.left) {
-        (true,  Normal,  Down) => Normal,
-        (true,  _,       Down) => Clicked,
-        (true,  _,       Up)   => Highlighted,
-        (false, Clicked, Down) => Clicked,
-        _                      => Normal,
+    use self::State::{Normal, Highlighted, Clicked, AltClicked};
+    match (is_over, prev, mouse.left, mouse.alt_button_down()) {
+        (true,  Normal,        Down, _)       => Normal,
+        (true,  Normal,        Up,   Some(_)) => Normal,
+        (_,     AltClicked(b), Up,   _) if mouse.button(b) == Down => AltClicked(b),
+        (true,  Highlighted,   Up,   Some(b)) => AltClicked(b),
+        (true,  _,             Down, _)       => Clicked,
+        (true,  _,             Up,   _)       => Highlighted,
+        (false, Clicked,       Down, _)       => Clicked,
+        _                                     => Normal,
     }
 }
 
@@ -54,13 +73,11 @@ pub struct Button<'a, F> {
     ui_id: UIID,
     pos: Point,
     dim: Dimensions,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
+    maybe_icon: Option<char>,
     maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
 }
 
 impl<'a, F> Button<'a, F> {
@@ -72,35 +89,23 @@ impl<'a, F> Button<'a, F> {
             pos: [0.0, 0.0],
             dim: [64.0, 64.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
+            maybe_icon: None,
         }
     }
 
-}
-
-impl<'a, F> Colorable for Button<'a, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
+    /// Draw the glyph with the given codepoint from the theme's icon font,
+    /// to the left of the label if there is one.
+    pub fn icon(self, codepoint: char) -> Button<'a, F> {
+        Button { maybe_icon: Some(codepoint), ..self }
     }
-}
 
-impl<'a, F> Frameable for Button<'a, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
-        self
-    }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
-        self
-    }
 }
 
+composite_builder_impls!(Button<'a, F>);
+
 impl<'a, F> Callable<F> for Button<'a, F> {
     fn callback(mut self, cb: F) -> Self {
         self.maybe_callback = Some(cb);
@@ -108,6 +113,13 @@ impl<'a, F> Callable<F> for Button<'a, F> {
     }
 }
 
+impl<'a, F> Reactable<'a, Event> for Button<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
 impl<'a, F> Labelable<'a> for Button<'a, F> {
     fn label(mut self, text: &'a str) -> Self {
         self.maybe_label = Some(text);
@@ -115,78 +127,139 @@ impl<'a, F> Labelable<'a> for Button<'a, F> {
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
         self
     }
-}
 
-impl<'a, F> Positionable for Button<'a, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, F> Shapeable for Button<'a, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, F> ::draw::Drawable for Button<'a, F>
     where
         F: FnMut() + 'a
 {
-
     fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if let Some(Event::Clicked) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)() }
+        }
+    }
+}
 
+impl<'a> Button<'a, NoCallback> {
+
+    /// Draw the button, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the click needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Button<'a, F> {
+
+    /// Update and draw the button, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_button, &ui.theme);
+        ui.push_alpha(style.alpha);
         let state = *get_state(ui, self.ui_id);
         let mouse = ui.get_mouse_state();
         let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
         let new_state = get_new_state(is_over, state, mouse);
 
-        // Callback.
-        match (is_over, state, new_state) {
-            (true, State::Clicked, State::Highlighted) => match self.maybe_callback {
-                Some(ref mut callback) => (*callback)(), None => (),
-            }, _ => (),
-        }
+        let maybe_event = match (is_over, state, new_state) {
+            (true, State::Highlighted, State::Clicked) => Some(Event::Pressed),
+            (true, State::Clicked, State::Highlighted) => Some(Event::Clicked),
+            (true, State::AltClicked(b), State::Highlighted) => Some(Event::AltClicked(b)),
+            _ => None,
+        };
 
-        // Draw.
-        let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
+        // Draw. The state's color is applied via the Ui's color transition,
+        // so the rectangle itself is drawn in its Normal state.
+        let color = style.color;
+        let color = ui.transition_color(self.ui_id, new_state.as_rectangle_state().color(color));
+        let rect_state = rectangle::State::Normal;
+        let frame_w = style.frame;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
+            true => Some((frame_w, style.frame_color)),
             false => None,
         };
-        match self.maybe_label {
-            None => {
+        let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image,
+                                   new_state.as_rectangle_state());
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        match (self.maybe_icon, self.maybe_label) {
+            (None, None) => {
                 rectangle::draw(
-                    ui.win_w, ui.win_h, graphics, rect_state, self.pos,
-                    self.dim, maybe_frame, color
+                    ui, graphics, rect_state, self.pos,
+                    self.dim, maybe_frame, style.corner_radius, fill
                 )
             },
-            Some(text) => {
-                let text_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
-                let size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
+            (Some(codepoint), maybe_label) => {
+                rectangle::draw(
+                    ui, graphics, rect_state, self.pos,
+                    self.dim, maybe_frame, style.corner_radius, fill
+                );
+                // Center the icon and label together.
+                let icon_font = ui.theme.font_icon;
+                let icon_size = icon::size_for(self.dim);
+                let icon_w = icon::width(ui, icon_font, icon_size, codepoint);
+                let (text_w, gap) = match maybe_label {
+                    Some(text) => {
+                        let text_w = label::width(ui, style.font, style.label_font_size, text);
+                        (text_w, ui.theme.padding)
+                    },
+                    None => (0.0, 0.0),
+                };
+                let x = self.pos[0] + (self.dim[0] - (icon_w + gap + text_w)) / 2.0;
+                let icon_y = self.pos[1] + (self.dim[1] - icon_size as f64) / 2.0;
+                icon::draw(ui, graphics, [x, icon_y], icon_font, icon_size, style.label_color, codepoint);
+                if let Some(text) = maybe_label {
+                    let size = style.label_font_size;
+                    let text_y = self.pos[1] + (self.dim[1] - size as f64) / 2.0;
+                    let text_pos = [x + icon_w + gap, text_y];
+                    ui.draw_text(graphics, text_pos, style.font, size, style.label_color, text);
+                }
+            },
+            (None, Some(text)) => {
+                let text_color = style.label_color;
+                let size = style.label_font_size;
                 rectangle::draw_with_centered_label(
-                    ui.win_w, ui.win_h, graphics, ui, rect_state,
-                    self.pos, self.dim, maybe_frame, color,
-                    text, size, text_color
+                    ui, graphics, rect_state,
+                    self.pos, self.dim, maybe_frame, style.corner_radius, fill,
+                    text, style.font, size, text_color
                 )
             },
         }
 
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::Button(new_state), self.pos, self.dim);
 
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
@@ -2,3 +2,18 @@
 pub trait Callable<T> {
     fn callback(self, cb: T) -> Self;
 }
+
+/// The callback type of a widget drawn with its `react` method, which
+/// returns what happened to the widget rather than calling a callback.
+/// Widgets built without a callback have this type, so the closures that
+/// would capture application state needn't outlive the widget.
+pub type NoCallback = fn();
+
+/// A trait for widgets that describe what happened to them each frame with
+/// an event, given to the `on_event` closure as well as returned by the
+/// widget's `react` method. Unlike a widget's callback, which is only given
+/// its value, the event distinguishes i.e. a value that is changing from a
+/// value that has been committed.
+pub trait Reactable<'a, E> {
+    fn on_event<G>(self, on_event: G) -> Self where G: FnMut(E) + 'a;
+}
@@ -0,0 +1,32 @@
+/// Access to a clipboard through which widgets may copy and paste text.
+/// Backends may implement this for the system clipboard and give it to the
+/// `Ui` via `Ui::set_clipboard`.
+pub trait Clipboard {
+    /// The text currently on the clipboard, if there is any.
+    fn get_contents(&mut self) -> Option<String>;
+    /// Replace the clipboard's contents with the given text.
+    fn set_contents(&mut self, contents: String);
+}
+
+/// A clipboard local to the application, used when no system clipboard
+/// has been given to the `Ui`.
+#[derive(Clone, Debug)]
+pub struct LocalClipboard {
+    maybe_contents: Option<String>,
+}
+
+impl LocalClipboard {
+    /// Construct an empty LocalClipboard.
+    pub fn new() -> LocalClipboard {
+        LocalClipboard { maybe_contents: None }
+    }
+}
+
+impl Clipboard for LocalClipboard {
+    fn get_contents(&mut self) -> Option<String> {
+        self.maybe_contents.clone()
+    }
+    fn set_contents(&mut self, contents: String) {
+        self.maybe_contents = Some(contents);
+    }
+}
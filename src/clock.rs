@@ -0,0 +1,115 @@
+//! The clock consulted by the `Ui` for the current time, i.e. for color
+//! transitions, animations and the blinking of the TextBox cursor.
+//!
+//! The clock reads its time from a `TimeSource` (the system time unless
+//! given another), and may be paused, set to a given time or mocked so that
+//! the `Ui` is deterministic while testing or playing back a recording.
+
+use clock_ticks::precise_time_s;
+
+/// A source of the current time in seconds.
+pub trait TimeSource {
+    fn now(&self) -> f64;
+}
+
+/// The precise system time, used when no other source is given.
+#[derive(Copy, Clone, Debug)]
+pub struct SystemTime;
+
+impl TimeSource for SystemTime {
+    fn now(&self) -> f64 {
+        precise_time_s()
+    }
+}
+
+/// The `Ui`'s clock.
+pub struct Clock {
+    source: Box<TimeSource>,
+    /// The time to return in place of the source's, if any.
+    maybe_mock_time: Option<f64>,
+    /// The source's time at which the clock was paused, if it is paused.
+    maybe_paused_at: Option<f64>,
+    /// The amount by which the clock is behind its source, having been
+    /// paused or set.
+    offset: f64,
+}
+
+impl Clock {
+
+    /// A clock reading the system time.
+    pub fn new() -> Clock {
+        Clock::with_source(Box::new(SystemTime))
+    }
+
+    /// A clock reading the given source.
+    pub fn with_source(source: Box<TimeSource>) -> Clock {
+        Clock {
+            source: source,
+            maybe_mock_time: None,
+            maybe_paused_at: None,
+            offset: 0.0,
+        }
+    }
+
+    /// Read the time from the given source from now on, continuing from the
+    /// current time.
+    pub fn set_source(&mut self, source: Box<TimeSource>) {
+        let time = self.source_time() - self.offset;
+        self.source = source;
+        if self.maybe_paused_at.is_some() {
+            self.maybe_paused_at = Some(self.source.now());
+        }
+        self.set_time(time);
+    }
+
+    /// The current time in seconds. This is the mock time if one has been
+    /// set, otherwise the source's time less any time spent paused.
+    pub fn now(&self) -> f64 {
+        match self.maybe_mock_time {
+            Some(time) => time,
+            None => self.source_time() - self.offset,
+        }
+    }
+
+    /// Set the clock to the given time, from which it continues.
+    pub fn set_time(&mut self, time: f64) {
+        self.offset = self.source_time() - time;
+    }
+
+    /// Set a mock time to be returned by `Clock::now` in place of the
+    /// source's time (i.e. for deterministic playback and testing), or
+    /// `None` to return to using the source's time.
+    pub fn set_mock_time(&mut self, maybe_time: Option<f64>) {
+        self.maybe_mock_time = maybe_time;
+    }
+
+    /// The mock time returned by `Clock::now`, if one has been set.
+    pub fn mock_time(&self) -> Option<f64> {
+        self.maybe_mock_time
+    }
+
+    /// Stop the clock, freezing animations until it is resumed.
+    pub fn pause(&mut self) {
+        if self.maybe_paused_at.is_none() {
+            self.maybe_paused_at = Some(self.source.now());
+        }
+    }
+
+    /// Continue the clock from the time at which it was paused.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.maybe_paused_at.take() {
+            self.offset += self.source.now() - paused_at;
+        }
+    }
+
+    /// Whether or not the clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.maybe_paused_at.is_some()
+    }
+
+    /// The source's time, or the time at which it was paused.
+    fn source_time(&self) -> f64 {
+        self.maybe_paused_at.unwrap_or_else(|| self.source.now())
+    }
+
+}
@@ -4,17 +4,18 @@ use std::default::Default;
 use std::fmt::{Debug, Formatter, Error};
 use std::ops::{Add, Sub, Mul, Div};
 use std::ascii::AsciiExt;
-use rustc_serialize::hex::ToHex;
+use rustc_serialize::hex::{ FromHex, ToHex };
 use rustc_serialize::{
     Decodable, Encodable,
     Decoder, Encoder,
     DecoderHelpers, EncoderHelpers
 };
+use image::NinePatch;
 use utils::clampf32;
 
 /// A basic color struct for general color use
 /// made of red, green, blue and alpha elements.
-#[derive(Copy)]
+#[derive(Copy, PartialEq)]
 pub struct Color(pub [f32; 4]);
 
 impl Color {
@@ -90,6 +91,117 @@ impl Color {
         Color([1f32, 1f32, 1f32, 1f32])
     }
 
+    /// Basic constructor for a Red Color struct (`#FF0000`).
+    pub fn red() -> Color {
+        Color([1.0, 0.0, 0.0, 1.0])
+    }
+
+    /// Basic constructor for a Green Color struct (`#008000`).
+    pub fn green() -> Color {
+        Color([0.0, 0.502, 0.0, 1.0])
+    }
+
+    /// Basic constructor for a Blue Color struct (`#0000FF`).
+    pub fn blue() -> Color {
+        Color([0.0, 0.0, 1.0, 1.0])
+    }
+
+    /// Basic constructor for a Yellow Color struct (`#FFFF00`).
+    pub fn yellow() -> Color {
+        Color([1.0, 1.0, 0.0, 1.0])
+    }
+
+    /// Basic constructor for a Orange Color struct (`#FFA500`).
+    pub fn orange() -> Color {
+        Color([1.0, 0.647, 0.0, 1.0])
+    }
+
+    /// Basic constructor for a Purple Color struct (`#800080`).
+    pub fn purple() -> Color {
+        Color([0.502, 0.0, 0.502, 1.0])
+    }
+
+    /// Basic constructor for a Pink Color struct (`#FFC0CB`).
+    pub fn pink() -> Color {
+        Color([1.0, 0.753, 0.796, 1.0])
+    }
+
+    /// Basic constructor for a Brown Color struct (`#A52A2A`).
+    pub fn brown() -> Color {
+        Color([0.647, 0.165, 0.165, 1.0])
+    }
+
+    /// Basic constructor for a Grey Color struct (`#808080`).
+    pub fn grey() -> Color {
+        Color([0.502, 0.502, 0.502, 1.0])
+    }
+
+    /// Basic constructor for a Light Grey Color struct (`#D3D3D3`).
+    pub fn light_grey() -> Color {
+        Color([0.827, 0.827, 0.827, 1.0])
+    }
+
+    /// Basic constructor for a Dark Grey Color struct (`#A9A9A9`).
+    pub fn dark_grey() -> Color {
+        Color([0.663, 0.663, 0.663, 1.0])
+    }
+
+    /// Basic constructor for a Charcoal Color struct (`#36454F`).
+    pub fn charcoal() -> Color {
+        Color([0.212, 0.271, 0.31, 1.0])
+    }
+
+    /// Basic constructor for a Dodger Blue Color struct (`#1E90FF`).
+    pub fn dodger_blue() -> Color {
+        Color([0.118, 0.565, 1.0, 1.0])
+    }
+
+    /// Basic constructor for a Sky Blue Color struct (`#87CEEB`).
+    pub fn sky_blue() -> Color {
+        Color([0.529, 0.808, 0.922, 1.0])
+    }
+
+    /// Basic constructor for a Navy Color struct (`#000080`).
+    pub fn navy() -> Color {
+        Color([0.0, 0.0, 0.502, 1.0])
+    }
+
+    /// Basic constructor for a Teal Color struct (`#008080`).
+    pub fn teal() -> Color {
+        Color([0.0, 0.502, 0.502, 1.0])
+    }
+
+    /// Basic constructor for a Lime Green Color struct (`#32CD32`).
+    pub fn lime_green() -> Color {
+        Color([0.196, 0.804, 0.196, 1.0])
+    }
+
+    /// Basic constructor for a Crimson Color struct (`#DC143C`).
+    pub fn crimson() -> Color {
+        Color([0.863, 0.078, 0.235, 1.0])
+    }
+
+    /// Basic constructor for a Gold Color struct (`#FFD700`).
+    pub fn gold() -> Color {
+        Color([1.0, 0.843, 0.0, 1.0])
+    }
+
+    /// Construct a color from a hex string in the format `#RRGGBB` or
+    /// `#RRGGBBAA` (the `#` is optional), e.g. `Color::from_hex("#1e90ff")`.
+    pub fn from_hex(hex: &str) -> Result<Color, String> {
+        let digits = if hex.starts_with("#") { &hex[1..] } else { hex };
+        let bytes = match digits.from_hex() {
+            Ok(bytes) => bytes,
+            Err(e) => return Err(format!("Invalid hex color \"{}\": {}", hex, e)),
+        };
+        let to_f32 = |byte: u8| byte as f32 / 255.0;
+        match bytes.len() {
+            3 => Ok(Color([to_f32(bytes[0]), to_f32(bytes[1]), to_f32(bytes[2]), 1.0])),
+            4 => Ok(Color([to_f32(bytes[0]), to_f32(bytes[1]), to_f32(bytes[2]), to_f32(bytes[3])])),
+            _ => Err(format!("Invalid hex color \"{}\": expected 6 or 8 digits", hex)),
+        }
+    }
+
     /// Clamp the Color's values between 0f32 and 1f32.
     fn clamp(c: Color) -> Color {
         Color([
@@ -100,6 +212,12 @@ impl Color {
         ])
     }
 
+    /// Return the color with its alpha multiplied by the given amount.
+    pub fn fade(&self, alpha: f32) -> Color {
+        let Color(c) = *self;
+        Color([c[0], c[1], c[2], c[3] * alpha])
+    }
+
     /// Return a highlighted version of the current Color.
     pub fn highlighted(&self) -> Color {
         let luminance = self.luminance();
@@ -169,8 +287,28 @@ impl Color {
     /// the Color the most. This will be useful for determining
     /// a readable color for text on any given background Color.
     pub fn plain_contrast(&self) -> Color {
-        if self.luminance() > 0.5f32 { Color::black() }
-        else { Color::white() }
+        let (black, white) = (Color::black(), Color::white());
+        if self.contrast_ratio(black) >= self.contrast_ratio(white) { black }
+        else { white }
+    }
+
+    /// Return the relative luminance of the color as perceived by the eye
+    /// (as defined by WCAG), from `0.0` for black to `1.0` for white.
+    pub fn relative_luminance(&self) -> f32 {
+        fn linear(chan: f32) -> f32 {
+            let chan = clampf32(chan);
+            if chan <= 0.03928 { chan / 12.92 }
+            else { ((chan + 0.055) / 1.055).powf(2.4) }
+        }
+        0.2126 * linear(self.r()) + 0.7152 * linear(self.g()) + 0.0722 * linear(self.b())
+    }
+
+    /// Return the ratio of contrast between this color and another, from
+    /// `1.0` for identical luminance to `21.0` for black against white.
+    pub fn contrast_ratio(&self, other: Color) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+        (lighter + 0.05) / (darker + 0.05)
     }
 
     /// Return the luminance of the color.
@@ -198,6 +336,128 @@ impl Color {
         let hex = vals.to_hex().to_ascii_uppercase();
         format!("#{}", &hex)
     }
+
+    /// Construct a color from hue (in degrees), saturation, lightness and alpha.
+    pub fn hsla(h: f32, s: f32, l: f32, a: f32) -> Color {
+        let (r, g, b) = hsl_to_rgb(h, clampf32(s), clampf32(l));
+        Color([r, g, b, a])
+    }
+
+    /// Construct an opaque color from hue (in degrees), saturation and lightness.
+    pub fn hsl(h: f32, s: f32, l: f32) -> Color {
+        Color::hsla(h, s, l, 1.0)
+    }
+
+    /// Return the hue (in degrees), saturation, lightness and alpha of the color.
+    pub fn to_hsla(&self) -> (f32, f32, f32, f32) {
+        let (h, s, l) = rgb_to_hsl(self.r(), self.g(), self.b());
+        (h, s, l, self.a())
+    }
+
+    /// Return the color with the given hue (in degrees).
+    pub fn with_hue(&self, hue: f32) -> Color {
+        let (_, s, l, a) = self.to_hsla();
+        Color::hsla(hue, s, l, a)
+    }
+
+    /// Return the color with the given saturation.
+    pub fn with_saturation(&self, saturation: f32) -> Color {
+        let (h, _, l, a) = self.to_hsla();
+        Color::hsla(h, saturation, l, a)
+    }
+
+    /// Return the color with the given HSL lightness.
+    pub fn with_luminance(&self, lightness: f32) -> Color {
+        let (h, s, _, a) = self.to_hsla();
+        Color::hsla(h, s, lightness, a)
+    }
+
+    /// Linearly interpolate between this color (at `0.0`) and another (at `1.0`).
+    pub fn interpolate(&self, other: Color, t: f32) -> Color {
+        Color([
+            self.r() + (other.r() - self.r()) * t,
+            self.g() + (other.g() - self.g()) * t,
+            self.b() + (other.b() - self.b()) * t,
+            self.a() + (other.a() - self.a()) * t,
+        ])
+    }
+
+    /// Return the color at `t` (from `0.0` to `1.0`) along a ramp blending
+    /// between the given colors, which are evenly spaced along it.
+    pub fn from_ramp(stops: &[Color], t: f32) -> Color {
+        match stops.len() {
+            0 => Color::black(),
+            1 => stops[0],
+            len => {
+                let pos = clampf32(t) * (len - 1) as f32;
+                let idx = ::std::cmp::min(pos.floor() as usize, len - 2);
+                stops[idx].interpolate(stops[idx + 1], pos - idx as f32)
+            },
+        }
+    }
+
+    /// Return the color opposite this one on the color wheel.
+    pub fn complementary(&self) -> Color {
+        let (h, s, l, a) = self.to_hsla();
+        Color::hsla(h + 180.0, s, l, a)
+    }
+
+    /// Return the two colors `angle` degrees either side of this one on the color wheel.
+    pub fn analogous(&self, angle: f32) -> (Color, Color) {
+        let (h, s, l, a) = self.to_hsla();
+        (Color::hsla(h - angle, s, l, a), Color::hsla(h + angle, s, l, a))
+    }
+
+    /// Return `n` colors beginning with this one, with hues evenly spaced
+    /// around the color wheel. Useful for coloring the items of a chart.
+    pub fn hues(&self, n: usize) -> Vec<Color> {
+        let (h, s, l, a) = self.to_hsla();
+        (0..n).map(|i| Color::hsla(h + 360.0 * i as f32 / n as f32, s, l, a)).collect()
+    }
+
+    /// Return `n` progressively darker shades of this color, ending just before black.
+    pub fn shades(&self, n: usize) -> Vec<Color> {
+        let black = Color([0.0, 0.0, 0.0, self.a()]);
+        (1..n + 1).map(|i| self.interpolate(black, i as f32 / (n + 1) as f32)).collect()
+    }
+
+    /// Return `n` progressively lighter tints of this color, ending just before white.
+    pub fn tints(&self, n: usize) -> Vec<Color> {
+        let white = Color([1.0, 1.0, 1.0, self.a()]);
+        (1..n + 1).map(|i| self.interpolate(white, i as f32 / (n + 1) as f32)).collect()
+    }
+}
+
+/// Convert hue (in degrees), saturation and lightness to red, green and blue.
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    let h = ((h % 360.0) + 360.0) % 360.0 / 60.0;
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = l - chroma / 2.0;
+    (r + m, g + m, b + m)
+}
+
+/// Convert red, green and blue to hue (in degrees), saturation and lightness.
+fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let l = (max + min) / 2.0;
+    if chroma == 0.0 { return (0.0, 0.0, l) }
+    let h = if max == r { ((g - b) / chroma) % 6.0 }
+            else if max == g { (b - r) / chroma + 2.0 }
+            else { (r - g) / chroma + 4.0 };
+    let h = (h * 60.0 + 360.0) % 360.0;
+    let s = chroma / (1.0 - (2.0 * l - 1.0).abs());
+    (h, s, l)
 }
 
 fn to_8_bit(chan: f32) -> u8 {
@@ -303,6 +563,51 @@ impl Encodable for Color {
     }
 }
 
+/// The direction in which the colors of a linear gradient change.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum GradientDirection {
+    /// From the left edge to the right edge.
+    Horizontal,
+    /// From the top edge to the bottom edge.
+    Vertical,
+}
+
+/// A fill that blends smoothly between two colors.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Gradient {
+    /// Blend from the first color to the second in the given direction.
+    Linear(Color, Color, GradientDirection),
+    /// Blend from the first color at the center to the second at the edges.
+    Radial(Color, Color),
+}
+
+impl Gradient {
+    /// Return the gradient with the given function applied to both of its colors.
+    pub fn map<F>(self, f: F) -> Gradient where F: Fn(Color) -> Color {
+        match self {
+            Gradient::Linear(a, b, direction) => Gradient::Linear(f(a), f(b), direction),
+            Gradient::Radial(a, b) => Gradient::Radial(f(a), f(b)),
+        }
+    }
+}
+
+/// The fill of a rectangle: a single color, a gradient or a tinted
+/// nine-patch image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Fill {
+    Color(Color),
+    Gradient(Gradient),
+    NinePatch(NinePatch, Color),
+}
+
+impl From<Color> for Fill {
+    fn from(color: Color) -> Fill { Fill::Color(color) }
+}
+
+impl From<Gradient> for Fill {
+    fn from(gradient: Gradient) -> Fill { Fill::Gradient(gradient) }
+}
+
 /// A trait used for "colorable" widget context types.
 pub trait Colorable: Sized {
     fn color(self, color: Color) -> Self;
@@ -311,3 +616,10 @@ pub trait Colorable: Sized {
         self.color(Color([r, g, b, a]))
     }
 }
+
+/// A trait used for widget context types whose opacity may be set.
+pub trait Fadeable: Sized {
+    /// Multiply the alpha of every color with which the widget is drawn by
+    /// the given amount, i.e. to fade the widget in or out.
+    fn alpha(self, alpha: f32) -> Self;
+}
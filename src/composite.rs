@@ -0,0 +1,86 @@
+//! Helpers for widgets composed of other widgets, i.e. a slider with a label
+//! and a text box for typing its value.
+//!
+//! A composite is given a single UIID from which it claims a range of
+//! consecutive UIIDs, one for each of its parts, so that the application
+//! needn't keep track of them. The first UIID of the range is the
+//! composite's own, with which it may keep state of its own and whose
+//! children its parts should be made (see `Ui::set_parent`). Styling given
+//! to the composite is forwarded to its parts with `forward_style`, and
+//! `composite_builder_impls!` implements its builder traits. The
+//! composite's alpha isn't forwarded, as the composite fades all of its
+//! parts at once via `Ui::push_alpha`.
+
+use color::Colorable;
+use frame::Frameable;
+use label::Labelable;
+use theme::WidgetStyle;
+use ui::UIID;
+
+/// A range of consecutive UIIDs owned by a composite widget.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IdRange {
+    start: UIID,
+    len: usize,
+}
+
+impl IdRange {
+
+    /// The `len` UIIDs beginning at `start`.
+    pub fn new(start: UIID, len: usize) -> IdRange {
+        IdRange { start: start, len: len }
+    }
+
+    /// The composite's own UIID, i.e. the first of the range.
+    pub fn own(&self) -> UIID {
+        self.start
+    }
+
+    /// The UIID for the part at the given index, where the composite's own
+    /// UIID is at index `0`.
+    pub fn get(&self, idx: usize) -> UIID {
+        assert!(idx < self.len, "Index {} is beyond the {} UIIDs of the composite.", idx, self.len);
+        self.start + idx as UIID
+    }
+
+    /// The number of UIIDs in the range.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The first UIID following the range, i.e. for the next widget.
+    pub fn next(&self) -> UIID {
+        self.start + self.len as UIID
+    }
+
+}
+
+/// Give a part of a composite each of the style values that were given to
+/// the composite.
+pub fn forward_style<W>(mut widget: W, style: &WidgetStyle) -> W
+    where W: Colorable + Frameable
+{
+    if let Some(color) = style.maybe_color { widget = widget.color(color) }
+    if let Some(width) = style.maybe_frame { widget = widget.frame(width) }
+    if let Some(color) = style.maybe_frame_color { widget = widget.frame_color(color) }
+    if let Some(gradient) = style.maybe_gradient { widget = widget.gradient(gradient) }
+    if let Some(radius) = style.maybe_corner_radius { widget = widget.corner_radius(radius) }
+    if let Some(shadow) = style.maybe_shadow {
+        widget = widget.shadow(shadow.offset, shadow.softness, shadow.color);
+    }
+    if let Some(patch) = style.maybe_background_image {
+        widget = widget.background_image(patch.id, patch.insets);
+    }
+    widget
+}
+
+/// Give a part of a composite each of the label style values that were
+/// given to the composite.
+pub fn forward_label_style<'a, W>(mut widget: W, style: &WidgetStyle) -> W
+    where W: Labelable<'a>
+{
+    if let Some(color) = style.maybe_label_color { widget = widget.label_color(color) }
+    if let Some(size) = style.maybe_label_font_size { widget = widget.label_font_size(size) }
+    if let Some(font) = style.maybe_font { widget = widget.label_font(font) }
+    widget
+}
@@ -0,0 +1,446 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use mouse::ButtonState::{ Down, Up };
+use piston::input::keyboard::Key;
+use point::Point;
+use rectangle;
+use text_box;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+use widget::Widget;
+
+/// The most commands remembered for recalling with Up and Down.
+const MAX_HISTORY: usize = 256;
+
+/// The text drawn before the input line.
+const PROMPT: &'static str = "> ";
+
+/// Represents the state of the Console widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted,
+    /// The console was clicked and is taking keyboard input.
+    Capturing,
+}
+
+widget_fns!(Console, State, Widget::Console(State::Normal));
+
+/// What happened to the Console, returned by `Console::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    /// The input line was changed by the user.
+    Edited,
+    /// Return was pressed with the given command on the input line, which was
+    /// then cleared and the command added to the history.
+    Submitted(String),
+}
+
+/// The parts of the console kept between frames as its widget memory.
+struct Memory {
+    /// The submitted commands, oldest first.
+    history: Vec<String>,
+    /// The index of the command recalled via Up and Down, if any.
+    maybe_recall: Option<usize>,
+    /// The input as it was before a command was recalled.
+    draft: String,
+    /// The byte index of the cursor within the input.
+    cursor: usize,
+    /// The number of lines the output is scrolled up from its end.
+    scroll: f64,
+    /// The completions of the input last offered via Tab.
+    completions: Vec<String>,
+}
+
+impl Memory {
+    fn new() -> Memory {
+        Memory {
+            history: Vec::new(),
+            maybe_recall: None,
+            draft: String::new(),
+            cursor: 0,
+            scroll: 0.0,
+            completions: Vec::new(),
+        }
+    }
+}
+
+/// The byte index of the character boundary before the given index.
+fn prev_boundary(text: &str, idx: usize) -> usize {
+    text[..idx].char_indices().next_back().map_or(0, |(i, _)| i)
+}
+
+/// The byte index of the character boundary after the given index.
+fn next_boundary(text: &str, idx: usize) -> usize {
+    text[idx..].chars().next().map_or(idx, |ch| idx + ch.len_utf8())
+}
+
+/// The longest prefix shared by all of the given strings.
+fn common_prefix(strings: &[String]) -> &str {
+    let first = match strings.first() {
+        Some(first) => first,
+        None => return "",
+    };
+    let mut len = first.len();
+    for s in strings[1..].iter() {
+        len = first.char_indices().zip(s.chars())
+            .take_while(|&((i, a), b)| a == b && i < len)
+            .last().map_or(0, |((i, a), _)| i + a.len_utf8());
+    }
+    &first[..len]
+}
+
+/// An in-app command console, i.e. for debugging a game or tool: the lines
+/// of output above an input line. Clicking the console gives it the
+/// keyboard. Return submits the input, Up and Down recall earlier commands
+/// and Tab completes the input via the completion function, if one is
+/// given. The mouse wheel scrolls back through the output, which otherwise
+/// follows its end.
+pub struct Console<'a, F> {
+    ui_id: UIID,
+    lines: &'a [String],
+    input: &'a mut String,
+    maybe_completer: Option<Box<Fn(&str) -> Vec<String> + 'a>>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> Console<'a, F> {
+
+    /// Create a console context to be built upon, showing the given lines of
+    /// output above the given input.
+    pub fn new(ui_id: UIID, lines: &'a [String], input: &'a mut String) -> Console<'a, F> {
+        Console {
+            ui_id: ui_id,
+            lines: lines,
+            input: input,
+            maybe_completer: None,
+            pos: [0.0, 0.0],
+            dim: [512.0, 256.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// A function returning the commands that the given input may be completed
+    /// to, for Tab completion. A single completion replaces the input, while
+    /// many extend it by the prefix they share and are listed above it.
+    pub fn completion<G>(mut self, completer: G) -> Console<'a, F>
+        where G: Fn(&str) -> Vec<String> + 'a
+    {
+        self.maybe_completer = Some(Box::new(completer));
+        self
+    }
+
+}
+
+composite_builder_impls!(Console<'a, F>);
+
+impl<'a, F> Callable<F> for Console<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for Console<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Labelable<'a> for Console<'a, F> {
+    /// Consoles aren't labelled; their output is drawn instead.
+    fn label(self, _text: &'a str) -> Self {
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for Console<'a, F> where F: FnMut(&str) + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Submitted(command)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(&command) }
+        }
+    }
+}
+
+impl<'a> Console<'a, NoCallback> {
+
+    /// Draw the console, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to a command needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Console<'a, F> {
+
+    /// Apply a key pressed while the console has the keyboard.
+    fn press(&mut self, memory: &mut Memory, key: Key, maybe_event: &mut Option<Event>) {
+        let cursor = memory.cursor;
+        match key {
+            Key::Left => memory.cursor = prev_boundary(&self.input, cursor),
+            Key::Right => memory.cursor = next_boundary(&self.input, cursor),
+            Key::Home => memory.cursor = 0,
+            Key::End => memory.cursor = self.input.len(),
+            Key::Backspace if cursor > 0 => {
+                let prev = prev_boundary(&self.input, cursor);
+                self.input.drain(prev..cursor);
+                memory.cursor = prev;
+                memory.maybe_recall = None;
+                *maybe_event = maybe_event.take().or(Some(Event::Edited));
+            },
+            Key::Delete if cursor < self.input.len() => {
+                let next = next_boundary(&self.input, cursor);
+                self.input.drain(cursor..next);
+                memory.maybe_recall = None;
+                *maybe_event = maybe_event.take().or(Some(Event::Edited));
+            },
+            Key::Up | Key::Down if !memory.history.is_empty() => {
+                let last = memory.history.len() - 1;
+                let recall = match (key, memory.maybe_recall) {
+                    (Key::Up, None) => {
+                        memory.draft = self.input.clone();
+                        Some(last)
+                    },
+                    (Key::Up, Some(idx)) => Some(idx.saturating_sub(1)),
+                    (_, Some(idx)) if idx < last => Some(idx + 1),
+                    _ => None,
+                };
+                if recall != memory.maybe_recall {
+                    self.input.clear();
+                    match recall {
+                        Some(idx) => self.input.push_str(&memory.history[idx]),
+                        None => self.input.push_str(&memory.draft),
+                    }
+                    memory.maybe_recall = recall;
+                    memory.cursor = self.input.len();
+                    *maybe_event = maybe_event.take().or(Some(Event::Edited));
+                }
+            },
+            Key::Tab => if let Some(ref completer) = self.maybe_completer {
+                let completions = completer(&self.input);
+                let completed = match completions.len() {
+                    0 => None,
+                    1 => Some(completions[0].clone()),
+                    _ => Some(common_prefix(&completions).to_string()),
+                };
+                if let Some(completed) = completed {
+                    if completed.len() > self.input.len() && completed.starts_with(&self.input[..]) {
+                        *self.input = completed;
+                        memory.cursor = self.input.len();
+                        *maybe_event = maybe_event.take().or(Some(Event::Edited));
+                    }
+                }
+                memory.completions = match completions.len() {
+                    1 => Vec::new(),
+                    _ => completions,
+                };
+            },
+            Key::Return if !self.input.is_empty() => {
+                let command = ::std::mem::replace(self.input, String::new());
+                if memory.history.last() != Some(&command) {
+                    memory.history.push(command.clone());
+                    if memory.history.len() > MAX_HISTORY {
+                        memory.history.remove(0);
+                    }
+                }
+                memory.maybe_recall = None;
+                memory.draft.clear();
+                memory.cursor = 0;
+                memory.scroll = 0.0;
+                memory.completions.clear();
+                *maybe_event = Some(Event::Submitted(command));
+            },
+            _ => (),
+        }
+    }
+
+    /// Update and draw the console, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        // Consoles are drawn in the monospace font unless given another.
+        let defaults = WidgetStyle { maybe_font: Some(ui.theme.font_mono), ..WidgetStyle::new() };
+        let style = self.style.resolve_with(ui.theme.maybe_console, defaults, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let mut memory = ui.take_widget_memory::<Memory>(self.ui_id).unwrap_or_else(Memory::new);
+        // The output is scrolled back as it was saved via `Ui::save_state`.
+        if let Some(scroll) = ui.take_restored_scroll(self.ui_id) {
+            memory.scroll = scroll;
+        }
+        let prev_scroll = memory.scroll;
+        // The input may have been changed by the application.
+        if memory.cursor > self.input.len() || !self.input.is_char_boundary(memory.cursor) {
+            memory.cursor = self.input.len();
+        }
+
+        let font = style.font;
+        let font_size = style.label_font_size;
+        let padding = ui.theme.padding;
+        let line_h = font_size as f64 + padding / 2.0;
+        let frame_w = style.frame;
+        let inner_pos = [self.pos[0] + frame_w + padding, self.pos[1] + frame_w + padding];
+        let inner_dim = [self.dim[0] - (frame_w + padding) * 2.0, self.dim[1] - (frame_w + padding) * 2.0];
+        let input_y = inner_pos[1] + inner_dim[1] - font_size as f64;
+        let output_dim = [inner_dim[0], (input_y - padding - inner_pos[1]).max(0.0)];
+
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let new_state = match (is_over, state, mouse.left) {
+            (_, State::Capturing, Up) => State::Capturing,
+            (true, State::Highlighted, Down) | (true, State::Capturing, Down) => State::Capturing,
+            (true, State::Normal, Down) => State::Normal,
+            (true, _, Up) => State::Highlighted,
+            _ => State::Normal,
+        };
+        if is_over {
+            ui.set_mouse_cursor(MouseCursor::Text);
+        }
+
+        // Scroll back through the output, no further than its first line.
+        let visible_lines = (output_dim[1] / line_h).floor();
+        let max_scroll = (self.lines.len() as f64 - visible_lines).max(0.0);
+        if is_over {
+            memory.scroll = clamp(memory.scroll + mouse.scroll[1], 0.0, max_scroll);
+        }
+
+        // Typing and keys, while capturing and not already handled by another widget.
+        let mut maybe_event = None;
+        let is_input_free = ui.text_input_consumer().map_or(true, |ui_id| ui_id == self.ui_id);
+        if let (State::Capturing, true) = (new_state, is_input_free) {
+            {
+                // Typing over a recalled command makes it the draft.
+                let input = &mut *self.input;
+                let Memory { ref mut cursor, ref mut maybe_recall, .. } = memory;
+                let maybe_event = &mut maybe_event;
+                ui.with_entered_text(|_, entered_text| {
+                    for t in entered_text.iter() {
+                        input.insert_str(*cursor, t);
+                        *cursor += t.len();
+                        *maybe_recall = None;
+                        *maybe_event = Some(Event::Edited);
+                    }
+                });
+            }
+            let key_count = ui.pressed_keys().len();
+            for i in 0..key_count {
+                let key = ui.pressed_keys()[i];
+                self.press(&mut memory, key, &mut maybe_event);
+            }
+            ui.consume_text_input(self.ui_id);
+            ui.claim_text_focus(self.ui_id);
+        }
+        let new_state = match ui.pressed_keys().contains(&Key::Escape) {
+            true if new_state == State::Capturing && is_input_free => State::Highlighted,
+            _ => new_state,
+        };
+
+        // Draw the background.
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        let fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
+
+        // Draw the output upwards from its last line, along with the
+        // completions last offered, which sit just above the input.
+        let text_color = style.label_color;
+        let hint_color = text_color.fade(0.5);
+        ui.push_clip(inner_pos, output_dim);
+        let mut y = inner_pos[1] + output_dim[1] - line_h;
+        if !memory.completions.is_empty() {
+            let hint = memory.completions.join("  ");
+            ui.draw_text(graphics, [inner_pos[0], y], font, font_size, hint_color, &hint);
+            y -= line_h;
+        }
+        let skip = memory.scroll.round() as usize;
+        for line in self.lines.iter().rev().skip(skip) {
+            if y + line_h < inner_pos[1] { break }
+            ui.draw_text(graphics, [inner_pos[0], y], font, font_size, text_color, line);
+            y -= line_h;
+        }
+        ui.pop_clip();
+
+        // A bar along the right edge shows where the output is scrolled to.
+        if max_scroll > 0.0 {
+            let bar_h = (output_dim[1] * visible_lines / self.lines.len() as f64).max(padding * 2.0);
+            let bar_y = inner_pos[1] + (output_dim[1] - bar_h) * (1.0 - memory.scroll / max_scroll);
+            let bar_x = inner_pos[0] + inner_dim[0] + padding / 2.0;
+            ui.draw_line(graphics, [bar_x, bar_y, bar_x, bar_y + bar_h], 2.0, true, hint_color);
+        }
+
+        // Draw the input line beneath a divider, and the cursor while capturing.
+        let divider_y = input_y - padding / 2.0;
+        ui.draw_line(graphics, [inner_pos[0], divider_y, inner_pos[0] + inner_dim[0], divider_y],
+                     1.0, false, style.frame_color.fade(0.5));
+        let prompt_w = ui.text_width(font, font_size, PROMPT);
+        ui.push_clip([inner_pos[0], input_y], [inner_dim[0], font_size as f64]);
+        ui.draw_text(graphics, [inner_pos[0], input_y], font, font_size, hint_color, PROMPT);
+        ui.draw_text(graphics, [inner_pos[0] + prompt_w, input_y], font, font_size, text_color, &self.input);
+        ui.pop_clip();
+        if new_state == State::Capturing {
+            let cursor_x = inner_pos[0] + prompt_w + ui.text_width(font, font_size, &self.input[..memory.cursor]);
+            text_box::draw_cursor(ui, graphics, style.color, cursor_x, input_y, font_size as f64);
+        }
+
+        if memory.scroll != prev_scroll {
+            ui.persist_scroll(self.ui_id, memory.scroll);
+        }
+        ui.set_widget_memory(self.ui_id, memory);
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Console(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event.clone(), self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
@@ -0,0 +1,26 @@
+/// The shapes of mouse cursor that widgets may request while hovered (see
+/// `Ui::set_mouse_cursor`). The `Ui` only collects the request, leaving the
+/// window code to apply it with `Ui::mouse_cursor` once the widgets are drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MouseCursor {
+    /// The default pointer.
+    Arrow,
+    /// The I-beam, i.e. over editable text.
+    Text,
+    /// The pointing hand, i.e. over a link.
+    Hand,
+    /// Precise selection, i.e. over an XYPad.
+    Crosshair,
+    /// Moving something that has been grabbed.
+    Move,
+    /// Resizing to the left or right.
+    ResizeHorizontal,
+    /// Resizing up or down.
+    ResizeVertical,
+    /// Resizing from the top-left or bottom-right corner.
+    ResizeTopLeftBottomRight,
+    /// Resizing from the top-right or bottom-left corner.
+    ResizeTopRightBottomLeft,
+    /// The action isn't allowed, i.e. over a disabled widget.
+    NotAllowed,
+}
@@ -0,0 +1,540 @@
+use bezier;
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use mouse::Mouse;
+use oval;
+use point::Point;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+use widget::Widget;
+
+/// The distance in pixels from a point to each of its tangent handles.
+const HANDLE_LENGTH: f64 = 32.0;
+
+/// The steepest slope that may be given to a point via its handles.
+const MAX_SLOPE: f64 = 64.0;
+
+/// A control point of a curve mapping `0.0 ... 1.0` to `0.0 ... 1.0`, along
+/// with the slope of the curve as it passes through the point.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct CurvePoint {
+    pub x: f64,
+    pub y: f64,
+    /// The change in `y` per change in `x` at the point.
+    pub slope: f64,
+}
+
+impl CurvePoint {
+    /// A point through which the curve passes with the given slope.
+    pub fn new(x: f64, y: f64, slope: f64) -> CurvePoint {
+        CurvePoint { x: x, y: y, slope: slope }
+    }
+}
+
+/// Evaluate the curve through the given points, which must be sorted by
+/// `x`, at the given `x`. Neighbouring points are joined by cubic Hermite
+/// splines, and the curve is flat before the first point and after the
+/// last. A curve without points maps every value to itself.
+pub fn evaluate(points: &[CurvePoint], x: f64) -> f64 {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return x,
+    };
+    if x <= first.x { return first.y }
+    if x >= last.x { return last.y }
+    let idx = points.iter().position(|p| p.x > x).unwrap_or(points.len() - 1);
+    let (a, b) = (&points[idx - 1], &points[idx]);
+    let dx = b.x - a.x;
+    if dx <= 0.0 { return b.y }
+    let t = (x - a.x) / dx;
+    let (t2, t3) = (t * t, t * t * t);
+    (2.0 * t3 - 3.0 * t2 + 1.0) * a.y
+        + (t3 - 2.0 * t2 + t) * dx * a.slope
+        + (-2.0 * t3 + 3.0 * t2) * b.y
+        + (t3 - t2) * dx * b.slope
+}
+
+/// Sample the curve through the given points at `count` evenly spaced
+/// values from `0.0` to `1.0` inclusive, i.e. to build a lookup table.
+pub fn sample(points: &[CurvePoint], count: usize) -> Vec<f64> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![evaluate(points, 0.0)],
+        _ => (0..count).map(|i| evaluate(points, i as f64 / (count - 1) as f64)).collect(),
+    }
+}
+
+/// The slope of the curve through the given points at the given `x`.
+fn slope_at(points: &[CurvePoint], x: f64) -> f64 {
+    let e = 1.0e-4;
+    (evaluate(points, x + e) - evaluate(points, x - e)) / (2.0 * e)
+}
+
+/// Which of a point's tangent handles.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// The elements of the CurveEditor beneath the mouse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Element {
+    /// The area in which the curve is drawn.
+    Pad,
+    /// The control point at the given index.
+    Point(usize),
+    /// One of the tangent handles of the point at the given index.
+    Handle(usize, Side),
+}
+
+impl Element {
+    /// The index of the point to which the element belongs, if any.
+    fn point_idx(&self) -> Option<usize> {
+        match *self {
+            Element::Point(idx) | Element::Handle(idx, _) => Some(idx),
+            Element::Pad => None,
+        }
+    }
+}
+
+/// The mouse button with which an element was pressed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MouseButton {
+    Left,
+    Right,
+}
+
+/// Represents the state of the CurveEditor widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Element),
+    Clicked(Element, MouseButton),
+}
+
+impl State {
+    /// Return the associated Rectangle state.
+    fn as_rectangle_state(&self) -> rectangle::State {
+        match self {
+            &State::Normal => rectangle::State::Normal,
+            &State::Highlighted(_) => rectangle::State::Highlighted,
+            &State::Clicked(_, _) => rectangle::State::Clicked,
+        }
+    }
+
+    /// The element highlighted or pressed, if any.
+    fn element(&self) -> Option<Element> {
+        match *self {
+            State::Highlighted(elem) | State::Clicked(elem, _) => Some(elem),
+            State::Normal => None,
+        }
+    }
+}
+
+widget_fns!(CurveEditor, State, Widget::CurveEditor(State::Normal));
+
+/// What happened to the CurveEditor, returned by `CurveEditor::react` and
+/// given to its `on_event` closure along with the index of the point.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// A point was added by clicking the curve's background.
+    Added(usize),
+    /// The point is being dragged.
+    Moved(usize),
+    /// The point's slope is being changed via one of its handles.
+    SlopeChanged(usize),
+    /// The point or one of its handles was released after being dragged.
+    Released(usize),
+    /// The point was removed by clicking it with the right mouse button.
+    Removed(usize),
+}
+
+/// Determine and return the new state from the previous state and the
+/// element beneath the mouse. A pressed element remains pressed while the
+/// mouse is dragged beyond it.
+fn get_new_state(is_over_elem: Option<Element>, prev: State, mouse: Mouse) -> State {
+    use mouse::ButtonState::{ Down, Up };
+    use self::MouseButton::{ Left, Right };
+    use self::State::{ Normal, Highlighted, Clicked };
+    match (is_over_elem, prev, mouse.left, mouse.right) {
+        (_, Clicked(elem, Left), Down, _) => Clicked(elem, Left),
+        (_, Clicked(elem, Right), _, Down) => Clicked(elem, Right),
+        (Some(elem), Highlighted(_), Down, Up) => Clicked(elem, Left),
+        (Some(elem), Highlighted(_), Up, Down) => Clicked(elem, Right),
+        (Some(elem), _, Up, Up) => Highlighted(elem),
+        _ => Normal,
+    }
+}
+
+/// A widget for editing a curve that maps `0.0 ... 1.0` to `0.0 ... 1.0`,
+/// i.e. an easing or tone curve. Points are dragged to move them, their
+/// tangent handles are dragged to change the curve's slope through them,
+/// clicking the background adds a point and right-clicking a point
+/// removes it. The value of the curve beneath the mouse is shown while
+/// hovering. Use `curve_editor::evaluate` or `curve_editor::sample` to
+/// apply the resulting curve.
+pub struct CurveEditor<'a, F> {
+    ui_id: UIID,
+    points: &'a mut Vec<CurvePoint>,
+    grid_divisions: usize,
+    pt_radius: f64,
+    line_width: f64,
+    font_size: FontSize,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+    maybe_label: Option<&'a str>,
+}
+
+impl<'a, F> CurveEditor<'a, F> {
+
+    /// Create a curve editor context to be built upon. The points should be
+    /// sorted by `x`.
+    pub fn new(ui_id: UIID, points: &'a mut Vec<CurvePoint>) -> CurveEditor<'a, F> {
+        CurveEditor {
+            ui_id: ui_id,
+            points: points,
+            grid_divisions: 4,
+            pt_radius: 5.0,
+            line_width: 2.0,
+            font_size: 14,
+            pos: [0.0, 0.0],
+            dim: [256.0, 256.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+            maybe_label: None,
+        }
+    }
+
+    /// The number of cells into which the grid divides each axis. A grid
+    /// of `0` or `1` divisions isn't drawn.
+    pub fn grid(self, divisions: usize) -> CurveEditor<'a, F> {
+        CurveEditor { grid_divisions: divisions, ..self }
+    }
+
+    #[inline]
+    pub fn point_radius(self, radius: f64) -> CurveEditor<'a, F> {
+        CurveEditor { pt_radius: radius, ..self }
+    }
+
+    #[inline]
+    pub fn line_width(self, width: f64) -> CurveEditor<'a, F> {
+        CurveEditor { line_width: width, ..self }
+    }
+
+    #[inline]
+    pub fn value_font_size(self, size: FontSize) -> CurveEditor<'a, F> {
+        CurveEditor { font_size: size, ..self }
+    }
+
+}
+
+composite_builder_impls!(CurveEditor<'a, F>);
+
+impl<'a, F> Callable<F> for CurveEditor<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for CurveEditor<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Labelable<'a> for CurveEditor<'a, F> {
+    fn label(mut self, text: &'a str) -> Self {
+        self.maybe_label = Some(text);
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for CurveEditor<'a, F>
+    where
+        F: FnMut(&mut Vec<CurvePoint>, usize) + 'a
+{
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let maybe_idx = match self.update(ui, graphics) {
+            Some(Event::Added(idx)) | Some(Event::Moved(idx)) | Some(Event::SlopeChanged(idx)) |
+            Some(Event::Released(idx)) | Some(Event::Removed(idx)) => Some(idx),
+            None => None,
+        };
+        if let (Some(idx), Some(callback)) = (maybe_idx, self.maybe_callback.as_mut()) {
+            callback(self.points, idx)
+        }
+    }
+}
+
+impl<'a> CurveEditor<'a, NoCallback> {
+
+    /// Draw the curve_editor, returning what happened to it. An alternative
+    /// to `Callable::callback` for when the reaction to the change needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> CurveEditor<'a, F> {
+
+    /// Update and draw the curve_editor, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_curve_editor, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let frame_w = style.frame;
+        let pad_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let pad_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+        let len = self.points.len();
+
+        // Conversions between the curve's unit square and the pad's pixels.
+        let to_pixels = |x: f64, y: f64| -> Point {
+            [pad_pos[0] + x * pad_dim[0], pad_pos[1] + (1.0 - y) * pad_dim[1]]
+        };
+        let to_unit = |p: Point| -> (f64, f64) {
+            (clamp((p[0] - pad_pos[0]) / pad_dim[0], 0.0, 1.0),
+             clamp(1.0 - (p[1] - pad_pos[1]) / pad_dim[1], 0.0, 1.0))
+        };
+        // The handle on the given side of a point lies along its tangent.
+        let handle_pos = |point: &CurvePoint, side: Side| -> Point {
+            let center = to_pixels(point.x, point.y);
+            let (dx, dy) = (pad_dim[0], -point.slope * pad_dim[1]);
+            let length = (dx * dx + dy * dy).sqrt();
+            let sign = match side { Side::Left => -1.0, Side::Right => 1.0 };
+            [center[0] + sign * dx / length * HANDLE_LENGTH, center[1] + sign * dy / length * HANDLE_LENGTH]
+        };
+
+        // The handles of the point last highlighted or pressed are shown, and
+        // are checked before the points themselves.
+        let maybe_active = state.element().and_then(|elem| elem.point_idx()).and_then(|idx| match idx < len {
+            true => Some(idx),
+            false => None,
+        });
+        let is_over_elem = match rectangle::is_over(self.pos, mouse.pos, self.dim) {
+            false => None,
+            true => {
+                let handle = maybe_active.and_then(|idx| {
+                    let point = &self.points[idx];
+                    [Side::Left, Side::Right].iter()
+                        .find(|&&side| oval::is_over_circle(handle_pos(point, side), mouse.pos, self.pt_radius))
+                        .map(|&side| Element::Handle(idx, side))
+                });
+                let point = self.points.iter().position(|p| {
+                    oval::is_over_circle(to_pixels(p.x, p.y), mouse.pos, self.pt_radius)
+                }).map(Element::Point);
+                Some(handle.or(point).unwrap_or(Element::Pad))
+            },
+        };
+        let new_state = get_new_state(is_over_elem, state, mouse);
+
+        // Apply the drag of a point or handle, or the click of the background.
+        let (mouse_x, mouse_y) = to_unit(mouse.pos);
+        let mut maybe_event = None;
+        match (state, new_state) {
+            (_, State::Clicked(Element::Point(idx), MouseButton::Left)) if idx < len => {
+                let min_x = if idx > 0 { self.points[idx - 1].x } else { 0.0 };
+                let max_x = if idx + 1 < len { self.points[idx + 1].x } else { 1.0 };
+                let (x, y) = (clamp(mouse_x, min_x, max_x), mouse_y);
+                if self.points[idx].x != x || self.points[idx].y != y {
+                    self.points[idx].x = x;
+                    self.points[idx].y = y;
+                    maybe_event = Some(Event::Moved(idx));
+                }
+            },
+            (_, State::Clicked(Element::Handle(idx, side), MouseButton::Left)) if idx < len => {
+                let point = self.points[idx];
+                let center = to_pixels(point.x, point.y);
+                // Measured in pixels so that the handle follows the mouse exactly.
+                let dx = (mouse.pos[0] - center[0]) / pad_dim[0];
+                let dy = (center[1] - mouse.pos[1]) / pad_dim[1];
+                let dx = match side {
+                    Side::Left => dx.min(-1.0e-6),
+                    Side::Right => dx.max(1.0e-6),
+                };
+                let slope = clamp(dy / dx, -MAX_SLOPE, MAX_SLOPE);
+                if point.slope != slope {
+                    self.points[idx].slope = slope;
+                    maybe_event = Some(Event::SlopeChanged(idx));
+                }
+            },
+            (State::Clicked(elem, MouseButton::Left), _) => match elem {
+                Element::Point(idx) | Element::Handle(idx, _) if idx < len =>
+                    maybe_event = Some(Event::Released(idx)),
+                // A new point continues the curve's current slope.
+                Element::Pad if is_over_elem == Some(Element::Pad) => {
+                    let slope = slope_at(&self.points, mouse_x);
+                    let idx = self.points.iter().filter(|p| p.x <= mouse_x).count();
+                    self.points.insert(idx, CurvePoint::new(mouse_x, mouse_y, slope));
+                    maybe_event = Some(Event::Added(idx));
+                },
+                _ => (),
+            },
+            (State::Clicked(Element::Point(idx), MouseButton::Right), _)
+                if idx < len && is_over_elem == Some(Element::Point(idx)) => {
+                self.points.remove(idx);
+                maybe_event = Some(Event::Removed(idx));
+            },
+            _ => (),
+        }
+        let len = self.points.len();
+        let maybe_active = match new_state.element().and_then(|elem| elem.point_idx()) {
+            Some(idx) if idx < len => Some(idx),
+            _ => None,
+        };
+
+        // Draw the background and the grid.
+        let color = ui.transition_color(self.ui_id, new_state.as_rectangle_state().color(style.color));
+        let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image,
+                                   new_state.as_rectangle_state());
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
+        let line_color = style.color.plain_contrast();
+        let grid_color = line_color.fade(0.15);
+        for i in 1..self.grid_divisions {
+            let perc = i as f64 / self.grid_divisions as f64;
+            let x = pad_pos[0] + pad_dim[0] * perc;
+            let y = pad_pos[1] + pad_dim[1] * perc;
+            ui.draw_line(graphics, [x, pad_pos[1], x, pad_pos[1] + pad_dim[1]], 1.0, false, grid_color);
+            ui.draw_line(graphics, [pad_pos[0], y, pad_pos[0] + pad_dim[0], y], 1.0, false, grid_color);
+        }
+        if let Some(text) = self.maybe_label {
+            let size = style.label_font_size;
+            let text_pos = [pad_pos[0] + ui.theme.padding, pad_pos[1] + ui.theme.padding];
+            ui.draw_text(graphics, text_pos, style.font, size, style.label_color, text);
+        }
+
+        // Draw the curve. Each segment's Hermite spline is drawn as the
+        // equivalent cubic bezier, whose control points lie a third of the
+        // way along the segment on each point's tangent.
+        ui.push_clip(pad_pos, pad_dim);
+        match (self.points.first().cloned(), self.points.last().cloned()) {
+            (Some(first), Some(last)) => {
+                let start = to_pixels(first.x, first.y);
+                let end = to_pixels(last.x, last.y);
+                ui.draw_line(graphics, [pad_pos[0], start[1], start[0], start[1]],
+                             self.line_width, true, line_color);
+                ui.draw_line(graphics, [end[0], end[1], pad_pos[0] + pad_dim[0], end[1]],
+                             self.line_width, true, line_color);
+                for w in self.points.windows(2) {
+                    let (a, b) = (w[0], w[1]);
+                    let third = (b.x - a.x) / 3.0;
+                    bezier::draw_cubic(ui, graphics,
+                                       to_pixels(a.x, a.y),
+                                       to_pixels(a.x + third, a.y + a.slope * third),
+                                       to_pixels(b.x - third, b.y - b.slope * third),
+                                       to_pixels(b.x, b.y),
+                                       self.line_width, line_color);
+                }
+            },
+            _ => {
+                let (start, end) = (to_pixels(0.0, 0.0), to_pixels(1.0, 1.0));
+                ui.draw_line(graphics, [start[0], start[1], end[0], end[1]], self.line_width, true, line_color);
+            },
+        }
+        ui.pop_clip();
+
+        // Draw the points, and the handles of the active point.
+        for (i, point) in self.points.iter().enumerate() {
+            let radius = if maybe_active == Some(i) { self.pt_radius } else { self.pt_radius * 0.75 };
+            oval::draw_circle(ui, graphics, to_pixels(point.x, point.y), radius, line_color);
+        }
+        if let Some(idx) = maybe_active {
+            let point = self.points[idx];
+            let center = to_pixels(point.x, point.y);
+            for &side in [Side::Left, Side::Right].iter() {
+                let handle = handle_pos(&point, side);
+                ui.draw_line(graphics, [center[0], center[1], handle[0], handle[1]], 1.0, false, line_color);
+                oval::draw_circle(ui, graphics, handle, self.pt_radius * 0.6, line_color);
+            }
+        }
+
+        // Show the value beneath the mouse, or of the point being dragged.
+        let maybe_readout = match new_state {
+            State::Clicked(Element::Point(idx), _) | State::Highlighted(Element::Point(idx)) if idx < len => {
+                let point = self.points[idx];
+                Some((point.x, point.y))
+            },
+            State::Highlighted(Element::Pad) => Some((mouse_x, evaluate(&self.points, mouse_x))),
+            _ => None,
+        };
+        if let Some((x, y)) = maybe_readout {
+            let marker = to_pixels(x, clamp(y, 0.0, 1.0));
+            oval::draw_circle(ui, graphics, marker, self.pt_radius * 0.5, line_color);
+            let mut readout = ui.scratch_string();
+            readout.push_str(&ui.theme.number_format.format(x, 2));
+            readout.push_str(", ");
+            readout.push_str(&ui.theme.number_format.format(y, 2));
+            let readout_w = ui.text_width(style.font, self.font_size, &readout);
+            let pad = ui.theme.padding;
+            // The readout stays within the pad, on whichever side has room.
+            let readout_x = match marker[0] + pad + readout_w > pad_pos[0] + pad_dim[0] {
+                true => marker[0] - pad - readout_w,
+                false => marker[0] + pad,
+            };
+            let readout_y = match marker[1] - pad - (self.font_size as f64) < pad_pos[1] {
+                true => marker[1] + pad,
+                false => marker[1] - pad - self.font_size as f64,
+            };
+            ui.draw_text(graphics, [readout_x, readout_y], style.font, self.font_size, line_color, &readout);
+            ui.recycle_string(readout);
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::CurveEditor(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
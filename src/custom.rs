@@ -0,0 +1,65 @@
+use dimensions::Dimensions;
+use graphics;
+use graphics::{ Graphics, Transformed };
+use graphics::character::CharacterCache;
+use graphics::math::Matrix2d;
+use point::Point;
+use position::Positionable;
+use shape::Shapeable;
+use ui::{ UIID, Ui };
+
+/// A widget whose contents are drawn by the user, i.e. a game viewport or a
+/// custom visualization. The widget takes part in the layout and hit-testing
+/// of the `Ui` like any other, while the given closure is free to draw
+/// anything to the `Graphics` backend.
+///
+/// The closure receives the widget's `[x, y, w, h]` rectangle, the backend
+/// and a transform whose origin is the top-left corner of the widget. Its
+/// drawing is not captured by `Ui::capture_primitives`.
+pub struct Custom<F> {
+    ui_id: UIID,
+    pos: Point,
+    dim: Dimensions,
+    draw_fn: F,
+}
+
+impl<F> Custom<F> {
+
+    /// Create a custom widget context to be built upon.
+    pub fn new(ui_id: UIID, draw_fn: F) -> Custom<F> {
+        Custom {
+            ui_id: ui_id,
+            pos: [0.0, 0.0],
+            dim: [128.0, 128.0],
+            draw_fn: draw_fn,
+        }
+    }
+
+    /// Place the widget and call the closure to draw its contents.
+    pub fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            F: FnMut([f64; 4], &mut B, Matrix2d)
+    {
+        // Anything batched so far must be drawn beneath the custom content.
+        ui.flush_rectangles(graphics);
+        let rect = [self.pos[0], self.pos[1], self.dim[0], self.dim[1]];
+        let transform = graphics::abs_transform(ui.win_w, ui.win_h).trans(self.pos[0], self.pos[1]);
+        (self.draw_fn)(rect, graphics, transform);
+        ui.set_place(self.ui_id, self.pos, self.dim);
+    }
+
+}
+
+impl<F> Positionable for Custom<F> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<F> Shapeable for Custom<F> {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
@@ -0,0 +1,130 @@
+//! A trait by which other crates may implement widgets with persistent state.
+//!
+//! The widgets within conrod keep their state in variants of the
+//! `widget::Widget` enum. A `CustomWidget` instead has its state stored by
+//! the `Ui` on its behalf (as the widget memory of its UIID), so it may be
+//! implemented without any changes to conrod itself.
+//!
+//! ```ignore
+//! impl CustomWidget for Knob {
+//!     type State = knob::State;
+//!     type Style = knob::Style;
+//!     type Event = f64;
+//!     ...
+//! }
+//!
+//! // Each frame:
+//! if let Some(value) = Knob::new(KNOB, value).point([20.0, 20.0]).set(ui, graphics) {
+//!     ...
+//! }
+//! ```
+
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use mouse::Mouse;
+use piston::input::keyboard::Key;
+use point::Point;
+use shape::Shapeable;
+use std::any::Any;
+use theme::Theme;
+use ui::{ UIID, Ui };
+use widget::Placing;
+
+/// What a custom widget is given to update its state.
+pub struct UpdateArgs<'a, S: 'a, St: 'a> {
+    pub ui_id: UIID,
+    /// The widget's state as of the previous frame.
+    pub state: &'a S,
+    pub style: &'a St,
+    pub pos: Point,
+    pub dim: Dimensions,
+    pub mouse: Mouse,
+    pub keys_just_pressed: &'a [Key],
+    pub keys_just_released: &'a [Key],
+    pub text_just_entered: &'a [String],
+    /// Where the widget was placed during the previous frame.
+    pub placing: Placing,
+}
+
+/// What a custom widget is given to draw itself.
+pub struct DrawArgs<'a, S: 'a, St: 'a> {
+    pub ui_id: UIID,
+    /// The widget's newly updated state.
+    pub state: &'a S,
+    pub style: &'a St,
+    pub pos: Point,
+    pub dim: Dimensions,
+}
+
+/// A widget implemented outside of conrod. Its builder implements the
+/// trait, and `CustomWidget::set` updates and draws it.
+pub trait CustomWidget: Shapeable + Sized {
+    /// The state kept between frames.
+    type State: Any;
+    /// The style with which the widget is drawn, resolved from the theme.
+    type Style;
+    /// What may happen to the widget, returned by `CustomWidget::set`.
+    type Event;
+
+    /// The UIID with which the widget's state and placing are stored.
+    fn ui_id(&self) -> UIID;
+
+    /// The position of the widget's top-left corner.
+    fn get_pos(&self) -> Point;
+
+    /// The state with which the widget begins, i.e. the first time it's set.
+    fn init_state(&self) -> Self::State;
+
+    /// The style for the widget given the current theme.
+    fn style(&self, theme: &Theme) -> Self::Style;
+
+    /// Return the widget's new state, along with whatever happened to it.
+    fn update(&mut self, args: UpdateArgs<Self::State, Self::Style>) -> (Self::State, Option<Self::Event>);
+
+    /// Draw the widget in its new state.
+    fn draw<B, C>(&self, args: DrawArgs<Self::State, Self::Style>, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache;
+
+    /// Update and draw the widget, storing its new state and placing with
+    /// the `Ui` and returning what happened to it. If the UIID held the
+    /// state of some other kind of widget, the widget begins anew.
+    fn set<B, C>(mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Self::Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let ui_id = self.ui_id();
+        let (pos, dim) = (self.get_pos(), self.get_dim());
+        let state = match ui.take_widget_memory::<Self::State>(ui_id) {
+            Some(state) => state,
+            None => self.init_state(),
+        };
+        let style = self.style(&ui.theme);
+        let (new_state, maybe_event) = self.update(UpdateArgs {
+            ui_id: ui_id,
+            state: &state,
+            style: &style,
+            pos: pos,
+            dim: dim,
+            mouse: ui.get_mouse_state(),
+            keys_just_pressed: &ui.keys_just_pressed[..],
+            keys_just_released: &ui.keys_just_released[..],
+            text_just_entered: &ui.text_just_entered[..],
+            placing: ui.get_placing(ui_id),
+        });
+        self.draw(DrawArgs {
+            ui_id: ui_id,
+            state: &new_state,
+            style: &style,
+            pos: pos,
+            dim: dim,
+        }, ui, graphics);
+        ui.set_widget_memory(ui_id, new_state);
+        ui.set_place(ui_id, pos, dim);
+        maybe_event
+    }
+
+}
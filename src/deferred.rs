@@ -0,0 +1,84 @@
+use graphics::character::CharacterCache;
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+use ui::Ui;
+
+/// A closure waiting to be called with the `Ui`.
+type Callback<C> = Box<FnMut(&mut Ui<C>)>;
+
+/// A queue of closures to be called with the `Ui` once the frame's widgets
+/// have been drawn (see `Ui::deferred`).
+///
+/// Widget callbacks can't be given the `Ui`, as it is borrowed by the widget
+/// being drawn. A handle to the queue may be captured by a callback instead,
+/// so that its reaction (i.e. focusing another widget or opening a dialog)
+/// may be queued and carried out once the `Ui` is free:
+///
+/// ```ignore
+/// let deferred = ui.deferred();
+/// Button::new(OPEN)
+///     .callback(|| deferred.push(|ui| ui.set_user_state(DIALOG, true)))
+///     .draw(ui, graphics);
+/// ```
+pub struct Deferred<C>
+    where
+        C: CharacterCache
+{
+    queue: Rc<RefCell<Vec<Callback<C>>>>,
+}
+
+impl<C> Clone for Deferred<C>
+    where
+        C: CharacterCache
+{
+    fn clone(&self) -> Deferred<C> {
+        Deferred { queue: self.queue.clone() }
+    }
+}
+
+impl<C> Deferred<C>
+    where
+        C: CharacterCache
+{
+
+    /// Construct an empty queue.
+    pub fn new() -> Deferred<C> {
+        Deferred { queue: Rc::new(RefCell::new(Vec::new())) }
+    }
+
+    /// Queue the given closure to be called with the `Ui` once the frame's
+    /// widgets have been drawn.
+    pub fn push<F>(&self, callback: F)
+        where
+            C: 'static,
+            F: FnOnce(&mut Ui<C>) + 'static
+    {
+        let mut maybe_callback = Some(callback);
+        self.queue.borrow_mut().push(Box::new(move |ui: &mut Ui<C>| {
+            if let Some(callback) = maybe_callback.take() {
+                callback(ui)
+            }
+        }));
+    }
+
+    /// The number of closures waiting to be called.
+    pub fn len(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    /// Whether or not there are no closures waiting to be called.
+    pub fn is_empty(&self) -> bool {
+        self.queue.borrow().is_empty()
+    }
+
+    /// Call each closure that is waiting in the order it was queued. Closures
+    /// queued by those being called wait until the next time the queue is run.
+    pub fn run(&self, ui: &mut Ui<C>) {
+        let callbacks = mem::replace(&mut *self.queue.borrow_mut(), Vec::new());
+        for mut callback in callbacks.into_iter() {
+            callback(ui);
+        }
+    }
+
+}
@@ -0,0 +1,763 @@
+//! Docking, in which an application's panels are arranged by the user by
+//! dragging them by their tabs onto the edges or centers of other panels.
+//!
+//! The arrangement is a `DockLayout`: a tree of splits whose leaves are
+//! panels stacked as tabs, along with the panels floating above it. The
+//! layout is owned by the application so that it may be saved (see
+//! `DockLayout::to_json`) and the user's arrangement restored next time.
+//!
+//! Each frame a `Dock` is constructed with the layout and drawn with a
+//! closure that draws the content of each visible panel within the rect
+//! given to it. While a panel is being dragged, the dock targets beneath the
+//! mouse are previewed, and dropping the panel onto one splits the layout
+//! there. Panels dropped elsewhere float.
+
+use color::{ Color, Colorable, Fadeable };
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use mouse::ButtonState;
+use point::Point;
+use position::Positionable;
+use rectangle;
+use rustc_serialize::{ json, Decodable, Encodable };
+use shape::Shapeable;
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+
+/// Identifies one of the application's panels, and indexes its title.
+pub type PanelId = usize;
+
+/// The distance the mouse must move with a tab held before the panel is dragged.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// The width of the dividers between split nodes.
+const DIVIDER_WIDTH: f64 = 4.0;
+
+/// The width of the strips along the outer edges of the dock onto which
+/// panels may be dropped to dock them beside the whole layout.
+const OUTER_EDGE: f64 = 24.0;
+
+/// The largest size of a panel once it is undocked.
+const MAX_FLOATING_DIM: Dimensions = [320.0, 240.0];
+
+/// The direction along which a split divides its space.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Axis {
+    /// The two nodes are side by side.
+    Horizontal,
+    /// The first node is above the second.
+    Vertical,
+}
+
+/// Where a panel is docked relative to the panel it is dropped onto.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Zone {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Among the panel's tabs.
+    Center,
+}
+
+impl Zone {
+    /// The axis of the split made by docking in the zone, and whether or not
+    /// the docked panel comes first.
+    fn split(&self) -> Option<(Axis, bool)> {
+        match *self {
+            Zone::Left => Some((Axis::Horizontal, true)),
+            Zone::Right => Some((Axis::Horizontal, false)),
+            Zone::Top => Some((Axis::Vertical, true)),
+            Zone::Bottom => Some((Axis::Vertical, false)),
+            Zone::Center => None,
+        }
+    }
+
+    /// The part of the given rect that a panel docked in the zone would take.
+    fn preview(&self, pos: Point, dim: Dimensions) -> (Point, Dimensions) {
+        let (half_w, half_h) = (dim[0] / 2.0, dim[1] / 2.0);
+        match *self {
+            Zone::Left => (pos, [half_w, dim[1]]),
+            Zone::Right => ([pos[0] + half_w, pos[1]], [half_w, dim[1]]),
+            Zone::Top => (pos, [dim[0], half_h]),
+            Zone::Bottom => ([pos[0], pos[1] + half_h], [dim[0], half_h]),
+            Zone::Center => (pos, dim),
+        }
+    }
+}
+
+/// A node of the docked layout.
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum DockNode {
+    /// Panels stacked as tabs, along with the index of the one shown.
+    Tabs(Vec<PanelId>, usize),
+    /// Two nodes dividing their space along the axis, the given proportion
+    /// of which goes to the first.
+    Split(Axis, f64, Box<DockNode>, Box<DockNode>),
+}
+
+/// A panel floating above the docked layout.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct Floating {
+    pub panel: PanelId,
+    /// The position of the panel relative to the top left of the dock.
+    pub pos: Point,
+    pub dim: Dimensions,
+}
+
+/// The arrangement of the panels within a dock.
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct DockLayout {
+    pub maybe_root: Option<DockNode>,
+    /// The floating panels, the topmost last.
+    pub floating: Vec<Floating>,
+}
+
+impl DockLayout {
+
+    /// A layout without any panels.
+    pub fn new() -> DockLayout {
+        DockLayout { maybe_root: None, floating: Vec::new() }
+    }
+
+    /// Whether or not the panel is docked or floating within the layout.
+    pub fn contains(&self, panel: PanelId) -> bool {
+        self.floating.iter().any(|f| f.panel == panel)
+            || self.maybe_root.as_ref().map_or(false, |root| contains(root, panel))
+    }
+
+    /// Remove the panel from wherever it is, returning whether or not it was there.
+    pub fn remove(&mut self, panel: PanelId) -> bool {
+        let was_contained = self.contains(panel);
+        self.floating.retain(|f| f.panel != panel);
+        self.maybe_root = self.maybe_root.take().and_then(|root| remove(root, panel));
+        was_contained
+    }
+
+    /// Dock the panel in the given zone of the target panel, or of the whole
+    /// layout if there is no target or it isn't docked.
+    pub fn dock(&mut self, panel: PanelId, maybe_target: Option<PanelId>, zone: Zone) {
+        self.remove(panel);
+        let new = DockNode::Tabs(vec![panel], 0);
+        self.maybe_root = Some(match self.maybe_root.take() {
+            None => new,
+            Some(root) => {
+                let maybe_target = match maybe_target {
+                    Some(target) if contains(&root, target) => Some(target),
+                    _ => None,
+                };
+                match (maybe_target, zone.split()) {
+                    (Some(target), _) => insert(root, target, zone, panel),
+                    (None, Some((axis, true))) => DockNode::Split(axis, 0.5, Box::new(new), Box::new(root)),
+                    (None, Some((axis, false))) => DockNode::Split(axis, 0.5, Box::new(root), Box::new(new)),
+                    // Docked in the center of the whole layout, the panel
+                    // joins the tabs of the first panel.
+                    (None, None) => {
+                        let first = first_panel(&root);
+                        insert(root, first, Zone::Center, panel)
+                    },
+                }
+            },
+        });
+    }
+
+    /// Float the panel at the given position relative to the dock.
+    pub fn float(&mut self, panel: PanelId, pos: Point, dim: Dimensions) {
+        self.remove(panel);
+        self.floating.push(Floating { panel: panel, pos: pos, dim: dim });
+    }
+
+    /// Construct a layout from its JSON representation.
+    pub fn from_json(json_str: &str) -> Result<DockLayout, String> {
+        let json_object = match json::Json::from_str(json_str) {
+            Ok(json_object) => json_object,
+            Err(e) => return Err(format!("Failed to construct json_object from str: {}", Error::description(&e))),
+        };
+        let mut decoder = json::Decoder::new(json_object);
+        match Decodable::decode(&mut decoder) {
+            Ok(layout) => Ok(layout),
+            Err(e) => Err(format!("Failed to construct DockLayout from json decoder: {}", Error::description(&e))),
+        }
+    }
+
+    /// Return the layout's JSON representation.
+    pub fn to_json(&self) -> Result<String, String> {
+        let mut json_string = String::new();
+        {
+            let mut encoder = json::Encoder::new_pretty(&mut json_string);
+            if let Err(e) = self.encode(&mut encoder) {
+                return Err(format!("Failed to encode DockLayout: {}", Error::description(&e)));
+            }
+        }
+        Ok(json_string)
+    }
+
+    /// Load a layout from a JSON file.
+    pub fn load(path: &str) -> Result<DockLayout, String> {
+        let mut file = match File::open(&Path::new(path)) {
+            Ok(file) => file,
+            Err(e) => return Err(format!("Failed to open file for DockLayout: {}", Error::description(&e))),
+        };
+        let mut contents = String::new();
+        if let Err(e) = ::std::io::Read::read_to_string(&mut file, &mut contents) {
+            return Err(format!("Failed to load DockLayout correctly: {}", Error::description(&e)));
+        }
+        DockLayout::from_json(&contents)
+    }
+
+    /// Save the layout to a JSON file.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json_string = try!(self.to_json());
+        let mut file = match File::create(&Path::new(path)) {
+            Ok(file) => file,
+            Err(e) => return Err(format!("Failed to create a File at the given path: {}", Error::description(&e)))
+        };
+        match ::std::io::Write::write_all(&mut file, json_string.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("DockLayout failed to save correctly: {}", Error::description(&e))),
+        }
+    }
+
+}
+
+/// Whether or not the panel is within the node.
+fn contains(node: &DockNode, panel: PanelId) -> bool {
+    match *node {
+        DockNode::Tabs(ref panels, _) => panels.contains(&panel),
+        DockNode::Split(_, _, ref a, ref b) => contains(a, panel) || contains(b, panel),
+    }
+}
+
+/// The first panel of the node's first tabs.
+fn first_panel(node: &DockNode) -> PanelId {
+    match *node {
+        DockNode::Tabs(ref panels, _) => panels[0],
+        DockNode::Split(_, _, ref a, _) => first_panel(a),
+    }
+}
+
+/// The node without the panel. Tabs left empty are removed, along with the
+/// splits left with a single node.
+fn remove(node: DockNode, panel: PanelId) -> Option<DockNode> {
+    match node {
+        DockNode::Tabs(mut panels, active) => match panels.iter().position(|&p| p == panel) {
+            Some(idx) => {
+                panels.remove(idx);
+                let active = if idx < active { active - 1 } else { active };
+                match panels.is_empty() {
+                    true => None,
+                    false => {
+                        let active = ::std::cmp::min(active, panels.len() - 1);
+                        Some(DockNode::Tabs(panels, active))
+                    },
+                }
+            },
+            None => Some(DockNode::Tabs(panels, active)),
+        },
+        DockNode::Split(axis, ratio, a, b) => match (remove(*a, panel), remove(*b, panel)) {
+            (Some(a), Some(b)) => Some(DockNode::Split(axis, ratio, Box::new(a), Box::new(b))),
+            (Some(node), None) | (None, Some(node)) => Some(node),
+            (None, None) => None,
+        },
+    }
+}
+
+/// The node with the panel docked in the given zone of the target panel.
+fn insert(node: DockNode, target: PanelId, zone: Zone, panel: PanelId) -> DockNode {
+    match node {
+        DockNode::Tabs(mut panels, active) => {
+            if !panels.contains(&target) {
+                return DockNode::Tabs(panels, active)
+            }
+            let new = DockNode::Tabs(vec![panel], 0);
+            match zone.split() {
+                None => {
+                    panels.push(panel);
+                    let active = panels.len() - 1;
+                    DockNode::Tabs(panels, active)
+                },
+                Some((axis, true)) =>
+                    DockNode::Split(axis, 0.5, Box::new(new), Box::new(DockNode::Tabs(panels, active))),
+                Some((axis, false)) =>
+                    DockNode::Split(axis, 0.5, Box::new(DockNode::Tabs(panels, active)), Box::new(new)),
+            }
+        },
+        DockNode::Split(axis, ratio, a, b) => DockNode::Split(axis, ratio,
+                                                              Box::new(insert(*a, target, zone, panel)),
+                                                              Box::new(insert(*b, target, zone, panel))),
+    }
+}
+
+/// Show the panel among the tabs that hold it.
+fn activate(node: &mut DockNode, panel: PanelId) {
+    match *node {
+        DockNode::Tabs(ref panels, ref mut active) => if let Some(idx) = panels.iter().position(|&p| p == panel) {
+            *active = idx;
+        },
+        DockNode::Split(_, _, ref mut a, ref mut b) => {
+            activate(a, panel);
+            activate(b, panel);
+        },
+    }
+}
+
+/// Set the ratio of the split at the given path of child indices.
+fn set_ratio(node: &mut DockNode, path: &[usize], new_ratio: f64) {
+    if let DockNode::Split(_, ref mut ratio, ref mut a, ref mut b) = *node {
+        match path.first() {
+            None => *ratio = new_ratio,
+            Some(&0) => set_ratio(a, &path[1..], new_ratio),
+            Some(_) => set_ratio(b, &path[1..], new_ratio),
+        }
+    }
+}
+
+/// A leaf of the docked layout, placed within the dock.
+struct Leaf {
+    panels: Vec<PanelId>,
+    active: usize,
+    pos: Point,
+    dim: Dimensions,
+}
+
+/// The divider of a split, placed within the dock.
+struct Divider {
+    /// The child indices leading to the split from the root.
+    path: Vec<usize>,
+    axis: Axis,
+    pos: Point,
+    dim: Dimensions,
+    /// The rect divided by the split.
+    split_pos: Point,
+    split_dim: Dimensions,
+}
+
+/// Place the leaves and dividers of the node within the given rect.
+fn lay_out(node: &DockNode, pos: Point, dim: Dimensions, path: &mut Vec<usize>,
+           leaves: &mut Vec<Leaf>, dividers: &mut Vec<Divider>) {
+    match *node {
+        DockNode::Tabs(ref panels, active) => leaves.push(Leaf {
+            panels: panels.clone(),
+            active: active,
+            pos: pos,
+            dim: dim,
+        }),
+        DockNode::Split(axis, ratio, ref a, ref b) => {
+            let (a_rect, divider_rect, b_rect) = match axis {
+                Axis::Horizontal => {
+                    let a_w = ((dim[0] - DIVIDER_WIDTH) * ratio).max(0.0);
+                    let b_x = pos[0] + a_w + DIVIDER_WIDTH;
+                    ((pos, [a_w, dim[1]]),
+                     ([pos[0] + a_w, pos[1]], [DIVIDER_WIDTH, dim[1]]),
+                     ([b_x, pos[1]], [(pos[0] + dim[0] - b_x).max(0.0), dim[1]]))
+                },
+                Axis::Vertical => {
+                    let a_h = ((dim[1] - DIVIDER_WIDTH) * ratio).max(0.0);
+                    let b_y = pos[1] + a_h + DIVIDER_WIDTH;
+                    ((pos, [dim[0], a_h]),
+                     ([pos[0], pos[1] + a_h], [dim[0], DIVIDER_WIDTH]),
+                     ([pos[0], b_y], [dim[0], (pos[1] + dim[1] - b_y).max(0.0)]))
+                },
+            };
+            dividers.push(Divider {
+                path: path.clone(),
+                axis: axis,
+                pos: divider_rect.0,
+                dim: divider_rect.1,
+                split_pos: pos,
+                split_dim: dim,
+            });
+            path.push(0);
+            lay_out(a, a_rect.0, a_rect.1, path, leaves, dividers);
+            path.pop();
+            path.push(1);
+            lay_out(b, b_rect.0, b_rect.1, path, leaves, dividers);
+            path.pop();
+        },
+    }
+}
+
+/// What is being dragged within the dock.
+#[derive(Clone, Debug, PartialEq)]
+enum Drag {
+    None,
+    /// A panel's tab or title is held but hasn't yet moved far enough to be
+    /// dragged. The grab is the offset of the mouse from the panel's top left.
+    Pending { panel: PanelId, from: Point, grab: Point, dim: Dimensions },
+    Panel { panel: PanelId, grab: Point, dim: Dimensions },
+    Divider { path: Vec<usize>, axis: Axis, split_pos: Point, split_dim: Dimensions },
+}
+
+/// The dock's state, kept between frames as its widget memory.
+struct Memory {
+    drag: Drag,
+    was_mouse_down: bool,
+}
+
+/// What happened to the Dock's layout, returned by `Dock::react`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The panel was dropped onto a dock target.
+    Docked(PanelId),
+    /// The panel was dragged out of the layout to float.
+    Undocked(PanelId),
+    /// The floating panel was moved.
+    Moved(PanelId),
+    /// The panel's tab was clicked, showing it.
+    Activated(PanelId),
+    /// A divider between panels was dragged.
+    Resized,
+    /// The layout was replaced by the one loaded via `Ui::load_state`.
+    Restored,
+}
+
+/// Draws the panels of a `DockLayout` and lets the user rearrange them.
+/// Each panel has a tab (or, when floating, a title bar) showing its title,
+/// by which it is dragged. A new `Dock` should be constructed and drawn each
+/// frame.
+pub struct Dock<'a> {
+    ui_id: UIID,
+    layout: &'a mut DockLayout,
+    titles: &'a [String],
+    pos: Point,
+    dim: Dimensions,
+    style: WidgetStyle,
+}
+
+impl<'a> Dock<'a> {
+
+    /// Create a dock context to be built upon, titling each panel with the
+    /// string at its `PanelId`.
+    pub fn new(ui_id: UIID, layout: &'a mut DockLayout, titles: &'a [String]) -> Dock<'a> {
+        Dock {
+            ui_id: ui_id,
+            layout: layout,
+            titles: titles,
+            pos: [0.0, 0.0],
+            dim: [640.0, 480.0],
+            style: WidgetStyle::new(),
+        }
+    }
+
+}
+
+impl<'a> Colorable for Dock<'a> {
+    fn color(mut self, color: Color) -> Self {
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a> Fadeable for Dock<'a> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
+        self
+    }
+}
+
+impl<'a> Positionable for Dock<'a> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<'a> Shapeable for Dock<'a> {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
+
+impl<'a> Dock<'a> {
+
+    /// The panel's title, or nothing if it wasn't given one.
+    fn title(&self, panel: PanelId) -> &str {
+        self.titles.get(panel).map_or("", |title| &title[..])
+    }
+
+    /// The dock target beneath the given position for the dragged panel,
+    /// along with the rect that the panel would take.
+    fn drop_target(&self, leaves: &[Leaf], pos: Point, panel: PanelId)
+        -> Option<(Option<PanelId>, Zone, Point, Dimensions)>
+    {
+        if !rectangle::is_over(self.pos, pos, self.dim) { return None }
+        let (dock_pos, dock_dim) = (self.pos, self.dim);
+        if self.layout.maybe_root.is_none() {
+            return Some((None, Zone::Center, dock_pos, dock_dim))
+        }
+        // The outer edges of the dock dock beside the whole layout.
+        let rel = [pos[0] - dock_pos[0], pos[1] - dock_pos[1]];
+        let maybe_edge = if rel[0] < OUTER_EDGE { Some(Zone::Left) }
+            else if rel[0] > dock_dim[0] - OUTER_EDGE { Some(Zone::Right) }
+            else if rel[1] < OUTER_EDGE { Some(Zone::Top) }
+            else if rel[1] > dock_dim[1] - OUTER_EDGE { Some(Zone::Bottom) }
+            else { None };
+        if let Some(zone) = maybe_edge {
+            let (preview_pos, preview_dim) = zone.preview(dock_pos, dock_dim);
+            return Some((None, zone, preview_pos, preview_dim))
+        }
+        // A panel can't be docked beside itself alone.
+        leaves.iter().find(|leaf| rectangle::is_over(leaf.pos, pos, leaf.dim)).and_then(|leaf| {
+            leaf.panels.iter().cloned().find(|&p| p != panel).map(|target| {
+                let t = [(pos[0] - leaf.pos[0]) / leaf.dim[0], (pos[1] - leaf.pos[1]) / leaf.dim[1]];
+                let is_central = t[0] > 1.0 / 3.0 && t[0] < 2.0 / 3.0 && t[1] > 1.0 / 3.0 && t[1] < 2.0 / 3.0;
+                let zone = match is_central {
+                    true => Zone::Center,
+                    false => {
+                        let edges = [(t[0], Zone::Left), (1.0 - t[0], Zone::Right),
+                                     (t[1], Zone::Top), (1.0 - t[1], Zone::Bottom)];
+                        edges.iter().fold(edges[0], |nearest, &edge| if edge.0 < nearest.0 { edge } else { nearest }).1
+                    },
+                };
+                let (preview_pos, preview_dim) = zone.preview(leaf.pos, leaf.dim);
+                (Some(target), zone, preview_pos, preview_dim)
+            })
+        })
+    }
+
+    /// Draw the dock, calling `draw_panel` with the rect of each visible
+    /// panel's content, and return what happened to the layout.
+    pub fn react<B, C, F>(&mut self, ui: &mut Ui<C>, graphics: &mut B, mut draw_panel: F) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            F: FnMut(&mut Ui<C>, &mut B, PanelId, Point, Dimensions)
+    {
+        let style = self.style.resolve(ui.theme.maybe_dock, &ui.theme);
+        ui.push_alpha(style.alpha);
+        // A layout loaded via `Ui::load_state` replaces the application's.
+        let is_restored = match ui.take_restored_dock_layout(self.ui_id) {
+            Some(layout) => {
+                *self.layout = layout;
+                true
+            },
+            None => false,
+        };
+        let mouse = ui.get_mouse_state();
+        let is_mouse_down = match mouse.left { ButtonState::Down => true, ButtonState::Up => false };
+        let mut memory = match ui.take_widget_memory::<Memory>(self.ui_id) {
+            Some(memory) => memory,
+            None => Memory { drag: Drag::None, was_mouse_down: false },
+        };
+        let padding = ui.theme.padding;
+        let font_size = ui.theme.font_size_small;
+        let tab_h = font_size as f64 + padding * 2.0;
+
+        let mut leaves = Vec::new();
+        let mut dividers = Vec::new();
+        if let Some(ref root) = self.layout.maybe_root {
+            lay_out(root, self.pos, self.dim, &mut Vec::new(), &mut leaves, &mut dividers);
+        }
+        // The tabs of each leaf, side by side along its top.
+        let mut tabs = Vec::new();
+        for leaf in leaves.iter() {
+            let mut x = leaf.pos[0];
+            for &panel in leaf.panels.iter() {
+                let w = ui.text_width(style.font, font_size, self.title(panel)) + padding * 2.0;
+                tabs.push((panel, [x, leaf.pos[1]], [w, tab_h]));
+                x += w;
+            }
+        }
+
+        // Pressing a title bar, tab or divider begins dragging it. A floating
+        // panel is raised above the others when pressed.
+        let mut maybe_event = None;
+        if is_mouse_down && !memory.was_mouse_down {
+            let over_float = self.layout.floating.iter().rposition(|f| {
+                rectangle::is_over([self.pos[0] + f.pos[0], self.pos[1] + f.pos[1]], mouse.pos, f.dim)
+            });
+            let over_tab = tabs.iter().find(|&&(_, pos, dim)| rectangle::is_over(pos, mouse.pos, dim));
+            let over_divider = dividers.iter().find(|d| rectangle::is_over(d.pos, mouse.pos, d.dim));
+            memory.drag = match (over_float, over_tab, over_divider) {
+                (Some(idx), _, _) => {
+                    let float = self.layout.floating.remove(idx);
+                    self.layout.floating.push(float);
+                    let float_pos = [self.pos[0] + float.pos[0], self.pos[1] + float.pos[1]];
+                    match mouse.pos[1] < float_pos[1] + tab_h {
+                        true => Drag::Pending {
+                            panel: float.panel,
+                            from: mouse.pos,
+                            grab: [mouse.pos[0] - float_pos[0], mouse.pos[1] - float_pos[1]],
+                            dim: float.dim,
+                        },
+                        false => Drag::None,
+                    }
+                },
+                (None, Some(&(panel, pos, _)), _) => {
+                    if let Some(ref mut root) = self.layout.maybe_root {
+                        activate(root, panel);
+                    }
+                    maybe_event = Some(Event::Activated(panel));
+                    let leaf_dim = leaves.iter().find(|leaf| leaf.panels.contains(&panel))
+                        .map_or(MAX_FLOATING_DIM, |leaf| leaf.dim);
+                    Drag::Pending {
+                        panel: panel,
+                        from: mouse.pos,
+                        grab: [mouse.pos[0] - pos[0], mouse.pos[1] - pos[1]],
+                        dim: [leaf_dim[0].min(MAX_FLOATING_DIM[0]), leaf_dim[1].min(MAX_FLOATING_DIM[1])],
+                    }
+                },
+                (None, None, Some(divider)) => Drag::Divider {
+                    path: divider.path.clone(),
+                    axis: divider.axis,
+                    split_pos: divider.split_pos,
+                    split_dim: divider.split_dim,
+                },
+                (None, None, None) => Drag::None,
+            };
+        }
+
+        // Move whatever is being dragged, and drop it once released.
+        let drag = ::std::mem::replace(&mut memory.drag, Drag::None);
+        memory.drag = match (drag, is_mouse_down) {
+            (Drag::Pending { panel, from, grab, dim }, true) => {
+                let (dx, dy) = (mouse.pos[0] - from[0], mouse.pos[1] - from[1]);
+                match (dx * dx + dy * dy).sqrt() > DRAG_THRESHOLD {
+                    true => Drag::Panel { panel: panel, grab: grab, dim: dim },
+                    false => Drag::Pending { panel: panel, from: from, grab: grab, dim: dim },
+                }
+            },
+            (Drag::Panel { panel, grab, dim }, true) => {
+                let new_pos = [mouse.pos[0] - grab[0] - self.pos[0], mouse.pos[1] - grab[1] - self.pos[1]];
+                if let Some(float) = self.layout.floating.iter_mut().find(|f| f.panel == panel) {
+                    if float.pos != new_pos {
+                        float.pos = new_pos;
+                        maybe_event = Some(Event::Moved(panel));
+                    }
+                }
+                ui.set_mouse_cursor(MouseCursor::Move);
+                ui.mark_damaged(self.pos, self.dim);
+                Drag::Panel { panel: panel, grab: grab, dim: dim }
+            },
+            (Drag::Panel { panel, grab, dim }, false) => {
+                let is_floating = self.layout.floating.iter().any(|f| f.panel == panel);
+                match self.drop_target(&leaves, mouse.pos, panel) {
+                    Some((maybe_target, zone, _, _)) => {
+                        self.layout.dock(panel, maybe_target, zone);
+                        maybe_event = Some(Event::Docked(panel));
+                    },
+                    None if !is_floating => {
+                        let pos = [mouse.pos[0] - grab[0] - self.pos[0], mouse.pos[1] - grab[1] - self.pos[1]];
+                        self.layout.float(panel, pos, dim);
+                        maybe_event = Some(Event::Undocked(panel));
+                    },
+                    None => (),
+                }
+                ui.mark_damaged(self.pos, self.dim);
+                Drag::None
+            },
+            (Drag::Divider { path, axis, split_pos, split_dim }, true) => {
+                // Neither side of a split may be dragged closed.
+                let ratio = match axis {
+                    Axis::Horizontal => (mouse.pos[0] - split_pos[0]) / split_dim[0],
+                    Axis::Vertical => (mouse.pos[1] - split_pos[1]) / split_dim[1],
+                };
+                if let Some(ref mut root) = self.layout.maybe_root {
+                    set_ratio(root, &path, ratio.max(0.1).min(0.9));
+                }
+                if ui.mouse_moved() {
+                    maybe_event = Some(Event::Resized);
+                }
+                Drag::Divider { path: path, axis: axis, split_pos: split_pos, split_dim: split_dim }
+            },
+            (_, _) => Drag::None,
+        };
+        memory.was_mouse_down = is_mouse_down;
+
+        // The layout may have changed, so it is placed again for drawing.
+        let mut leaves = Vec::new();
+        let mut dividers = Vec::new();
+        if let Some(ref root) = self.layout.maybe_root {
+            lay_out(root, self.pos, self.dim, &mut Vec::new(), &mut leaves, &mut dividers);
+        }
+        let over_divider = dividers.iter().find(|d| rectangle::is_over(d.pos, mouse.pos, d.dim)).map(|d| d.axis);
+        let resizing_axis = match memory.drag {
+            Drag::Divider { axis, .. } => Some(axis),
+            _ => over_divider,
+        };
+        match resizing_axis {
+            Some(Axis::Horizontal) => ui.set_mouse_cursor(MouseCursor::ResizeHorizontal),
+            Some(Axis::Vertical) => ui.set_mouse_cursor(MouseCursor::ResizeVertical),
+            None => (),
+        }
+
+        // Draw each leaf's tabs above the content of its shown panel.
+        let tab_color = rectangle::State::Highlighted.color(style.color);
+        let active_tab_color = style.color;
+        for leaf in leaves.iter() {
+            ui.draw_rectangle(graphics, leaf.pos, [leaf.dim[0], tab_h], tab_color);
+            ui.push_clip(leaf.pos, [leaf.dim[0], tab_h]);
+            let mut x = leaf.pos[0];
+            for (i, &panel) in leaf.panels.iter().enumerate() {
+                let w = ui.text_width(style.font, font_size, self.title(panel)) + padding * 2.0;
+                if i == leaf.active {
+                    ui.draw_rectangle(graphics, [x, leaf.pos[1]], [w, tab_h], active_tab_color);
+                }
+                ui.draw_text(graphics, [x + padding, leaf.pos[1] + padding], style.font, font_size,
+                             style.label_color, self.title(panel));
+                x += w;
+            }
+            ui.pop_clip();
+            let content_pos = [leaf.pos[0], leaf.pos[1] + tab_h];
+            let content_dim = [leaf.dim[0], (leaf.dim[1] - tab_h).max(0.0)];
+            ui.draw_rectangle(graphics, content_pos, content_dim, style.color);
+            if let Some(&panel) = leaf.panels.get(leaf.active) {
+                ui.push_clip(content_pos, content_dim);
+                draw_panel(ui, graphics, panel, content_pos, content_dim);
+                ui.pop_clip();
+            }
+        }
+        for divider in dividers.iter() {
+            ui.draw_rectangle(graphics, divider.pos, divider.dim, style.frame_color);
+        }
+
+        // Draw the floating panels above, each beneath its title bar.
+        let floating = self.layout.floating.clone();
+        for float in floating.iter() {
+            let pos = [self.pos[0] + float.pos[0], self.pos[1] + float.pos[1]];
+            rectangle::draw(ui, graphics, rectangle::State::Normal, pos, float.dim,
+                            Some((style.frame, style.frame_color)), style.corner_radius, style.color);
+            ui.draw_rectangle(graphics, pos, [float.dim[0], tab_h], tab_color);
+            ui.push_clip(pos, [float.dim[0], tab_h]);
+            ui.draw_text(graphics, [pos[0] + padding, pos[1] + padding], style.font, font_size,
+                         style.label_color, self.title(float.panel));
+            ui.pop_clip();
+            let content_pos = [pos[0], pos[1] + tab_h];
+            let content_dim = [float.dim[0], (float.dim[1] - tab_h).max(0.0)];
+            ui.push_clip(content_pos, content_dim);
+            draw_panel(ui, graphics, float.panel, content_pos, content_dim);
+            ui.pop_clip();
+        }
+
+        // While a docked panel is dragged, a ghost of it follows the mouse,
+        // and the space it would take if dropped is previewed.
+        if let Drag::Panel { panel, grab, dim } = memory.drag {
+            let highlight = style.color.plain_contrast();
+            if let Some((_, _, preview_pos, preview_dim)) = self.drop_target(&leaves, mouse.pos, panel) {
+                ui.draw_rectangle(graphics, preview_pos, preview_dim, highlight.fade(0.2));
+            }
+            if !floating.iter().any(|f| f.panel == panel) {
+                let pos = [mouse.pos[0] - grab[0], mouse.pos[1] - grab[1]];
+                ui.draw_rectangle(graphics, pos, dim, highlight.fade(0.1));
+                ui.draw_rectangle(graphics, pos, [dim[0], tab_h], tab_color.fade(0.6));
+                ui.draw_text(graphics, [pos[0] + padding, pos[1] + padding], style.font, font_size,
+                             style.label_color, self.title(panel));
+            }
+        }
+
+        ui.set_widget_memory(self.ui_id, memory);
+        ui.pop_alpha();
+        if maybe_event.is_some() {
+            ui.persist_dock_layout(self.ui_id, self.layout);
+        }
+        match (maybe_event, is_restored) {
+            (None, true) => Some(Event::Restored),
+            _ => maybe_event,
+        }
+    }
+
+}
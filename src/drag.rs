@@ -0,0 +1,63 @@
+//! Dragging payloads from one widget to another.
+//!
+//! A widget begins a drag with `Ui::start_drag`, giving a payload and a
+//! preview that is drawn beneath the cursor until the mouse is released.
+//! Widgets that accept payloads call `Ui::drop_target` each frame, which
+//! tells them whether a payload they accept is being dragged over them (so
+//! that they may highlight themselves) and delivers the payload when it is
+//! dropped on them.
+
+use color::Color;
+use dimensions::Dimensions;
+use image::ImageId;
+use std::any::Any;
+use ui::UIID;
+
+/// What is drawn beneath the cursor while dragging.
+#[derive(Clone, Debug)]
+pub enum Preview {
+    /// Nothing is drawn (i.e. the source widget draws its own preview).
+    Nothing,
+    /// A rectangle of the given dimensions and color.
+    Rectangle(Dimensions, Color),
+    /// The given text, in the theme's label color.
+    Text(String),
+    /// The image with the given `ImageId` at the given dimensions.
+    Image(ImageId, Dimensions),
+}
+
+/// A drag in progress.
+pub struct Drag {
+    source: UIID,
+    payload: Box<Any>,
+    preview: Preview,
+}
+
+impl Drag {
+
+    /// Begin dragging the given payload from the widget with the given UIID.
+    pub fn new<T: Any>(source: UIID, payload: T, preview: Preview) -> Drag {
+        Drag { source: source, payload: Box::new(payload), preview: preview }
+    }
+
+    /// The UIID of the widget from which the payload is being dragged.
+    pub fn source(&self) -> UIID {
+        self.source
+    }
+
+    /// What is drawn beneath the cursor.
+    pub fn preview(&self) -> &Preview {
+        &self.preview
+    }
+
+    /// The payload, if it is of type `T`.
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref::<T>()
+    }
+
+    /// Consume the drag, returning its payload.
+    pub fn into_payload(self) -> Box<Any> {
+        self.payload
+    }
+
+}
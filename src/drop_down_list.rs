@@ -1,15 +1,16 @@
-use callback::Callable;
-use frame::Frameable;
-use label::{ FontSize, Labelable };
-use color::{ Color, Colorable };
+use callback::{ Callable, NoCallback, Reactable };
+use label::{ FontId, FontSize, Labelable };
+use color::Color;
 use dimensions::Dimensions;
+use history::Value;
 use mouse::Mouse;
+use piston::input::keyboard::Key;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use theme::WidgetStyle;
 use rectangle;
+use scroll::Kinetic;
 use ui::{ UIID, Ui };
-use vecmath::vec2_add;
+use utils::clamp;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
 use widget::Widget;
@@ -18,15 +19,22 @@ use widget::Widget;
 pub type Idx = usize;
 pub type Len = usize;
 
+/// How far an open list is scrolled from its first item, and how fast it
+/// is still scrolling after a flick of the mouse wheel.
+pub type Scroll = Kinetic;
+
+/// The width of the scrollbar drawn beside a list that doesn't fit.
+const SCROLLBAR_WIDTH: f64 = 6.0;
+
 /// Represents the state of the menu.
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum State {
     Closed(DrawState),
-    Open(DrawState),
+    Open(DrawState, Scroll),
 }
 
 /// Represents the state of the DropDownList widget.
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum DrawState {
     Normal,
     Highlighted(Idx, Len),
@@ -48,19 +56,62 @@ impl State {
     /// Translate the DropDownList's State to the equivalent rectangle::State.
     fn as_rect_state(&self) -> rectangle::State {
         match self {
-            &State::Open(draw_state) | &State::Closed(draw_state) => draw_state.as_rect_state(),
+            &State::Open(draw_state, _) | &State::Closed(draw_state) => draw_state.as_rect_state(),
         }
     }
 }
 
 widget_fns!(DropDownList, State, Widget::DropDownList(State::Closed(DrawState::Normal)));
 
-/// Is the cursor currently over the widget? If so which item?
+/// What happened to the DropDownList, returned by `DropDownList::react` and
+/// given to its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The list was opened.
+    Opened,
+    /// The list was closed without an item being selected.
+    Closed,
+    /// The item at the given index was clicked, closing the list.
+    Selected(Idx),
+}
+
+/// The height of the whole list, in which neighbouring items share a frame.
+fn list_height(item_h: f64, frame_w: f64, len: Len) -> f64 {
+    let len = len as f64;
+    item_h * len - (len - 1.0) * frame_w
+}
+
+/// The rectangle occupied by the open list. The list opens downward from
+/// the drop_down_list unless it doesn't fit and there is more room above,
+/// in which case it opens upward. Either way it is cut off at the edge of
+/// the window and must be scrolled to reach the remaining items.
+fn list_rect(pos: Point, dim: Dimensions, frame_w: f64, len: Len, win_h: f64) -> (Point, Dimensions) {
+    let total_h = list_height(dim[1], frame_w, len);
+    let below_h = (win_h - pos[1]).max(dim[1]);
+    let above_h = pos[1] + dim[1];
+    if total_h <= below_h || below_h >= above_h {
+        (pos, [dim[0], total_h.min(below_h)])
+    } else {
+        let h = total_h.min(above_h);
+        ([pos[0], above_h - h], [dim[0], h])
+    }
+}
+
+/// The indices of the items containing the filter text, ignoring case.
+fn filter_items(strings: &[String], filter: &str) -> Vec<Idx> {
+    let lowercase = |s: &str| s.chars().flat_map(|ch| ch.to_lowercase()).collect::<String>();
+    let filter = lowercase(filter);
+    (0..strings.len()).filter(|&i| lowercase(&strings[i]).contains(&filter[..])).collect()
+}
+
+/// Is the cursor currently over the widget? If so which row of the list?
 fn is_over(pos: Point,
            mouse_pos: Point,
            dim: Dimensions,
+           frame_w: f64,
            state: State,
-           len: Len) -> Option<Idx> {
+           len: Len,
+           win_h: f64) -> Option<Idx> {
     match state {
         State::Closed(_) => {
             match rectangle::is_over(pos, mouse_pos, dim) {
@@ -68,11 +119,16 @@ fn is_over(pos: Point,
                 true => Some(0),
             }
         },
-        State::Open(_) => {
-            let total_h = dim[1] * len as f64;
-            match rectangle::is_over(pos, mouse_pos, [dim[0], total_h]) {
+        State::Open(_, scroll) => {
+            if len == 0 { return None }
+            let (list_pos, list_dim) = list_rect(pos, dim, frame_w, len, win_h);
+            match rectangle::is_over(list_pos, mouse_pos, list_dim) {
                 false => None,
-                true => Some((((mouse_pos[1] - pos[1]) / total_h) * len as f64) as usize),
+                true => {
+                    let y = (mouse_pos[1] - list_pos[1] + scroll.offset).max(0.0);
+                    let idx = (y / (dim[1] - frame_w)) as usize;
+                    Some(::std::cmp::min(idx, len - 1))
+                },
             }
         },
     }
@@ -83,6 +139,7 @@ fn is_over(pos: Point,
 fn get_new_state(is_over_idx: Option<Idx>,
                  len: Len,
                  state: State,
+                 scroll: Scroll,
                  mouse: Mouse) -> State {
     use self::DrawState::{Normal, Clicked, Highlighted};
     use mouse::ButtonState::{Down, Up};
@@ -96,27 +153,27 @@ fn get_new_state(is_over_idx: Option<Idx>,
                         (Highlighted(_, _), Up)   => State::Closed(Highlighted(0, len)),
                         (Highlighted(_, _), Down) => State::Closed(Clicked(0, len)),
                         (Clicked(_, _),     Down) => State::Closed(Clicked(0, len)),
-                        (Clicked(_, _),     Up)   => State::Open(Normal),
+                        (Clicked(_, _),     Up)   => State::Open(Normal, scroll),
                     }
                 },
                 None => State::Closed(Normal),
             }
         },
-        State::Open(draw_state) => {
+        State::Open(draw_state, _) => {
             match is_over_idx {
                 Some(idx) => {
                     match (draw_state, mouse.left) {
-                        (Normal,            Down) => State::Open(Normal),
+                        (Normal,            Down) => State::Open(Normal, scroll),
                         (Normal,            Up)   |
-                        (Highlighted(_, _), Up)   => State::Open(Highlighted(idx, len)),
-                        (Highlighted(_, _), Down) => State::Open(Clicked(idx, len)),
-                        (Clicked(p_idx, _), Down) => State::Open(Clicked(p_idx, len)),
+                        (Highlighted(_, _), Up)   => State::Open(Highlighted(idx, len), scroll),
+                        (Highlighted(_, _), Down) => State::Open(Clicked(idx, len), scroll),
+                        (Clicked(p_idx, _), Down) => State::Open(Clicked(p_idx, len), scroll),
                         (Clicked(_, _),     Up)   => State::Closed(Normal),
                     }
                 },
                 None => {
                     match (draw_state, mouse.left) {
-                        (Highlighted(p_idx, _), Up) => State::Open(Highlighted(p_idx, len)),
+                        (Highlighted(p_idx, _), Up) => State::Open(Highlighted(p_idx, len), scroll),
                         _ => State::Closed(Normal),
                     }
                 },
@@ -133,12 +190,9 @@ pub struct DropDownList<'a, F> {
     pos: Point,
     dim: Dimensions,
     maybe_callback: Option<F>,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
 }
 
 impl<'a, F> DropDownList<'a, F> {
@@ -152,37 +206,25 @@ impl<'a, F> DropDownList<'a, F> {
             pos: [0.0, 0.0],
             dim: [128.0, 32.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
         }
     }
 }
 
-impl<'a, F> Colorable for DropDownList<'a, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
-    }
-}
+composite_builder_impls!(DropDownList<'a, F>);
 
-impl<'a, F> Frameable for DropDownList<'a, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
-        self
-    }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
+impl<'a, F> Callable<F> for DropDownList<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
         self
     }
 }
 
-impl<'a, F> Callable<F> for DropDownList<'a, F> {
-    fn callback(mut self, cb: F) -> Self {
-        self.maybe_callback = Some(cb);
+impl<'a, F> Reactable<'a, Event> for DropDownList<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
@@ -195,77 +237,261 @@ impl<'a, F> Labelable<'a> for DropDownList<'a, F>
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
         self
     }
-}
 
-impl<'a, F> Positionable for DropDownList<'a, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, F> Shapeable for DropDownList<'a, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
     where
         F: FnMut(&mut Option<Idx>, Idx, String) + 'a
 {
-
     fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if let Some(Event::Selected(idx)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback {
+                (*callback)(self.selected, idx, (*self.strings)[idx].clone())
+            }
+        }
+    }
+}
+
+impl<'a> DropDownList<'a, NoCallback> {
+
+    /// Draw the drop_down_list, returning what happened to it. An alternative
+    /// to `Callable::callback` for when the reaction to the selection needs to
+    /// borrow application state. The selection is not changed by the list
+    /// itself.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
 
+impl<'a, F> DropDownList<'a, F> {
+
+    /// Update and draw the drop_down_list, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_drop_down_list, &ui.theme);
+        ui.push_alpha(style.alpha);
         let state = *get_state(ui, self.ui_id);
         let mouse = ui.get_mouse_state();
-        let is_over_idx = is_over(self.pos, mouse.pos, self.dim, state, self.strings.len());
-        let new_state = get_new_state(is_over_idx, self.strings.len(), state, mouse);
+
+        // Text typed while the list is open filters its items. The filter is
+        // kept as the drop_down_list's widget memory until the list is closed.
+        // Input already handled by another widget is left be.
+        let is_input_free = ui.text_input_consumer().map_or(true, |ui_id| ui_id == self.ui_id);
+        let filter = match state {
+            State::Open(_, _) => {
+                let mut filter = ui.widget_memory::<String>(self.ui_id).cloned().unwrap_or_else(String::new);
+                if is_input_free {
+                    for text in ui.entered_text().iter() {
+                        filter.extend(text.chars().filter(|ch| !ch.is_control()));
+                    }
+                    if ui.keys_just_pressed.contains(&Key::Backspace) {
+                        filter.pop();
+                    }
+                    ui.consume_text_input(self.ui_id);
+                }
+                filter
+            },
+            State::Closed(_) => String::new(),
+        };
+        let visible = filter_items(&self.strings[..], &filter);
+        let len = visible.len();
+        let frame_w = style.frame;
+        let (list_pos, list_dim) = list_rect(self.pos, self.dim, frame_w, len, ui.win_h);
+        let item_step = self.dim[1] - frame_w;
+        let max_scroll = (list_height(self.dim[1], frame_w, len) - list_dim[1]).max(0.0);
 
         let sel = match *self.selected {
             Some(idx) if idx < self.strings.len() => { Some(idx) },
             _ => None,
         };
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
-        let t_size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
-        let t_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
-
-        // Call the `callback` closure if mouse was released
-        // on one of the DropDownMenu items.
-        match (state, new_state) {
-            (State::Open(o_d_state), State::Closed(c_d_state)) => {
+
+        let time = ui.time();
+        let scroll = match state {
+            // The mouse wheel scrolls the open list while the cursor is over it.
+            // Once the wheel stops, a flicked list coasts to rest. Scrolling
+            // past either end stretches the list, which then springs back.
+            State::Open(_, scroll) => {
+                let limit = ui.theme.overscroll_limit;
+                let scroll = match rectangle::is_over(list_pos, mouse.pos, list_dim) {
+                    true if mouse.scroll[1] != 0.0 =>
+                        scroll.stretch_by(-mouse.scroll[1] * item_step, time, 0.0, max_scroll, limit),
+                    _ => scroll.coast_within(time, ui.theme.scroll_friction, 0.0, max_scroll, limit),
+                };
+                if ui.theme.kinetic_scrolling { scroll } else { scroll.stop() }
+            },
+            // The list opens scrolled as it was saved via `Ui::save_state`,
+            // otherwise so that the selected item is centered.
+            State::Closed(_) => match (ui.restored_scroll(self.ui_id), sel) {
+                (Some(offset), _) => Kinetic::new(clamp(offset, 0.0, max_scroll), time),
+                (None, Some(idx)) => {
+                    let scroll = idx as f64 * item_step + (self.dim[1] - list_dim[1]) / 2.0;
+                    Kinetic::new(clamp(scroll, 0.0, max_scroll), time)
+                },
+                (None, None) => Kinetic::new(0.0, time),
+            },
+        };
+        let scrolled_state = match state {
+            State::Open(draw_state, _) => State::Open(draw_state, scroll),
+            State::Closed(draw_state) => State::Closed(draw_state),
+        };
+        let is_over_idx = is_over(self.pos, mouse.pos, self.dim, frame_w, scrolled_state, len, ui.win_h)
+            .and_then(|row| visible.get(row).cloned());
+        let mut new_state = get_new_state(is_over_idx, self.strings.len(), state, scroll, mouse);
+
+        // Up and Down move the highlight through the filtered items, which
+        // remains where the keyboard left it until the mouse is moved.
+        let mut maybe_key_selected = None;
+        let mut key_moved = false;
+        if let (State::Open(prev_draw_state, _), State::Open(draw_state, _)) = (state, new_state) {
+            let draw_state = match (prev_draw_state, draw_state) {
+                (DrawState::Highlighted(_, _), DrawState::Highlighted(_, _)) if !ui.mouse_moved() =>
+                    prev_draw_state,
+                _ => draw_state,
+            };
+            let mut maybe_row = match draw_state {
+                DrawState::Highlighted(idx, _) => visible.iter().position(|&i| i == idx),
+                _ => None,
+            };
+            let pressed_keys: &[Key] = if is_input_free { ui.pressed_keys() } else { &[] };
+            for key in pressed_keys.iter() {
+                match *key {
+                    Key::Up if len > 0 => {
+                        key_moved = true;
+                        maybe_row = match maybe_row {
+                            Some(row) if row > 0 => Some(row - 1),
+                            Some(_) => Some(0),
+                            None => Some(len - 1),
+                        };
+                    },
+                    Key::Down if len > 0 => {
+                        key_moved = true;
+                        maybe_row = match maybe_row {
+                            Some(row) => Some(::std::cmp::min(row + 1, len - 1)),
+                            None => Some(0),
+                        };
+                    },
+                    Key::Return => maybe_key_selected = maybe_row.map(|row| visible[row]),
+                    _ => (),
+                }
+            }
+            new_state = match (maybe_key_selected, maybe_row) {
+                (Some(_), _) => State::Closed(DrawState::Normal),
+                (None, Some(row)) => {
+                    // Scroll just far enough to reveal an item highlighted via
+                    // the keyboard, bringing a coasting list to rest.
+                    let scroll = match key_moved {
+                        true => {
+                            let row_y = row as f64 * item_step;
+                            let offset = clamp(scroll.offset, row_y + self.dim[1] - list_dim[1], row_y);
+                            scroll.scroll_to(clamp(offset, 0.0, max_scroll))
+                        },
+                        false => scroll,
+                    };
+                    let draw_state = DrawState::Highlighted(visible[row], self.strings.len());
+                    State::Open(draw_state, scroll)
+                },
+                (None, None) => State::Open(draw_state, scroll),
+            };
+        }
+
+        match new_state {
+            State::Open(_, scroll) => {
+                ui.claim_text_focus(self.ui_id);
+                ui.set_widget_memory(self.ui_id, filter);
+                match state {
+                    State::Closed(_) => { ui.take_restored_scroll(self.ui_id); },
+                    State::Open(_, prev_scroll) => if scroll.offset != prev_scroll.offset {
+                        ui.persist_scroll(self.ui_id, scroll.offset);
+                    },
+                }
+            },
+            State::Closed(_) => if let State::Open(_, _) = state {
+                ui.take_widget_memory::<String>(self.ui_id);
+            },
+        }
+
+        let color = style.color;
+        let t_size = style.label_font_size;
+        let t_color = style.label_color;
+
+        // An item is selected if the mouse was released on it or Enter was pressed.
+        let maybe_event = match (state, new_state) {
+            _ if maybe_key_selected.is_some() => maybe_key_selected.map(Event::Selected),
+            (State::Closed(_), State::Open(_, _)) => Some(Event::Opened),
+            (State::Open(o_d_state, _), State::Closed(c_d_state)) => {
                 match (o_d_state, c_d_state) {
-                    (DrawState::Clicked(idx, _), DrawState::Normal) => {
-                        match self.maybe_callback {
-                            Some(ref mut callback) => (*callback)(self.selected, idx, (*self.strings)[idx].clone()),
-                            None => (),
-                        }
-                    }, _ => (),
+                    (DrawState::Clicked(idx, _), DrawState::Normal) => Some(Event::Selected(idx)),
+                    _ => Some(Event::Closed),
                 }
-            }, _ => (),
+            }, _ => None,
+        };
+        // Changing the selection is recorded in the history. As the list
+        // can't be cleared by selecting, a first selection is only saved.
+        if let Some(Event::Selected(idx)) = maybe_event {
+            let after = Value::Number(idx as f64);
+            match *self.selected {
+                Some(prev_idx) if prev_idx != idx =>
+                    ui.record_change(self.ui_id, Value::Number(prev_idx as f64), after),
+                Some(_) => (),
+                None => ui.persist_value(self.ui_id, after),
+            }
         }
+        // A selection that was undone, redone or loaded via `Ui::load_state`
+        // is re-fired as though selected.
+        let maybe_event = match ui.take_restored(self.ui_id) {
+            Some(Value::Number(idx)) if idx >= 0.0 && (idx as Idx) < self.strings.len() =>
+                Some(Event::Selected(idx as Idx)),
+            _ => maybe_event,
+        };
 
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
+            true => Some((frame_w, style.frame_color)),
             false => None,
         };
 
+        // The area covered by the drop_down_list, which is the list when it is open.
+        let (rect_pos, rect_dim) = match new_state {
+            State::Open(_, _) => (list_pos, list_dim),
+            State::Closed(_) => (self.pos, self.dim),
+        };
+
+        // The shadow falls beneath the whole list when it is open.
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, rect_pos, rect_dim, style.corner_radius, shadow);
+        }
+
         match new_state {
 
             State::Closed(_) => {
-                let rect_state = new_state.as_rect_state();
+                let color = ui.transition_color(self.ui_id, new_state.as_rect_state().color(color));
+                let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image,
+                                           new_state.as_rect_state());
+                let rect_state = rectangle::State::Normal;
                 let text = match sel {
                     Some(idx) => &(*self.strings)[idx][..],
                     None => match self.maybe_label {
@@ -274,14 +500,24 @@ impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
                     },
                 };
                 rectangle::draw_with_centered_label(
-                    ui.win_w, ui.win_h, graphics, ui, rect_state,
-                    self.pos, self.dim, maybe_frame, color,
-                    text, t_size, t_color
+                    ui, graphics, rect_state,
+                    self.pos, self.dim, maybe_frame, style.corner_radius, fill,
+                    text, style.font, t_size, t_color
                 )
             },
 
-            State::Open(draw_state) => {
-                for (i, string) in self.strings.iter().enumerate() {
+            State::Open(draw_state, scroll) => {
+                // Each item's state color is applied when it is drawn.
+                let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image,
+                                           rectangle::State::Normal);
+                ui.push_clip(list_pos, list_dim);
+                for (row, &i) in visible.iter().enumerate() {
+                    let string = &self.strings[i];
+                    let idx_y = list_pos[1] + item_step * row as f64 - scroll.offset;
+                    // Items scrolled out of the list needn't be drawn.
+                    if idx_y + self.dim[1] < list_pos[1] || idx_y > list_pos[1] + list_dim[1] {
+                        continue
+                    }
                     let rect_state = match sel {
                         None => {
                             match draw_state {
@@ -313,19 +549,42 @@ impl<'a, F> ::draw::Drawable for DropDownList<'a, F>
                             }
                         },
                     };
-                    let idx_y = self.dim[1] * i as f64 - i as f64 * frame_w;
-                    let idx_pos = vec2_add(self.pos, [0.0, idx_y]);
+                    let idx_pos = [list_pos[0], idx_y];
                     rectangle::draw_with_centered_label(
-                        ui.win_w, ui.win_h, graphics, ui, rect_state, idx_pos,
-                        self.dim, maybe_frame, color, &string,
-                        t_size, t_color
+                        ui, graphics, rect_state, idx_pos,
+                        self.dim, maybe_frame, style.corner_radius, fill, string,
+                        style.font, t_size, t_color
                     )
                 }
+                // A list stretched past either end is tinted in the gap left
+                // at that end until it springs back.
+                let over = scroll.overscroll(0.0, max_scroll);
+                if over != 0.0 {
+                    let limit = ui.theme.overscroll_limit;
+                    let tint = style.frame_color.fade((over.abs() / limit).min(1.0) as f32 * 0.5);
+                    let gap_y = if over < 0.0 { list_pos[1] } else { list_pos[1] + list_dim[1] - over };
+                    ui.draw_rectangle(graphics, [list_pos[0], gap_y], [list_dim[0], over.abs()], tint);
+                }
+                // The scrollbar shows which part of a list that doesn't fit is visible.
+                if max_scroll > 0.0 {
+                    let bar_h = (list_dim[1] * list_dim[1] / (list_dim[1] + max_scroll))
+                        .max(SCROLLBAR_WIDTH * 2.0);
+                    let bar_y = list_pos[1] + (list_dim[1] - bar_h) * clamp(scroll.offset / max_scroll, 0.0, 1.0);
+                    let bar_x = list_pos[0] + list_dim[0] - frame_w - SCROLLBAR_WIDTH;
+                    ui.draw_rectangle(graphics, [bar_x, bar_y], [SCROLLBAR_WIDTH, bar_h], style.frame_color);
+                }
+                ui.pop_clip();
             },
 
         }
 
-        set_state(ui, self.ui_id, Widget::DropDownList(new_state), self.pos, self.dim);
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::DropDownList(new_state), rect_pos, rect_dim);
 
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
@@ -1,20 +1,18 @@
 use std::cmp::Ordering;
 use num::{ Float, ToPrimitive, FromPrimitive };
-use callback::Callable;
-use frame::Frameable;
-use label::{ FontSize, Labelable };
-use color::{ Color, Colorable };
+use callback::{ Callable, NoCallback, Reactable };
+use label::{ FontId, FontSize, Labelable };
+use color::Color;
 use dimensions::Dimensions;
-use graphics;
 use graphics::{
     Graphics,
 };
 use graphics::character::CharacterCache;
 use label;
 use mouse::Mouse;
+use oval;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use theme::WidgetStyle;
 use rectangle;
 use rectangle::{
     Corner
@@ -121,7 +119,7 @@ fn is_over_and_closest(pos: Point,
                     let distance = (mouse_pos[0] - p_pos[0]).powf(2.0)
                                  + (mouse_pos[1] - p_pos[1]).powf(2.0);
                     //let distance = ::std::num::abs(mouse_pos.x - p_pos.x);
-                    if distance <= pt_radius.powf(2.0) {
+                    if oval::is_over_circle(p_pos, mouse_pos, pt_radius) {
                         return (Some(Element::EnvPoint(i, (p_pos[0], p_pos[1]))),
                                 Some(Element::EnvPoint(i, (p_pos[0], p_pos[1]))))
                     }
@@ -175,22 +173,18 @@ fn get_new_state(is_over_elem: Option<Element>,
     }
 }
 
-/// Draw a circle at the given position.
-fn draw_circle<B: Graphics>(
-    win_w: f64,
-    win_h: f64,
-    graphics: &mut B,
-    pos: Point,
-    color: Color,
-    radius: f64
-) {
-    graphics::Ellipse::new(color.0)
-        .draw(
-            [pos[0], pos[1], 2.0 * radius, 2.0 * radius],
-            &graphics::default_draw_state(),
-            graphics::abs_transform(win_w, win_h),
-            graphics
-        );
+/// What happened to the EnvelopeEditor, returned by `EnvelopeEditor::react`
+/// and given to its `on_event` closure along with the index of the point.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// A point was added to the envelope.
+    Added(usize),
+    /// The point is being dragged.
+    Dragging(usize),
+    /// The point was released after being dragged.
+    Released(usize),
+    /// The point was removed from the envelope.
+    Removed(usize),
 }
 
 /// A context on which the builder pattern can be implemented.
@@ -206,12 +200,9 @@ pub struct EnvelopeEditor<'a, E:'a, F> where E: EnvelopePoint {
     pos: Point,
     dim: Dimensions,
     maybe_callback: Option<F>,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
 }
 
 impl<'a, E, F> EnvelopeEditor<'a, E, F> where E: EnvelopePoint {
@@ -253,46 +244,31 @@ impl <'a, E, F> EnvelopeEditor<'a, E, F> where E: EnvelopePoint {
             pos: [0.0, 0.0],
             dim: [256.0, 128.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
         }
     }
 }
 
-impl<'a, E, F> Colorable for EnvelopeEditor<'a, E, F>
-    where
-        E: EnvelopePoint
-{
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
-    }
-}
+composite_builder_impls!(EnvelopeEditor<'a, E, F> where E: EnvelopePoint);
 
-impl<'a, E, F> Frameable for EnvelopeEditor<'a, E, F>
+impl<'a, E, F> Callable<F> for EnvelopeEditor<'a, E, F>
     where
         E: EnvelopePoint
 {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
-        self
-    }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
         self
     }
 }
 
-impl<'a, E, F> Callable<F> for EnvelopeEditor<'a, E, F>
+impl<'a, E, F> Reactable<'a, Event> for EnvelopeEditor<'a, E, F>
     where
         E: EnvelopePoint
 {
-    fn callback(mut self, cb: F) -> Self {
-        self.maybe_callback = Some(cb);
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
@@ -307,47 +283,78 @@ impl<'a, E, F> Labelable<'a> for EnvelopeEditor<'a, E, F>
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, E, F> Positionable for EnvelopeEditor<'a, E, F>
+impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
     where
-        E: EnvelopePoint
+        E: EnvelopePoint,
+        <E as EnvelopePoint>::X: Float,
+        <E as EnvelopePoint>::Y: Float,
+        F: FnMut(&mut Vec<E>, usize) + 'a
 {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
-        self
+    #[inline]
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        match self.update(ui, graphics) {
+            Some(Event::Dragging(idx)) | Some(Event::Released(idx)) | Some(Event::Removed(idx)) => {
+                if let Some(ref mut callback) = self.maybe_callback { callback(self.env, idx) }
+            },
+            _ => (),
+        }
     }
 }
 
-impl<'a, E, F> Shapeable for EnvelopeEditor<'a, E, F>
+impl<'a, E> EnvelopeEditor<'a, E, NoCallback>
     where
-        E: EnvelopePoint
+        E: EnvelopePoint,
+        <E as EnvelopePoint>::X: Float,
+        <E as EnvelopePoint>::Y: Float
 {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+
+    /// Draw the envelope_editor, returning what happened to it. An
+    /// alternative to `Callable::callback` for when the reaction to the
+    /// change needs to borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
 }
 
-impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
+impl<'a, E, F> EnvelopeEditor<'a, E, F>
     where
         E: EnvelopePoint,
         <E as EnvelopePoint>::X: Float,
-        <E as EnvelopePoint>::Y: Float,
-        F: FnMut(&mut Vec<E>, usize) + 'a
+        <E as EnvelopePoint>::Y: Float
 {
-    #[inline]
-    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+
+    /// Update and draw the envelope_editor, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        let style = self.style.resolve(ui.theme.maybe_envelope_editor, &ui.theme);
+        ui.push_alpha(style.alpha);
         let state = *get_state(ui, self.ui_id);
         let mouse = ui.get_mouse_state();
         let skew = self.skew_y_range;
@@ -356,11 +363,11 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
         let font_size = self.font_size;
 
         // Rect.
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
+        let color = style.color;
+        let frame_w = style.frame;
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
+            true => Some((frame_w, style.frame_color)),
             false => None,
         };
         let pad_pos = vec2_add(self.pos, [frame_w; 2]);
@@ -382,28 +389,30 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
         let new_state = get_new_state(is_over_elem, state, mouse);
 
         // Draw rect.
-        rectangle::draw(ui.win_w, ui.win_h, graphics,
-                        new_state.as_rectangle_state(),
-                        self.pos, self.dim, maybe_frame, color);
+        let rect_color = ui.transition_color(self.ui_id, new_state.as_rectangle_state().color(color));
+        let fill = rectangle::fill(rect_color, style.maybe_gradient, style.maybe_background_image,
+                                   new_state.as_rectangle_state());
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
 
         // If there's a label, draw it.
         if let Some(l_text) = self.maybe_label {
-            let l_size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
-            let l_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
-            let l_w = label::width(ui, l_size, l_text);
+            let l_size = style.label_font_size;
+            let l_color = style.label_color;
+            let l_w = label::width(ui, style.font, l_size, l_text);
             let l_pos = [pad_pos[0] + (pad_dim[0] - l_w) / 2.0,
                          pad_pos[1] + (pad_dim[1] - l_size as f64) / 2.0];
-            ui.draw_text(graphics, l_pos, l_size, l_color, l_text);
+            ui.draw_text(graphics, l_pos, style.font, l_size, l_color, l_text);
         };
 
         // Draw the envelope lines.
         match self.env.len() {
             0 | 1 => (),
             _ => {
-                let Color(col) = color.plain_contrast();
-                let line = graphics::Line::new_round(col, 0.5 * self.line_width);
-                let draw_state = graphics::default_draw_state();
-                let transform = graphics::abs_transform(ui.win_w, ui.win_h);
+                let line_color = color.plain_contrast();
                 for i in 1..perc_env.len() {
                     let (x_a, y_a, _) = perc_env[i - 1];
                     let (x_b, y_b, _) = perc_env[i];
@@ -411,12 +420,8 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                map_range(y_a, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1])];
                     let p_b = [map_range(x_b, 0.0, 1.0, pad_pos[0], pad_pos[0] + pad_dim[0]),
                                map_range(y_b, 0.0, 1.0, pad_pos[1] + pad_dim[1], pad_pos[1])];
-                    line.draw(
-                        [p_a[0], p_a[1], p_b[0], p_b[1]],
-                        draw_state,
-                        transform,
-                        graphics
-                    );
+                    ui.draw_line(graphics, [p_a[0], p_a[1], p_b[0], p_b[1]],
+                                 self.line_width, true, line_color);
                 }
             },
         }
@@ -456,19 +461,21 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                         max_y - min_y,
                         pad_dim[1] as usize
                     );
-                    let xy_string = format!("{}, {}", x_string, y_string);
-                    let xy_string_w = label::width(ui, font_size, &xy_string);
+                    let mut xy_string = ui.scratch_string();
+                    xy_string.push_str(&x_string);
+                    xy_string.push_str(", ");
+                    xy_string.push_str(&y_string);
+                    let xy_string_w = label::width(ui, style.font, font_size, &xy_string);
                     let xy_string_pos = match rectangle::corner(pad_pos, p_pos, pad_dim) {
                         Corner::TopLeft => [p_pos[0], p_pos[1]],
                         Corner::TopRight => [p_pos[0] - xy_string_w, p_pos[1]],
                         Corner::BottomLeft => [p_pos[0], p_pos[1] - font_size as f64],
                         Corner::BottomRight => [p_pos[0] - xy_string_w, p_pos[1] - font_size as f64],
                     };
-                    ui.draw_text(graphics, xy_string_pos,
+                    ui.draw_text(graphics, xy_string_pos, style.font,
                                 font_size, color.plain_contrast(), &xy_string);
-                    draw_circle(ui.win_w, ui.win_h, graphics,
-                                vec2_sub(p_pos, [pt_radius, pt_radius]),
-                                color.plain_contrast(), pt_radius);
+                    ui.recycle_string(xy_string);
+                    oval::draw_circle(ui, graphics, p_pos, pt_radius, color.plain_contrast());
                 };
 
                 match elem {
@@ -523,6 +530,7 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
 
         // If a point is currently clicked, check for callback
         // and value setting conditions.
+        let mut maybe_event = None;
         match is_clicked_env_point {
 
             Some(idx) => {
@@ -537,18 +545,12 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                 let (new_x, new_y) = get_new_value(&perc_env, idx, mouse.pos[0], mouse.pos[1]);
                                 self.env[idx].set_x(new_x);
                                 self.env[idx].set_y(new_y);
-                                match self.maybe_callback {
-                                    Some(ref mut callback) => callback(self.env, idx),
-                                    None => (),
-                                }
+                                maybe_event = Some(Event::Released(idx));
                             },
                             MouseButton::Right => {
                                 // Delete the point and trigger the callback.
                                 self.env.remove(idx);
-                                match self.maybe_callback {
-                                    Some(ref mut callback) => callback(self.env, idx),
-                                    None => (),
-                                }
+                                maybe_event = Some(Event::Removed(idx));
                             },
                         }
                     },
@@ -563,10 +565,7 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                     // Adjust the point and trigger the callback.
                                     self.env[idx].set_x(new_x);
                                     self.env[idx].set_y(new_y);
-                                    match self.maybe_callback {
-                                        Some(ref mut callback) => callback(self.env, idx),
-                                        None => (),
-                                    }
+                                    maybe_event = Some(Event::Dragging(idx));
                                 }
                             }, _ => (),
                         }
@@ -588,6 +587,7 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                     let (new_x, new_y) = get_new_value(&perc_env, 0, mouse.pos[0], mouse.pos[1]);
                                     let new_point = EnvelopePoint::new(new_x, new_y);
                                     self.env.push(new_point);
+                                    maybe_event = Some(Event::Added(0));
                                 }, _ => (),
                             }
                         }, _ => (),
@@ -610,11 +610,14 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
                                         (map_range(new_x_perc, 0.0, 1.0, min_x, max_x),
                                          map_range(new_y_perc, 0.0, 1.0, min_y, max_y))
                                     };
+                                    // The sort is stable, so the new point follows those at or before it.
+                                    let new_idx = self.env.iter().filter(|p| p.get_x() <= new_x).count();
                                     let new_point = EnvelopePoint::new(new_x, new_y);
                                     self.env.push(new_point);
                                     self.env.sort_by(|a, b| if a.get_x() > b.get_x() { Ordering::Greater }
                                                             else if a.get_x() < b.get_x() { Ordering::Less }
                                                             else { Ordering::Equal });
+                                    maybe_event = Some(Event::Added(new_idx));
                                 }, _ => (),
                             }
                         }, _ => (),
@@ -626,7 +629,13 @@ impl<'a, E, F> ::draw::Drawable for EnvelopeEditor<'a, E, F>
         }
 
         // Set the new state.
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::EnvelopeEditor(new_state), self.pos, self.dim);
 
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
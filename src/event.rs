@@ -0,0 +1,101 @@
+use graphics::character::CharacterCache;
+use piston::input::Button;
+use ui::Ui;
+
+/// The input events to which the `Ui` responds. Events from the window are
+/// translated into `Input`s by `Ui::handle_event`, however they may also be
+/// passed directly via `Ui::handle_input` (i.e. for testing).
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Input {
+    /// The window is about to be rendered with the given width and height in pixels.
+    Render(f64, f64),
+    /// The mouse cursor moved to the given position in pixels.
+    MouseCursor(f64, f64),
+    /// The mouse wheel (or trackpad) scrolled by the given amount.
+    MouseScroll(f64, f64),
+    /// A mouse or keyboard button was pressed.
+    Press(Button),
+    /// A mouse or keyboard button was released.
+    Release(Button),
+    /// Some text was entered.
+    Text(String),
+}
+
+/// A stream of `Input`s captured from a `Ui` along with the time (in seconds
+/// since the recording began) at which each was received.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct Record {
+    pub start_time: f64,
+    pub inputs: Vec<(f64, Input)>,
+}
+
+impl Record {
+
+    /// Begin a new, empty recording at the given time.
+    pub fn new(start_time: f64) -> Record {
+        Record { start_time: start_time, inputs: Vec::new() }
+    }
+
+    /// Add an input that was received at the given time.
+    pub fn push(&mut self, time: f64, input: Input) {
+        let elapsed = time - self.start_time;
+        self.inputs.push((elapsed, input));
+    }
+
+}
+
+/// Replays a `Record` deterministically, one frame at a time. While playing,
+/// the `Ui`'s time is mocked so that animations and cursor blinking occur
+/// exactly as they did during recording. The `Ui`'s clock is restored once
+/// playback is finished or stopped.
+pub struct Playback {
+    record: Record,
+    idx: usize,
+    /// The `Ui`'s mock time from before playback began, once it has begun.
+    maybe_prev_mock_time: Option<Option<f64>>,
+}
+
+impl Playback {
+
+    /// Construct a Playback of the given Record.
+    pub fn new(record: Record) -> Playback {
+        Playback { record: record, idx: 0, maybe_prev_mock_time: None }
+    }
+
+    /// Whether or not every input has been played back.
+    pub fn is_finished(&self) -> bool {
+        self.idx >= self.record.inputs.len()
+    }
+
+    /// Pass the recorded inputs to the `Ui` up to and including the next
+    /// `Render` input, after which the widgets for the frame should be drawn.
+    /// Returns `false` (having restored the `Ui`'s clock) if there were no
+    /// inputs left to play back.
+    pub fn next_frame<C: CharacterCache>(&mut self, ui: &mut Ui<C>) -> bool {
+        if self.is_finished() {
+            self.stop(ui);
+            return false
+        }
+        if self.maybe_prev_mock_time.is_none() {
+            self.maybe_prev_mock_time = Some(ui.mock_time());
+        }
+        while self.idx < self.record.inputs.len() {
+            let (time, ref input) = self.record.inputs[self.idx];
+            self.idx += 1;
+            ui.set_mock_time(Some(time));
+            ui.handle_input(input.clone());
+            if let Input::Render(_, _) = *input { break }
+        }
+        true
+    }
+
+    /// Stop playing back, restoring the `Ui`'s clock to how it was before
+    /// playback began.
+    pub fn stop<C: CharacterCache>(&mut self, ui: &mut Ui<C>) {
+        self.idx = self.record.inputs.len();
+        if let Some(maybe_time) = self.maybe_prev_mock_time.take() {
+            ui.set_mock_time(maybe_time);
+        }
+    }
+
+}
@@ -0,0 +1,265 @@
+use button::{ self, Button };
+use callback::NoCallback;
+use color::Colorable;
+use dimensions::Dimensions;
+use draw::Drawable;
+use drop_down_list::{ self, DropDownList, Idx };
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use history::Value;
+use label::{ Label, Labelable };
+use point::Point;
+use position::Positionable;
+use shape::Shapeable;
+use text_box::TextBox;
+use toggle::{ self, Toggle };
+use ui::{ UIID, Ui };
+
+/// The result of validating a field, giving the message to display beneath
+/// the field if its value is invalid.
+pub type Validation = Result<(), String>;
+
+/// What the form held when it was submitted.
+#[derive(Clone, Debug)]
+pub struct Submission {
+    /// Whether or not every field was valid.
+    pub is_valid: bool,
+    /// The label and value of each field in the order they were drawn.
+    pub values: Vec<(String, Value)>,
+    /// The label and error message of each invalid field.
+    pub errors: Vec<(String, String)>,
+}
+
+/// The state of the form kept between frames as its widget memory.
+#[derive(Copy, Clone, Debug)]
+struct State {
+    show_errors: bool,
+}
+
+/// Lays out labelled fields one beneath another, validating each as it is
+/// drawn. Once the form has been submitted, the error message of each
+/// invalid field is displayed beneath it in the theme's `error_color`.
+///
+/// The form keeps its state with its own UIID, while its fields and submit
+/// button use the UIIDs that follow in the order they are drawn. A new
+/// `Form` should be constructed and its fields drawn each frame.
+pub struct Form {
+    ui_id: UIID,
+    next_ui_id: UIID,
+    pos: Point,
+    dim: Dimensions,
+    label_w: f64,
+    /// The offset of the next field from the top of the form.
+    y: f64,
+    values: Vec<(String, Value)>,
+    errors: Vec<(String, String)>,
+}
+
+impl Form {
+
+    /// Create a form context to be built upon. The dimensions are those of
+    /// each field.
+    pub fn new(ui_id: UIID) -> Form {
+        Form {
+            ui_id: ui_id,
+            next_ui_id: ui_id + 1,
+            pos: [0.0, 0.0],
+            dim: [192.0, 32.0],
+            label_w: 128.0,
+            y: 0.0,
+            values: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// A builder method for the width of the column of labels to the left
+    /// of the fields.
+    pub fn label_width(self, width: f64) -> Form {
+        Form { label_w: width, ..self }
+    }
+
+    /// Draw a text box field.
+    pub fn text_box<B, C, V>(
+        &mut self,
+        ui: &mut Ui<C>,
+        graphics: &mut B,
+        label: &str,
+        text: &mut String,
+        validate: V
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            V: Fn(&str) -> Validation
+    {
+        let (ui_id, pos) = self.begin_field(ui, graphics, label);
+        TextBox::<NoCallback>::new(ui_id, text).point(pos).dim(self.dim).react(ui, graphics);
+        let validation = validate(text);
+        self.end_field(ui, graphics, label, Value::Text(text.clone()), validation);
+    }
+
+    /// Draw a drop_down_list field. The field's value is the selected string.
+    pub fn drop_down_list<B, C, V>(
+        &mut self,
+        ui: &mut Ui<C>,
+        graphics: &mut B,
+        label: &str,
+        strings: &mut Vec<String>,
+        selected: &mut Option<Idx>,
+        validate: V
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            V: Fn(Option<Idx>) -> Validation
+    {
+        let (ui_id, pos) = self.begin_field(ui, graphics, label);
+        let maybe_event = DropDownList::<NoCallback>::new(ui_id, strings, selected)
+            .point(pos)
+            .dim(self.dim)
+            .react(ui, graphics);
+        if let Some(drop_down_list::Event::Selected(idx)) = maybe_event {
+            *selected = Some(idx);
+        }
+        let validation = validate(*selected);
+        let value = Value::Text(selected.map_or(String::new(), |idx| strings[idx].clone()));
+        self.end_field(ui, graphics, label, value, validation);
+    }
+
+    /// Draw a toggle field.
+    pub fn toggle<B, C, V>(
+        &mut self,
+        ui: &mut Ui<C>,
+        graphics: &mut B,
+        label: &str,
+        value: &mut bool,
+        validate: V
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            V: Fn(bool) -> Validation
+    {
+        let (ui_id, pos) = self.begin_field(ui, graphics, label);
+        let maybe_event = Toggle::<NoCallback>::new(ui_id, *value)
+            .point(pos)
+            .dim([self.dim[1], self.dim[1]])
+            .react(ui, graphics);
+        if let Some(toggle::Event::Toggled(new_value)) = maybe_event {
+            *value = new_value;
+        }
+        let validation = validate(*value);
+        self.end_field(ui, graphics, label, Value::Bool(*value), validation);
+    }
+
+    /// Draw the button that submits the form, returning what the form held
+    /// if it was clicked. Errors are displayed from the first submission on.
+    pub fn submit<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B, label: &str) -> Option<Submission>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let ui_id = self.next_ui_id;
+        self.next_ui_id += 1;
+        let pos = [self.pos[0] + self.label_w, self.pos[1] + self.y];
+        let maybe_event = Button::<NoCallback>::new(ui_id)
+            .point(pos)
+            .dim(self.dim)
+            .label(label)
+            .react(ui, graphics);
+        self.y += self.dim[1] + ui.theme.padding;
+        match maybe_event {
+            Some(button::Event::Clicked) => {
+                ui.set_widget_memory(self.ui_id, State { show_errors: true });
+                Some(Submission {
+                    is_valid: self.is_valid(),
+                    values: self.values.clone(),
+                    errors: self.errors.clone(),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    /// Stop displaying errors until the form is next submitted, i.e. once a
+    /// valid submission has been handled.
+    pub fn hide_errors<C: CharacterCache>(&self, ui: &mut Ui<C>) {
+        ui.set_widget_memory(self.ui_id, State { show_errors: false });
+    }
+
+    /// Whether or not every field drawn so far is valid.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The label and value of each field drawn so far.
+    pub fn values(&self) -> &[(String, Value)] {
+        &self.values[..]
+    }
+
+    /// The label and error message of each invalid field drawn so far.
+    pub fn errors(&self) -> &[(String, String)] {
+        &self.errors[..]
+    }
+
+    /// Draw the field's label, returning the UIID and position for the field.
+    fn begin_field<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B, label: &str) -> (UIID, Point)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let ui_id = self.next_ui_id;
+        self.next_ui_id += 1;
+        let size = ui.theme.font_size_medium;
+        let y = self.pos[1] + self.y;
+        Label::new(label)
+            .point([self.pos[0], y + (self.dim[1] - size as f64) / 2.0])
+            .size(size)
+            .draw(ui, graphics);
+        (ui_id, [self.pos[0] + self.label_w, y])
+    }
+
+    /// Record the field's value and validation, drawing its error message
+    /// beneath it if it is invalid and errors are being displayed.
+    fn end_field<B, C>(
+        &mut self,
+        ui: &mut Ui<C>,
+        graphics: &mut B,
+        label: &str,
+        value: Value,
+        validation: Validation
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.y += self.dim[1] + ui.theme.padding;
+        self.values.push((label.to_string(), value));
+        if let Err(message) = validation {
+            let show_errors = ui.widget_memory::<State>(self.ui_id).map_or(false, |state| state.show_errors);
+            if show_errors {
+                let size = ui.theme.font_size_small;
+                Label::new(&message)
+                    .point([self.pos[0] + self.label_w, self.pos[1] + self.y - ui.theme.padding / 2.0])
+                    .size(size)
+                    .color(ui.theme.error_color)
+                    .draw(ui, graphics);
+                self.y += size as f64 + ui.theme.padding;
+            }
+            self.errors.push((label.to_string(), message));
+        }
+    }
+
+}
+
+impl Positionable for Form {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl Shapeable for Form {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
@@ -1,4 +1,5 @@
-use color::Color;
+use color::{ Color, Gradient, GradientDirection };
+use image::ImageId;
 
 /// To be used as a parameter for defining the aesthetic
 /// of the widget frame.
@@ -13,6 +14,29 @@ pub enum Framing {
 pub trait Frameable: Sized {
     fn frame(self, width: f64) -> Self;
     fn frame_color(self, color: Color) -> Self;
+    /// Fill the widget with a linear gradient from `a` to `b`.
+    fn color_gradient(self, a: Color, b: Color, direction: GradientDirection) -> Self {
+        self.gradient(Gradient::Linear(a, b, direction))
+    }
+    /// Fill the widget with the given gradient rather than a single color.
+    ///
+    /// Ignored by default for widgets that don't draw a gradient.
+    fn gradient(self, _gradient: Gradient) -> Self { self }
+    /// Round the corners of the widget (and its frame) with the given radius.
+    ///
+    /// Ignored by default for widgets that don't draw rounded corners.
+    fn corner_radius(self, _radius: f64) -> Self { self }
+    /// Cast a shadow beneath the widget, offset by the given amount and
+    /// blurred over the given distance.
+    ///
+    /// Ignored by default for widgets that don't draw a shadow.
+    fn shadow(self, _offset: [f64; 2], _softness: f64, _color: Color) -> Self { self }
+    /// Draw the widget's background with the given image using nine-slice
+    /// scaling, where `insets` are the `[left, top, right, bottom]` sizes of
+    /// the image's border in pixels.
+    ///
+    /// Ignored by default for widgets that don't draw a background image.
+    fn background_image(self, _id: ImageId, _insets: [f64; 4]) -> Self { self }
     fn frame_rgba(self, r: f32, g: f32, b: f32, a: f32) -> Self {
         self.frame_color(Color([r, g, b, a]))
     }
@@ -0,0 +1,443 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use image::ImageId;
+use label::{ FontId, FontSize, Labelable };
+use marquee::{ self, Marquee };
+use mouse::ButtonState::{ Down, Up };
+use piston::input::keyboard::Key;
+use point::Point;
+use rectangle;
+use scroll::Kinetic;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use widget::Widget;
+
+/// The most seconds between two clicks on a thumbnail for them to activate it.
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+
+/// An image in the gallery, with the caption drawn beneath it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thumbnail {
+    pub image: ImageId,
+    pub caption: String,
+}
+
+impl Thumbnail {
+    /// A thumbnail of the image added to the `Ui`'s `image_map` with the given id.
+    pub fn new(image: ImageId, caption: &str) -> Thumbnail {
+        Thumbnail { image: image, caption: caption.to_string() }
+    }
+}
+
+/// What the mouse is doing to the Gallery.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    /// The mouse is over the gallery, and over the thumbnail at the given
+    /// index if there is one.
+    Highlighted(Option<usize>),
+    Clicked(Option<usize>),
+}
+
+/// Represents the state of the Gallery widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    pub interaction: Interaction,
+    pub scroll: Kinetic,
+    /// The thumbnail last clicked and when, from which ranges are selected
+    /// and double clicks detected.
+    pub maybe_last_click: Option<(usize, f64)>,
+    /// The rubber band dragged from between thumbnails, in the positions of
+    /// the unscrolled grid.
+    pub marquee: Marquee,
+}
+
+widget_fns!(Gallery, State, Widget::Gallery(State {
+    interaction: Interaction::Normal,
+    scroll: Kinetic::new(0.0, 0.0),
+    maybe_last_click: None,
+    marquee: Marquee::new(),
+}));
+
+/// What happened to the Gallery, returned by `Gallery::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// Thumbnails were selected or deselected.
+    SelectionChanged,
+    /// The thumbnail at the given index was double clicked.
+    Activated(usize),
+}
+
+/// The arrangement of the thumbnails in rows of cells.
+#[derive(Clone, Copy)]
+struct Grid {
+    pos: Point,
+    thumb: f64,
+    cell_h: f64,
+    spacing: f64,
+    columns: usize,
+}
+
+impl Grid {
+
+    /// The position of the cell at the given index, scrolled by the given offset.
+    fn cell_pos(&self, idx: usize, offset: f64) -> Point {
+        let (row, col) = (idx / self.columns, idx % self.columns);
+        [self.pos[0] + self.spacing + col as f64 * (self.thumb + self.spacing),
+         self.pos[1] + self.spacing + row as f64 * (self.cell_h + self.spacing) - offset]
+    }
+
+    /// The index of the cell beneath the given position, whether or not there's
+    /// a thumbnail in it.
+    fn cell_at(&self, pos: Point, offset: f64) -> Option<usize> {
+        let x = pos[0] - self.pos[0] - self.spacing;
+        let y = pos[1] - self.pos[1] - self.spacing + offset;
+        if x < 0.0 || y < 0.0 { return None }
+        let (col, row) = ((x / (self.thumb + self.spacing)) as usize, (y / (self.cell_h + self.spacing)) as usize);
+        let is_in_cell = x - col as f64 * (self.thumb + self.spacing) < self.thumb
+            && y - row as f64 * (self.cell_h + self.spacing) < self.cell_h;
+        match col < self.columns && is_in_cell {
+            true => Some(row * self.columns + col),
+            false => None,
+        }
+    }
+
+    /// The range of rows at least partly within the given height.
+    fn visible_rows(&self, offset: f64, height: f64) -> (usize, usize) {
+        let step = self.cell_h + self.spacing;
+        let first = (offset / step).floor().max(0.0) as usize;
+        let last = ((offset + height) / step).ceil().max(0.0) as usize;
+        (first, last)
+    }
+
+}
+
+/// A scrollable grid of image thumbnails with captions, i.e. for picking an
+/// asset or browsing photos. Clicking a thumbnail selects it; with
+/// `multi_select`, clicking with the shortcut modifier (Ctrl, or Cmd on OS X)
+/// toggles a thumbnail, clicking with Shift selects a range and dragging
+/// from between thumbnails selects those within the marquee. Double
+/// clicking a thumbnail activates it. Only the visible rows are laid out
+/// and drawn, so that galleries of thousands of images stay responsive.
+pub struct Gallery<'a, F> {
+    ui_id: UIID,
+    thumbnails: &'a [Thumbnail],
+    selected: &'a mut Vec<usize>,
+    is_multi_select: bool,
+    thumbnail_size: f64,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> Gallery<'a, F> {
+
+    /// Create a gallery context to be built upon, with the indices of the
+    /// selected thumbnails kept in `selected`.
+    pub fn new(ui_id: UIID, thumbnails: &'a [Thumbnail], selected: &'a mut Vec<usize>) -> Gallery<'a, F> {
+        Gallery {
+            ui_id: ui_id,
+            thumbnails: thumbnails,
+            selected: selected,
+            is_multi_select: false,
+            thumbnail_size: 96.0,
+            pos: [0.0, 0.0],
+            dim: [512.0, 384.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// Whether or not many thumbnails may be selected at once.
+    pub fn multi_select(self, is_multi_select: bool) -> Gallery<'a, F> {
+        Gallery { is_multi_select: is_multi_select, ..self }
+    }
+
+    /// The width and height of each thumbnail, not including its caption.
+    pub fn thumbnail_size(self, size: f64) -> Gallery<'a, F> {
+        Gallery { thumbnail_size: size, ..self }
+    }
+
+}
+
+composite_builder_impls!(Gallery<'a, F>);
+
+impl<'a, F> Callable<F> for Gallery<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for Gallery<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Labelable<'a> for Gallery<'a, F> {
+    /// Galleries aren't labelled as a whole; their thumbnails are captioned instead.
+    fn label(self, _text: &'a str) -> Self {
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for Gallery<'a, F> where F: FnMut(usize) + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Activated(idx)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(idx) }
+        }
+    }
+}
+
+impl<'a> Gallery<'a, NoCallback> {
+
+    /// Draw the gallery, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the gallery needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Gallery<'a, F> {
+
+    /// Update and draw the gallery, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_gallery, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let time = ui.time();
+        let padding = ui.theme.padding;
+        let font_size = style.label_font_size;
+        let frame_w = style.frame;
+        let inner_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let inner_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+        let len = self.thumbnails.len();
+
+        // As many columns fit as there is room for, and the rows scroll.
+        let thumb = self.thumbnail_size.min(inner_dim[0] - padding * 2.0).max(1.0);
+        let columns = ::std::cmp::max(((inner_dim[0] - padding) / (thumb + padding)) as usize, 1);
+        let grid = Grid {
+            pos: inner_pos,
+            thumb: thumb,
+            cell_h: thumb + font_size as f64 + padding,
+            spacing: padding,
+            columns: columns,
+        };
+        let rows = (len + columns - 1) / columns;
+        let content_h = rows as f64 * (grid.cell_h + padding) + padding;
+        let max_scroll = (content_h - inner_dim[1]).max(0.0);
+        let is_over = rectangle::is_over(inner_pos, mouse.pos, inner_dim);
+        let limit = ui.theme.overscroll_limit;
+        // The grid is scrolled back as it was saved via `Ui::save_state`.
+        let prev_scroll = match ui.take_restored_scroll(self.ui_id) {
+            Some(offset) => Kinetic::new(offset, time),
+            None => state.scroll,
+        };
+        let scroll = match is_over {
+            true if mouse.scroll[1] != 0.0 =>
+                prev_scroll.stretch_by(-mouse.scroll[1] * (grid.cell_h + padding) / 2.0, time, 0.0, max_scroll, limit),
+            _ => prev_scroll.coast_within(time, ui.theme.scroll_friction, 0.0, max_scroll, limit),
+        };
+        let scroll = if ui.theme.kinetic_scrolling { scroll } else { scroll.stop().clamp(0.0, max_scroll) };
+        let offset = scroll.offset;
+        if offset != state.scroll.offset {
+            ui.persist_scroll(self.ui_id, offset);
+        }
+
+        let is_over_idx = match is_over {
+            true => grid.cell_at(mouse.pos, offset).and_then(|idx| if idx < len { Some(idx) } else { None }),
+            false => None,
+        };
+        let interaction = match (is_over, state.interaction, mouse.left) {
+            (true,  Interaction::Normal,        Down) => Interaction::Normal,
+            (true,  Interaction::Clicked(idx),  Down) => Interaction::Clicked(idx),
+            (true,  _,                          Down) => Interaction::Clicked(is_over_idx),
+            (true,  _,                          Up)   => Interaction::Highlighted(is_over_idx),
+            (false, Interaction::Clicked(idx),  Down) => Interaction::Clicked(idx),
+            _                                         => Interaction::Normal,
+        };
+
+        // Pressing a thumbnail selects it, toggles it or selects the range
+        // from the last clicked, and pressing between thumbnails deselects.
+        let mut maybe_event = None;
+        let mut maybe_last_click = state.maybe_last_click;
+        if let (Interaction::Highlighted(_), Interaction::Clicked(maybe_idx)) = (state.interaction, interaction) {
+            let is_toggling = self.is_multi_select && ui.is_shortcut_modifier_held();
+            let is_extending = self.is_multi_select
+                && (ui.is_key_held(Key::LShift) || ui.is_key_held(Key::RShift));
+            let prev_selected = self.selected.clone();
+            match (maybe_idx, maybe_last_click) {
+                (Some(idx), Some((last, _))) if is_extending && last < len => {
+                    self.selected.clear();
+                    self.selected.extend(::std::cmp::min(idx, last)..::std::cmp::max(idx, last) + 1);
+                },
+                (Some(idx), _) if is_toggling => match self.selected.iter().position(|&i| i == idx) {
+                    Some(pos) => { self.selected.remove(pos); },
+                    None => self.selected.push(idx),
+                },
+                (Some(idx), _) => {
+                    self.selected.clear();
+                    self.selected.push(idx);
+                },
+                (None, _) if !is_toggling && !is_extending => self.selected.clear(),
+                (None, _) => (),
+            }
+            if *self.selected != prev_selected {
+                maybe_event = Some(Event::SelectionChanged);
+            }
+            if let Some(idx) = maybe_idx {
+                maybe_last_click = match maybe_last_click {
+                    Some((last, at)) if last == idx && time - at < DOUBLE_CLICK_TIME && !is_extending => {
+                        maybe_event = Some(Event::Activated(idx));
+                        None
+                    },
+                    // A range is extended from where it began.
+                    Some((last, at)) if is_extending => Some((last, at)),
+                    _ => Some((idx, time)),
+                };
+            }
+        }
+
+        // With `multi_select`, pressing between thumbnails begins a marquee,
+        // which selects the thumbnails it overlaps as it is dragged.
+        let content_mouse = [mouse.pos[0], mouse.pos[1] + offset];
+        let mut marquee = state.marquee;
+        if let (Interaction::Highlighted(_), Interaction::Clicked(None)) = (state.interaction, interaction) {
+            if self.is_multi_select {
+                marquee = Marquee::begin(content_mouse);
+            }
+        }
+        let (marquee, marquee_event) = marquee.update(content_mouse, mouse.left);
+        if let Some(marquee::Event::Changed(rect_pos, rect_dim)) = marquee_event {
+            let prev_selected = self.selected.clone();
+            self.selected.clear();
+            let (first_row, last_row) = grid.visible_rows(rect_pos[1] - grid.pos[1], rect_dim[1]);
+            for idx in ::std::cmp::min(first_row * columns, len)..::std::cmp::min(last_row * columns + columns, len) {
+                if marquee::overlaps(rect_pos, rect_dim, grid.cell_pos(idx, 0.0), [thumb, grid.cell_h]) {
+                    self.selected.push(idx);
+                }
+            }
+            if *self.selected != prev_selected {
+                maybe_event = Some(Event::SelectionChanged);
+            }
+        }
+        let new_state = State {
+            interaction: interaction,
+            scroll: scroll,
+            maybe_last_click: maybe_last_click,
+            marquee: marquee,
+        };
+
+        // Draw the background.
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        let fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
+
+        // Draw only the rows in view. Each image is fitted to its cell,
+        // keeping its aspect ratio, above its centered caption.
+        ui.push_clip(inner_pos, inner_dim);
+        let (first_row, last_row) = grid.visible_rows(offset, inner_dim[1]);
+        let first = ::std::cmp::min(first_row * columns, len);
+        let last = ::std::cmp::min(last_row * columns + columns, len);
+        let hovered = match interaction {
+            Interaction::Highlighted(maybe_idx) | Interaction::Clicked(maybe_idx) => maybe_idx,
+            Interaction::Normal => None,
+        };
+        for idx in first..last {
+            let thumbnail = &self.thumbnails[idx];
+            let cell_pos = grid.cell_pos(idx, offset);
+            let cell_dim = [thumb, grid.cell_h];
+            let is_selected = self.selected.contains(&idx);
+            let cell_state = match (is_selected, hovered == Some(idx)) {
+                (true, _) => Some(rectangle::State::Clicked),
+                (false, true) => Some(rectangle::State::Highlighted),
+                (false, false) => None,
+            };
+            if let Some(cell_state) = cell_state {
+                let gap = padding / 2.0;
+                ui.draw_rectangle(graphics, [cell_pos[0] - gap, cell_pos[1] - gap],
+                                  [cell_dim[0] + padding, cell_dim[1] + padding], cell_state.color(style.color));
+            }
+            if let Some(image_dim) = ui.image_map.dimensions(thumbnail.image) {
+                let scale = (thumb / image_dim[0]).min(thumb / image_dim[1]);
+                let dim = [image_dim[0] * scale, image_dim[1] * scale];
+                let pos = [cell_pos[0] + (thumb - dim[0]) / 2.0, cell_pos[1] + (thumb - dim[1]) / 2.0];
+                ui.draw_image(graphics, thumbnail.image, None, pos, dim, None);
+            }
+            let caption_w = ui.text_width(style.font, font_size, &thumbnail.caption).min(thumb);
+            let caption_pos = [cell_pos[0] + (thumb - caption_w) / 2.0, cell_pos[1] + thumb + padding / 2.0];
+            ui.push_clip([cell_pos[0], caption_pos[1]], [thumb, font_size as f64 + padding / 2.0]);
+            ui.draw_text(graphics, caption_pos, style.font, font_size, style.label_color, &thumbnail.caption);
+            ui.pop_clip();
+        }
+        if let Some((rect_pos, rect_dim)) = marquee.rect() {
+            marquee::draw(ui, graphics, [rect_pos[0], rect_pos[1] - offset], rect_dim, style.color.plain_contrast());
+        }
+        ui.pop_clip();
+
+        // A bar along the right edge shows where the grid is scrolled to.
+        if max_scroll > 0.0 {
+            let bar_h = (inner_dim[1] * inner_dim[1] / content_h).max(padding * 2.0);
+            let bar_y = inner_pos[1] + (inner_dim[1] - bar_h) * (offset / max_scroll).max(0.0).min(1.0);
+            let bar_x = inner_pos[0] + inner_dim[0] - padding / 2.0;
+            ui.draw_line(graphics, [bar_x, bar_y, bar_x, bar_y + bar_h], 2.0, true,
+                         style.color.plain_contrast().fade(0.4));
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Gallery(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
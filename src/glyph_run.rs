@@ -0,0 +1,111 @@
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize };
+use std::collections::HashMap;
+use ui::Ui;
+
+/// Adjustments to the advance between pairs of characters, as a fraction of
+/// the font size. The `CharacterCache` doesn't provide kerning, so tables
+/// are loaded by the application and given to the `Ui` via `Ui::set_kerning`.
+pub type KerningTable = HashMap<(char, char), f64>;
+
+/// The horizontal layout of a single line of text at a given font and size.
+/// Runs are cached by the `Ui` (see `Ui::glyph_run`) so that text which
+/// doesn't change between frames is only measured once.
+#[derive(Clone, Debug)]
+pub struct GlyphRun {
+    /// The offset of each character's left edge from the start of the run,
+    /// followed by the width of the whole run.
+    offsets: Vec<f64>,
+}
+
+impl GlyphRun {
+
+    /// Measure the given text, applying the font's kerning (if it has any)
+    /// between each pair of characters.
+    pub fn new<C: CharacterCache>(ui: &mut Ui<C>, font: FontId, size: FontSize, text: &str) -> GlyphRun {
+        let mut offsets = Vec::with_capacity(text.len() + 1);
+        let mut x = 0.0;
+        let mut maybe_prev = None;
+        offsets.push(x);
+        for ch in text.chars() {
+            if let Some(prev) = maybe_prev {
+                // Kerning shifts the start of this character.
+                x += ui.kerning(font, size, prev, ch);
+                let last = offsets.len() - 1;
+                offsets[last] = x;
+            }
+            x += ui.get_character_w(font, size, ch);
+            offsets.push(x);
+            maybe_prev = Some(ch);
+        }
+        GlyphRun { offsets: offsets }
+    }
+
+    /// The number of characters in the run.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// The width of the whole run.
+    pub fn width(&self) -> f64 {
+        self.offsets[self.len()]
+    }
+
+    /// The offset of the left edge of the character at the given index from
+    /// the start of the run. Indices past the end give the run's width.
+    pub fn x_at(&self, idx: usize) -> f64 {
+        self.offsets[::std::cmp::min(idx, self.len())]
+    }
+
+    /// The width of the character at the given index.
+    pub fn advance(&self, idx: usize) -> f64 {
+        self.x_at(idx + 1) - self.x_at(idx)
+    }
+
+    /// The width of the characters between the given indices.
+    pub fn width_between(&self, start: usize, end: usize) -> f64 {
+        self.x_at(end) - self.x_at(start)
+    }
+
+    /// Update the run for an edit that replaced `removed` characters at the
+    /// given index with `inserted` characters, where `text` is the text after
+    /// the edit. Only the inserted characters and the character before them
+    /// (whose kerning depends on its successor) are measured again; the
+    /// offsets of the characters that follow are shifted.
+    pub fn splice<C: CharacterCache>(
+        &mut self,
+        ui: &mut Ui<C>,
+        font: FontId,
+        size: FontSize,
+        text: &str,
+        idx: usize,
+        removed: usize,
+        inserted: usize
+    ) {
+        let old_len = self.len();
+        let mut advances = Vec::with_capacity(old_len - removed + inserted);
+        advances.extend((0..idx).map(|i| self.advance(i)));
+        advances.extend((0..inserted).map(|_| 0.0));
+        advances.extend((idx + removed..old_len).map(|i| self.advance(i)));
+        let first = if idx > 0 { idx - 1 } else { 0 };
+        let last = ::std::cmp::min(idx + inserted, advances.len());
+        let mut chars = text.chars().skip(first);
+        let mut maybe_ch = chars.next();
+        for i in first..last {
+            let ch = match maybe_ch { Some(ch) => ch, None => break };
+            let maybe_next = chars.next();
+            // Each character's advance includes the kerning before its successor.
+            let kerning = maybe_next.map_or(0.0, |next| ui.kerning(font, size, ch, next));
+            advances[i] = ui.get_character_w(font, size, ch) + kerning;
+            maybe_ch = maybe_next;
+        }
+        let mut x = 0.0;
+        self.offsets.clear();
+        self.offsets.push(x);
+        for advance in advances.into_iter() {
+            x += advance;
+            self.offsets.push(x);
+        }
+    }
+
+}
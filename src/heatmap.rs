@@ -0,0 +1,234 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::{ Color, Colorable, Fadeable };
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use position::Positionable;
+use rectangle;
+use shape::Shapeable;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use widget::Widget;
+
+/// Represents the state of the Heatmap widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    /// The mouse is over the cell at the given row and column.
+    Highlighted(usize, usize),
+}
+
+widget_fns!(Heatmap, State, Widget::Heatmap(State::Normal));
+
+/// What happened to the Heatmap, returned by `Heatmap::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The mouse is over the cell at the given row and column, which has the
+    /// given value.
+    Hovered(usize, usize, f64),
+}
+
+/// A grid of values drawn as colored cells, i.e. for showing a correlation
+/// matrix or activity by hour and weekday. Each value is colored by where it
+/// lies between the least and greatest values along a color ramp. The cell
+/// beneath the mouse is outlined and reported each frame.
+pub struct Heatmap<'a, F> {
+    ui_id: UIID,
+    values: &'a [f64],
+    columns: usize,
+    maybe_range: Option<(f64, f64)>,
+    maybe_ramp: Option<&'a [Color]>,
+    gap: f64,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> Heatmap<'a, F> {
+
+    /// Create a heatmap context to be built upon, laying the given values out
+    /// row by row, `columns` values to a row.
+    pub fn new(ui_id: UIID, values: &'a [f64], columns: usize) -> Heatmap<'a, F> {
+        Heatmap {
+            ui_id: ui_id,
+            values: values,
+            columns: columns,
+            maybe_range: None,
+            maybe_ramp: None,
+            gap: 1.0,
+            pos: [0.0, 0.0],
+            dim: [256.0, 256.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// The values at the start and end of the color ramp, rather than the
+    /// least and greatest of the values.
+    pub fn range(self, min: f64, max: f64) -> Heatmap<'a, F> {
+        Heatmap { maybe_range: Some((min, max)), ..self }
+    }
+
+    /// The colors of the ramp, evenly spaced from the least value to the
+    /// greatest. Defaults to blending from the theme's background color to
+    /// the heatmap's color.
+    pub fn ramp(self, colors: &'a [Color]) -> Heatmap<'a, F> {
+        Heatmap { maybe_ramp: Some(colors), ..self }
+    }
+
+    /// The gap between neighbouring cells.
+    pub fn gap(self, gap: f64) -> Heatmap<'a, F> {
+        Heatmap { gap: gap, ..self }
+    }
+
+}
+
+impl<'a, F> Colorable for Heatmap<'a, F> {
+    fn color(mut self, color: Color) -> Self {
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a, F> Fadeable for Heatmap<'a, F> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
+        self
+    }
+}
+
+impl<'a, F> Callable<F> for Heatmap<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for Heatmap<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Positionable for Heatmap<'a, F> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<'a, F> Shapeable for Heatmap<'a, F> {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
+
+impl<'a, F> ::draw::Drawable for Heatmap<'a, F> where F: FnMut(usize, usize, f64) + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Hovered(row, col, value)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(row, col, value) }
+        }
+    }
+}
+
+impl<'a> Heatmap<'a, NoCallback> {
+
+    /// Draw the heatmap, returning the cell beneath the mouse. An alternative
+    /// to `Callable::callback` for when the reaction to hovering needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Heatmap<'a, F> {
+
+    /// Update and draw the heatmap, returning the cell beneath the mouse.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_heatmap, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let mouse = ui.get_mouse_state();
+        let columns = ::std::cmp::max(self.columns, 1);
+        let rows = (self.values.len() + columns - 1) / columns;
+        let cell_dim = [self.dim[0] / columns as f64, self.dim[1] / ::std::cmp::max(rows, 1) as f64];
+
+        // The cell beneath the mouse, if there's a value in it.
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let new_state = match is_over {
+            true => {
+                let col = ((mouse.pos[0] - self.pos[0]) / cell_dim[0]).floor() as usize;
+                let row = ((mouse.pos[1] - self.pos[1]) / cell_dim[1]).floor() as usize;
+                let (col, row) = (::std::cmp::min(col, columns - 1), row);
+                match row * columns + col < self.values.len() {
+                    true => State::Highlighted(row, col),
+                    false => State::Normal,
+                }
+            },
+            false => State::Normal,
+        };
+        let maybe_event = match new_state {
+            State::Highlighted(row, col) => Some(Event::Hovered(row, col, self.values[row * columns + col])),
+            State::Normal => None,
+        };
+
+        // Each value is colored by where it lies within the range.
+        let (min, max) = self.maybe_range.unwrap_or_else(|| {
+            self.values.iter().fold((::std::f64::MAX, ::std::f64::MIN), |(min, max), &v| (min.min(v), max.max(v)))
+        });
+        let default_ramp = [ui.theme.background_color, style.color];
+        let ramp: &[Color] = match self.maybe_ramp {
+            Some(ramp) => ramp,
+            None => &default_ramp,
+        };
+        let color_at = |value: f64| {
+            let t = match max > min {
+                true => ((value - min) / (max - min)) as f32,
+                false => 0.5,
+            };
+            Color::from_ramp(ramp, t)
+        };
+        let gap = self.gap.min(cell_dim[0] / 2.0).min(cell_dim[1] / 2.0).max(0.0);
+        for (i, &value) in self.values.iter().enumerate() {
+            let (row, col) = (i / columns, i % columns);
+            let cell_pos = [self.pos[0] + col as f64 * cell_dim[0], self.pos[1] + row as f64 * cell_dim[1]];
+            ui.draw_rectangle(graphics, cell_pos, [cell_dim[0] - gap, cell_dim[1] - gap], color_at(value));
+        }
+
+        // Outline the cell beneath the mouse.
+        if let State::Highlighted(row, col) = new_state {
+            let color = color_at(self.values[row * columns + col]).plain_contrast();
+            let (l, t) = (self.pos[0] + col as f64 * cell_dim[0], self.pos[1] + row as f64 * cell_dim[1]);
+            let (r, b) = (l + cell_dim[0] - gap, t + cell_dim[1] - gap);
+            for &line in [[l, t, r, t], [r, t, r, b], [r, b, l, b], [l, b, l, t]].iter() {
+                ui.draw_line(graphics, line, 1.0, false, color);
+            }
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Heatmap(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
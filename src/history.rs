@@ -0,0 +1,131 @@
+//! An optional history of the values committed via widgets, so that changes
+//! may be undone and redone (see `Ui::enable_history`).
+//!
+//! Widgets record a change once it is committed, i.e. when a slider, XY pad
+//! or number dialer is released, a text box is submitted, a toggle is
+//! clicked or an item of a drop down list is selected. When a change is undone or redone, the widget that made it
+//! re-fires its event with the restored value the next time it is drawn, so
+//! the application applies the restored value just as it would a change
+//! made by the user.
+
+use std::collections::HashMap;
+use ui::UIID;
+
+/// A value committed via a widget.
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Value {
+    Bool(bool),
+    Number(f64),
+    /// A pair of numbers, i.e. the x and y values of an XYPad.
+    Point(f64, f64),
+    Text(String),
+}
+
+/// A change from one value to another made via the widget with the given UIID.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub ui_id: UIID,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// The changes that may be undone and redone.
+#[derive(Clone, Debug)]
+pub struct History {
+    undo_stack: Vec<Change>,
+    redo_stack: Vec<Change>,
+    /// The greatest number of changes that may be undone.
+    max_len: usize,
+    /// The value of each widget at the beginning of a change not yet committed.
+    uncommitted: HashMap<UIID, Value>,
+    /// The values to be restored by each widget when it is next drawn.
+    restored: HashMap<UIID, Value>,
+}
+
+impl History {
+
+    /// Construct an empty history which remembers up to `max_len` changes.
+    pub fn new(max_len: usize) -> History {
+        History {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            max_len: max_len,
+            uncommitted: HashMap::new(),
+            restored: HashMap::new(),
+        }
+    }
+
+    /// Remember the value of the widget at the beginning of a change, i.e.
+    /// when a slider is pressed.
+    pub fn begin(&mut self, ui_id: UIID, before: Value) {
+        self.uncommitted.insert(ui_id, before);
+    }
+
+    /// Record the change begun by the widget, now that its value has been
+    /// committed. Does nothing if no change was begun.
+    pub fn commit(&mut self, ui_id: UIID, after: Value) {
+        if let Some(before) = self.uncommitted.remove(&ui_id) {
+            self.push(Change { ui_id: ui_id, before: before, after: after });
+        }
+    }
+
+    /// Record a change, after which the changes that were undone may no
+    /// longer be redone. Changes that don't change the value are ignored.
+    pub fn push(&mut self, change: Change) {
+        if change.before == change.after { return }
+        self.redo_stack.clear();
+        self.undo_stack.push(change);
+        if self.undo_stack.len() > self.max_len {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Undo the latest change, returning `false` if there was none.
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(change) => {
+                self.restored.insert(change.ui_id, change.before.clone());
+                self.redo_stack.push(change);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Redo the latest change that was undone, returning `false` if there was none.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(change) => {
+                self.restored.insert(change.ui_id, change.after.clone());
+                self.undo_stack.push(change);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Whether or not there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether or not there is a change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Take the value that the widget should restore, if a change it made
+    /// was undone or redone since it was last drawn.
+    pub fn take_restored(&mut self, ui_id: UIID) -> Option<Value> {
+        self.restored.remove(&ui_id)
+    }
+
+    /// Forget every change.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.uncommitted.clear();
+        self.restored.clear();
+    }
+
+}
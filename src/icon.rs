@@ -0,0 +1,39 @@
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize };
+use point::Point;
+use ui::Ui;
+
+/// The size of an icon relative to the smaller side of the widget it's drawn within.
+pub const ICON_SCALE: f64 = 0.6;
+
+/// The font size at which to draw an icon within a widget of the given dimensions.
+pub fn size_for(dim: Dimensions) -> FontSize {
+    (dim[0].min(dim[1]) * ICON_SCALE).round() as FontSize
+}
+
+/// The width of the icon with the given codepoint.
+pub fn width<C: CharacterCache>(ui: &mut Ui<C>, font: FontId, size: FontSize, codepoint: char) -> f64 {
+    ui.get_character_w(font, size, codepoint)
+}
+
+/// Draw the icon with the given codepoint with its top-left corner at the given position.
+pub fn draw<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    pos: Point,
+    font: FontId,
+    size: FontSize,
+    color: Color,
+    codepoint: char
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let mut text = String::with_capacity(4);
+    text.push(codepoint);
+    ui.draw_text(graphics, pos, font, size, color, &text);
+}
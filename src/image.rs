@@ -0,0 +1,203 @@
+use color::{ Color, Colorable };
+use dimensions::Dimensions;
+use graphics::{ Graphics, ImageSize };
+use graphics::character::CharacterCache;
+use point::Point;
+use position::Positionable;
+use shape::Shapeable;
+use ui::Ui;
+
+/// Identifies a texture registered with the `Ui`'s `ImageMap`. Widgets refer
+/// to images by their `ImageId` so that they needn't know the concrete type
+/// of the `Graphics` backend's textures.
+pub type ImageId = usize;
+
+/// The textures registered with a `Ui`, each of which is given a stable
+/// `ImageId` that remains valid until the texture is removed.
+pub struct ImageMap<T> {
+    textures: Vec<Option<T>>,
+}
+
+impl<T> ImageMap<T>
+    where
+        T: ImageSize
+{
+
+    /// Construct an empty ImageMap.
+    pub fn new() -> ImageMap<T> {
+        ImageMap { textures: Vec::new() }
+    }
+
+    /// Register a texture, returning the `ImageId` with which it may be drawn.
+    pub fn insert(&mut self, texture: T) -> ImageId {
+        // Reuse the slot of a removed texture if there is one.
+        match self.textures.iter().position(|slot| slot.is_none()) {
+            Some(id) => {
+                self.textures[id] = Some(texture);
+                id
+            },
+            None => {
+                self.textures.push(Some(texture));
+                self.textures.len() - 1
+            },
+        }
+    }
+
+    /// Replace the texture with the given `ImageId`, returning the previous
+    /// texture. Widgets referring to the `ImageId` will draw the new texture.
+    pub fn replace(&mut self, id: ImageId, texture: T) -> Option<T> {
+        if id >= self.textures.len() { return None }
+        ::std::mem::replace(&mut self.textures[id], Some(texture))
+    }
+
+    /// Remove the texture with the given `ImageId`, after which its id may be
+    /// reused for another texture.
+    pub fn remove(&mut self, id: ImageId) -> Option<T> {
+        match self.textures.get_mut(id) {
+            Some(slot) => slot.take(),
+            None => None,
+        }
+    }
+
+    /// Return the texture with the given `ImageId`, if there is one.
+    pub fn get(&self, id: ImageId) -> Option<&T> {
+        match self.textures.get(id) {
+            Some(&Some(ref texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// The dimensions of the texture with the given `ImageId` in pixels.
+    pub fn dimensions(&self, id: ImageId) -> Option<Dimensions> {
+        self.get(id).map(|texture| {
+            let (w, h) = texture.get_size();
+            [w as f64, h as f64]
+        })
+    }
+
+}
+
+/// An image drawn with nine-slice scaling: its corners keep their size, its
+/// edges are stretched along one axis and its center along both, so that a
+/// bitmap border may frame a rectangle of any size.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct NinePatch {
+    pub id: ImageId,
+    /// The `[left, top, right, bottom]` insets of the image's center slice in pixels.
+    pub insets: [f64; 4],
+}
+
+/// Draw a nine-patch image over the given rectangle, tinted with the given
+/// color. Where the rectangle is smaller than the image's corners, the
+/// corners are shrunk to fit.
+pub fn draw_nine_patch<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    nine_patch: NinePatch,
+    pos: Point,
+    dim: Dimensions,
+    tint: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let (tex_w, tex_h) = match ui.image_map.dimensions(nine_patch.id) {
+        Some(dim) => (dim[0], dim[1]),
+        None => return,
+    };
+    let insets = nine_patch.insets;
+    let (left, top, right, bottom) = (insets[0], insets[1], insets[2], insets[3]);
+    let scale = 1.0f64.min(dim[0] / (left + right)).min(dim[1] / (top + bottom));
+    let src_xs = [0.0, left, tex_w - right, tex_w];
+    let src_ys = [0.0, top, tex_h - bottom, tex_h];
+    let dst_xs = [0.0, left * scale, dim[0] - right * scale, dim[0]];
+    let dst_ys = [0.0, top * scale, dim[1] - bottom * scale, dim[1]];
+    for row in 0..3 {
+        for col in 0..3 {
+            let src_w = src_xs[col + 1] - src_xs[col];
+            let src_h = src_ys[row + 1] - src_ys[row];
+            let dst_w = dst_xs[col + 1] - dst_xs[col];
+            let dst_h = dst_ys[row + 1] - dst_ys[row];
+            if src_w <= 0.0 || src_h <= 0.0 || dst_w <= 0.0 || dst_h <= 0.0 { continue }
+            let src_rect = [src_xs[col] as i32, src_ys[row] as i32, src_w as i32, src_h as i32];
+            let dst_pos = [pos[0] + dst_xs[col], pos[1] + dst_ys[row]];
+            ui.draw_image(graphics, nine_patch.id, Some(src_rect), dst_pos, [dst_w, dst_h], Some(tint));
+        }
+    }
+}
+
+/// Displays an image registered with the `Ui`'s `ImageMap`.
+#[derive(Copy, Clone)]
+pub struct Image {
+    id: ImageId,
+    pos: Point,
+    maybe_dim: Option<Dimensions>,
+    maybe_src_rect: Option<[i32; 4]>,
+    maybe_color: Option<Color>,
+}
+
+impl Image {
+
+    /// An image builder method to be implemented on the Ui.
+    pub fn new(id: ImageId) -> Image {
+        Image {
+            id: id,
+            pos: [0.0, 0.0],
+            maybe_dim: None,
+            maybe_src_rect: None,
+            maybe_color: None,
+        }
+    }
+
+    /// Only display the part of the texture within the given `[x, y, w, h]`
+    /// rectangle (in pixels).
+    pub fn src_rect(self, rect: [i32; 4]) -> Image {
+        Image { maybe_src_rect: Some(rect), ..self }
+    }
+
+}
+
+impl Colorable for Image {
+    /// Tint the image with the given color.
+    fn color(mut self, color: Color) -> Self {
+        self.maybe_color = Some(color);
+        self
+    }
+}
+
+impl Positionable for Image {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl Shapeable for Image {
+    fn get_dim(&self) -> Dimensions {
+        self.maybe_dim.unwrap_or([0.0, 0.0])
+    }
+    fn dim(mut self, dim: Dimensions) -> Self {
+        self.maybe_dim = Some(dim);
+        self
+    }
+}
+
+impl ::draw::Drawable for Image {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        // Unless given, the dimensions are those of the displayed part of the texture.
+        let dim = match (self.maybe_dim, self.maybe_src_rect) {
+            (Some(dim), _) => dim,
+            (None, Some(rect)) => [rect[2] as f64, rect[3] as f64],
+            (None, None) => match ui.image_map.dimensions(self.id) {
+                Some(dim) => dim,
+                None => return,
+            },
+        };
+        ui.draw_image(graphics, self.id, self.maybe_src_rect, self.pos, dim, self.maybe_color);
+    }
+}
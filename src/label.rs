@@ -1,12 +1,24 @@
+use bidi;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
-use color::{ Color, Colorable };
+use color::{ Color, Colorable, Fadeable };
+use icon;
+use mouse::ButtonState;
+use piston::input::keyboard::Key;
 use point::Point;
 use position::Positionable;
-use ui::Ui;
+use rectangle;
+use text;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use widget::Widget;
 
 pub type FontSize = u32;
 
+/// Identifies one of the fonts added to the `Ui` (see `Ui::add_font`). The
+/// font given when constructing the `Ui` is always `0`.
+pub type FontId = usize;
+
 /// An enum for passing in label information to widget arguments.
 pub enum Labeling<'a> {
     Label(&'a str, FontSize, Color),
@@ -15,11 +27,8 @@ pub enum Labeling<'a> {
 
 /// Determine the pixel width of the final text bitmap.
 #[inline]
-pub fn width<C: CharacterCache>(ui: &mut Ui<C>, size: FontSize, text: &str) -> f64 {
-    text.chars().fold(0u32, |a, ch| {
-        let character = ui.get_character(size, ch);
-        a + character.width() as u32
-    }) as f64
+pub fn width<C: CharacterCache>(ui: &mut Ui<C>, font: FontId, size: FontSize, text: &str) -> f64 {
+    text::width(ui, font, size, text)
 }
 
 /// Determine a suitable FontSize from a given rectangle height.
@@ -29,6 +38,23 @@ pub fn auto_size_from_rect_height(rect_height: f64) -> FontSize {
     if size % 2 == 0 { size } else { size - 1u32 }
 }
 
+/// Represents the state of a selectable Label.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    /// The `(anchor, cursor)` character indices of the selection, if any.
+    maybe_selection: Option<(usize, usize)>,
+    is_dragging: bool,
+    was_mouse_down: bool,
+}
+
+impl State {
+    fn new() -> State {
+        State { maybe_selection: None, is_dragging: false, was_mouse_down: false }
+    }
+}
+
+widget_fns!(Label, State, Widget::Label(State::new()));
+
 /// A trait used for widget types that take a label.
 pub trait Labelable<'a>: Sized {
     fn label(self, text: &'a str) -> Self;
@@ -37,13 +63,14 @@ pub trait Labelable<'a>: Sized {
         self.label_color(Color([r, g, b, a]))
     }
     fn label_font_size(self, size: FontSize) -> Self;
-    fn small_font<C>(self, ui: &Ui<C>) -> Self {
+    fn label_font(self, font: FontId) -> Self;
+    fn small_font<C: CharacterCache>(self, ui: &Ui<C>) -> Self {
         self.label_font_size(ui.theme.font_size_small)
     }
-    fn medium_font<C>(self, ui: &Ui<C>) -> Self {
+    fn medium_font<C: CharacterCache>(self, ui: &Ui<C>) -> Self {
         self.label_font_size(ui.theme.font_size_medium)
     }
-    fn large_font<C>(self, ui: &Ui<C>) -> Self {
+    fn large_font<C: CharacterCache>(self, ui: &Ui<C>) -> Self {
         self.label_font_size(ui.theme.font_size_large)
     }
 }
@@ -53,7 +80,9 @@ pub struct Label<'a> {
     text: &'a str,
     pos: Point,
     size: FontSize,
-    maybe_color: Option<Color>,
+    maybe_icon: Option<char>,
+    maybe_ui_id: Option<UIID>,
+    style: WidgetStyle,
 }
 
 impl<'a> Label<'a> {
@@ -61,6 +90,23 @@ impl<'a> Label<'a> {
     pub fn size(self, size: FontSize) -> Label<'a> {
         Label { size: size, ..self }
     }
+    /// A builder method for specifying the font.
+    pub fn font(mut self, font: FontId) -> Label<'a> {
+        self.style.maybe_font = Some(font);
+        self
+    }
+    /// A builder method for drawing the glyph with the given codepoint from
+    /// the theme's icon font before the text, at the same size.
+    pub fn icon(self, codepoint: char) -> Label<'a> {
+        Label { maybe_icon: Some(codepoint), ..self }
+    }
+    /// A builder method allowing the text to be selected by dragging over it
+    /// and the selection copied to the `Ui`'s clipboard with Ctrl+C (Cmd+C on
+    /// OS X). Selectable labels keep their selection between frames, so they
+    /// require a UIID.
+    pub fn selectable(self, ui_id: UIID) -> Label<'a> {
+        Label { maybe_ui_id: Some(ui_id), ..self }
+    }
 }
 
 impl<'a> Label<'a> {
@@ -71,7 +117,9 @@ impl<'a> Label<'a> {
             text: text,
             pos: [0.0, 0.0],
             size: 24u32,
-            maybe_color: None,
+            maybe_icon: None,
+            maybe_ui_id: None,
+            style: WidgetStyle::new(),
         }
     }
 
@@ -79,7 +127,14 @@ impl<'a> Label<'a> {
 
 impl<'a> Colorable for Label<'a> {
     fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a> Fadeable for Label<'a> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
         self
     }
 }
@@ -97,7 +152,84 @@ impl<'a> ::draw::Drawable for Label<'a> {
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-        let color = self.maybe_color.unwrap_or(Color::black());
-        ui.draw_text(graphics, self.pos, self.size, color, self.text);
+        // Labels are black unless given another color.
+        let defaults = WidgetStyle { maybe_color: Some(Color::black()), ..WidgetStyle::new() };
+        let style = self.style.resolve_with(ui.theme.maybe_label, defaults, &ui.theme);
+        let (color, font) = (style.color, style.font);
+        ui.push_alpha(style.alpha);
+        let mut pos = self.pos;
+        if let Some(codepoint) = self.maybe_icon {
+            let icon_font = ui.theme.font_icon;
+            icon::draw(ui, graphics, pos, icon_font, self.size, color, codepoint);
+            pos[0] += icon::width(ui, icon_font, self.size, codepoint) + ui.theme.padding;
+        }
+        if !self.text.is_empty() {
+            if let Some(ui_id) = self.maybe_ui_id {
+                self.update_selection(ui, graphics, ui_id, pos, font);
+            }
+            let text = bidi::reorder(self.text);
+            ui.draw_text(graphics, pos, font, self.size, color, &text);
+        }
+        ui.pop_alpha();
     }
 }
+
+impl<'a> Label<'a> {
+
+    /// Update the selection of a selectable label from the mouse and keyboard,
+    /// drawing its highlight behind the text.
+    fn update_selection<B, C>(
+        &self,
+        ui: &mut Ui<C>,
+        graphics: &mut B,
+        ui_id: UIID,
+        pos: Point,
+        font: FontId
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let dim = [text::width(ui, font, self.size, self.text), self.size as f64];
+        let state = *get_state(ui, ui_id);
+        let mouse = ui.get_mouse_state();
+        let is_mouse_down = match mouse.left { ButtonState::Down => true, ButtonState::Up => false };
+        let mut new_state = State { was_mouse_down: is_mouse_down, ..state };
+        if is_mouse_down {
+            let (idx, _) = text::closest_index(ui, font, self.size, self.text, mouse.pos[0] - pos[0]);
+            if !state.was_mouse_down {
+                // A new click either begins a selection or clears the last.
+                new_state.is_dragging = rectangle::is_over(pos, mouse.pos, dim);
+                new_state.maybe_selection = match new_state.is_dragging {
+                    true => Some((idx, idx)),
+                    false => None,
+                };
+            } else if let (true, Some((anchor, _))) = (state.is_dragging, state.maybe_selection) {
+                new_state.maybe_selection = Some((anchor, idx));
+            }
+        } else {
+            new_state.is_dragging = false;
+        }
+
+        if let Some((anchor, cursor)) = new_state.maybe_selection {
+            let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+            if start != end {
+                let start_x = text::cursor_x(ui, font, self.size, self.text, start);
+                let end_x = text::cursor_x(ui, font, self.size, self.text, end);
+                let color = ui.theme.shape_color.highlighted();
+                rectangle::draw(ui, graphics, rectangle::State::Normal,
+                                [pos[0] + start_x.min(end_x), pos[1]],
+                                [(end_x - start_x).abs(), dim[1]], None, 0.0, color);
+                let is_copying = ui.is_shortcut_modifier_held()
+                    && ui.keys_just_pressed.contains(&Key::C);
+                if is_copying {
+                    let selected = self.text.chars().skip(start).take(end - start).collect();
+                    ui.clipboard().set_contents(selected);
+                }
+            }
+        }
+
+        set_state(ui, ui_id, Widget::Label(new_state), pos, dim);
+    }
+
+}
@@ -0,0 +1,143 @@
+use callback::NoCallback;
+use color::Colorable;
+use composite::{ IdRange, forward_label_style, forward_style };
+use dimensions::Dimensions;
+use draw::Drawable;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ self, Label };
+use num::{ Float, FromPrimitive, ToPrimitive };
+use point::Point;
+use position::Positionable;
+use shape::Shapeable;
+use slider::{ self, Slider };
+use text_box::{ self, TextBox };
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+
+/// The index of each part within the composite's range of UIIDs.
+const SLIDER: usize = 1;
+const TEXT_BOX: usize = 2;
+const NUM_IDS: usize = 3;
+
+/// A slider with a label to its left and a text box to its right, which
+/// displays the value and in which a value may be typed.
+///
+/// The labeled slider claims three consecutive UIIDs beginning with the one
+/// given (see `LabeledSlider::ids`). Its color, frame and label styling are
+/// forwarded to the slider and text box.
+pub struct LabeledSlider<'a, T> {
+    ids: IdRange,
+    value: T,
+    min: T,
+    max: T,
+    label: &'a str,
+    pos: Point,
+    dim: Dimensions,
+    style: WidgetStyle,
+    value_w: f64,
+    precision: usize,
+}
+
+impl<'a, T> LabeledSlider<'a, T> {
+
+    /// Create a labeled slider context to be built upon.
+    pub fn new(ui_id: UIID, label: &'a str, value: T, min: T, max: T) -> LabeledSlider<'a, T> {
+        LabeledSlider {
+            ids: IdRange::new(ui_id, NUM_IDS),
+            value: value,
+            min: min,
+            max: max,
+            label: label,
+            pos: [0.0, 0.0],
+            dim: [320.0, 32.0],
+            style: WidgetStyle::new(),
+            value_w: 64.0,
+            precision: 2,
+        }
+    }
+
+    /// A builder method for the width of the text box.
+    pub fn value_width(self, width: f64) -> LabeledSlider<'a, T> {
+        LabeledSlider { value_w: width, ..self }
+    }
+
+    /// A builder method for the number of fractional digits displayed in the text box.
+    pub fn precision(self, precision: usize) -> LabeledSlider<'a, T> {
+        LabeledSlider { precision: precision, ..self }
+    }
+
+    /// The UIIDs claimed by the labeled slider. The next widget may use `ids().next()`.
+    pub fn ids(&self) -> IdRange {
+        self.ids
+    }
+
+}
+
+composite_builder_impls!(LabeledSlider<'a, T>);
+
+impl<'a, T> LabeledSlider<'a, T>
+    where
+        T: Float + FromPrimitive + ToPrimitive
+{
+
+    /// Draw the labeled slider, returning what happened to its slider. A
+    /// value typed into the text box is given as `slider::Event::Released`.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<slider::Event<T>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_slider, &ui.theme);
+        let pad = ui.theme.padding;
+        let size = style.label_font_size;
+        ui.push_alpha(style.alpha);
+
+        let label_w = label::width(ui, style.font, size, self.label);
+        Label::new(self.label)
+            .point([self.pos[0], self.pos[1] + (self.dim[1] - size as f64) / 2.0])
+            .size(size)
+            .color(style.label_color)
+            .draw(ui, graphics);
+
+        let slider_w = (self.dim[0] - label_w - self.value_w - pad * 2.0).max(0.0);
+        let slider = Slider::<T, NoCallback>::new(self.ids.get(SLIDER), self.value, self.min, self.max)
+            .point([self.pos[0] + label_w + pad, self.pos[1]])
+            .dim([slider_w, self.dim[1]]);
+        let mut maybe_event = forward_label_style(forward_style(slider, &self.style), &self.style)
+            .react(ui, graphics);
+        let value = maybe_event.map_or(self.value, |event| event.value());
+
+        // The composite's own UIID keeps the text box's text. While a value
+        // isn't being typed, the text box displays the slider's value.
+        let text_box_id = self.ids.get(TEXT_BOX);
+        let mut text = ui.take_widget_memory::<String>(self.ids.own()).unwrap_or_else(String::new);
+        if ui.text_focus() != Some(text_box_id) {
+            text = format!("{:.*}", self.precision, value.to_f64().unwrap());
+        }
+        let maybe_text_event = {
+            let text_box = TextBox::<NoCallback>::new(text_box_id, &mut text)
+                .font_size(size)
+                .point([self.pos[0] + self.dim[0] - self.value_w, self.pos[1]])
+                .dim([self.value_w, self.dim[1]]);
+            forward_style(text_box, &self.style).react(ui, graphics)
+        };
+        if let Some(text_box::Event::Submitted) = maybe_text_event {
+            let (min, max) = (self.min.to_f64().unwrap(), self.max.to_f64().unwrap());
+            let maybe_typed = text.trim().parse::<f64>().ok()
+                .and_then(|typed| FromPrimitive::from_f64(clamp(typed, min, max)));
+            if let Some(typed) = maybe_typed {
+                maybe_event = Some(slider::Event::Released(typed));
+            }
+        }
+        ui.pop_alpha();
+        ui.set_widget_memory(self.ids.own(), text);
+        ui.set_place(self.ids.own(), self.pos, self.dim);
+        ui.set_parent(self.ids.get(SLIDER), self.ids.own());
+        ui.set_parent(text_box_id, self.ids.own());
+
+        maybe_event
+    }
+
+}
@@ -12,55 +12,147 @@ extern crate num;
 
 pub use background::Background;
 pub use button::Button;
+pub use console::Console;
+pub use curve_editor::CurveEditor;
+pub use curve_editor::CurvePoint;
+pub use custom::Custom;
+pub use deferred::Deferred;
+pub use dock::Dock;
+pub use dock::DockLayout;
+pub use drag::Drag;
 pub use drop_down_list::DropDownList;
 pub use envelope_editor::EnvelopeEditor;
 pub use envelope_editor::EnvelopePoint;
+pub use form::Form;
+pub use gallery::Gallery;
+pub use heatmap::Heatmap;
 pub use label::Label;
+pub use labeled_slider::LabeledSlider;
+pub use line::PointPath;
+pub use link::Link;
+pub use node_editor::NodeEditor;
+pub use num_pad::NumPad;
 pub use number_dialer::NumberDialer;
+pub use number_format::NumberFormat;
+pub use rating::Rating;
+pub use reorderable_list::ReorderableList;
+pub use ruler::Ruler;
 pub use slider::Slider;
+pub use sparkline::Sparkline;
+pub use split_button::SplitButton;
 pub use text_box::TextBox;
+pub use timeline::Timeline;
 pub use toggle::Toggle;
+pub use viewport::Viewport;
 pub use widget_matrix::WidgetMatrix;
 pub use xy_pad::XYPad;
 
-pub use callback::Callable;
-pub use color::{Color, Colorable};
+pub use callback::{Callable, NoCallback, Reactable};
+pub use clipboard::{Clipboard, LocalClipboard};
+pub use clock::{Clock, TimeSource};
+pub use cursor::MouseCursor;
+pub use composite::IdRange;
+pub use color::{Color, Colorable, Fadeable, Fill, Gradient, GradientDirection};
+pub use custom_widget::{CustomWidget, DrawArgs, UpdateArgs};
 pub use dimensions::Dimensions;
 pub use draw::Drawable;
 pub use frame::{Framing, Frameable};
-pub use label::Labelable;
+pub use glyph_run::{GlyphRun, KerningTable};
+pub use history::History;
+pub use image::{Image, ImageId, ImageMap, NinePatch};
+pub use label::{FontId, Labelable};
+pub use locale::Locale;
 pub use point::Point;
+pub use rectangle::Shadow;
+pub use render_cache::{RenderCache, RenderTarget};
+pub use scale::Scale;
+pub use scroll::Kinetic;
 pub use position::Positionable;
+pub use property::{Bindable, Property};
 pub use shape::Shapeable;
-pub use theme::Theme;
-pub use ui::Ui;
+pub use shortcuts::{Shortcut, Shortcuts};
+pub use stats::FrameStats;
+pub use theme::{Style, Theme, WidgetStyle};
+pub use trace::TraceEvent;
+pub use ui::{Diagnostic, Ui};
 pub use widget::Widget;
 
 #[macro_use]
 pub mod macros;
 
+pub mod animation;
+pub mod arena;
 pub mod background;
+pub mod bezier;
+pub mod bidi;
 pub mod button;
 pub mod callback;
+pub mod clipboard;
+pub mod clock;
+pub mod composite;
 pub mod color;
+pub mod console;
+pub mod cursor;
+pub mod curve_editor;
+pub mod custom;
+pub mod custom_widget;
+pub mod deferred;
+pub mod dock;
+pub mod drag;
 pub mod dimensions;
 pub mod draw;
 pub mod drop_down_list;
 pub mod envelope_editor;
+pub mod event;
+pub mod form;
 pub mod frame;
+pub mod gallery;
+pub mod glyph_run;
+pub mod heatmap;
+pub mod history;
+pub mod icon;
+pub mod image;
 pub mod label;
+pub mod labeled_slider;
+pub mod line;
+pub mod link;
+pub mod locale;
+pub mod marquee;
 pub mod mouse;
+pub mod node_editor;
+pub mod num_pad;
 pub mod number_dialer;
+pub mod number_format;
+pub mod oval;
+pub mod persist;
 pub mod point;
+pub mod polygon;
 pub mod position;
+pub mod primitive;
+pub mod property;
+pub mod rating;
 pub mod rectangle;
+pub mod render_cache;
+pub mod reorderable_list;
+pub mod ruler;
+pub mod scale;
+pub mod scroll;
 pub mod shape;
+pub mod shortcuts;
 pub mod slider;
+pub mod sparkline;
+pub mod split_button;
+pub mod stats;
+pub mod testing;
+pub mod text;
 pub mod text_box;
 pub mod theme;
+pub mod timeline;
 pub mod toggle;
+pub mod trace;
 pub mod ui;
 pub mod utils;
+pub mod viewport;
 pub mod widget;
 pub mod widget_matrix;
 pub mod xy_pad;
@@ -0,0 +1,140 @@
+
+use color::{ Color, Colorable };
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use ui::Ui;
+
+/// The style of the ends of each line segment.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Cap {
+    Flat,
+    Round,
+}
+
+/// A context on which the builder pattern can be implemented for drawing a
+/// path of straight lines between a series of points.
+pub struct PointPath<'a> {
+    points: &'a [Point],
+    thickness: f64,
+    cap: Cap,
+    maybe_color: Option<Color>,
+    maybe_dash: Option<(f64, f64)>,
+    maybe_arrow_head: Option<f64>,
+}
+
+impl<'a> PointPath<'a> {
+
+    /// Create a path between the given points to be built upon.
+    pub fn new(points: &'a [Point]) -> PointPath<'a> {
+        PointPath {
+            points: points,
+            thickness: 1.0,
+            cap: Cap::Flat,
+            maybe_color: None,
+            maybe_dash: None,
+            maybe_arrow_head: None,
+        }
+    }
+
+    /// A builder method for specifying the thickness of the lines.
+    pub fn thickness(self, thickness: f64) -> PointPath<'a> {
+        PointPath { thickness: thickness, ..self }
+    }
+
+    /// A builder method for specifying the style of the ends of each line.
+    pub fn cap(self, cap: Cap) -> PointPath<'a> {
+        PointPath { cap: cap, ..self }
+    }
+
+    /// A builder method for dashing the path with dashes of the given length
+    /// separated by gaps of the given length.
+    pub fn dashed(self, dash: f64, gap: f64) -> PointPath<'a> {
+        PointPath { maybe_dash: Some((dash, gap)), ..self }
+    }
+
+    /// A builder method for ending the path with an arrow head of the given length.
+    pub fn arrow_head(self, length: f64) -> PointPath<'a> {
+        PointPath { maybe_arrow_head: Some(length), ..self }
+    }
+
+}
+
+impl<'a> Colorable for PointPath<'a> {
+    fn color(mut self, color: Color) -> Self {
+        self.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a> ::draw::Drawable for PointPath<'a> {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if self.points.len() < 2 { return }
+        let color = self.maybe_color.unwrap_or(Color::black());
+        let round = self.cap == Cap::Round;
+        let mut points = ui.scratch_points();
+        points.extend(self.points.iter().cloned());
+
+        // Shorten the final segment so that it ends at the base of the arrow head.
+        let maybe_head = self.maybe_arrow_head.and_then(|length| {
+            let len = points.len();
+            let (from, tip) = (points[len - 2], points[len - 1]);
+            let (dx, dy) = (tip[0] - from[0], tip[1] - from[1]);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance == 0.0 { return None }
+            let (ux, uy) = (dx / distance, dy / distance);
+            let length = length.min(distance);
+            let base = [tip[0] - ux * length, tip[1] - uy * length];
+            let half_w = length / 2.0;
+            points[len - 1] = base;
+            Some([tip,
+                  [base[0] - uy * half_w, base[1] + ux * half_w],
+                  [base[0] + uy * half_w, base[1] - ux * half_w]])
+        });
+
+        let segments = match self.maybe_dash {
+            Some((dash, gap)) => dashes(&points, dash, gap),
+            None => points.windows(2).map(|w| [w[0][0], w[0][1], w[1][0], w[1][1]]).collect(),
+        };
+        for &segment in segments.iter() {
+            ui.draw_line(graphics, segment, self.thickness, round, color);
+        }
+        if let Some(head) = maybe_head {
+            ui.draw_polygon(graphics, &head, color);
+        }
+        ui.recycle_points(points);
+    }
+}
+
+/// Split the path between the given points into dashes of the given length
+/// separated by gaps of the given length. The pattern continues around corners.
+fn dashes(points: &[Point], dash: f64, gap: f64) -> Vec<[f64; 4]> {
+    let mut segments = Vec::new();
+    if dash <= 0.0 { return segments }
+    let period = dash + gap.max(0.0);
+    // The distance along the dash pattern at the start of the current segment.
+    let mut offset = 0.0;
+    for w in points.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 { continue }
+        let at = |d: f64| [a[0] + dx * d / length, a[1] + dy * d / length];
+        // Walk each dash that overlaps this segment.
+        let mut d = -(offset % period);
+        while d < length {
+            let (start, end) = (d.max(0.0), (d + dash).min(length));
+            if end > start {
+                let (p, q) = (at(start), at(end));
+                segments.push([p[0], p[1], q[0], q[1]]);
+            }
+            d += period;
+        }
+        offset += length;
+    }
+    segments
+}
@@ -0,0 +1,243 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::{ Color, Colorable, Fadeable };
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize };
+use mouse::ButtonState::{ Down, Up };
+use piston::input::keyboard::Key;
+use point::Point;
+use position::Positionable;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use widget::Widget;
+
+/// What the mouse is doing to the Link.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted,
+    Clicked,
+}
+
+/// Represents the state of the Link widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    pub interaction: Interaction,
+    /// Whether or not the link has the keyboard, so that it may be activated
+    /// with Return or Space.
+    pub focused: bool,
+}
+
+widget_fns!(Link, State, Widget::Link(State { interaction: Interaction::Normal, focused: false }));
+
+/// What happened to the Link, returned by `Link::react` and given to its
+/// `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The link was clicked, or Return or Space was pressed while it had
+    /// the keyboard.
+    Activated,
+}
+
+/// A line of text in the theme's link color which is underlined while
+/// hovered, i.e. for opening a URL or navigating to another view. Clicking
+/// the link gives it the keyboard until the mouse is pressed elsewhere or
+/// Escape is pressed, and while it has the keyboard it is outlined and may
+/// be activated again with Return or Space.
+pub struct Link<'a, F> {
+    ui_id: UIID,
+    text: &'a str,
+    pos: Point,
+    maybe_size: Option<FontSize>,
+    maybe_focus: Option<bool>,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> Link<'a, F> {
+
+    /// Create a link context to be built upon.
+    pub fn new(ui_id: UIID, text: &'a str) -> Link<'a, F> {
+        Link {
+            ui_id: ui_id,
+            text: text,
+            pos: [0.0, 0.0],
+            maybe_size: None,
+            maybe_focus: None,
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// A builder method for specifying font_size.
+    pub fn size(self, size: FontSize) -> Link<'a, F> {
+        Link { maybe_size: Some(size), ..self }
+    }
+
+    /// A builder method for specifying the font.
+    pub fn font(mut self, font: FontId) -> Link<'a, F> {
+        self.style.maybe_font = Some(font);
+        self
+    }
+
+    /// Give the link the keyboard or take it away, i.e. as the application
+    /// moves focus between its widgets with Tab.
+    pub fn focused(self, focused: bool) -> Link<'a, F> {
+        Link { maybe_focus: Some(focused), ..self }
+    }
+
+}
+
+impl<'a, F> Colorable for Link<'a, F> {
+    fn color(mut self, color: Color) -> Self {
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a, F> Fadeable for Link<'a, F> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
+        self
+    }
+}
+
+impl<'a, F> Callable<F> for Link<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for Link<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Positionable for Link<'a, F> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for Link<'a, F> where F: FnMut() + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Activated) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)() }
+        }
+    }
+}
+
+impl<'a> Link<'a, NoCallback> {
+
+    /// Draw the link, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the link needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Link<'a, F> {
+
+    /// Update and draw the link, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        // Links are drawn in the theme's link color unless given another.
+        let defaults = WidgetStyle { maybe_color: Some(ui.theme.link_color), ..WidgetStyle::new() };
+        let style = self.style.resolve_with(ui.theme.maybe_link, defaults, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let color = style.color;
+        let size = self.maybe_size.unwrap_or(style.label_font_size);
+        let dim = [ui.text_width(style.font, size, self.text), size as f64];
+        let is_over = rectangle::is_over(self.pos, mouse.pos, dim);
+
+        let interaction = match (is_over, state.interaction, mouse.left) {
+            (true,  Interaction::Normal,  Down) => Interaction::Normal,
+            (true,  _,                    Down) => Interaction::Clicked,
+            (true,  _,                    Up)   => Interaction::Highlighted,
+            (false, Interaction::Clicked, Down) => Interaction::Clicked,
+            _                                   => Interaction::Normal,
+        };
+        let mut maybe_event = match (is_over, state.interaction, interaction) {
+            (true, Interaction::Clicked, Interaction::Highlighted) => Some(Event::Activated),
+            _ => None,
+        };
+        if is_over {
+            ui.set_mouse_cursor(MouseCursor::Hand);
+        }
+
+        // Pressing the link gives it the keyboard and pressing elsewhere takes it away.
+        let is_input_free = ui.text_input_consumer().map_or(true, |ui_id| ui_id == self.ui_id);
+        let mut focused = match (interaction, mouse.left) {
+            (Interaction::Clicked, _) => true,
+            (_, Down) => false,
+            _ => state.focused,
+        };
+        focused = self.maybe_focus.unwrap_or(focused);
+        if focused && is_input_free {
+            let key_count = ui.pressed_keys().len();
+            for i in 0..key_count {
+                match ui.pressed_keys()[i] {
+                    Key::Return | Key::Space => maybe_event = Some(Event::Activated),
+                    Key::Escape => focused = false,
+                    _ => (),
+                }
+            }
+            if maybe_event.is_some() {
+                ui.consume_text_input(self.ui_id);
+            }
+        }
+        let new_state = State { interaction: interaction, focused: focused };
+
+        // Draw the text, underlined while hovered and outlined while focused.
+        let text_color = match interaction {
+            Interaction::Clicked => rectangle::State::Clicked.color(color),
+            _ => color,
+        };
+        ui.draw_text(graphics, self.pos, style.font, size, text_color, self.text);
+        if interaction != Interaction::Normal {
+            let y = self.pos[1] + dim[1] + 1.0;
+            ui.draw_line(graphics, [self.pos[0], y, self.pos[0] + dim[0], y], 1.0, false, text_color);
+        }
+        if focused {
+            let gap = ui.theme.padding / 2.0;
+            let (l, t) = (self.pos[0] - gap, self.pos[1] - gap);
+            let (r, b) = (self.pos[0] + dim[0] + gap, self.pos[1] + dim[1] + gap);
+            for &line in [[l, t, r, t], [r, t, r, b], [r, b, l, b], [l, b, l, t]].iter() {
+                ui.draw_line(graphics, line, 1.0, false, color.fade(0.6));
+            }
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Link(new_state), self.pos, dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
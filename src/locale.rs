@@ -0,0 +1,79 @@
+//! Translations of the strings displayed by the `Ui`.
+//!
+//! Strings are looked up by key via `Ui::tr`, with the English text serving
+//! as the key. A key without a translation for the current language is
+//! displayed as it is, so an application only needs to provide translations
+//! for the languages it ships besides English.
+//!
+//! ```ignore
+//! ui.locale.add("de", "Cancel", "Abbrechen");
+//! ui.locale.set_language("de");
+//! Button::new(CANCEL).label(ui.tr("Cancel"))
+//! ```
+
+use std::collections::HashMap;
+
+/// The current language along with the translations for each language.
+#[derive(Clone, Debug)]
+pub struct Locale {
+    language: String,
+    /// The translation of each key, for each language.
+    translations: HashMap<String, HashMap<String, String>>,
+}
+
+impl Locale {
+
+    /// Construct a locale without any translations for the given language.
+    pub fn new(language: &str) -> Locale {
+        Locale {
+            language: language.to_string(),
+            translations: HashMap::new(),
+        }
+    }
+
+    /// The current language.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Switch to the given language. Strings are displayed in the new
+    /// language from the next time their widgets are drawn.
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    /// Add the translation of the key for the given language, replacing any
+    /// translation already added.
+    pub fn add(&mut self, language: &str, key: &str, text: &str) {
+        self.translations.entry(language.to_string()).or_insert_with(HashMap::new)
+            .insert(key.to_string(), text.to_string());
+    }
+
+    /// Add each of the translations for the given language.
+    pub fn add_all<'a, I>(&mut self, language: &str, translations: I)
+        where I: IntoIterator<Item = (&'a str, &'a str)>
+    {
+        for (key, text) in translations {
+            self.add(language, key, text);
+        }
+    }
+
+    /// Forget the translations for the given language.
+    pub fn remove_language(&mut self, language: &str) {
+        self.translations.remove(language);
+    }
+
+    /// Whether or not there are any translations for the given language.
+    pub fn has_language(&self, language: &str) -> bool {
+        self.translations.contains_key(language)
+    }
+
+    /// The translation of the key for the current language, or the key
+    /// itself if it hasn't been translated.
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.translations.get(&self.language)
+            .and_then(|translations| translations.get(key))
+            .map_or(key, |text| &text[..])
+    }
+
+}
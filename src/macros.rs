@@ -7,44 +7,125 @@ macro_rules! widget_fns(
         fn default() -> ::widget::Widget { $default }
 
         /// Get a reference to the widget associated with the given UIID.
-        fn get_widget<C>(
+        fn get_widget<C: ::graphics::character::CharacterCache>(
             ui: &mut ::ui::Ui<C>,
             ui_id: ::ui::UIID
         ) -> &mut ::widget::Widget {
             ui.get_widget(ui_id, default())
         }
 
-        /// Get the current State for the widget.
-        fn get_state<C>(
+        /// Get the current State for the widget. If the UIID belonged to a
+        /// different kind of widget, its state is reset to the default and the
+        /// conflict is listed in `Ui::diagnostics`.
+        fn get_state<C: ::graphics::character::CharacterCache>(
             ui: &mut ::ui::Ui<C>,
             ui_id: ::ui::UIID
         ) -> &$widget_state {
             match *get_widget(ui, ui_id) {
                 ::widget::Widget::$widget(ref state) => state,
-                _ => panic!("The Widget variant returned by uiontext is different to that which \
-                           was requested (Check that there are no UIID conflicts)."),
+                _ => unreachable!(),
             }
         }
 
         /// Set the state for the widget in the Ui.
-        fn set_state<C>(
+        fn set_state<C: ::graphics::character::CharacterCache>(
             ui: &mut ::ui::Ui<C>,
             ui_id: ::ui::UIID,
             new_state: ::widget::Widget,
             pos: ::point::Point,
             dim: ::dimensions::Dimensions
         ) {
-            match *get_widget(ui, ui_id) {
-                ref mut state => {
-                    if !state.matches(&new_state) {
-                        panic!("The Widget variant returned by Ui is different to that which \
-                                   was requested (Check that there are no UIID conflicts).");
-                    }
-                    *state = new_state;
-                }
-            }
+            let prev_state = ::std::mem::replace(get_widget(ui, ui_id), new_state);
+            ui.record_transition(ui_id, prev_state, new_state);
             ui.set_place(ui_id, pos, dim);
         }
 
     )
 );
+
+/// Implement `Positionable`, `Shapeable`, `Colorable`, `Fadeable` and
+/// `Frameable` for a widget builder with `pos`, `dim` and
+/// `style: WidgetStyle` fields. A composite's styling may then be forwarded
+/// to the widgets of which it is composed (see `composite::forward_style`).
+/// Bounds on the builder's generics may follow a `where`.
+///
+/// ```ignore
+/// composite_builder_impls!(LabeledSlider<'a, T>);
+/// composite_builder_impls!(EnvelopeEditor<'a, E, F> where E: EnvelopePoint);
+/// ```
+#[macro_export]
+macro_rules! composite_builder_impls(
+    ($composite:ident < $($generic:tt),* >) => (
+        composite_builder_impls!($composite<$($generic),*> where);
+    );
+    ($composite:ident < $($generic:tt),* > where $($bound:tt)*) => (
+
+        impl<$($generic),*> $crate::position::Positionable for $composite<$($generic),*>
+            where $($bound)*
+        {
+            fn point(mut self, pos: $crate::point::Point) -> Self {
+                self.pos = pos;
+                self
+            }
+        }
+
+        impl<$($generic),*> $crate::shape::Shapeable for $composite<$($generic),*>
+            where $($bound)*
+        {
+            fn get_dim(&self) -> $crate::dimensions::Dimensions { self.dim }
+            fn dim(mut self, dim: $crate::dimensions::Dimensions) -> Self { self.dim = dim; self }
+        }
+
+        impl<$($generic),*> $crate::color::Colorable for $composite<$($generic),*>
+            where $($bound)*
+        {
+            fn color(mut self, color: $crate::color::Color) -> Self {
+                self.style.maybe_color = Some(color);
+                self
+            }
+        }
+
+        impl<$($generic),*> $crate::color::Fadeable for $composite<$($generic),*>
+            where $($bound)*
+        {
+            fn alpha(mut self, alpha: f32) -> Self {
+                self.style.maybe_alpha = Some(alpha);
+                self
+            }
+        }
+
+        impl<$($generic),*> $crate::frame::Frameable for $composite<$($generic),*>
+            where $($bound)*
+        {
+            fn frame(mut self, width: f64) -> Self {
+                self.style.maybe_frame = Some(width);
+                self
+            }
+            fn frame_color(mut self, color: $crate::color::Color) -> Self {
+                self.style.maybe_frame_color = Some(color);
+                self
+            }
+            fn gradient(mut self, gradient: $crate::color::Gradient) -> Self {
+                self.style.maybe_gradient = Some(gradient);
+                self
+            }
+            fn corner_radius(mut self, radius: f64) -> Self {
+                self.style.maybe_corner_radius = Some(radius);
+                self
+            }
+            fn shadow(mut self, offset: [f64; 2], softness: f64, color: $crate::color::Color) -> Self {
+                self.style.maybe_shadow = Some($crate::rectangle::Shadow {
+                    offset: offset,
+                    softness: softness,
+                    color: color,
+                });
+                self
+            }
+            fn background_image(mut self, id: $crate::image::ImageId, insets: [f64; 4]) -> Self {
+                self.style.maybe_background_image = Some($crate::image::NinePatch { id: id, insets: insets });
+                self
+            }
+        }
+
+    )
+);
@@ -0,0 +1,108 @@
+//! Rubber band selection, in which pressing on an empty part of a widget and
+//! dragging draws a translucent rectangle from where the press began, and
+//! whatever the rectangle overlaps is selected.
+//!
+//! A `Marquee` is kept as part of a widget's state. The widget begins one
+//! with `Marquee::begin` when the left button is pressed over the region in
+//! which selecting is allowed (i.e. between the items rather than on one),
+//! then calls `update` each frame with the mouse position, which reports
+//! the rectangle as it changes and once more when the button is released.
+//! The positions may be those of the screen, or of the widget's scrolled or
+//! zoomed content so that the rectangle stays anchored to the content.
+
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use mouse::ButtonState;
+use point::Point;
+use ui::Ui;
+
+/// A selection rectangle, which may be being dragged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Marquee {
+    /// Where the drag began and where the mouse was last, while dragging.
+    maybe_drag: Option<(Point, Point)>,
+}
+
+/// What happened to the Marquee's rectangle, returned by `Marquee::update`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The rectangle was dragged to the given position and dimensions.
+    Changed(Point, Dimensions),
+    /// The button was released with the rectangle at the given position and
+    /// dimensions.
+    Finished(Point, Dimensions),
+}
+
+impl Marquee {
+
+    /// A marquee that isn't being dragged.
+    pub fn new() -> Marquee {
+        Marquee { maybe_drag: None }
+    }
+
+    /// A marquee dragged from the given position.
+    pub fn begin(anchor: Point) -> Marquee {
+        Marquee { maybe_drag: Some((anchor, anchor)) }
+    }
+
+    /// Whether or not the marquee is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.maybe_drag.is_some()
+    }
+
+    /// The position and dimensions of the rectangle, while it is being dragged.
+    pub fn rect(&self) -> Option<(Point, Dimensions)> {
+        self.maybe_drag.map(|(anchor, last)| rect(anchor, last))
+    }
+
+    /// Stretch the rectangle to the given position while the button is held,
+    /// and finish it once released.
+    pub fn update(self, pos: Point, button: ButtonState) -> (Marquee, Option<Event>) {
+        match (self.maybe_drag, button) {
+            (None, _) => (self, None),
+            (Some((anchor, last)), ButtonState::Down) => {
+                let maybe_event = match pos != last {
+                    true => {
+                        let (rect_pos, rect_dim) = rect(anchor, pos);
+                        Some(Event::Changed(rect_pos, rect_dim))
+                    },
+                    false => None,
+                };
+                (Marquee { maybe_drag: Some((anchor, pos)) }, maybe_event)
+            },
+            (Some((anchor, _)), ButtonState::Up) => {
+                let (rect_pos, rect_dim) = rect(anchor, pos);
+                (Marquee::new(), Some(Event::Finished(rect_pos, rect_dim)))
+            },
+        }
+    }
+
+}
+
+/// The rectangle with the given opposite corners.
+pub fn rect(a: Point, b: Point) -> (Point, Dimensions) {
+    ([a[0].min(b[0]), a[1].min(b[1])], [(a[0] - b[0]).abs(), (a[1] - b[1]).abs()])
+}
+
+/// Whether or not the two rectangles overlap, i.e. whether a marquee
+/// selects an item.
+pub fn overlaps(a_pos: Point, a_dim: Dimensions, b_pos: Point, b_dim: Dimensions) -> bool {
+    a_pos[0] < b_pos[0] + b_dim[0] && b_pos[0] < a_pos[0] + a_dim[0]
+        && a_pos[1] < b_pos[1] + b_dim[1] && b_pos[1] < a_pos[1] + a_dim[1]
+}
+
+/// Draw a selection rectangle at the given screen position, translucently
+/// filled and outlined with the given color.
+pub fn draw<B, C>(ui: &mut Ui<C>, graphics: &mut B, pos: Point, dim: Dimensions, color: Color)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    ui.draw_rectangle(graphics, pos, dim, color.fade(0.15));
+    let (l, t, r, b) = (pos[0], pos[1], pos[0] + dim[0], pos[1] + dim[1]);
+    for &line in [[l, t, r, t], [r, t, r, b], [r, b, l, b], [l, b, l, t]].iter() {
+        ui.draw_line(graphics, line, 1.0, false, color);
+    }
+}
@@ -2,12 +2,20 @@
 use point::Point;
 
 /// Represents the current state of a mouse button.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ButtonState {
     Up,
     Down,
 }
 
+/// One of the buttons of the Mouse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Button {
+    Left,
+    Middle,
+    Right,
+}
+
 /// Represents the current state of the Mouse.
 #[derive(Copy, Clone)]
 pub struct Mouse {
@@ -15,6 +23,8 @@ pub struct Mouse {
     pub left: ButtonState,
     pub middle: ButtonState,
     pub right: ButtonState,
+    /// The distance scrolled by the mouse wheel (or trackpad) since the last frame.
+    pub scroll: [f64; 2],
 }
 
 impl Mouse {
@@ -23,6 +33,26 @@ impl Mouse {
                left: ButtonState,
                middle: ButtonState,
                right: ButtonState) -> Mouse {
-        Mouse { pos: pos, left: left, middle: middle, right: right }
+        Mouse { pos: pos, left: left, middle: middle, right: right, scroll: [0.0, 0.0] }
+    }
+
+    /// The state of the given button.
+    pub fn button(&self, button: Button) -> ButtonState {
+        match button {
+            Button::Left => self.left,
+            Button::Middle => self.middle,
+            Button::Right => self.right,
+        }
+    }
+
+    /// The right or middle button, if either is held down (the right taking
+    /// precedence), for widgets offering alternate actions such as context
+    /// menus.
+    pub fn alt_button_down(&self) -> Option<Button> {
+        match (self.right, self.middle) {
+            (ButtonState::Down, _) => Some(Button::Right),
+            (_, ButtonState::Down) => Some(Button::Middle),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,658 @@
+use bezier;
+use callback::Reactable;
+use color::Color;
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use marquee::{ self, Marquee };
+use mouse::ButtonState::{ Down, Up };
+use oval;
+use point::Point;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+use widget::Widget;
+
+/// The width of each node on the canvas, before zooming.
+const NODE_WIDTH: f64 = 140.0;
+
+/// The radius of each port, before zooming.
+const PORT_RADIUS: f64 = 5.0;
+
+/// The factor by which each step of the mouse wheel zooms.
+const ZOOM_STEP: f64 = 1.1;
+
+/// The least and greatest zoom of the canvas.
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 4.0;
+
+/// A named input or output of a node. Outputs may only be connected to
+/// inputs of the same kind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Port {
+    pub name: String,
+    pub kind: String,
+}
+
+impl Port {
+    /// A port of the given kind.
+    pub fn new(name: &str, kind: &str) -> Port {
+        Port { name: name.to_string(), kind: kind.to_string() }
+    }
+}
+
+/// A box on the canvas with inputs down its left edge and outputs down its
+/// right edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    /// The top left of the node on the canvas.
+    pub pos: Point,
+    pub title: String,
+    pub inputs: Vec<Port>,
+    pub outputs: Vec<Port>,
+    pub selected: bool,
+}
+
+impl Node {
+
+    /// A node without any ports, with its top left at the given canvas position.
+    pub fn new(title: &str, pos: Point) -> Node {
+        Node {
+            pos: pos,
+            title: title.to_string(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            selected: false,
+        }
+    }
+
+    /// Add an input of the given kind.
+    pub fn input(mut self, name: &str, kind: &str) -> Node {
+        self.inputs.push(Port::new(name, kind));
+        self
+    }
+
+    /// Add an output of the given kind.
+    pub fn output(mut self, name: &str, kind: &str) -> Node {
+        self.outputs.push(Port::new(name, kind));
+        self
+    }
+
+}
+
+/// A wire from an output of one node to an input of another.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Connection {
+    pub from_node: usize,
+    pub from_port: usize,
+    pub to_node: usize,
+    pub to_port: usize,
+}
+
+/// Whether a port is an input or an output.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Side {
+    Input,
+    Output,
+}
+
+/// A port of a node.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PortRef {
+    pub node: usize,
+    pub side: Side,
+    pub port: usize,
+}
+
+/// The elements of the NodeEditor beneath the mouse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Element {
+    Canvas,
+    Node(usize),
+    Port(PortRef),
+}
+
+/// What the mouse is doing to the NodeEditor.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Element),
+    /// The selected nodes are being dragged, with the mouse last at the
+    /// given canvas position.
+    Dragging(Point),
+    /// A wire is being dragged from the given port.
+    Wiring(PortRef),
+    /// A marquee is being dragged over the canvas, anchored to canvas
+    /// positions so that it follows the canvas as it zooms.
+    Selecting(Marquee),
+    /// The canvas is being panned with the middle button, with the mouse
+    /// last at the given position.
+    Panning(Point),
+    /// The given wire replaced the one connected to its input, whose
+    /// disconnection was reported first. Its connection is reported next.
+    Replaced(Connection),
+}
+
+/// The part of the canvas that is visible.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct View {
+    /// The canvas position at the top left of the widget.
+    pub offset: Point,
+    pub zoom: f64,
+}
+
+/// Represents the state of the NodeEditor widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    pub view: View,
+    pub interaction: Interaction,
+}
+
+widget_fns!(NodeEditor, State, Widget::NodeEditor(State {
+    view: View { offset: [0.0, 0.0], zoom: 1.0 },
+    interaction: Interaction::Normal,
+}));
+
+/// What happened to the NodeEditor, returned by `NodeEditor::react` and
+/// given to its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// A wire was dragged between two ports. A wire already connected to the
+    /// input is replaced, and reported as `Disconnected` the frame before.
+    Connected(Connection),
+    /// A wire was dragged away from the input it was connected to, or was
+    /// replaced by another wire dragged to that input.
+    Disconnected(Connection),
+    /// The selected nodes were dragged.
+    Moved,
+    /// Nodes were selected or deselected.
+    SelectionChanged,
+}
+
+/// The sizes of the parts of each node, and the mapping between the canvas
+/// and the window.
+#[derive(Clone, Copy)]
+struct Layout {
+    origin: Point,
+    view: View,
+    title_h: f64,
+    row_h: f64,
+}
+
+impl Layout {
+
+    fn to_screen(&self, p: Point) -> Point {
+        [self.origin[0] + (p[0] - self.view.offset[0]) * self.view.zoom,
+         self.origin[1] + (p[1] - self.view.offset[1]) * self.view.zoom]
+    }
+
+    fn to_canvas(&self, p: Point) -> Point {
+        [self.view.offset[0] + (p[0] - self.origin[0]) / self.view.zoom,
+         self.view.offset[1] + (p[1] - self.origin[1]) / self.view.zoom]
+    }
+
+    /// The rect of the node within the window.
+    fn node_rect(&self, node: &Node) -> (Point, Dimensions) {
+        let rows = ::std::cmp::max(node.inputs.len(), node.outputs.len()) as f64;
+        let h = self.title_h + rows * self.row_h + self.row_h / 2.0;
+        (self.to_screen(node.pos), [NODE_WIDTH * self.view.zoom, h * self.view.zoom])
+    }
+
+    /// The center of the port within the window.
+    fn port_pos(&self, node: &Node, side: Side, port: usize) -> Point {
+        let x = match side {
+            Side::Input => node.pos[0],
+            Side::Output => node.pos[0] + NODE_WIDTH,
+        };
+        self.to_screen([x, node.pos[1] + self.title_h + (port as f64 + 0.5) * self.row_h])
+    }
+
+    /// The topmost element beneath the given position.
+    fn element_at(&self, nodes: &[Node], pos: Point) -> Element {
+        let radius = (PORT_RADIUS + 2.0) * self.view.zoom;
+        for (i, node) in nodes.iter().enumerate().rev() {
+            for j in 0..node.inputs.len() {
+                if oval::is_over_circle(self.port_pos(node, Side::Input, j), pos, radius) {
+                    return Element::Port(PortRef { node: i, side: Side::Input, port: j })
+                }
+            }
+            for j in 0..node.outputs.len() {
+                if oval::is_over_circle(self.port_pos(node, Side::Output, j), pos, radius) {
+                    return Element::Port(PortRef { node: i, side: Side::Output, port: j })
+                }
+            }
+            let (node_pos, node_dim) = self.node_rect(node);
+            if rectangle::is_over(node_pos, pos, node_dim) {
+                return Element::Node(i)
+            }
+        }
+        Element::Canvas
+    }
+
+}
+
+/// The port with the given reference, if it exists.
+fn port(nodes: &[Node], port: PortRef) -> Option<&Port> {
+    nodes.get(port.node).and_then(|node| match port.side {
+        Side::Input => node.inputs.get(port.port),
+        Side::Output => node.outputs.get(port.port),
+    })
+}
+
+/// The connection made by a wire between the two ports, if they are an
+/// output and an input of the same kind on different nodes.
+fn connection(nodes: &[Node], a: PortRef, b: PortRef) -> Option<Connection> {
+    let (from, to) = match (a.side, b.side) {
+        (Side::Output, Side::Input) => (a, b),
+        (Side::Input, Side::Output) => (b, a),
+        _ => return None,
+    };
+    match (port(nodes, from), port(nodes, to)) {
+        (Some(out), Some(inp)) if from.node != to.node && out.kind == inp.kind => Some(Connection {
+            from_node: from.node,
+            from_port: from.port,
+            to_node: to.node,
+            to_port: to.port,
+        }),
+        _ => None,
+    }
+}
+
+/// A color for the given kind of port, so that ports of the same kind are
+/// easily told apart from the rest.
+fn kind_color(kind: &str) -> Color {
+    let hash = kind.bytes().fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32));
+    Color::hsl((hash % 360) as f32, 0.6, 0.5)
+}
+
+/// A canvas of nodes with typed input and output ports, connected by wires.
+/// Nodes are dragged by their bodies and selected by clicking them or by
+/// dragging a marquee over the canvas. Wires are made by dragging from one
+/// port to another of the same kind, and removed by dragging them away from
+/// their input. The mouse wheel zooms and dragging with the middle button
+/// pans. The nodes and connections are changed in place, and what happened
+/// is reported via `on_event` or `react`.
+pub struct NodeEditor<'a> {
+    ui_id: UIID,
+    nodes: &'a mut Vec<Node>,
+    connections: &'a mut Vec<Connection>,
+    maybe_node_color: Option<Color>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a> NodeEditor<'a> {
+
+    /// Create a node editor context to be built upon.
+    pub fn new(ui_id: UIID, nodes: &'a mut Vec<Node>, connections: &'a mut Vec<Connection>) -> NodeEditor<'a> {
+        NodeEditor {
+            ui_id: ui_id,
+            nodes: nodes,
+            connections: connections,
+            maybe_node_color: None,
+            pos: [0.0, 0.0],
+            dim: [512.0, 384.0],
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// The color of the nodes. Defaults to a highlighted canvas color.
+    pub fn node_color(self, color: Color) -> NodeEditor<'a> {
+        NodeEditor { maybe_node_color: Some(color), ..self }
+    }
+
+}
+
+composite_builder_impls!(NodeEditor<'a>);
+
+impl<'a> Reactable<'a, Event> for NodeEditor<'a> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a> Labelable<'a> for NodeEditor<'a> {
+    /// Node editors aren't labelled as a whole; their nodes are titled instead.
+    fn label(self, _text: &'a str) -> Self {
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a> ::draw::Drawable for NodeEditor<'a> {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics);
+    }
+}
+
+impl<'a> NodeEditor<'a> {
+
+    /// Draw the node editor, returning what happened to it.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+    /// Connect the two ports if they may be connected, replacing any wire
+    /// already connected to the input. Returns the event to report, along
+    /// with the wire whose connection is left to be reported next frame when
+    /// another wire was replaced.
+    fn connect(&mut self, a: PortRef, b: PortRef) -> (Option<Event>, Option<Connection>) {
+        let new = match connection(&self.nodes, a, b) {
+            Some(new) => new,
+            None => return (None, None),
+        };
+        let maybe_idx = self.connections.iter()
+            .position(|c| c.to_node == new.to_node && c.to_port == new.to_port);
+        match maybe_idx {
+            Some(idx) if self.connections[idx] != new => {
+                let replaced = ::std::mem::replace(&mut self.connections[idx], new);
+                (Some(Event::Disconnected(replaced)), Some(new))
+            },
+            Some(_) => (None, None),
+            None => {
+                self.connections.push(new);
+                (Some(Event::Connected(new)), None)
+            },
+        }
+    }
+
+    /// Update and draw the node editor, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_node_editor, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let padding = ui.theme.padding;
+        let frame_w = style.frame;
+        let inner_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let inner_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+        let is_over = rectangle::is_over(inner_pos, mouse.pos, inner_dim);
+
+        // The wheel zooms about the canvas position beneath the mouse.
+        let mut view = state.view;
+        if is_over && mouse.scroll[1] != 0.0 {
+            let layout = Layout { origin: inner_pos, view: view, title_h: 0.0, row_h: 0.0 };
+            let anchor = layout.to_canvas(mouse.pos);
+            let zoom = clamp(view.zoom * ZOOM_STEP.powf(mouse.scroll[1]), MIN_ZOOM, MAX_ZOOM);
+            view = View {
+                offset: [anchor[0] - (mouse.pos[0] - inner_pos[0]) / zoom,
+                         anchor[1] - (mouse.pos[1] - inner_pos[1]) / zoom],
+                zoom: zoom,
+            };
+        }
+        let font_size = style.label_font_size as f64;
+        let layout = Layout {
+            origin: inner_pos,
+            view: view,
+            title_h: font_size + padding * 2.0,
+            row_h: font_size + padding,
+        };
+        let canvas_mouse = layout.to_canvas(mouse.pos);
+        let is_over_elem = match is_over {
+            true => Some(layout.element_at(&self.nodes, mouse.pos)),
+            false => None,
+        };
+        let highlighted = is_over_elem.map_or(Interaction::Normal, Interaction::Highlighted);
+
+        // Apply the mouse to the nodes, the wires and the view.
+        let mut maybe_event = None;
+        let interaction = match (state.interaction, mouse.left, mouse.middle) {
+            (Interaction::Replaced(new), _, _) => {
+                maybe_event = Some(Event::Connected(new));
+                highlighted
+            },
+            (Interaction::Panning(last), _, Down) => {
+                view.offset = [view.offset[0] - (mouse.pos[0] - last[0]) / view.zoom,
+                               view.offset[1] - (mouse.pos[1] - last[1]) / view.zoom];
+                Interaction::Panning(mouse.pos)
+            },
+            (Interaction::Normal, Up, Down) | (Interaction::Highlighted(_), Up, Down) if is_over =>
+                Interaction::Panning(mouse.pos),
+            (Interaction::Wiring(from), Up, _) => match is_over_elem {
+                Some(Element::Port(to)) => match self.connect(from, to) {
+                    (event, Some(new)) => {
+                        maybe_event = event;
+                        Interaction::Replaced(new)
+                    },
+                    (event, None) => {
+                        maybe_event = event;
+                        highlighted
+                    },
+                },
+                _ => highlighted,
+            },
+            (_, Up, _) => highlighted,
+            (Interaction::Highlighted(elem), Down, _) => match elem {
+                Element::Node(idx) if idx < self.nodes.len() => {
+                    if !self.nodes[idx].selected {
+                        for node in self.nodes.iter_mut() { node.selected = false; }
+                        self.nodes[idx].selected = true;
+                        maybe_event = Some(Event::SelectionChanged);
+                    }
+                    Interaction::Dragging(canvas_mouse)
+                },
+                Element::Port(port) => {
+                    // Dragging a wire away from its input picks it back up from its output.
+                    let maybe_idx = match port.side {
+                        Side::Input => self.connections.iter()
+                            .position(|c| c.to_node == port.node && c.to_port == port.port),
+                        Side::Output => None,
+                    };
+                    match maybe_idx {
+                        Some(idx) => {
+                            let removed = self.connections.remove(idx);
+                            maybe_event = Some(Event::Disconnected(removed));
+                            Interaction::Wiring(PortRef {
+                                node: removed.from_node,
+                                side: Side::Output,
+                                port: removed.from_port,
+                            })
+                        },
+                        None => Interaction::Wiring(port),
+                    }
+                },
+                Element::Canvas => {
+                    if self.nodes.iter().any(|node| node.selected) {
+                        for node in self.nodes.iter_mut() { node.selected = false; }
+                        maybe_event = Some(Event::SelectionChanged);
+                    }
+                    Interaction::Selecting(Marquee::begin(canvas_mouse))
+                },
+                _ => Interaction::Normal,
+            },
+            (Interaction::Dragging(last), Down, _) => {
+                let delta = [canvas_mouse[0] - last[0], canvas_mouse[1] - last[1]];
+                if delta[0] != 0.0 || delta[1] != 0.0 {
+                    for node in self.nodes.iter_mut().filter(|node| node.selected) {
+                        node.pos = [node.pos[0] + delta[0], node.pos[1] + delta[1]];
+                    }
+                    maybe_event = Some(Event::Moved);
+                }
+                Interaction::Dragging(canvas_mouse)
+            },
+            (Interaction::Selecting(marquee), Down, _) => {
+                let (marquee, marquee_event) = marquee.update(canvas_mouse, mouse.left);
+                if let Some(marquee::Event::Changed(rect_pos, rect_dim)) = marquee_event {
+                    let rect_pos = layout.to_screen(rect_pos);
+                    let rect_dim = [rect_dim[0] * view.zoom, rect_dim[1] * view.zoom];
+                    for node in self.nodes.iter_mut() {
+                        let (node_pos, node_dim) = layout.node_rect(node);
+                        let selected = marquee::overlaps(rect_pos, rect_dim, node_pos, node_dim);
+                        if node.selected != selected {
+                            node.selected = selected;
+                            maybe_event = Some(Event::SelectionChanged);
+                        }
+                    }
+                }
+                Interaction::Selecting(marquee)
+            },
+            (Interaction::Wiring(from), Down, _) => Interaction::Wiring(from),
+            (_, Down, _) => Interaction::Normal,
+        };
+        let new_state = State { view: view, interaction: interaction };
+
+        match interaction {
+            Interaction::Highlighted(Element::Node(_)) | Interaction::Dragging(_) |
+            Interaction::Panning(_) => ui.set_mouse_cursor(MouseCursor::Move),
+            Interaction::Highlighted(Element::Port(_)) | Interaction::Wiring(_) =>
+                ui.set_mouse_cursor(MouseCursor::Crosshair),
+            _ => (),
+        }
+
+        // Draw the canvas.
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        let fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
+        ui.push_clip(inner_pos, inner_dim);
+
+        // The layout is redone, as panning may have moved the view.
+        let layout = Layout { view: view, ..layout };
+        let zoom = view.zoom;
+        let wire_w = (2.0 * zoom).max(1.0);
+        let wire_color = style.color.plain_contrast().fade(0.6);
+        let wire_reach = |from: Point, to: Point| ((to[0] - from[0]).abs() / 2.0).max(32.0 * zoom);
+
+        // Draw the wires beneath the nodes.
+        for c in self.connections.iter() {
+            let from = PortRef { node: c.from_node, side: Side::Output, port: c.from_port };
+            let to = PortRef { node: c.to_node, side: Side::Input, port: c.to_port };
+            if port(&self.nodes, from).is_none() || port(&self.nodes, to).is_none() { continue }
+            let p0 = layout.port_pos(&self.nodes[c.from_node], Side::Output, c.from_port);
+            let p3 = layout.port_pos(&self.nodes[c.to_node], Side::Input, c.to_port);
+            let reach = wire_reach(p0, p3);
+            let color = match self.nodes[c.from_node].selected || self.nodes[c.to_node].selected {
+                true => style.color.plain_contrast(),
+                false => wire_color,
+            };
+            bezier::draw_cubic(ui, graphics, p0, [p0[0] + reach, p0[1]], [p3[0] - reach, p3[1]], p3,
+                               wire_w, color);
+        }
+
+        // Draw each node, with its title above its inputs and outputs. While
+        // wiring, the ports that can't be connected to are faded.
+        let node_color = self.maybe_node_color.unwrap_or(style.color.highlighted());
+        let text_size = (font_size * zoom).round() as FontSize;
+        let show_text = text_size >= 4;
+        let port_r = PORT_RADIUS * zoom;
+        let maybe_wiring = match interaction {
+            Interaction::Wiring(from) => Some(from),
+            _ => None,
+        };
+        for (i, node) in self.nodes.iter().enumerate() {
+            let (node_pos, node_dim) = layout.node_rect(node);
+            if !marquee::overlaps(node_pos, node_dim, inner_pos, inner_dim) { continue }
+            let rect_state = match interaction {
+                _ if node.selected => rectangle::State::Clicked,
+                Interaction::Highlighted(Element::Node(idx)) if idx == i => rectangle::State::Highlighted,
+                _ => rectangle::State::Normal,
+            };
+            let color = rect_state.color(node_color);
+            rectangle::draw(ui, graphics, rectangle::State::Normal, node_pos, node_dim,
+                            Some((1.0, style.frame_color)), style.corner_radius * zoom, color);
+            let title_bottom = node_pos[1] + layout.title_h * zoom;
+            ui.draw_line(graphics, [node_pos[0], title_bottom, node_pos[0] + node_dim[0], title_bottom],
+                         1.0, false, style.frame_color);
+            let text_color = color.plain_contrast();
+            ui.push_clip(node_pos, node_dim);
+            if show_text {
+                let text_pos = [node_pos[0] + padding * zoom, node_pos[1] + padding * zoom];
+                ui.draw_text(graphics, text_pos, style.font, text_size, text_color, &node.title);
+            }
+            ui.pop_clip();
+            let sides = [(Side::Input, &node.inputs), (Side::Output, &node.outputs)];
+            for &(side, ports) in sides.iter() {
+                for (j, p) in ports.iter().enumerate() {
+                    let center = layout.port_pos(node, side, j);
+                    let this = PortRef { node: i, side: side, port: j };
+                    let is_usable = maybe_wiring.map_or(true, |from| {
+                        from == this || connection(&self.nodes, from, this).is_some()
+                    });
+                    let port_color = match is_usable {
+                        true => kind_color(&p.kind),
+                        false => kind_color(&p.kind).fade(0.25),
+                    };
+                    oval::draw_circle(ui, graphics, center, port_r, port_color);
+                    if !show_text { continue }
+                    let text_y = center[1] - text_size as f64 / 2.0;
+                    let text_x = match side {
+                        Side::Input => center[0] + port_r + padding * zoom,
+                        Side::Output => {
+                            let w = ui.text_width(style.font, text_size, &p.name);
+                            center[0] - port_r - padding * zoom - w
+                        },
+                    };
+                    ui.draw_text(graphics, [text_x, text_y], style.font, text_size, text_color, &p.name);
+                }
+            }
+        }
+
+        // Draw the wire being dragged and the marquee.
+        match interaction {
+            Interaction::Wiring(from) => if port(&self.nodes, from).is_some() {
+                let start = layout.port_pos(&self.nodes[from.node], from.side, from.port);
+                let reach = wire_reach(start, mouse.pos);
+                let (p0, p1, p2, p3) = match from.side {
+                    Side::Output => (start, [start[0] + reach, start[1]], [mouse.pos[0] - reach, mouse.pos[1]], mouse.pos),
+                    Side::Input => (mouse.pos, [mouse.pos[0] + reach, mouse.pos[1]], [start[0] - reach, start[1]], start),
+                };
+                bezier::draw_cubic(ui, graphics, p0, p1, p2, p3, wire_w, style.color.plain_contrast());
+            },
+            Interaction::Selecting(marquee) => if let Some((pos, dim)) = marquee.rect() {
+                let dim = [dim[0] * zoom, dim[1] * zoom];
+                marquee::draw(ui, graphics, layout.to_screen(pos), dim, style.color.plain_contrast());
+            },
+            _ => (),
+        }
+
+        ui.pop_clip();
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::NodeEditor(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
@@ -0,0 +1,283 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use mouse::ButtonState::{ Down, Up };
+use point::Point;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use widget::Widget;
+
+/// A key of the NumPad.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Key {
+    Digit(u8),
+    /// The theme's decimal separator.
+    Point,
+    Backspace,
+    Enter,
+}
+
+/// The keys above Enter, row by row.
+const KEYS: [[Key; 3]; 4] = [
+    [Key::Digit(7), Key::Digit(8), Key::Digit(9)],
+    [Key::Digit(4), Key::Digit(5), Key::Digit(6)],
+    [Key::Digit(1), Key::Digit(2), Key::Digit(3)],
+    [Key::Point, Key::Digit(0), Key::Backspace],
+];
+
+/// Represents the state of the NumPad widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Key),
+    Clicked(Key),
+}
+
+widget_fns!(NumPad, State, Widget::NumPad(State::Normal));
+
+/// What happened to the NumPad, returned by `NumPad::react` and given to its
+/// `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// A digit or the decimal separator was added, or the last character
+    /// removed.
+    Edited,
+    /// Enter was pressed.
+    Entered,
+}
+
+/// A grid of large keys for entering a number without a keyboard, i.e. on a
+/// touch screen kiosk. The digits, the decimal separator and Backspace edit
+/// the given string, and Enter submits it. Keys act when released over them,
+/// so that a touch may slide off a key to cancel it. A `TextBox` may pop one
+/// up beneath itself while editing (see `TextBox::num_pad`). Backspace and
+/// Enter are labelled with the translations of "Back" and "Enter" (see
+/// `Locale`). The keypad captures the mouse from the widgets beneath it.
+pub struct NumPad<'a, F> {
+    ui_id: UIID,
+    text: &'a mut String,
+    is_decimal: bool,
+    maybe_max_len: Option<usize>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> NumPad<'a, F> {
+
+    /// Create a numeric keypad context to be built upon, editing the given text.
+    pub fn new(ui_id: UIID, text: &'a mut String) -> NumPad<'a, F> {
+        NumPad {
+            ui_id: ui_id,
+            text: text,
+            is_decimal: true,
+            maybe_max_len: None,
+            pos: [0.0, 0.0],
+            dim: [192.0, 256.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// Whether or not a decimal separator may be entered. Without one the
+    /// decimal separator key is left blank.
+    pub fn decimal(self, is_decimal: bool) -> NumPad<'a, F> {
+        NumPad { is_decimal: is_decimal, ..self }
+    }
+
+    /// The most characters the text may hold.
+    pub fn max_len(self, len: usize) -> NumPad<'a, F> {
+        NumPad { maybe_max_len: Some(len), ..self }
+    }
+
+}
+
+composite_builder_impls!(NumPad<'a, F>);
+
+impl<'a, F> Callable<F> for NumPad<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for NumPad<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Labelable<'a> for NumPad<'a, F> {
+    /// NumPads aren't labelled as a whole; their keys are labelled instead.
+    fn label(self, _text: &'a str) -> Self {
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for NumPad<'a, F> where F: FnMut(&mut String) + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Entered) = self.update(ui, graphics) {
+            let NumPad { ref mut maybe_callback, ref mut text, .. } = *self;
+            if let Some(ref mut callback) = *maybe_callback { (*callback)(*text) }
+        }
+    }
+}
+
+impl<'a> NumPad<'a, NoCallback> {
+
+    /// Draw the keypad, returning what happened to the text. An alternative
+    /// to `Callable::callback` for when the reaction to the text needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> NumPad<'a, F> {
+
+    /// The position and dimensions of each key, with Enter along the bottom.
+    fn key_rects(&self) -> Vec<(Key, Point, Dimensions)> {
+        let rows = KEYS.len() + 1;
+        let key_dim = [self.dim[0] / 3.0, self.dim[1] / rows as f64];
+        let mut rects = Vec::with_capacity(KEYS.len() * 3 + 1);
+        for (row, keys) in KEYS.iter().enumerate() {
+            for (col, &key) in keys.iter().enumerate() {
+                let pos = [self.pos[0] + col as f64 * key_dim[0], self.pos[1] + row as f64 * key_dim[1]];
+                rects.push((key, pos, key_dim));
+            }
+        }
+        let enter_pos = [self.pos[0], self.pos[1] + KEYS.len() as f64 * key_dim[1]];
+        rects.push((Key::Enter, enter_pos, [self.dim[0], key_dim[1]]));
+        rects
+    }
+
+    /// Update and draw the keypad, returning what happened to the text.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_num_pad, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.mouse_state_for(self.ui_id);
+        let separator = ui.theme.number_format.decimal_separator;
+        let rects = self.key_rects();
+        let is_over_key = rects.iter()
+            .find(|&&(_, pos, dim)| rectangle::is_over(pos, mouse.pos, dim))
+            .map(|&(key, _, _)| key)
+            .and_then(|key| match key {
+                Key::Point if !self.is_decimal => None,
+                _ => Some(key),
+            });
+
+        // A key acts when released over the key that was pressed.
+        let new_state = match (is_over_key, state, mouse.left) {
+            (_,         State::Clicked(key), Down) => State::Clicked(key),
+            (Some(_),   State::Normal,       Down) => State::Normal,
+            (Some(key), _,                   Down) => State::Clicked(key),
+            (Some(key), _,                   Up)   => State::Highlighted(key),
+            _                                      => State::Normal,
+        };
+        let mut maybe_event = None;
+        if let (State::Clicked(pressed), State::Highlighted(released)) = (state, new_state) {
+            if pressed == released {
+                let is_full = self.maybe_max_len.map_or(false, |len| self.text.chars().count() >= len);
+                match released {
+                    Key::Digit(d) if !is_full => {
+                        self.text.push((b'0' + d) as char);
+                        maybe_event = Some(Event::Edited);
+                    },
+                    Key::Point if !is_full && !self.text.contains(separator) => {
+                        self.text.push(separator);
+                        maybe_event = Some(Event::Edited);
+                    },
+                    Key::Backspace => if self.text.pop().is_some() {
+                        maybe_event = Some(Event::Edited);
+                    },
+                    Key::Enter => maybe_event = Some(Event::Entered),
+                    _ => (),
+                }
+            }
+        }
+
+        // Draw the background, then each key with its label centered.
+        let maybe_frame = match style.frame > 0.0 {
+            true => Some((style.frame, style.frame_color)),
+            false => None,
+        };
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        ui.draw_rectangle(graphics, self.pos, self.dim, style.frame_color);
+        let font_size = style.label_font_size;
+        let gap = style.frame.max(1.0);
+        for &(key, pos, dim) in rects.iter() {
+            let text = match key {
+                Key::Digit(d) => ((b'0' + d) as char).to_string(),
+                Key::Point if self.is_decimal => separator.to_string(),
+                Key::Point => continue,
+                Key::Backspace => ui.tr("Back").to_string(),
+                Key::Enter => ui.tr("Enter").to_string(),
+            };
+            let rect_state = match new_state {
+                State::Clicked(k) if k == key => rectangle::State::Clicked,
+                State::Highlighted(k) if k == key => rectangle::State::Highlighted,
+                _ => rectangle::State::Normal,
+            };
+            let color = rect_state.color(style.color);
+            let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image, rect_state);
+            let key_pos = [pos[0] + gap / 2.0, pos[1] + gap / 2.0];
+            let key_dim = [dim[0] - gap, dim[1] - gap];
+            rectangle::draw(ui, graphics, rectangle::State::Normal, key_pos, key_dim,
+                            maybe_frame, style.corner_radius, fill);
+            let text_w = ui.text_width(style.font, font_size, &text);
+            let text_pos = [key_pos[0] + (key_dim[0] - text_w) / 2.0,
+                            key_pos[1] + (key_dim[1] - font_size as f64) / 2.0];
+            ui.draw_text(graphics, text_pos, style.font, font_size, style.label_color, &text);
+        }
+
+        ui.pop_alpha();
+        // The keypad is drawn over other widgets, which mustn't react to it.
+        ui.capture_mouse(self.ui_id, self.pos, self.dim);
+        set_state(ui, self.ui_id, Widget::NumPad(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
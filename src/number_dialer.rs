@@ -1,19 +1,20 @@
 use std::cmp::Ordering;
 use num::{ Float, ToPrimitive, FromPrimitive };
 use std::iter::repeat;
-use frame::Frameable;
-use callback::Callable;
-use color::{ Color, Colorable };
-use label::{ FontSize, Labelable };
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use label::{ FontId, FontSize, Labelable };
 use dimensions::Dimensions;
-use graphics;
-use graphics::{ Graphics, Transformed };
+use graphics::Graphics;
 use graphics::character::CharacterCache;
+use history::Value;
 use label;
+use mouse;
 use mouse::Mouse;
+use number_format::NumberFormat;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use property::{ Bindable, Property };
+use theme::WidgetStyle;
 use rectangle;
 use utils::{
     clamp,
@@ -38,11 +39,13 @@ pub enum Element {
 }
 
 /// Represents the state of the Button widget.
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum State {
     Normal,
     Highlighted(Element),
     Clicked(Element),
+    /// Pressed with the right or middle button.
+    AltClicked(mouse::Button),
 }
 
 widget_fns!(NumberDialer, State, Widget::NumberDialer(State::Normal));
@@ -86,12 +89,29 @@ fn value_glyph_slot_width(size: FontSize) -> f64 {
 }
 
 /// Return the dimensions of value string glyphs.
-fn val_string_width(font_size: FontSize, val_string: &String) -> f64 {
+fn val_string_width(font_size: FontSize, val_string: &str) -> f64 {
     let slot_w = value_glyph_slot_width(font_size);
-    let val_string_w = slot_w * val_string.len() as f64;
+    let val_string_w = slot_w * val_string.chars().count() as f64;
     val_string_w
 }
 
+/// The index within the value string of the digit drawn in the given slot of
+/// its formatted display, or `None` if the slot holds a thousands separator.
+fn digit_index(val_string: &str, display_string: &str, slot: usize) -> Option<usize> {
+    let val_bytes = val_string.as_bytes();
+    let mut idx = 0;
+    for (i, ch) in display_string.chars().enumerate() {
+        // Thousands separators are only inserted between the integer digits,
+        // where the display differs from the value string.
+        let is_separator = idx < val_bytes.len() && val_bytes[idx] != b'.' && val_bytes[idx] as char != ch;
+        if i == slot {
+            return match is_separator { true => None, false => Some(idx) };
+        }
+        if !is_separator { idx += 1 }
+    }
+    None
+}
+
 /// Determine if the cursor is over the number_dialer and if so, which element.
 #[inline]
 fn is_over(pos: Point,
@@ -137,24 +157,27 @@ fn is_over(pos: Point,
 fn get_new_state(is_over_elem: Option<Element>, prev: State, mouse: Mouse) -> State {
     use mouse::ButtonState::{Down, Up};
     use self::Element::ValueGlyph;
-    use self::State::{Normal, Highlighted, Clicked};
-    match (is_over_elem, prev, mouse.left) {
-        (Some(_),    Normal,          Down) => Normal,
-        (Some(elem), _,               Up)   => Highlighted(elem),
-        (Some(elem), Highlighted(_),  Down) => Clicked(elem),
-        (Some(_),    Clicked(p_elem), Down) => {
+    use self::State::{Normal, Highlighted, Clicked, AltClicked};
+    match (is_over_elem, prev, mouse.left, mouse.alt_button_down()) {
+        (Some(_),    Normal,          Down, _)       => Normal,
+        (Some(_),    Normal,          Up,   Some(_)) => Normal,
+        (_,          AltClicked(b),   Up,   _) if mouse.button(b) == Down => AltClicked(b),
+        (Some(_),    Highlighted(_),  Up,   Some(b)) => AltClicked(b),
+        (Some(elem), _,               Up,   _)       => Highlighted(elem),
+        (Some(elem), Highlighted(_),  Down, _)       => Clicked(elem),
+        (Some(_),    Clicked(p_elem), Down, _)       => {
             match p_elem {
                 ValueGlyph(idx, _) => Clicked(ValueGlyph(idx, mouse.pos[1])),
                 _                  => Clicked(p_elem),
             }
         },
-        (None,       Clicked(p_elem), Down) => {
+        (None,       Clicked(p_elem), Down, _)       => {
             match p_elem {
                 ValueGlyph(idx, _) => Clicked(ValueGlyph(idx, mouse.pos[1])),
                 _                  => Clicked(p_elem),
             }
         },
-        _                                   => Normal,
+        _                                            => Normal,
     }
 }
 
@@ -199,16 +222,15 @@ fn get_new_value<T>(val: T, min: T, max: T, idx: usize, y_ord: Ordering, val_str
 /// Draw the value string glyphs.
 #[inline]
 fn draw_value_string<B, C: CharacterCache>(
-    win_w: f64,
-    win_h: f64,
-    graphics: &mut B,
     ui: &mut Ui<C>,
+    graphics: &mut B,
     state: State,
     slot_y: f64,
     rect_color: Color,
     slot_w: f64,
     pad_h: f64,
     pos: Point,
+    font: FontId,
     size: FontSize,
     font_color: Color,
     string: &str
@@ -217,55 +239,63 @@ fn draw_value_string<B, C: CharacterCache>(
         B: Graphics<Texture = <C as CharacterCache>::Texture>,
         C: CharacterCache
 {
-    let mut x = 0.0f64;
-    let y = 0.0f64;
-    let Color(font_col) = font_color;
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h)
-        .trans(pos[0], pos[1] + size as f64);
+    let mut x = pos[0];
     let half_slot_w = slot_w / 2.0;
-    let image = graphics::Image::new_colored(font_col);
+    let mut glyph = ui.scratch_string();
     for (i, ch) in string.chars().enumerate() {
-        let character = ui.get_character(size, ch);
         match state {
             State::Highlighted(elem) => match elem {
                 Element::ValueGlyph(idx, _) => {
-                    let context_slot_y = slot_y - (pos[1] + size as f64);
                     let rect_color = if idx == i { rect_color.highlighted() }
                                      else { rect_color };
-                    graphics::Rectangle::new(rect_color.0).draw(
-                        [x as f64, context_slot_y, size as f64, pad_h],
-                        draw_state,
-                        transform,
-                        graphics
-                    );
+                    ui.draw_rectangle(graphics, [x, slot_y], [size as f64, pad_h], rect_color);
                 },
                 _ => (),
             },
             State::Clicked(elem) => match elem {
                 Element::ValueGlyph(idx, _) => {
-                    let context_slot_y = slot_y - (pos[1] + size as f64);
                     let rect_color = if idx == i { rect_color.clicked() }
                                      else { rect_color };
-                    graphics::Rectangle::new(rect_color.0).draw(
-                        [x, context_slot_y, size as f64, pad_h],
-                        draw_state,
-                        transform,
-                        graphics
-                    );
+                    ui.draw_rectangle(graphics, [x, slot_y], [size as f64, pad_h], rect_color);
                 },
                 _ => (),
             },
             _ => (),
         };
-        let x_shift = half_slot_w - 0.5 * character.width();
-        let d = transform.trans(
-                x + character.left() + x_shift,
-                y - character.top()
-            );
-        image.draw(&character.texture, draw_state, d, graphics);
+        // Center the glyph within its slot.
+        let x_shift = half_slot_w - 0.5 * ui.get_character_w(font, size, ch);
+        glyph.clear();
+        glyph.push(ch);
+        ui.draw_text(graphics, [x + x_shift, pos[1]], font, size, font_color, &glyph);
         x += slot_w;
     }
+    ui.recycle_string(glyph);
+}
+
+/// What happened to the NumberDialer, returned by `NumberDialer::react` and given to
+/// its `on_event` closure along with the number_dialer's value.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event<T> {
+    /// The mouse was pressed over the number_dialer.
+    Pressed(T),
+    /// The value is changing while the mouse is held.
+    Dragging(T),
+    /// The mouse was released, committing the value.
+    Released(T),
+    /// The right or middle button was released over the number_dialer after
+    /// pressing it with the same, i.e. to open a context menu. The value is
+    /// left unchanged.
+    AltClicked(mouse::Button, T),
+}
+
+impl<T: Copy> Event<T> {
+    /// The value of the number_dialer when the event occurred.
+    pub fn value(&self) -> T {
+        match *self {
+            Event::Pressed(value) | Event::Dragging(value) | Event::Released(value) |
+            Event::AltClicked(_, value) => value,
+        }
+    }
 }
 
 /// A context on which the builder pattern can be implemented.
@@ -277,13 +307,12 @@ pub struct NumberDialer<'a, T, F> {
     pos: Point,
     dim: Dimensions,
     precision: u8,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
     maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event<T>) + 'a>>,
+    maybe_property: Option<Property<T>>,
+    maybe_number_format: Option<NumberFormat>,
 }
 
 impl<'a, T: Float, F> NumberDialer<'a, T, F> {
@@ -297,38 +326,43 @@ impl<'a, T: Float, F> NumberDialer<'a, T, F> {
             pos: [0.0, 0.0],
             dim: [128.0, 48.0],
             precision: precision,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
             maybe_callback: None,
+            maybe_on_event: None,
+            maybe_property: None,
+            maybe_number_format: None,
         }
     }
-}
 
-impl<'a, T, F> Colorable for NumberDialer<'a, T, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
+    /// Display the value with the given format rather than the theme's. The
+    /// dialer's own precision is used unless the format specifies one, and
+    /// any thousands separators are skipped over when dialing.
+    pub fn number_format(self, format: NumberFormat) -> NumberDialer<'a, T, F> {
+        NumberDialer { maybe_number_format: Some(format), ..self }
     }
 }
 
-impl<'a, T, F> Frameable for NumberDialer<'a, T, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
+composite_builder_impls!(NumberDialer<'a, T, F>);
+
+impl<'a, T, F> Callable<F> for NumberDialer<'a, T, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
         self
     }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
+}
+
+impl<'a, T, F> Bindable<T> for NumberDialer<'a, T, F> where T: Clone {
+    fn bind(mut self, property: &Property<T>) -> Self {
+        self.value = property.get();
+        self.maybe_property = Some(property.clone());
         self
     }
 }
 
-impl<'a, T, F> Callable<F> for NumberDialer<'a, T, F> {
-    fn callback(mut self, cb: F) -> Self {
-        self.maybe_callback = Some(cb);
+impl<'a, T, F> Reactable<'a, Event<T>> for NumberDialer<'a, T, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event<T>) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
@@ -341,28 +375,21 @@ impl<'a, T, F> Labelable<'a> for NumberDialer<'a, T, F>
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
         self
     }
-}
 
-impl<'a, T, F> Positionable for NumberDialer<'a, T, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, T, F> Shapeable for NumberDialer<'a, T, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
     where
         T: Float + FromPrimitive + ToPrimitive + ToString,
@@ -377,55 +404,110 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if let Some(event) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(event.value()) }
+        }
+    }
+
+}
+
+impl<'a, T> NumberDialer<'a, T, NoCallback>
+    where
+        T: Float + FromPrimitive + ToPrimitive + ToString
+{
+
+    /// Draw the number_dialer, returning what happened to it. An alternative
+    /// to `Callable::callback` for when the reaction to the change needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event<T>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
 
+impl<'a, T, F> NumberDialer<'a, T, F>
+    where
+        T: Float + FromPrimitive + ToPrimitive + ToString
+{
+
+    /// Update and draw the number_dialer, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event<T>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_number_dialer, &ui.theme);
+        ui.push_alpha(style.alpha);
         let state = *get_state(ui, self.ui_id);
         let mouse = ui.get_mouse_state();
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
+        let frame_w = style.frame;
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
+            true => Some((frame_w, style.frame_color)),
             false => None,
         };
         let pad_h = self.dim[1] - frame_w2;
-        let font_size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
+        let font_size = style.label_font_size;
         let label_string = match self.maybe_label {
             Some(text) => format!("{}: ", text),
             None => String::new(),
         };
         let label_dim = match label_string.len() {
             0 => [0.0, 0.0],
-            _ => [label::width(ui, font_size, &label_string), font_size as f64],
+            _ => [label::width(ui, style.font, font_size, &label_string), font_size as f64],
+        };
+        let format = self.maybe_number_format.clone().unwrap_or_else(|| ui.theme.number_format.clone());
+        let precision = format.maybe_precision.unwrap_or(self.precision);
+        let unit = format.maybe_unit.clone().unwrap_or(String::new());
+        // The unit follows the dialed digits, so isn't part of their string.
+        let digits_format = NumberFormat { maybe_unit: None, ..format };
+        let val_string_len = self.max.to_string().len() + if precision == 0 { 0 }
+                                                          else { 1 + precision as usize };
+        let mut val_string = create_val_string(self.value, val_string_len, precision);
+        let mut display_string = digits_format.localize(&val_string);
+        let (val_string_w, val_string_h) = (val_string_width(font_size, &display_string), font_size as f64);
+        let unit_w = match unit.len() {
+            0 => 0.0,
+            _ => label::width(ui, style.font, font_size, &unit),
         };
-        let val_string_len = self.max.to_string().len() + if self.precision == 0 { 0 }
-                                                          else { 1 + self.precision as usize };
-        let mut val_string = create_val_string(self.value, val_string_len, self.precision);
-        let (val_string_w, val_string_h) = (val_string_width(font_size, &val_string), font_size as f64);
-        let label_x = self.pos[0] + (self.dim[0] - (label_dim[0] + val_string_w)) / 2.0;
+        let label_x = self.pos[0] + (self.dim[0] - (label_dim[0] + val_string_w + unit_w)) / 2.0;
         let label_y = self.pos[1] + (self.dim[1] - font_size as f64) / 2.0;
         let label_pos = [label_x, label_y];
         let is_over_elem = is_over(self.pos, frame_w, mouse.pos, self.dim,
                                    label_pos, label_dim, val_string_w, val_string_h,
-                                   val_string.len());
+                                   display_string.chars().count());
         let new_state = get_new_state(is_over_elem, state, mouse);
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
+        let color = style.color;
 
         // Draw the widget rectangle.
-        rectangle::draw(ui.win_w, ui.win_h, graphics, rectangle::State::Normal,
-                        self.pos, self.dim, maybe_frame, color);
+        let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
 
         // If there's a label, draw it.
-        let val_string_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
+        let val_string_color = style.label_color;
         if self.maybe_label.is_some() {
-            ui.draw_text(graphics, label_pos, font_size, val_string_color, &label_string);
+            ui.draw_text(graphics, label_pos, style.font, font_size, val_string_color, &label_string);
         };
 
         // Determine new value from the initial state and the new state.
         let new_val = match (state, new_state) {
             (State::Clicked(elem), State::Clicked(new_elem)) => {
                 match (elem, new_elem) {
-                    (Element::ValueGlyph(idx, y), Element::ValueGlyph(_, new_y)) => {
-                        get_new_value(self.value, self.min, self.max, idx,
-                                      compare_f64s(new_y, y), &val_string)
+                    (Element::ValueGlyph(slot, y), Element::ValueGlyph(_, new_y)) => {
+                        match digit_index(&val_string, &display_string, slot) {
+                            Some(idx) => get_new_value(self.value, self.min, self.max, idx,
+                                                       compare_f64s(new_y, y), &val_string),
+                            None => self.value,
+                        }
                     }, _ => self.value,
                 }
             }, _ => self.value,
@@ -433,33 +515,59 @@ impl<'a, T, F> ::draw::Drawable for NumberDialer<'a, T, F>
 
         // If the value has changed, create a new string for val_string.
         if self.value != new_val {
-            val_string = create_val_string(new_val, val_string_len, self.precision)
+            val_string = create_val_string(new_val, val_string_len, precision);
+            display_string = digits_format.localize(&val_string);
         }
 
-        // Draw the value string.
+        // Draw the formatted value string followed by the unit.
         let val_string_pos = vec2_add(label_pos, [label_dim[0], 0.0]);
-        draw_value_string(ui.win_w, ui.win_h, graphics, ui, new_state,
+        draw_value_string(ui, graphics, new_state,
                           self.pos[1] + frame_w, color,
                           value_glyph_slot_width(font_size), pad_h,
                           val_string_pos,
+                          style.font,
                           font_size,
                           val_string_color,
-                          &val_string);
-
-        // Call the `callback` with the new value if the mouse is pressed/released
-        // on the widget or if the value has changed.
-        if self.value != new_val || match (state, new_state) {
-            (State::Highlighted(_), State::Clicked(_)) | (State::Clicked(_), State::Highlighted(_)) => true,
-            _ => false,
-        } {
-            match self.maybe_callback {
-                Some(ref mut callback) => (*callback)(new_val),
-                None => ()
-            }
+                          &display_string);
+        if unit.len() > 0 {
+            let unit_pos = vec2_add(val_string_pos, [val_string_w, 0.0]);
+            ui.draw_text(graphics, unit_pos, style.font, font_size, val_string_color, &unit);
         }
 
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::NumberDialer(new_state), self.pos, self.dim);
 
+        // React if the mouse is pressed/released on the widget or if the
+        // value has changed.
+        let maybe_event = match (state, new_state) {
+            (State::Highlighted(_), State::Clicked(_)) => Some(Event::Pressed(new_val)),
+            (State::Clicked(_), State::Highlighted(_))
+            | (State::Clicked(_), State::Normal) => Some(Event::Released(new_val)),
+            (State::AltClicked(b), State::Highlighted(_)) => Some(Event::AltClicked(b, new_val)),
+            _ if self.value != new_val => Some(Event::Dragging(new_val)),
+            _ => None,
+        };
+
+        // The change is recorded once released, and a change that was undone
+        // or redone is re-fired as though released.
+        match maybe_event {
+            Some(Event::Pressed(_)) =>
+                ui.begin_change(self.ui_id, Value::Number(self.value.to_f64().unwrap())),
+            Some(Event::Released(value)) =>
+                ui.commit_change(self.ui_id, Value::Number(value.to_f64().unwrap())),
+            _ => (),
+        }
+        let maybe_event = match ui.take_restored(self.ui_id) {
+            Some(Value::Number(value)) => FromPrimitive::from_f64(value).map(Event::Released),
+            _ => maybe_event,
+        };
+        if let (Some(event), Some(property)) = (maybe_event, self.maybe_property.as_ref()) {
+            property.set(event.value());
+        }
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
 
 }
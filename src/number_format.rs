@@ -0,0 +1,89 @@
+//! The formatting of the numbers displayed by widgets, so that values may
+//! be shown with the separators and units of the application's locale.
+//!
+//! Widgets use the `Theme`'s `number_format` unless given their own via
+//! their `number_format` builder method.
+
+/// How numeric widgets display their values.
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct NumberFormat {
+    /// The character between the integer and fractional digits.
+    pub decimal_separator: char,
+    /// The character between each group of three integer digits, if any.
+    pub maybe_thousands_separator: Option<char>,
+    /// The number of fractional digits. Where `None`, each widget chooses a
+    /// precision suited to its range and size.
+    pub maybe_precision: Option<u8>,
+    /// Appended to each value as it is, i.e. `" Hz"` or `"%"`.
+    pub maybe_unit: Option<String>,
+}
+
+impl NumberFormat {
+
+    /// Plain decimal numbers with a `.` separator and no grouping or unit.
+    pub fn new() -> NumberFormat {
+        NumberFormat {
+            decimal_separator: '.',
+            maybe_thousands_separator: None,
+            maybe_precision: None,
+            maybe_unit: None,
+        }
+    }
+
+    /// A builder method for the decimal separator.
+    pub fn decimal_separator(self, separator: char) -> NumberFormat {
+        NumberFormat { decimal_separator: separator, ..self }
+    }
+
+    /// A builder method for the separator between groups of three integer digits.
+    pub fn thousands_separator(self, separator: char) -> NumberFormat {
+        NumberFormat { maybe_thousands_separator: Some(separator), ..self }
+    }
+
+    /// A builder method for the number of fractional digits.
+    pub fn precision(self, precision: u8) -> NumberFormat {
+        NumberFormat { maybe_precision: Some(precision), ..self }
+    }
+
+    /// A builder method for the unit appended to each value.
+    pub fn unit(self, unit: &str) -> NumberFormat {
+        NumberFormat { maybe_unit: Some(unit.to_string()), ..self }
+    }
+
+    /// Format the value with the format's precision, or the given precision
+    /// if the format doesn't specify one.
+    pub fn format(&self, value: f64, default_precision: usize) -> String {
+        let precision = self.maybe_precision.map_or(default_precision, |p| p as usize);
+        self.localize(&format!("{:.*}", precision, value))
+    }
+
+    /// Apply the format's separators and unit to a number already formatted
+    /// with a `.` decimal separator, i.e. via `format!` or `to_string`.
+    pub fn localize(&self, formatted: &str) -> String {
+        let (sign, digits) = match formatted.starts_with("-") {
+            true => ("-", &formatted[1..]),
+            false => ("", formatted),
+        };
+        let (integer, maybe_fraction) = match digits.find('.') {
+            Some(idx) => (&digits[..idx], Some(&digits[idx + 1..])),
+            None => (digits, None),
+        };
+        let mut string = sign.to_string();
+        match self.maybe_thousands_separator {
+            Some(separator) => for (i, ch) in integer.chars().enumerate() {
+                if i > 0 && (integer.len() - i) % 3 == 0 { string.push(separator) }
+                string.push(ch);
+            },
+            None => string.push_str(integer),
+        }
+        if let Some(fraction) = maybe_fraction {
+            string.push(self.decimal_separator);
+            string.push_str(fraction);
+        }
+        if let Some(ref unit) = self.maybe_unit {
+            string.push_str(unit);
+        }
+        string
+    }
+
+}
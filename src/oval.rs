@@ -0,0 +1,76 @@
+
+use color::Color;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use rectangle::State;
+use ui::Ui;
+
+/// Draw an oval that fills the rectangle at the given position and
+/// dimensions. The primary purpose of this is to be used as a building
+/// block for circular widgets.
+pub fn draw<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    state: State,
+    pos: Point,
+    dim: Dimensions,
+    maybe_frame: Option<(f64, Color)>,
+    color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let f_width = match maybe_frame {
+        Some((f_width, f_color)) => {
+            ui.draw_ellipse(graphics, pos, dim, f_color);
+            f_width
+        },
+        None => 0.0,
+    };
+    ui.draw_ellipse(graphics,
+                    [pos[0] + f_width, pos[1] + f_width],
+                    [dim[0] - f_width * 2.0, dim[1] - f_width * 2.0],
+                    state.color(color));
+}
+
+/// Draw a filled circle centered at the given point.
+pub fn draw_circle<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    center: Point,
+    radius: f64,
+    color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    ui.draw_ellipse(graphics, [center[0] - radius, center[1] - radius],
+                    [2.0 * radius, 2.0 * radius], color);
+}
+
+/// Return whether or not the mouse is over the oval that fills
+/// the rectangle at the given position and dimensions.
+#[inline]
+pub fn is_over(pos: Point,
+               mouse_pos: Point,
+               dim: Dimensions) -> bool {
+    if dim[0] <= 0.0 || dim[1] <= 0.0 { return false }
+    let (rx, ry) = (dim[0] / 2.0, dim[1] / 2.0);
+    let x = (mouse_pos[0] - (pos[0] + rx)) / rx;
+    let y = (mouse_pos[1] - (pos[1] + ry)) / ry;
+    x * x + y * y <= 1.0
+}
+
+/// Return whether or not the mouse is over the circle
+/// with the given center and radius.
+#[inline]
+pub fn is_over_circle(center: Point,
+                      mouse_pos: Point,
+                      radius: f64) -> bool {
+    let (x, y) = (mouse_pos[0] - center[0], mouse_pos[1] - center[1]);
+    x * x + y * y <= radius * radius
+}
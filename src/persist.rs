@@ -0,0 +1,112 @@
+//! The state saved by `Ui::save_state` and restored by `Ui::load_state`, so
+//! that an application may reopen as the user left it.
+//!
+//! The following are saved keyed by each widget's UIID, so the UIIDs must be
+//! the same from one run to the next:
+//!
+//! - The latest value committed via each widget. Once loaded, each widget
+//!   re-fires its event with its saved value the next time it's drawn (as it
+//!   does when a change is undone), so the application applies the saved
+//!   values just as it would changes made by the user. Widgets that may be
+//!   collapsed or expanded save their flag as a `Value::Bool` in the same way.
+//! - The scroll offset of the `Gallery`, the `Console`'s output and the
+//!   `DropDownList`'s open list, which are scrolled back when next drawn.
+//! - The layout of each `Dock`, including the positions of its floating
+//!   panels, which replaces the application's layout when next drawn.
+
+use dock::DockLayout;
+use history::Value;
+use rustc_serialize::{ json, Decodable, Encodable };
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{ Read, Write };
+use ui::UIID;
+
+/// Everything saved about the widgets, keyed by their UIIDs.
+#[derive(Clone, Debug)]
+pub struct SavedState {
+    /// The latest value committed via each widget.
+    pub values: HashMap<UIID, Value>,
+    /// How far each scrollable widget is scrolled.
+    pub scroll_offsets: HashMap<UIID, f64>,
+    /// The layout of each `Dock`.
+    pub dock_layouts: HashMap<UIID, DockLayout>,
+}
+
+/// The saved state as it is written, sorted by UIID so that the output is
+/// the same from one save to the next.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+struct SavedJson {
+    values: Vec<(UIID, Value)>,
+    scroll_offsets: Vec<(UIID, f64)>,
+    dock_layouts: Vec<(UIID, DockLayout)>,
+}
+
+impl SavedState {
+
+    /// A state without anything saved.
+    pub fn new() -> SavedState {
+        SavedState {
+            values: HashMap::new(),
+            scroll_offsets: HashMap::new(),
+            dock_layouts: HashMap::new(),
+        }
+    }
+
+    /// Add everything saved in the other state, replacing what this state
+    /// saved for the same widgets.
+    pub fn extend(&mut self, other: SavedState) {
+        self.values.extend(other.values.into_iter());
+        self.scroll_offsets.extend(other.scroll_offsets.into_iter());
+        self.dock_layouts.extend(other.dock_layouts.into_iter());
+    }
+
+}
+
+/// The entries of the map sorted by UIID.
+fn sorted<T: Clone>(map: &HashMap<UIID, T>) -> Vec<(UIID, T)> {
+    let mut entries: Vec<(UIID, T)> = map.iter().map(|(&id, value)| (id, value.clone())).collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+/// Write the given state to the writer as JSON.
+pub fn save<W: Write>(state: &SavedState, writer: &mut W) -> Result<(), String> {
+    let saved = SavedJson {
+        values: sorted(&state.values),
+        scroll_offsets: sorted(&state.scroll_offsets),
+        dock_layouts: sorted(&state.dock_layouts),
+    };
+    let mut json_string = String::new();
+    {
+        let mut encoder = json::Encoder::new_pretty(&mut json_string);
+        if let Err(e) = saved.encode(&mut encoder) {
+            return Err(format!("Failed to encode the Ui's state: {}", Error::description(&e)));
+        }
+    }
+    match writer.write_all(json_string.as_bytes()) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to write the Ui's state: {}", Error::description(&e))),
+    }
+}
+
+/// Read a state written by `save` from the reader.
+pub fn load<R: Read>(reader: &mut R) -> Result<SavedState, String> {
+    let mut json_string = String::new();
+    if let Err(e) = reader.read_to_string(&mut json_string) {
+        return Err(format!("Failed to read the Ui's state: {}", Error::description(&e)));
+    }
+    let json_object = match json::Json::from_str(&json_string) {
+        Ok(json_object) => json_object,
+        Err(e) => return Err(format!("Failed to construct json_object from str: {}", Error::description(&e))),
+    };
+    let mut decoder = json::Decoder::new(json_object);
+    match <SavedJson as Decodable>::decode(&mut decoder) {
+        Ok(saved) => Ok(SavedState {
+            values: saved.values.into_iter().collect(),
+            scroll_offsets: saved.scroll_offsets.into_iter().collect(),
+            dock_layouts: saved.dock_layouts.into_iter().collect(),
+        }),
+        Err(e) => Err(format!("Failed to decode the Ui's state: {}", Error::description(&e))),
+    }
+}
@@ -0,0 +1,108 @@
+
+use color::Color;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use ui::Ui;
+
+/// Draw a filled polygon with the given vertices. The polygon may be convex
+/// or concave but should not intersect itself.
+pub fn draw<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    points: &[Point],
+    color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    ui.draw_polygon(graphics, points, color);
+}
+
+/// Return whether or not the mouse is within the polygon with the given
+/// vertices (using the even-odd rule).
+pub fn is_over(points: &[Point], mouse_pos: Point) -> bool {
+    let (x, y) = (mouse_pos[0], mouse_pos[1]);
+    let mut inside = false;
+    let mut j = match points.len() {
+        0 => return false,
+        len => len - 1,
+    };
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[j]);
+        if (a[1] > y) != (b[1] > y)
+        && x < (b[0] - a[0]) * (y - a[1]) / (b[1] - a[1]) + a[0] {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Split a simple (non self-intersecting) polygon into triangles by
+/// repeatedly clipping "ears" from it. Vertices in line with their
+/// neighbours (or repeated) are dropped, as they bound no area. Any
+/// remainder that can't be clipped (i.e. if the polygon does intersect
+/// itself) is discarded.
+pub fn triangulate(points: &[Point]) -> Vec<[Point; 3]> {
+    let mut triangles = Vec::new();
+    if points.len() < 3 { return triangles }
+    let mut idxs: Vec<usize> = (0..points.len()).collect();
+    // Ears are convex vertices, so ensure a consistent winding.
+    if signed_area(points) < 0.0 { idxs.reverse() }
+    while idxs.len() > 3 {
+        let len = idxs.len();
+        // A collinear vertex is never convex, so would never be clipped.
+        let maybe_collinear = (0..len).find(|&i| {
+            let a = points[idxs[(i + len - 1) % len]];
+            let c = points[idxs[(i + 1) % len]];
+            cross(a, points[idxs[i]], c) == 0.0
+        });
+        if let Some(i) = maybe_collinear {
+            idxs.remove(i);
+            continue;
+        }
+        let maybe_ear = (0..len).find(|&i| {
+            let a = points[idxs[(i + len - 1) % len]];
+            let b = points[idxs[i]];
+            let c = points[idxs[(i + 1) % len]];
+            cross(a, b, c) > 0.0 && !idxs.iter().any(|&j| {
+                let p = points[j];
+                p != a && p != b && p != c && is_in_triangle(p, a, b, c)
+            })
+        });
+        match maybe_ear {
+            Some(i) => {
+                let a = points[idxs[(i + len - 1) % len]];
+                let c = points[idxs[(i + 1) % len]];
+                triangles.push([a, points[idxs[i]], c]);
+                idxs.remove(i);
+            },
+            None => return triangles,
+        }
+    }
+    triangles.push([points[idxs[0]], points[idxs[1]], points[idxs[2]]]);
+    triangles
+}
+
+/// Twice the signed area of the polygon, positive if its vertices
+/// wind in the same direction as a positive `cross`.
+fn signed_area(points: &[Point]) -> f64 {
+    let len = points.len();
+    (0..len).fold(0.0, |area, i| {
+        let (a, b) = (points[i], points[(i + 1) % len]);
+        area + a[0] * b[1] - b[0] * a[1]
+    })
+}
+
+/// The cross product of the edges `a -> b` and `b -> c`.
+fn cross(a: Point, b: Point, c: Point) -> f64 {
+    (b[0] - a[0]) * (c[1] - b[1]) - (b[1] - a[1]) * (c[0] - b[0])
+}
+
+/// Whether or not the point `p` lies within the triangle `a, b, c`.
+fn is_in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    let (ab, bc, ca) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
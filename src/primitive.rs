@@ -0,0 +1,108 @@
+use color::{ Color, Gradient };
+use dimensions::Dimensions;
+use image::ImageId;
+use label::{ FontId, FontSize };
+use point::Point;
+
+/// The basic shapes and text from which every widget is drawn. When the `Ui`
+/// is capturing primitives (see `Ui::capture_primitives`), widgets produce a
+/// list of these rather than drawing to the `Graphics` backend, allowing
+/// frames to be serialized, compared or rendered by an alternative backend.
+#[derive(Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Primitive {
+    /// A filled rectangle.
+    Rectangle {
+        pos: Point,
+        dim: Dimensions,
+        color: Color,
+    },
+    /// A filled rectangle whose corners are rounded with the given radius.
+    RoundedRectangle {
+        pos: Point,
+        dim: Dimensions,
+        radius: f64,
+        color: Color,
+    },
+    /// A straight line between the points `[x1, y1, x2, y2]`.
+    Line {
+        line: [f64; 4],
+        width: f64,
+        round: bool,
+        color: Color,
+    },
+    /// A filled ellipse within the given rectangle.
+    Ellipse {
+        pos: Point,
+        dim: Dimensions,
+        color: Color,
+    },
+    /// A filled polygon with the given vertices.
+    Polygon {
+        points: Vec<Point>,
+        color: Color,
+    },
+    /// A rectangle filled with a gradient.
+    Gradient {
+        pos: Point,
+        dim: Dimensions,
+        gradient: Gradient,
+    },
+    /// A run of text whose top-left corner is at the given position.
+    Text {
+        pos: Point,
+        font: FontId,
+        size: FontSize,
+        color: Color,
+        text: String,
+    },
+    /// The part of an image within `maybe_src_rect` (or the whole image)
+    /// stretched over the given rectangle and optionally tinted.
+    Image {
+        id: ImageId,
+        maybe_src_rect: Option<[i32; 4]>,
+        pos: Point,
+        dim: Dimensions,
+        maybe_color: Option<Color>,
+    },
+    /// Clip the following primitives to the given rectangle until the
+    /// matching `PopClip`.
+    PushClip {
+        pos: Point,
+        dim: Dimensions,
+    },
+    /// Stop clipping to the rectangle given by the last `PushClip`.
+    PopClip,
+}
+
+impl Primitive {
+
+    /// Return the primitive with the alpha of its colors multiplied by the
+    /// given amount. An untinted image is tinted white with the alpha.
+    pub fn faded(self, alpha: f32) -> Primitive {
+        match self {
+            Primitive::Rectangle { pos, dim, color } =>
+                Primitive::Rectangle { pos: pos, dim: dim, color: color.fade(alpha) },
+            Primitive::RoundedRectangle { pos, dim, radius, color } =>
+                Primitive::RoundedRectangle { pos: pos, dim: dim, radius: radius, color: color.fade(alpha) },
+            Primitive::Line { line, width, round, color } =>
+                Primitive::Line { line: line, width: width, round: round, color: color.fade(alpha) },
+            Primitive::Ellipse { pos, dim, color } =>
+                Primitive::Ellipse { pos: pos, dim: dim, color: color.fade(alpha) },
+            Primitive::Polygon { points, color } =>
+                Primitive::Polygon { points: points, color: color.fade(alpha) },
+            Primitive::Gradient { pos, dim, gradient } =>
+                Primitive::Gradient { pos: pos, dim: dim, gradient: gradient.map(|c| c.fade(alpha)) },
+            Primitive::Text { pos, font, size, color, text } =>
+                Primitive::Text { pos: pos, font: font, size: size, color: color.fade(alpha), text: text },
+            Primitive::Image { id, maybe_src_rect, pos, dim, maybe_color } => Primitive::Image {
+                id: id,
+                maybe_src_rect: maybe_src_rect,
+                pos: pos,
+                dim: dim,
+                maybe_color: Some(maybe_color.unwrap_or(Color::white()).fade(alpha)),
+            },
+            primitive => primitive,
+        }
+    }
+
+}
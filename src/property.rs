@@ -0,0 +1,64 @@
+//! Shared values to which widgets may be bound.
+//!
+//! A widget bound to a `Property` displays the property's current value and
+//! writes any changes made via the widget back to the property, so that a
+//! settings panel needn't set up a callback for each of its widgets.
+//!
+//! ```ignore
+//! let volume = Property::new(0.5);
+//! // Each frame:
+//! Slider::new(VOLUME, 0.0, 0.0, 1.0).bind(&volume).react(ui, graphics);
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A handle to a shared value. Cloning the handle gives another handle to
+/// the same value.
+#[derive(Debug)]
+pub struct Property<T> {
+    value: Rc<RefCell<T>>,
+}
+
+impl<T> Clone for Property<T> {
+    fn clone(&self) -> Property<T> {
+        Property { value: self.value.clone() }
+    }
+}
+
+impl<T> Property<T> {
+
+    /// Construct a property holding the given value.
+    pub fn new(value: T) -> Property<T> {
+        Property { value: Rc::new(RefCell::new(value)) }
+    }
+
+    /// A copy of the current value.
+    pub fn get(&self) -> T where T: Clone {
+        self.value.borrow().clone()
+    }
+
+    /// Replace the value.
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+    }
+
+    /// Call the given function with a reference to the value.
+    pub fn with<F, R>(&self, f: F) -> R where F: FnOnce(&T) -> R {
+        f(&*self.value.borrow())
+    }
+
+    /// Call the given function with a mutable reference to the value.
+    pub fn with_mut<F, R>(&self, f: F) -> R where F: FnOnce(&mut T) -> R {
+        f(&mut *self.value.borrow_mut())
+    }
+
+}
+
+/// Widgets that may be bound to a `Property`, displaying its value and
+/// writing changes back to it.
+pub trait Bindable<T> {
+    /// Display the property's value, writing any change made via the widget
+    /// back to the property.
+    fn bind(self, property: &Property<T>) -> Self;
+}
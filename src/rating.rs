@@ -0,0 +1,268 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::{ Color, Colorable, Fadeable };
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use history::Value;
+use mouse::ButtonState::{ Down, Up };
+use point::Point;
+use position::Positionable;
+use property::{ Bindable, Property };
+use rectangle;
+use shape::Shapeable;
+use std::f64::consts::PI;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+use widget::Widget;
+
+/// Represents the state of the Rating widget. The rating under the mouse is
+/// previewed while hovering and pressing.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(f64),
+    Clicked(f64),
+}
+
+widget_fns!(Rating, State, Widget::Rating(State::Normal));
+
+/// What happened to the Rating, returned by `Rating::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The mouse was released over the stars, setting the given rating.
+    Rated(f64),
+}
+
+/// The rating previewed with the mouse at the given distance from the left
+/// of the stars, rounded up to the nearest whole or half star.
+fn rating_at(x: f64, star_w: f64, stars: usize, half_stars: bool) -> f64 {
+    let pos = x / star_w;
+    let rating = match half_stars {
+        true => (pos * 2.0).ceil() / 2.0,
+        false => pos.ceil(),
+    };
+    let min = if half_stars { 0.5 } else { 1.0 };
+    clamp(rating, min, stars as f64)
+}
+
+/// The vertices of a five-pointed star centered within the given square.
+fn star_points(pos: Point, size: f64) -> Vec<Point> {
+    let center = [pos[0] + size / 2.0, pos[1] + size / 2.0];
+    let outer = size * 0.48;
+    let inner = outer * 0.4;
+    (0..10).map(|i| {
+        let radius = if i % 2 == 0 { outer } else { inner };
+        let angle = -PI / 2.0 + i as f64 * PI / 5.0;
+        [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+    }).collect()
+}
+
+/// A row of stars for rating something, i.e. in a review form. Hovering
+/// previews a rating and clicking sets it. The rating is given as a number
+/// of stars, which may be fractional when displayed read-only.
+pub struct Rating<'a, F> {
+    ui_id: UIID,
+    value: f64,
+    stars: usize,
+    half_stars: bool,
+    read_only: bool,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    maybe_property: Option<Property<f64>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> Rating<'a, F> {
+
+    /// Create a rating context to be built upon, showing the given rating
+    /// out of five stars.
+    pub fn new(ui_id: UIID, value: f64) -> Rating<'a, F> {
+        Rating {
+            ui_id: ui_id,
+            value: value,
+            stars: 5,
+            half_stars: false,
+            read_only: false,
+            pos: [0.0, 0.0],
+            dim: [160.0, 32.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            maybe_property: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// Set the number of stars, which is the greatest rating.
+    pub fn stars(self, stars: usize) -> Rating<'a, F> {
+        Rating { stars: stars, ..self }
+    }
+
+    /// Whether or not half a star may be rated, rather than only whole stars.
+    pub fn half_stars(self, half_stars: bool) -> Rating<'a, F> {
+        Rating { half_stars: half_stars, ..self }
+    }
+
+    /// Whether or not the rating is only displayed, ignoring the mouse.
+    pub fn read_only(self, read_only: bool) -> Rating<'a, F> {
+        Rating { read_only: read_only, ..self }
+    }
+
+}
+
+impl<'a, F> Colorable for Rating<'a, F> {
+    fn color(mut self, color: Color) -> Self {
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a, F> Fadeable for Rating<'a, F> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
+        self
+    }
+}
+
+impl<'a, F> Callable<F> for Rating<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Bindable<f64> for Rating<'a, F> {
+    fn bind(mut self, property: &Property<f64>) -> Self {
+        self.value = property.get();
+        self.maybe_property = Some(property.clone());
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for Rating<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Positionable for Rating<'a, F> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<'a, F> Shapeable for Rating<'a, F> {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
+
+impl<'a, F> ::draw::Drawable for Rating<'a, F> where F: FnMut(f64) + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Rated(rating)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(rating) }
+        }
+    }
+}
+
+impl<'a> Rating<'a, NoCallback> {
+
+    /// Draw the rating, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the rating needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Rating<'a, F> {
+
+    /// Update and draw the rating, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_rating, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        // The stars are square, as large as fits the widget's height.
+        let stars = ::std::cmp::max(self.stars, 1);
+        let star_w = self.dim[1].min(self.dim[0] / stars as f64);
+        let stars_dim = [star_w * stars as f64, star_w];
+        let stars_pos = [self.pos[0], self.pos[1] + (self.dim[1] - star_w) / 2.0];
+        let is_over = !self.read_only && rectangle::is_over(stars_pos, mouse.pos, stars_dim);
+        let preview = rating_at(mouse.pos[0] - stars_pos[0], star_w, stars, self.half_stars);
+
+        let new_state = match (is_over, state, mouse.left) {
+            (true,  State::Normal,     Down) => State::Normal,
+            (true,  _,                 Down) => State::Clicked(preview),
+            (true,  _,                 Up)   => State::Highlighted(preview),
+            (false, State::Clicked(r), Down) => State::Clicked(r),
+            _                                => State::Normal,
+        };
+        let maybe_event = match (is_over, state, new_state) {
+            (true, State::Clicked(_), State::Highlighted(rating)) => {
+                ui.record_change(self.ui_id, Value::Number(self.value), Value::Number(rating));
+                Some(Event::Rated(rating))
+            },
+            _ => None,
+        };
+        // A change that was undone or redone is re-fired as though rated.
+        let maybe_event = match ui.take_restored(self.ui_id) {
+            Some(Value::Number(rating)) => Some(Event::Rated(rating)),
+            _ => maybe_event,
+        };
+        if is_over {
+            ui.set_mouse_cursor(MouseCursor::Hand);
+        }
+
+        // Each star is drawn empty, then filled up to the displayed rating.
+        let (displayed, fill_state) = match new_state {
+            State::Highlighted(rating) => (rating, rectangle::State::Highlighted),
+            State::Clicked(rating) => (rating, rectangle::State::Clicked),
+            State::Normal => (self.value, rectangle::State::Normal),
+        };
+        let fill_color = fill_state.color(style.color);
+        for i in 0..stars {
+            let star_pos = [stars_pos[0] + star_w * i as f64, stars_pos[1]];
+            let points = star_points(star_pos, star_w);
+            let fill = clamp(displayed - i as f64, 0.0, 1.0);
+            if fill < 1.0 {
+                ui.draw_polygon(graphics, &points, style.frame_color);
+            }
+            if fill > 0.0 {
+                ui.push_clip(star_pos, [star_w * fill, star_w]);
+                ui.draw_polygon(graphics, &points, fill_color);
+                ui.pop_clip();
+            }
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Rating(new_state), self.pos, self.dim);
+
+        if let (Some(Event::Rated(rating)), Some(property)) = (maybe_event, self.maybe_property.as_ref()) {
+            property.set(rating);
+        }
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
@@ -1,12 +1,12 @@
 
-use color::Color;
+use color::{ Color, Fill, Gradient };
 use dimensions::Dimensions;
-use graphics;
-use graphics::{ DrawState, Graphics };
-use graphics::math::Matrix2d;
+use graphics::Graphics;
 use graphics::character::CharacterCache;
+use image;
+use image::NinePatch;
 use label;
-use label::FontSize;
+use label::{ FontId, FontSize };
 use point::Point;
 use ui::Ui;
 use utils::map_range;
@@ -19,71 +19,149 @@ pub enum State {
     Clicked,
 }
 
+impl State {
+    /// Return the given color as it should appear for this State.
+    pub fn color(&self, color: Color) -> Color {
+        match *self {
+            State::Normal => color,
+            State::Highlighted => color.highlighted(),
+            State::Clicked => color.clicked(),
+        }
+    }
+}
+
+/// A soft shadow cast beneath a rectangle.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct Shadow {
+    /// The offset of the shadow from the rectangle.
+    pub offset: [f64; 2],
+    /// The distance over which the shadow's edges fade out.
+    pub softness: f64,
+    pub color: Color,
+}
+
+/// The maximum number of layers with which a shadow is drawn.
+const MAX_SHADOW_LAYERS: usize = 12;
+
+/// Draw a shadow beneath the rectangle at the given position and dimensions
+/// as a series of translucent rectangles that grow towards the soft edge.
+pub fn draw_shadow<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    pos: Point,
+    dim: Dimensions,
+    corner_radius: f64,
+    shadow: Shadow
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let pos = [pos[0] + shadow.offset[0], pos[1] + shadow.offset[1]];
+    let num_layers = ::std::cmp::min(shadow.softness.max(1.0) as usize, MAX_SHADOW_LAYERS);
+    let mut color = shadow.color;
+    color.set_a(shadow.color.a() / num_layers as f32);
+    // The overlapping layers accumulate towards the full alpha at the center.
+    for i in 0..num_layers {
+        let spread = shadow.softness * (1.0 - (i as f64 + 1.0) / num_layers as f64)
+                   - shadow.softness / 2.0;
+        ui.draw_rounded_rectangle(graphics,
+                                  [pos[0] - spread, pos[1] - spread],
+                                  [dim[0] + spread * 2.0, dim[1] + spread * 2.0],
+                                  (corner_radius + spread).max(0.0),
+                                  color);
+    }
+}
+
+/// Return the fill for a widget's rectangle. A background image takes
+/// precedence over a gradient, which takes precedence over the color. The
+/// state's color is applied to the image's tint and to both of a gradient's
+/// colors, otherwise the given (already state-colored) color is used.
+pub fn fill(
+    color: Color,
+    maybe_gradient: Option<Gradient>,
+    maybe_background_image: Option<NinePatch>,
+    state: State
+) -> Fill {
+    match (maybe_background_image, maybe_gradient) {
+        (Some(nine_patch), _) => Fill::NinePatch(nine_patch, state.color(Color::white())),
+        (None, Some(gradient)) => Fill::Gradient(gradient.map(|c| state.color(c))),
+        (None, None) => Fill::Color(color),
+    }
+}
+
 /// Draw a basic rectangle. The primary purpose
 /// of this is to be used as a building block for
-/// other widgets.
-pub fn draw<B: Graphics>(
-    win_w: f64,
-    win_h: f64,
+/// other widgets. The fill may be a `Color` or a `Gradient`.
+pub fn draw<B, C, F>(
+    ui: &mut Ui<C>,
     graphics: &mut B,
     state: State,
     pos: Point,
     dim: Dimensions,
     maybe_frame: Option<(f64, Color)>,
-    color: Color
-) {
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h);
+    corner_radius: f64,
+    fill: F
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache,
+        F: Into<Fill>
+{
     if let Some((_, f_color)) = maybe_frame {
-        draw_frame(draw_state, transform, graphics, pos, dim, f_color)
+        draw_frame(ui, graphics, pos, dim, corner_radius, f_color)
     }
     let f_width = if let Some((f_width, _)) = maybe_frame { f_width } else { 0.0 };
-    draw_normal(draw_state, transform, graphics, state, pos, dim, f_width, color);
+    draw_normal(ui, graphics, state, pos, dim, f_width, corner_radius, fill.into());
 }
 
 /// Draw the button border.
-fn draw_frame<B: Graphics>(
-    draw_state: &DrawState,
-    transform: Matrix2d,
+fn draw_frame<B, C>(
+    ui: &mut Ui<C>,
     graphics: &mut B,
     pos: Point,
     dim: Dimensions,
+    corner_radius: f64,
     color: Color
-) {
-    graphics::Rectangle::new(color.0)
-        .draw(
-            [pos[0], pos[1], dim[0], dim[1]],
-            draw_state,
-            transform,
-            graphics
-        );
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    ui.draw_rounded_rectangle(graphics, pos, dim, corner_radius, color);
 }
 
 /// Draw the rectangle while considering frame
 /// width for position and dimensions.
-fn draw_normal<B: Graphics>(
-    draw_state: &DrawState,
-    transform: Matrix2d,
+fn draw_normal<B, C>(
+    ui: &mut Ui<C>,
     graphics: &mut B,
     state: State,
     pos: Point,
     dim: Dimensions,
     frame_width: f64,
-    color: Color
-) {
-    let Color(col) = match state {
-        State::Normal => color,
-        State::Highlighted => color.highlighted(),
-        State::Clicked => color.clicked(),
-    };
-    graphics::Rectangle::new(col)
-        .draw([pos[0] + frame_width,
-            pos[1] + frame_width,
-            dim[0] - frame_width * 2.0,
-            dim[1] - frame_width * 2.0],
-        draw_state,
-        transform,
-        graphics);
+    corner_radius: f64,
+    fill: Fill
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let pos = [pos[0] + frame_width, pos[1] + frame_width];
+    let dim = [dim[0] - frame_width * 2.0, dim[1] - frame_width * 2.0];
+    // Keep the inner corners concentric with those of the frame.
+    let radius = (corner_radius - frame_width).max(0.0);
+    match fill {
+        Fill::Color(color) => ui.draw_rounded_rectangle(graphics, pos, dim, radius, state.color(color)),
+        // Gradients are drawn in bands and so their corners are not rounded.
+        Fill::Gradient(gradient) => {
+            let gradient = gradient.map(|c| state.color(c));
+            ui.draw_gradient(graphics, pos, dim, gradient)
+        },
+        Fill::NinePatch(nine_patch, tint) => {
+            image::draw_nine_patch(ui, graphics, nine_patch, pos, dim, state.color(tint))
+        },
+    }
 }
 
 /// Return whether or not the widget has been hit by a mouse_press.
@@ -99,34 +177,29 @@ pub fn is_over(pos: Point,
 }
 
 /// Draw a label centered within a rect of given position and dimensions.
-pub fn draw_with_centered_label<B, C>(
-    win_w: f64,
-    win_h: f64,
-    graphics: &mut B,
+pub fn draw_with_centered_label<B, C, F>(
     ui: &mut Ui<C>,
+    graphics: &mut B,
     state: State,
     pos: Point,
     dim: Dimensions,
     maybe_frame: Option<(f64, Color)>,
-    color: Color,
+    corner_radius: f64,
+    fill: F,
     text: &str,
+    font: FontId,
     font_size: FontSize,
     text_color: Color
 )
     where
         B: Graphics<Texture = <C as CharacterCache>::Texture>,
-        C: CharacterCache
+        C: CharacterCache,
+        F: Into<Fill>
 {
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h);
-    if let Some((_, f_color)) = maybe_frame {
-        draw_frame(draw_state, transform, graphics, pos, dim, f_color)
-    }
-    let f_width = if let Some((f_width, _)) = maybe_frame { f_width } else { 0.0 };
-    draw_normal(draw_state, transform, graphics, state, pos, dim, f_width, color);
-    let text_w = label::width(ui, font_size, text);
+    draw(ui, graphics, state, pos, dim, maybe_frame, corner_radius, fill);
+    let text_w = label::width(ui, font, font_size, text);
     let l_pos = [pos[0] + (dim[0] - text_w) / 2.0, pos[1] + (dim[1] - font_size as f64) / 2.0];
-    ui.draw_text(graphics, l_pos, font_size, text_color, text);
+    ui.draw_text(graphics, l_pos, font, font_size, text_color, text);
 }
 
 #[derive(Copy, Clone)]
@@ -0,0 +1,125 @@
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use std::collections::HashMap;
+use ui::{ UIID, Ui };
+
+/// A `Graphics` backend that is able to render into an offscreen texture.
+pub trait RenderTarget: Graphics {
+    /// Redirect all drawing that falls within the given `[x, y, w, h]`
+    /// rectangle of the window (in physical pixels) into a new texture of
+    /// the rectangle's size.
+    fn begin_render_to_texture(&mut self, rect: [u32; 4]);
+    /// Stop redirecting drawing and return the texture that was drawn to.
+    fn end_render_to_texture(&mut self) -> Self::Texture;
+}
+
+/// The rendered contents of a container.
+struct Cached<T> {
+    pos: Point,
+    dim: Dimensions,
+    /// The `Ui`'s render cache generation when the texture was rendered.
+    generation: u64,
+    /// The widgets that were set while rendering the texture.
+    widgets: Vec<UIID>,
+    texture: T,
+}
+
+/// Caches the rendering of containers whose contents haven't changed, so
+/// that each frame their texture is re-blitted rather than all of their
+/// widgets being drawn again.
+///
+/// A container is redrawn when the mouse is (or was last frame) over it,
+/// when keys are pressed or text is entered, while colors are animating, or
+/// when the `Ui`'s cache has been invalidated (i.e. by a resize or theme
+/// change). Changes to the values displayed by the container's widgets can't
+/// be detected, so the cache for a container must be invalidated whenever
+/// these are changed by the application.
+pub struct RenderCache<T> {
+    entries: HashMap<UIID, Cached<T>>,
+}
+
+impl<T> RenderCache<T> {
+
+    /// Construct an empty RenderCache.
+    pub fn new() -> RenderCache<T> {
+        RenderCache { entries: HashMap::new() }
+    }
+
+    /// Discard the cached rendering of the given container so that it is
+    /// redrawn on the next frame.
+    pub fn invalidate(&mut self, ui_id: UIID) {
+        self.entries.remove(&ui_id);
+    }
+
+    /// Discard the cached rendering of every container.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Draw the container with the given UIID occupying the given rectangle.
+    /// `draw_contents` draws the container's widgets and is only called when
+    /// the container needs to be redrawn, otherwise the cached texture is
+    /// drawn in its place.
+    pub fn draw<B, C, F>(
+        &mut self,
+        ui: &mut Ui<C>,
+        graphics: &mut B,
+        ui_id: UIID,
+        pos: Point,
+        dim: Dimensions,
+        draw_contents: F
+    )
+        where
+            B: RenderTarget<Texture = T>,
+            C: CharacterCache<Texture = T>,
+            F: FnOnce(&mut Ui<C>, &mut B)
+    {
+        // Captured primitives must describe the whole frame.
+        if ui.is_capturing_primitives() {
+            return draw_contents(ui, graphics);
+        }
+
+        let generation = ui.render_cache_generation();
+        let is_clean = match self.entries.get(&ui_id) {
+            Some(cached) => cached.pos == pos && cached.dim == dim
+                && cached.generation == generation
+                && !ui.needs_redraw(pos, dim),
+            None => false,
+        };
+        if let Some(stats) = ui.current_frame_stats_mut() {
+            if is_clean { stats.render_cache_hits += 1 } else { stats.render_cache_misses += 1 }
+        }
+
+        if !is_clean {
+            let pixel_factor = ui.pixel_factor();
+            let rect = [(pos[0] * pixel_factor).max(0.0) as u32,
+                        (pos[1] * pixel_factor).max(0.0) as u32,
+                        (dim[0] * pixel_factor).ceil() as u32,
+                        (dim[1] * pixel_factor).ceil() as u32];
+            let first = ui.updated_widget_count();
+            ui.flush_rectangles(graphics);
+            graphics.begin_render_to_texture(rect);
+            draw_contents(ui, graphics);
+            ui.flush_rectangles(graphics);
+            let texture = graphics.end_render_to_texture();
+            let widgets = ui.widgets_updated_since(first).to_vec();
+            self.entries.insert(ui_id, Cached {
+                pos: pos,
+                dim: dim,
+                generation: generation,
+                widgets: widgets,
+                texture: texture,
+            });
+        }
+
+        let cached = &self.entries[&ui_id];
+        // The container's widgets are still alive, even if they weren't set.
+        if is_clean {
+            ui.keep_widgets_alive(&cached.widgets);
+        }
+        ui.draw_texture(graphics, pos, dim, &cached.texture);
+    }
+
+}
@@ -0,0 +1,293 @@
+use animation;
+use callback::{ Callable, NoCallback, Reactable };
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use drag::Preview;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use mouse::ButtonState::{ Down, Up };
+use point::Point;
+use rectangle;
+use std::cmp;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+use widget::Widget;
+
+/// Tuple / Callback params.
+pub type Idx = usize;
+
+/// The distance the mouse must move while pressing a row before the row is
+/// grabbed and dragged.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// The payload of a row being dragged.
+struct Row(Idx);
+
+/// The space left in the list for the dragged row, which moves smoothly
+/// between positions as the row is dragged.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Gap {
+    /// The position (in rows) from which the gap is moving.
+    from: f64,
+    /// The position at which the dragged row would be dropped.
+    to: Idx,
+    /// The time at which the gap began moving.
+    start: f64,
+}
+
+impl Gap {
+    /// The position of the gap (in rows) at the given time.
+    fn position_at(&self, time: f64, duration: f64) -> f64 {
+        let perc = animation::ease_in_out(animation::progress(self.start, duration, time));
+        self.from + (self.to as f64 - self.from) * perc
+    }
+}
+
+/// Represents the state of the ReorderableList widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Normal,
+    Highlighted(Idx),
+    /// The row was pressed with the mouse at the given position, but hasn't
+    /// yet been dragged.
+    Pressed(Idx, Point),
+    /// The row is being dragged, having been grabbed at the given distance
+    /// from its top edge.
+    Dragging(Idx, f64, Gap),
+}
+
+widget_fns!(ReorderableList, State, Widget::ReorderableList(State::Normal));
+
+/// What happened to the ReorderableList, returned by `ReorderableList::react`
+/// and given to its `on_event` closure.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    /// The row at the given index was grabbed and is being dragged.
+    Grabbed(Idx),
+    /// A row was dropped at a new position. Gives the new ordering as the
+    /// index of the row that belongs at each position.
+    Reordered(Vec<Idx>),
+}
+
+/// A list of rows which may be reordered by dragging them to new positions.
+/// The list doesn't reorder its strings itself, but calls back with the new
+/// ordering so that the application may reorder whatever they represent.
+pub struct ReorderableList<'a, F> {
+    ui_id: UIID,
+    strings: &'a [String],
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> ReorderableList<'a, F> {
+
+    /// Create a reorderable list context to be built upon. The dimensions
+    /// are those of each row.
+    pub fn new(ui_id: UIID, strings: &'a [String]) -> ReorderableList<'a, F> {
+        ReorderableList {
+            ui_id: ui_id,
+            strings: strings,
+            pos: [0.0, 0.0],
+            dim: [128.0, 32.0],
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+}
+
+composite_builder_impls!(ReorderableList<'a, F>);
+
+impl<'a, F> Callable<F> for ReorderableList<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for ReorderableList<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for ReorderableList<'a, F>
+    where
+        F: FnMut(Vec<Idx>) + 'a
+{
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::Reordered(order)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(order) }
+        }
+    }
+}
+
+impl<'a> ReorderableList<'a, NoCallback> {
+
+    /// Draw the list, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the new ordering needs
+    /// to borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> ReorderableList<'a, F> {
+
+    /// Update and draw the list, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_reorderable_list, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let time = ui.time();
+        let duration = ui.theme.transition_duration;
+        let len = self.strings.len();
+        let row_h = self.dim[1];
+        let list_dim = [self.dim[0], row_h * len as f64];
+        let is_over_idx = match rectangle::is_over(self.pos, mouse.pos, list_dim) {
+            true => Some(cmp::min(((mouse.pos[1] - self.pos[1]) / row_h) as Idx, len - 1)),
+            false => None,
+        };
+        let hover_state = match is_over_idx {
+            Some(idx) => State::Highlighted(idx),
+            None => State::Normal,
+        };
+        // The position at which a row grabbed `grab_y` from its top would be dropped.
+        let top = self.pos[1];
+        let slot_at = |grab_y: f64| {
+            let slot = ((mouse.pos[1] - grab_y - top) / row_h).round();
+            clamp(slot, 0.0, len as f64 - 1.0) as Idx
+        };
+        let is_own_drag = ui.dragging().map(|drag| drag.source()) == Some(self.ui_id);
+
+        let mut maybe_event = None;
+        let new_state = match (state, mouse.left) {
+            (State::Dragging(idx, grab_y, gap), Down) => {
+                // The drag may have been cancelled via the `Ui`.
+                if !is_own_drag { State::Normal }
+                else {
+                    let slot = slot_at(grab_y);
+                    let gap = match slot == gap.to {
+                        true => gap,
+                        false => Gap { from: gap.position_at(time, duration), to: slot, start: time },
+                    };
+                    State::Dragging(idx, grab_y, gap)
+                }
+            },
+            (State::Dragging(_, grab_y, _), Up) => {
+                let mut maybe_dropped = None;
+                if is_own_drag {
+                    ui.drop_target(self.pos, list_dim, |row: Row| maybe_dropped = Some(row.0));
+                }
+                if let Some(idx) = maybe_dropped {
+                    let slot = slot_at(grab_y);
+                    if slot != idx {
+                        let mut order: Vec<Idx> = (0..len).filter(|&i| i != idx).collect();
+                        order.insert(slot, idx);
+                        maybe_event = Some(Event::Reordered(order));
+                    }
+                }
+                hover_state
+            },
+            (State::Pressed(idx, press_pos), Down) => {
+                let (dx, dy) = (mouse.pos[0] - press_pos[0], mouse.pos[1] - press_pos[1]);
+                if (dx * dx + dy * dy).sqrt() > DRAG_THRESHOLD && idx < len {
+                    ui.start_drag(self.ui_id, Row(idx), Preview::Nothing);
+                    maybe_event = Some(Event::Grabbed(idx));
+                    let grab_y = press_pos[1] - (self.pos[1] + idx as f64 * row_h);
+                    State::Dragging(idx, grab_y, Gap { from: idx as f64, to: idx, start: time })
+                } else {
+                    State::Pressed(idx, press_pos)
+                }
+            },
+            (State::Highlighted(_), Down) => match is_over_idx {
+                Some(idx) => State::Pressed(idx, mouse.pos),
+                None => State::Normal,
+            },
+            (_, Down) => State::Normal,
+            (_, Up) => hover_state,
+        };
+
+        match new_state {
+            State::Highlighted(_) | State::Pressed(_, _) => ui.set_mouse_cursor(MouseCursor::Hand),
+            State::Dragging(_, _, _) => ui.set_mouse_cursor(MouseCursor::Move),
+            State::Normal => (),
+        }
+
+        let frame_w = style.frame;
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        let fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+
+        // The rows beneath the gap are pushed down to make room for the dragged row.
+        let (maybe_dragged, maybe_gap_pos) = match new_state {
+            State::Dragging(idx, _, gap) => (Some(idx), Some(gap.position_at(time, duration))),
+            _ => (None, None),
+        };
+        let mut slot = 0;
+        for (i, string) in self.strings.iter().enumerate() {
+            if maybe_dragged == Some(i) { continue }
+            let shift = match maybe_gap_pos {
+                Some(gap_pos) => clamp(slot as f64 + 1.0 - gap_pos, 0.0, 1.0),
+                None => 0.0,
+            };
+            let row_pos = [self.pos[0], self.pos[1] + (slot as f64 + shift) * row_h];
+            let rect_state = match new_state {
+                State::Highlighted(idx) if idx == i => rectangle::State::Highlighted,
+                State::Pressed(idx, _) if idx == i => rectangle::State::Clicked,
+                _ => rectangle::State::Normal,
+            };
+            rectangle::draw_with_centered_label(
+                ui, graphics, rect_state, row_pos,
+                self.dim, maybe_frame, style.corner_radius, fill, string,
+                style.font, style.label_font_size, style.label_color
+            );
+            slot += 1;
+        }
+
+        // The dragged row follows the mouse, above the rest of the list.
+        if let State::Dragging(idx, grab_y, _) = new_state {
+            let row_pos = [self.pos[0], mouse.pos[1] - grab_y];
+            if let Some(shadow) = style.maybe_shadow {
+                rectangle::draw_shadow(ui, graphics, row_pos, self.dim, style.corner_radius, shadow);
+            }
+            rectangle::draw_with_centered_label(
+                ui, graphics, rectangle::State::Clicked, row_pos,
+                self.dim, maybe_frame, style.corner_radius, fill, &self.strings[idx],
+                style.font, style.label_font_size, style.label_color
+            );
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::ReorderableList(new_state), self.pos, list_dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event.clone(), self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
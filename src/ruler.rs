@@ -0,0 +1,327 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::{ Color, Colorable, Fadeable };
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use mouse::ButtonState::{ Down, Up };
+use point::Point;
+use position::Positionable;
+use rectangle;
+use shape::Shapeable;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::{ tick_precision, tick_step };
+use viewport::Transform;
+use widget::Widget;
+
+/// The least spacing in pixels between labelled ticks.
+const MIN_TICK_SPACING: f64 = 64.0;
+
+/// The size of the marker's arrow head.
+const MARKER_SIZE: f64 = 5.0;
+
+/// The direction along which a Ruler measures.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Orientation {
+    /// Measures left to right, i.e. above a canvas.
+    Horizontal,
+    /// Measures top to bottom, i.e. beside a canvas.
+    Vertical,
+}
+
+/// What the mouse is doing to the Ruler.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted,
+    /// The marker is being dragged.
+    Dragging,
+}
+
+/// Represents the state of the Ruler widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    pub interaction: Interaction,
+    /// The position of the marker, if it has been placed.
+    pub maybe_marker: Option<f64>,
+}
+
+widget_fns!(Ruler, State, Widget::Ruler(State { interaction: Interaction::Normal, maybe_marker: None }));
+
+/// What happened to the Ruler, returned by `Ruler::react` and given to its
+/// `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The marker was placed or dragged to the given position.
+    MarkerMoved(f64),
+    /// The marker was let go at the given position.
+    MarkerReleased(f64),
+}
+
+/// Tick marks labelled with the positions along one axis of a panned and
+/// zoomed view, i.e. above and beside a `Viewport` or a canvas editor.
+/// Pressing the ruler places a marker, which may be dragged, and whose
+/// position is reported as it moves. The marker may also be placed by the
+/// application with `marker`, i.e. to show the position of a guide.
+pub struct Ruler<'a, F> {
+    ui_id: UIID,
+    orientation: Orientation,
+    /// The screen position (along the orientation) at which `offset` lies.
+    origin: f64,
+    offset: f64,
+    scale: f64,
+    maybe_marker: Option<f64>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> Ruler<'a, F> {
+
+    /// Create a ruler context to be built upon, measuring the view in which
+    /// the position `offset` lies at the screen position `origin` (along the
+    /// orientation), with `scale` pixels to each unit.
+    pub fn new(ui_id: UIID, orientation: Orientation, origin: f64, offset: f64, scale: f64) -> Ruler<'a, F> {
+        let dim = match orientation {
+            Orientation::Horizontal => [256.0, 24.0],
+            Orientation::Vertical => [24.0, 256.0],
+        };
+        Ruler {
+            ui_id: ui_id,
+            orientation: orientation,
+            origin: origin,
+            offset: offset,
+            scale: scale,
+            maybe_marker: None,
+            pos: [0.0, 0.0],
+            dim: dim,
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// A ruler measuring the horizontal axis of a viewport's canvas.
+    pub fn horizontal(ui_id: UIID, transform: Transform) -> Ruler<'a, F> {
+        Ruler::new(ui_id, Orientation::Horizontal, transform.origin[0], transform.offset[0], transform.zoom)
+    }
+
+    /// A ruler measuring the vertical axis of a viewport's canvas.
+    pub fn vertical(ui_id: UIID, transform: Transform) -> Ruler<'a, F> {
+        Ruler::new(ui_id, Orientation::Vertical, transform.origin[1], transform.offset[1], transform.zoom)
+    }
+
+    /// Place the marker at the given position.
+    pub fn marker(self, position: f64) -> Ruler<'a, F> {
+        Ruler { maybe_marker: Some(position), ..self }
+    }
+
+}
+
+impl<'a, F> Colorable for Ruler<'a, F> {
+    fn color(mut self, color: Color) -> Self {
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a, F> Fadeable for Ruler<'a, F> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
+        self
+    }
+}
+
+impl<'a, F> Callable<F> for Ruler<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for Ruler<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Labelable<'a> for Ruler<'a, F> {
+    /// Rulers aren't labelled as a whole; their ticks are labelled instead.
+    fn label(self, _text: &'a str) -> Self {
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a, F> Positionable for Ruler<'a, F> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<'a, F> Shapeable for Ruler<'a, F> {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
+
+impl<'a, F> ::draw::Drawable for Ruler<'a, F> where F: FnMut(f64) + 'a {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        if let Some(Event::MarkerMoved(position)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(position) }
+        }
+    }
+}
+
+impl<'a> Ruler<'a, NoCallback> {
+
+    /// Draw the ruler, returning what happened to its marker. An alternative
+    /// to `Callable::callback` for when the reaction to the marker needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> Ruler<'a, F> {
+
+    /// Update and draw the ruler, returning what happened to its marker.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_ruler, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let padding = ui.theme.padding;
+        let font_size = style.label_font_size;
+        let is_horizontal = self.orientation == Orientation::Horizontal;
+        let scale = if self.scale > 0.0 { self.scale } else { 1.0 };
+
+        // Screen positions along the ruler and the positions they measure.
+        let (origin, offset) = (self.origin, self.offset);
+        let along = |p: Point| if is_horizontal { p[0] } else { p[1] };
+        let at = |position: f64| origin + (position - offset) * scale;
+        let position_at = |screen: f64| offset + (screen - origin) / scale;
+        let (start, length) = (along(self.pos), along(self.dim));
+        let (across_start, depth) = if is_horizontal { (self.pos[1], self.dim[1]) } else { (self.pos[0], self.dim[0]) };
+
+        // Pressing the ruler places the marker, which follows the mouse
+        // until released.
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        let interaction = match (is_over, state.interaction, mouse.left) {
+            (true,  Interaction::Normal,   Down) => Interaction::Normal,
+            (true,  _,                     Down) => Interaction::Dragging,
+            (true,  _,                     Up)   => Interaction::Highlighted,
+            (false, Interaction::Dragging, Down) => Interaction::Dragging,
+            _                                    => Interaction::Normal,
+        };
+        let mut maybe_marker = self.maybe_marker.or(state.maybe_marker);
+        let maybe_event = match (state.interaction, interaction) {
+            (_, Interaction::Dragging) => {
+                let screen = along(mouse.pos).max(start).min(start + length);
+                let position = position_at(screen);
+                maybe_marker = Some(position);
+                match state.interaction != Interaction::Dragging || ui.mouse_moved() {
+                    true => Some(Event::MarkerMoved(position)),
+                    false => None,
+                }
+            },
+            (Interaction::Dragging, _) => maybe_marker.map(|position| Event::MarkerReleased(position)),
+            _ => None,
+        };
+        match interaction {
+            Interaction::Highlighted | Interaction::Dragging if is_horizontal =>
+                ui.set_mouse_cursor(MouseCursor::ResizeHorizontal),
+            Interaction::Highlighted | Interaction::Dragging =>
+                ui.set_mouse_cursor(MouseCursor::ResizeVertical),
+            Interaction::Normal => (),
+        }
+        let new_state = State { interaction: interaction, maybe_marker: maybe_marker };
+
+        // Draw the background, with a line along the edge facing the view.
+        let line_color = style.color.plain_contrast();
+        let background = match interaction {
+            Interaction::Normal => rectangle::State::Highlighted.color(style.color),
+            _ => rectangle::State::Clicked.color(style.color),
+        };
+        ui.draw_rectangle(graphics, self.pos, self.dim, background);
+        let edge = across_start + depth;
+        let point = |a: f64, b: f64| if is_horizontal { [a, b] } else { [b, a] };
+        let line = |a: f64, b: f64, c: f64, d: f64| if is_horizontal { [a, b, c, d] } else { [b, a, d, c] };
+        ui.draw_line(graphics, line(start, edge, start + length, edge), 1.0, false, style.frame_color);
+
+        // Label each step, with minor ticks between.
+        ui.push_clip(self.pos, self.dim);
+        let step = tick_step(MIN_TICK_SPACING, scale);
+        let precision = tick_precision(step);
+        let first_tick = (position_at(start) / step).floor() as i64;
+        let last_tick = (position_at(start + length) / step).ceil() as i64;
+        for tick in first_tick..last_tick + 1 {
+            let position = tick as f64 * step;
+            let p = at(position);
+            ui.draw_line(graphics, line(p, across_start, p, edge), 1.0, false, line_color.fade(0.6));
+            let text = ui.theme.number_format.format(position, precision);
+            let text_pos = match is_horizontal {
+                true => [p + 2.0, across_start + padding / 2.0],
+                false => [across_start + padding / 2.0, p + 2.0],
+            };
+            ui.draw_text(graphics, text_pos, style.font, font_size, style.label_color, &text);
+            for minor in 1..5 {
+                let p = at(position + step * minor as f64 / 5.0);
+                ui.draw_line(graphics, line(p, edge - depth / 4.0, p, edge), 1.0, false, line_color.fade(0.6));
+            }
+        }
+
+        // Draw the marker as an arrow pointing into the view.
+        if let Some(position) = maybe_marker {
+            let p = at(position);
+            let marker_color = ui.theme.link_color;
+            ui.draw_line(graphics, line(p, across_start, p, edge), 1.0, false, marker_color);
+            let head = [point(p - MARKER_SIZE, edge - MARKER_SIZE),
+                        point(p + MARKER_SIZE, edge - MARKER_SIZE),
+                        point(p, edge)];
+            ui.draw_polygon(graphics, &head, marker_color);
+        }
+        ui.pop_clip();
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Ruler(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
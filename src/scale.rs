@@ -0,0 +1,55 @@
+use num::{ Float, FromPrimitive, ToPrimitive };
+
+/// Describes how the values of a widget are spread along its length, such
+/// as the values of a Slider along its track.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Scale {
+    /// Values are spread evenly.
+    Linear,
+    /// The percentage along the widget is raised to the given power to give
+    /// the percentage between the min and max value, so that a skew above
+    /// 1.0 gives more room to the lower values and a skew below 1.0 to the
+    /// higher values.
+    Skew(f64),
+    /// Values are spread logarithmically, so that each multiple of a value
+    /// (i.e. each octave of a frequency) covers the same distance. Ranges
+    /// that aren't wholly positive are spread evenly instead.
+    Logarithmic,
+}
+
+impl Scale {
+
+    /// The value at the given percentage (between 0.0 and 1.0) along the widget.
+    pub fn value_from_perc<T>(&self, perc: f64, min: T, max: T) -> T
+        where
+            T: Float + FromPrimitive + ToPrimitive
+    {
+        let (min_f, max_f) = (min.to_f64().unwrap(), max.to_f64().unwrap());
+        let value = match *self {
+            Scale::Skew(skew) => min_f + (max_f - min_f) * perc.powf(skew),
+            Scale::Logarithmic if min_f > 0.0 && max_f > 0.0 =>
+                min_f * (max_f / min_f).powf(perc),
+            _ => min_f + (max_f - min_f) * perc,
+        };
+        FromPrimitive::from_f64(value).unwrap()
+    }
+
+    /// The percentage (between 0.0 and 1.0) along the widget of the given value.
+    pub fn perc_from_value<T>(&self, value: T, min: T, max: T) -> f64
+        where
+            T: Float + FromPrimitive + ToPrimitive
+    {
+        let (value_f, min_f, max_f) = (value.to_f64().unwrap(),
+                                       min.to_f64().unwrap(),
+                                       max.to_f64().unwrap());
+        if min_f == max_f { return 0.0 }
+        let linear = (value_f - min_f) / (max_f - min_f);
+        match *self {
+            Scale::Skew(skew) => linear.max(0.0).powf(1.0 / skew),
+            Scale::Logarithmic if min_f > 0.0 && max_f > 0.0 && value_f > 0.0 =>
+                (value_f / min_f).ln() / (max_f / min_f).ln(),
+            _ => linear,
+        }
+    }
+
+}
@@ -0,0 +1,141 @@
+//! Kinetic scrolling, in which a scroll offset keeps moving once the drag
+//! or wheel flick that moved it is released, slowing down with friction.
+//!
+//! A `Kinetic` is kept as part of a widget's state. Each frame the widget
+//! either moves it via `scroll_by` (while dragging or when the wheel has
+//! turned) or lets it `coast`, then clamps it to the scrollable range.
+//!
+//! Alternatively `stretch_by` and `coast_within` let the offset overshoot
+//! either end of the range like a rubber band, springing back once released.
+
+/// Velocities below this many pixels per second are considered at rest.
+const MIN_VELOCITY: f64 = 5.0;
+
+/// How much of each new velocity measurement replaces the tracked velocity,
+/// smoothing out the jitter of individual frames.
+const VELOCITY_SMOOTHING: f64 = 0.8;
+
+/// The rate per second at which an overshoot springs back to the range.
+const SPRING_RATE: f64 = 12.0;
+
+/// The friction slowing an offset that is moving further beyond its range.
+const OVERSCROLL_FRICTION: f64 = 24.0;
+
+/// Overshoots smaller than this many pixels have finished springing back.
+const MIN_OVERSCROLL: f64 = 0.5;
+
+/// A scroll offset along with the velocity at which it is moving.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Kinetic {
+    /// The distance in pixels scrolled from the start of the content.
+    pub offset: f64,
+    /// The speed in pixels per second at which the offset is changing.
+    pub velocity: f64,
+    /// The time at which the offset was last updated.
+    time: f64,
+}
+
+impl Kinetic {
+
+    /// A resting scroll offset as of the given time.
+    pub fn new(offset: f64, time: f64) -> Kinetic {
+        Kinetic { offset: offset, velocity: 0.0, time: time }
+    }
+
+    /// Move the offset by the given distance, tracking the velocity of the
+    /// movement so that it may continue once released. A drag should call
+    /// this every frame while held, even when the mouse hasn't moved, so
+    /// that pausing before the release comes to rest.
+    pub fn scroll_by(self, distance: f64, time: f64) -> Kinetic {
+        let dt = time - self.time;
+        let velocity = match dt > 0.0 {
+            true => distance / dt * VELOCITY_SMOOTHING + self.velocity * (1.0 - VELOCITY_SMOOTHING),
+            false => self.velocity,
+        };
+        Kinetic { offset: self.offset + distance, velocity: velocity, time: time }
+    }
+
+    /// Continue moving at the tracked velocity, which decays exponentially
+    /// with the given friction (the proportion of the velocity lost per
+    /// second is `1 - e^-friction`).
+    pub fn coast(self, time: f64, friction: f64) -> Kinetic {
+        let dt = (time - self.time).max(0.0);
+        let velocity = self.velocity * (-friction * dt).exp();
+        let velocity = if velocity.abs() < MIN_VELOCITY { 0.0 } else { velocity };
+        Kinetic { offset: self.offset + self.velocity * dt, velocity: velocity, time: time }
+    }
+
+    /// Like `scroll_by`, but movement beyond the given range is resisted the
+    /// more it overshoots, so that the offset never passes either end by
+    /// more than `limit` pixels.
+    pub fn stretch_by(self, distance: f64, time: f64, min: f64, max: f64, limit: f64) -> Kinetic {
+        let over = self.overscroll(min, max);
+        let resistance = match over * distance > 0.0 {
+            true => (1.0 - over.abs() / limit).max(0.0),
+            false => 1.0,
+        };
+        let stretched = self.scroll_by(distance * resistance, time);
+        match stretched.overscroll(min, max) {
+            over if over < -limit => stretched.scroll_to(min - limit),
+            over if over > limit => stretched.scroll_to(max + limit),
+            _ => stretched,
+        }
+    }
+
+    /// Like `coast`, but an offset beyond the given range springs back to
+    /// it, and a flick carried past either end overshoots by no more than
+    /// `limit` pixels before springing back.
+    pub fn coast_within(self, time: f64, friction: f64, min: f64, max: f64, limit: f64) -> Kinetic {
+        let over = self.overscroll(min, max);
+        if over == 0.0 {
+            let coasted = self.coast(time, friction);
+            return match coasted.overscroll(min, max) {
+                over if over < -limit => coasted.scroll_to(min - limit),
+                over if over > limit => coasted.scroll_to(max + limit),
+                _ => coasted,
+            }
+        }
+        let dt = (time - self.time).max(0.0);
+        let bound = if over < 0.0 { min } else { max };
+        // Only movement further beyond the range carries on, and not for long.
+        let velocity = match self.velocity * over > 0.0 {
+            true => self.velocity * (-OVERSCROLL_FRICTION * dt).exp(),
+            false => 0.0,
+        };
+        let velocity = if velocity.abs() < MIN_VELOCITY { 0.0 } else { velocity };
+        let over = (over + velocity * dt) * (-SPRING_RATE * dt).exp();
+        let over = if over.abs() < MIN_OVERSCROLL { 0.0 } else { over.max(-limit).min(limit) };
+        Kinetic { offset: bound + over, velocity: velocity, time: time }
+    }
+
+    /// How far the offset is beyond the given range, negative where it is
+    /// before `min` and positive where it is after `max`.
+    pub fn overscroll(&self, min: f64, max: f64) -> f64 {
+        if self.offset < min { self.offset - min }
+        else if self.offset > max { self.offset - max }
+        else { 0.0 }
+    }
+
+    /// Jump to the given offset, coming to rest.
+    pub fn scroll_to(self, offset: f64) -> Kinetic {
+        Kinetic { offset: offset, velocity: 0.0, ..self }
+    }
+
+    /// Come to rest at the current offset.
+    pub fn stop(self) -> Kinetic {
+        Kinetic { velocity: 0.0, ..self }
+    }
+
+    /// Keep the offset within the given range, coming to rest at either end.
+    pub fn clamp(self, min: f64, max: f64) -> Kinetic {
+        if self.offset < min { self.scroll_to(min) }
+        else if self.offset > max { self.scroll_to(max) }
+        else { self }
+    }
+
+    /// Whether or not the offset is still moving.
+    pub fn is_moving(&self) -> bool {
+        self.velocity != 0.0
+    }
+
+}
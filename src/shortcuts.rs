@@ -0,0 +1,132 @@
+//! Keyboard shortcuts bound to the application's actions.
+//!
+//! The application binds key combinations to its own action ids via
+//! `Ui::shortcuts`. As keys are pressed the `Ui` matches them against the
+//! bindings, and the actions triggered during the frame may be read with
+//! `Ui::triggered_actions`.
+
+use locale::Locale;
+use piston::input::keyboard::Key;
+use std::fmt;
+
+/// Identifies one of the application's actions.
+pub type ActionId = usize;
+
+/// A key pressed while holding the given modifier keys. Either of the left
+/// or right modifier keys satisfy a modifier.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub key: Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub gui: bool,
+}
+
+impl Shortcut {
+
+    /// A shortcut triggered by pressing the given key without any modifiers.
+    pub fn new(key: Key) -> Shortcut {
+        Shortcut { key: key, ctrl: false, shift: false, alt: false, gui: false }
+    }
+
+    /// Require Ctrl to be held.
+    pub fn ctrl(self) -> Shortcut {
+        Shortcut { ctrl: true, ..self }
+    }
+
+    /// Require Shift to be held.
+    pub fn shift(self) -> Shortcut {
+        Shortcut { shift: true, ..self }
+    }
+
+    /// Require Alt to be held.
+    pub fn alt(self) -> Shortcut {
+        Shortcut { alt: true, ..self }
+    }
+
+    /// Require the Gui (i.e. Command or Windows) key to be held.
+    pub fn gui(self) -> Shortcut {
+        Shortcut { gui: true, ..self }
+    }
+
+    /// Whether or not the shortcut may be triggered while text is being
+    /// entered. Shortcuts without a Ctrl, Alt or Gui modifier would
+    /// otherwise fire while typing.
+    pub fn is_allowed_while_typing(&self) -> bool {
+        self.ctrl || self.alt || self.gui
+    }
+
+    /// The shortcut as it is usually written in menus, with the names of the
+    /// modifier keys translated for the locale's current language.
+    pub fn localized(&self, locale: &Locale) -> String {
+        let mut label = String::new();
+        for &(is_held, name) in self.modifiers().iter() {
+            if is_held {
+                label.push_str(locale.tr(name));
+                label.push('+');
+            }
+        }
+        label.push_str(&format!("{:?}", self.key));
+        label
+    }
+
+    /// Whether or not each modifier is required, along with its English name.
+    fn modifiers(&self) -> [(bool, &'static str); 4] {
+        [(self.ctrl, "Ctrl"), (self.alt, "Alt"), (self.shift, "Shift"), (self.gui, "Gui")]
+    }
+
+}
+
+/// Displays the shortcut as it is usually written in menus, i.e. `Ctrl+Shift+S`.
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(is_held, name) in self.modifiers().iter() {
+            if is_held { try!(write!(f, "{}+", name)) }
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// The bindings of shortcuts to actions.
+#[derive(Clone, Debug)]
+pub struct Shortcuts {
+    bindings: Vec<(Shortcut, ActionId)>,
+}
+
+impl Shortcuts {
+
+    /// Construct an empty set of bindings.
+    pub fn new() -> Shortcuts {
+        Shortcuts { bindings: Vec::new() }
+    }
+
+    /// Bind the shortcut to the given action, replacing any action to which
+    /// it was already bound.
+    pub fn bind(&mut self, shortcut: Shortcut, action: ActionId) {
+        self.unbind(shortcut);
+        self.bindings.push((shortcut, action));
+    }
+
+    /// Remove the binding of the given shortcut, if it is bound.
+    pub fn unbind(&mut self, shortcut: Shortcut) {
+        self.bindings.retain(|&(bound, _)| bound != shortcut);
+    }
+
+    /// Remove every binding.
+    pub fn clear(&mut self) {
+        self.bindings.clear();
+    }
+
+    /// The action to which the shortcut is bound, if any.
+    pub fn action_for(&self, shortcut: Shortcut) -> Option<ActionId> {
+        self.bindings.iter().find(|&&(bound, _)| bound == shortcut).map(|&(_, action)| action)
+    }
+
+    /// The first shortcut bound to the given action, if any, i.e. for
+    /// displaying beside the action in a menu.
+    pub fn shortcut_for(&self, action: ActionId) -> Option<Shortcut> {
+        self.bindings.iter().find(|&&(_, bound)| bound == action).map(|&(shortcut, _)| shortcut)
+    }
+
+}
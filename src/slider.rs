@@ -1,32 +1,33 @@
 use num::{ Float, ToPrimitive, FromPrimitive };
-use callback::Callable;
-use frame::Frameable;
-use color::{ Color, Colorable };
-use label::{ FontSize, Labelable };
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use label::{ FontId, FontSize, Labelable };
 use dimensions::Dimensions;
 use label;
+use mouse;
 use mouse::Mouse;
+use number_format::NumberFormat;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use history::Value;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use property::{ Bindable, Property };
+use theme::WidgetStyle;
 use rectangle;
 use ui::{ UIID, Ui };
-use utils::{
-    clamp,
-    percentage,
-    value_from_perc,
-};
+use scale::Scale;
+use utils::{ clamp, snap, val_to_string };
 use widget::Widget;
 use vecmath::vec2_add;
 
 /// Represents the state of the Button widget.
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum State {
     Normal,
     Highlighted,
     Clicked,
+    /// Pressed with the right or middle button.
+    AltClicked(mouse::Button),
 }
 
 impl State {
@@ -35,25 +36,54 @@ impl State {
         match self {
             &State::Normal => rectangle::State::Normal,
             &State::Highlighted => rectangle::State::Highlighted,
-            &State::Clicked => rectangle::State::Clicked,
+            &State::Clicked | &State::AltClicked(_) => rectangle::State::Clicked,
         }
     }
 }
 
 widget_fns!(Slider, State, Widget::Slider(State::Normal));
 
+/// What happened to the Slider, returned by `Slider::react` and given to
+/// its `on_event` closure along with the slider's value.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event<T> {
+    /// The mouse was pressed over the slider.
+    Pressed(T),
+    /// The value is changing while the mouse is held.
+    Dragging(T),
+    /// The mouse was released, committing the value.
+    Released(T),
+    /// The right or middle button was released over the slider after
+    /// pressing it with the same, i.e. to open a context menu. The value is
+    /// left unchanged.
+    AltClicked(mouse::Button, T),
+}
+
+impl<T: Copy> Event<T> {
+    /// The value of the slider when the event occurred.
+    pub fn value(&self) -> T {
+        match *self {
+            Event::Pressed(value) | Event::Dragging(value) | Event::Released(value) |
+            Event::AltClicked(_, value) => value,
+        }
+    }
+}
+
 /// Check the current state of the slider.
 fn get_new_state(is_over: bool,
                  prev: State,
                  mouse: Mouse) -> State {
     use mouse::ButtonState::{Down, Up};
-    use self::State::{Normal, Highlighted, Clicked};
-    match (is_over, prev, mouse.left) {
-        (true,  Normal,  Down) => Normal,
-        (true,  _,       Down) => Clicked,
-        (true,  _,       Up)   => Highlighted,
-        (false, Clicked, Down) => Clicked,
-        _ => Normal,
+    use self::State::{Normal, Highlighted, Clicked, AltClicked};
+    match (is_over, prev, mouse.left, mouse.alt_button_down()) {
+        (true,  Normal,        Down, _)       => Normal,
+        (true,  Normal,        Up,   Some(_)) => Normal,
+        (_,     AltClicked(b), Up,   _) if mouse.button(b) == Down => AltClicked(b),
+        (true,  Highlighted,   Up,   Some(b)) => AltClicked(b),
+        (true,  _,             Down, _)       => Clicked,
+        (true,  _,             Up,   _)       => Highlighted,
+        (false, Clicked,       Down, _)       => Clicked,
+        _                                     => Normal,
     }
 }
 
@@ -66,12 +96,16 @@ pub struct Slider<'a, T, F> {
     pos: Point,
     dim: Dimensions,
     maybe_callback: Option<F>,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    maybe_on_event: Option<Box<FnMut(Event<T>) + 'a>>,
+    maybe_property: Option<Property<T>>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
+    scale: Scale,
+    maybe_step: Option<T>,
+    maybe_tick_interval: Option<T>,
+    show_values: bool,
+    maybe_is_vertical: Option<bool>,
+    maybe_number_format: Option<NumberFormat>,
 }
 
 impl<'a, T, F> Slider<'a, T, F> {
@@ -85,37 +119,153 @@ impl<'a, T, F> Slider<'a, T, F> {
             pos: [0.0, 0.0],
             dim: [192.0, 48.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            maybe_property: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
+            scale: Scale::Linear,
+            maybe_step: None,
+            maybe_tick_interval: None,
+            show_values: false,
+            maybe_is_vertical: None,
+            maybe_number_format: None,
+        }
+    }
+
+    /// Skew the mapping from the slider's length to its value, raising the
+    /// percentage along the slider to the given power (see `Scale::Skew`).
+    pub fn skew(self, skew: f64) -> Slider<'a, T, F> {
+        Slider { scale: Scale::Skew(skew), ..self }
+    }
+
+    /// Spread the slider's values logarithmically, as suits frequencies and
+    /// gains. The range must be positive.
+    pub fn log_scale(self) -> Slider<'a, T, F> {
+        Slider { scale: Scale::Logarithmic, ..self }
+    }
+
+    /// Snap the dragged value to multiples of the given step from the min.
+    /// Snapping is disabled while Ctrl is held, allowing for finer control.
+    pub fn step(self, step: T) -> Slider<'a, T, F> {
+        Slider { maybe_step: Some(step), ..self }
+    }
+
+    /// Draw a tick mark along the track at each multiple of the given
+    /// interval from the min.
+    pub fn ticks(self, interval: T) -> Slider<'a, T, F> {
+        Slider { maybe_tick_interval: Some(interval), ..self }
+    }
+
+    /// Display the min, max and current values beside the slider (below it
+    /// when horizontal, to its right when vertical), using the theme's small
+    /// font size.
+    pub fn show_values(self) -> Slider<'a, T, F> {
+        Slider { show_values: true, ..self }
+    }
+
+    /// Display the values with the given format rather than the theme's.
+    pub fn number_format(self, format: NumberFormat) -> Slider<'a, T, F> {
+        Slider { maybe_number_format: Some(format), ..self }
+    }
+
+    /// Lay the slider out as a column, with the min at the bottom. Unless
+    /// given, the orientation follows the slider's longest side.
+    pub fn vertical(self) -> Slider<'a, T, F> {
+        Slider { maybe_is_vertical: Some(true), ..self }
+    }
+
+    /// Lay the slider out as a row, with the min at the left.
+    pub fn horizontal(self) -> Slider<'a, T, F> {
+        Slider { maybe_is_vertical: Some(false), ..self }
+    }
+
+    /// Whether the slider is laid out as a row rather than a column.
+    fn is_horizontal(&self) -> bool {
+        match self.maybe_is_vertical {
+            Some(is_vertical) => !is_vertical,
+            None => self.dim[0] > self.dim[1],
         }
     }
 }
 
-impl<'a, T, F> Colorable for Slider<'a, T, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
+/// Draw a tick mark across the near edge of the track at each multiple of
+/// `interval` from `min`. Ticks closer together than a few pixels are skipped.
+fn draw_ticks<B, C, T>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    track_pos: Point,
+    track_dim: Dimensions,
+    is_horizontal: bool,
+    min: T,
+    max: T,
+    interval: T,
+    scale: Scale,
+    color: Color
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache,
+        T: Float + FromPrimitive + ToPrimitive
+{
+    const MIN_TICK_SPACING: f64 = 4.0;
+    let (length, breadth) = match is_horizontal {
+        true => (track_dim[0], track_dim[1]),
+        false => (track_dim[1], track_dim[0]),
+    };
+    let range = (max - min).to_f64().unwrap().abs();
+    let interval_f = interval.to_f64().unwrap().abs();
+    if interval_f <= 0.0 || range / interval_f * MIN_TICK_SPACING > length { return }
+    let count = (range / interval_f).floor() as usize;
+    let tick_len = breadth / 4.0;
+    for i in 0..count + 1 {
+        let value = value_at_tick(min, max, interval, i);
+        let offset = scale.perc_from_value(value, min, max) * length;
+        let line = match is_horizontal {
+            true => {
+                let x = track_pos[0] + offset;
+                let y = track_pos[1] + track_dim[1];
+                [x, y - tick_len, x, y]
+            },
+            false => {
+                let x = track_pos[0];
+                let y = track_pos[1] + track_dim[1] - offset;
+                [x, y, x + tick_len, y]
+            },
+        };
+        ui.draw_line(graphics, line, 1.0, false, color);
     }
 }
 
-impl<'a, T, F> Frameable for Slider<'a, T, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
+/// The value of the tick at the given index from the min, towards the max.
+fn value_at_tick<T>(min: T, max: T, interval: T, idx: usize) -> T
+    where
+        T: Float + FromPrimitive + ToPrimitive
+{
+    let step = interval.to_f64().unwrap().abs() * if max < min { -1.0 } else { 1.0 };
+    let value = min.to_f64().unwrap() + step * idx as f64;
+    FromPrimitive::from_f64(value).unwrap()
+}
+
+composite_builder_impls!(Slider<'a, T, F>);
+
+impl<'a, T, F> Callable<F> for Slider<'a, T, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
         self
     }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
+}
+
+impl<'a, T, F> Bindable<T> for Slider<'a, T, F> where T: Clone {
+    fn bind(mut self, property: &Property<T>) -> Self {
+        self.value = property.get();
+        self.maybe_property = Some(property.clone());
         self
     }
 }
 
-impl<'a, T, F> Callable<F> for Slider<'a, T, F> {
-    fn callback(mut self, cb: F) -> Self {
-        self.maybe_callback = Some(cb);
+impl<'a, T, F> Reactable<'a, Event<T>> for Slider<'a, T, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event<T>) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
@@ -128,50 +278,78 @@ impl<'a, T, F> Labelable<'a> for Slider<'a, T, F>
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
         self
     }
-}
 
-impl<'a, T, F> Positionable for Slider<'a, T, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, T, F> Shapeable for Slider<'a, T, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
     where
         T: Float + FromPrimitive + ToPrimitive,
         F: FnMut(T) + 'a
 {
-
     fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if let Some(event) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(event.value()) }
+        }
+    }
+}
+
+impl<'a, T> Slider<'a, T, NoCallback>
+    where
+        T: Float + FromPrimitive + ToPrimitive
+{
+
+    /// Draw the slider, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the change needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event<T>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, T, F> Slider<'a, T, F>
+    where
+        T: Float + FromPrimitive + ToPrimitive
+{
 
+    /// Update and draw the slider, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event<T>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_slider, &ui.theme);
+        ui.push_alpha(style.alpha);
         let state = *get_state(ui, self.ui_id);
         let mouse = ui.get_mouse_state();
         let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
         let new_state = get_new_state(is_over, state, mouse);
 
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
+        let frame_w = style.frame;
         let frame_w2 = frame_w * 2.0;
-        let frame_color = self.maybe_frame_color.unwrap_or(ui.theme.frame_color);
+        let frame_color = style.frame_color;
 
-        let is_horizontal = self.dim[0] > self.dim[1];
+        let is_horizontal = self.is_horizontal();
         let (new_value, pad_pos, pad_dim) = if is_horizontal {
             // Horizontal.
             let p = vec2_add(self.pos, [frame_w, frame_w]);
@@ -179,10 +357,10 @@ impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
             let w = match (is_over, state, new_state) {
                 (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked)  =>
                      clamp(mouse.pos[0] - p[0], 0f64, max_w),
-                _ => clamp(percentage(self.value, self.min, self.max) as f64 * max_w, 0f64, max_w),
+                _ => clamp(self.scale.perc_from_value(self.value, self.min, self.max) * max_w, 0f64, max_w),
             };
             let h = self.dim[1] - frame_w2;
-            let new_value = value_from_perc((w / max_w) as f32, self.min, self.max);
+            let new_value = self.scale.value_from_perc(w / max_w, self.min, self.max);
             (new_value, p, [w, h])
         } else {
             // Vertical.
@@ -196,57 +374,167 @@ impl<'a, T, F> ::draw::Drawable for Slider<'a, T, F>
                     (h, p)
                 },
                 _ => {
-                    let h = clamp(percentage(self.value, self.min, self.max) as f64 * max_h, 0.0, max_h);
+                    let h = clamp(self.scale.perc_from_value(self.value, self.min, self.max) * max_h, 0.0, max_h);
                     let p = [corner[0], corner[1] + max_h - h];
                     (h, p)
                 },
             };
             let w = self.dim[0] - frame_w2;
-            let new_value = value_from_perc((h / max_h) as f32, self.min, self.max);
+            let new_value = self.scale.value_from_perc(h / max_h, self.min, self.max);
             (new_value, p, [w, h])
         };
 
-        // Callback.
-        match self.maybe_callback {
-            Some(ref mut callback) => {
-                if self.value != new_value || match (state, new_state) {
-                    (State::Highlighted, State::Clicked) | (State::Clicked, State::Highlighted) => true,
-                    _ => false,
-                } { (*callback)(new_value) }
-            }, None => (),
+        // Snap the dragged value to the step and the slider to the snapped value.
+        let is_dragging = match (is_over, state, new_state) {
+            (true, State::Highlighted, State::Clicked) | (_, State::Clicked, State::Clicked) => true,
+            _ => false,
+        };
+        let (new_value, pad_pos, pad_dim) = match self.maybe_step {
+            Some(step) if is_dragging && !ui.is_ctrl_held() => {
+                let snapped = snap(new_value, self.min, self.max, step);
+                let perc = clamp(self.scale.perc_from_value(snapped, self.min, self.max), 0.0, 1.0);
+                if is_horizontal {
+                    (snapped, pad_pos, [perc * (self.dim[0] - frame_w2), pad_dim[1]])
+                } else {
+                    let max_h = self.dim[1] - frame_w2;
+                    let h = perc * max_h;
+                    (snapped, [pad_pos[0], self.pos[1] + frame_w + max_h - h], [pad_dim[0], h])
+                }
+            },
+            _ => (new_value, pad_pos, pad_dim),
+        };
+        // Only a drag changes the value, as the value measured back from the
+        // pad drifts from `self.value` via the Skew and Log scales.
+        let new_value = if is_dragging { new_value } else { self.value };
+
+        let maybe_event = match (state, new_state) {
+            (State::Highlighted, State::Clicked) => Some(Event::Pressed(new_value)),
+            (State::Clicked, State::Highlighted)
+            | (State::Clicked, State::Normal) => Some(Event::Released(new_value)),
+            (State::AltClicked(b), State::Highlighted) => Some(Event::AltClicked(b, new_value)),
+            _ if is_dragging && self.value != new_value => Some(Event::Dragging(new_value)),
+            _ => None,
+        };
+
+        // The change is recorded once released, and a change that was undone
+        // or redone is re-fired as though released.
+        match maybe_event {
+            Some(Event::Pressed(_)) =>
+                ui.begin_change(self.ui_id, Value::Number(self.value.to_f64().unwrap())),
+            Some(Event::Released(value)) =>
+                ui.commit_change(self.ui_id, Value::Number(value.to_f64().unwrap())),
+            _ => (),
         }
+        let maybe_event = match ui.take_restored(self.ui_id) {
+            Some(Value::Number(value)) => FromPrimitive::from_f64(value).map(Event::Released),
+            _ => maybe_event,
+        };
 
         // Draw.
         let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
+        let color = style.color;
+        let color = ui.transition_color(self.ui_id, rect_state.color(color));
+        let fill = rectangle::fill(color, style.maybe_gradient, style.maybe_background_image,
+                                   rect_state);
+
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
 
         // Rectangle frame / backdrop.
-        rectangle::draw(ui.win_w, ui.win_h, graphics, rect_state,
-                        self.pos, self.dim, None, frame_color);
-        // Slider rectangle.
-        rectangle::draw(ui.win_w, ui.win_h, graphics, rect_state,
-                        pad_pos, pad_dim, None, color);
+        rectangle::draw(ui, graphics, rect_state,
+                        self.pos, self.dim, None, style.corner_radius, frame_color);
+        // Slider rectangle (the state's color is applied via the transition).
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        pad_pos, pad_dim, None, style.corner_radius, fill);
 
         // If there's a label, draw it.
         if let Some(text) = self.maybe_label {
-            let text_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
-            let size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
-            let is_horizontal = self.dim[0] > self.dim[1];
+            let text_color = style.label_color;
+            let size = style.label_font_size;
+            let is_horizontal = self.is_horizontal();
             let l_pos = if is_horizontal {
                 let x = pad_pos[0] + (pad_dim[1] - size as f64) / 2.0;
                 let y = pad_pos[1] + (pad_dim[1] - size as f64) / 2.0;
                 [x, y]
             } else {
-                let label_w = label::width(ui, size, &text);
+                let label_w = label::width(ui, style.font, size, &text);
                 let x = pad_pos[0] + (pad_dim[0] - label_w) / 2.0;
                 let y = pad_pos[1] + pad_dim[1] - pad_dim[0] - frame_w;
                 [x, y]
             };
             // Draw the label.
-            ui.draw_text(graphics, l_pos, size, text_color, &text);
+            ui.draw_text(graphics, l_pos, style.font, size, text_color, &text);
+        }
+
+        // Tick marks along the track.
+        if let Some(interval) = self.maybe_tick_interval {
+            let track_pos = vec2_add(self.pos, [frame_w, frame_w]);
+            let track_dim = [self.dim[0] - frame_w2, self.dim[1] - frame_w2];
+            draw_ticks(ui, graphics, track_pos, track_dim, is_horizontal,
+                       self.min, self.max, interval, self.scale, style.label_color);
+        }
+
+        // The min, max and current values beside the slider.
+        if self.show_values {
+            let size = ui.theme.font_size_small;
+            let gap = ui.theme.padding;
+            let (min_f, max_f) = (self.min.to_f64().unwrap(), self.max.to_f64().unwrap());
+            let length = (if is_horizontal { self.dim[0] } else { self.dim[1] }) as usize;
+            let format = self.maybe_number_format.clone().unwrap_or_else(|| ui.theme.number_format.clone());
+            // Unless the format specifies a precision, it suits the slider's range and length.
+            let to_string = |value: T| {
+                let value_f = value.to_f64().unwrap();
+                match format.maybe_precision {
+                    Some(precision) => format.format(value_f, precision as usize),
+                    None => format.localize(&val_to_string(value_f, max_f, (max_f - min_f).abs(), length)),
+                }
+            };
+            let (min_string, max_string) = (to_string(self.min), to_string(self.max));
+            let value_string = to_string(new_value);
+            let min_w = label::width(ui, style.font, size, &min_string);
+            let max_w = label::width(ui, style.font, size, &max_string);
+            let value_w = label::width(ui, style.font, size, &value_string);
+            let color = style.label_color;
+            if is_horizontal {
+                let y = self.pos[1] + self.dim[1] + gap;
+                let right = self.pos[0] + self.dim[0];
+                // The current value follows the end of the slider's pad.
+                let value_x = clamp(pad_pos[0] + pad_dim[0] - value_w / 2.0,
+                                    self.pos[0], right - value_w);
+                if value_x > self.pos[0] + min_w + gap {
+                    ui.draw_text(graphics, [self.pos[0], y], style.font, size, color, &min_string);
+                }
+                if value_x + value_w + gap < right - max_w {
+                    ui.draw_text(graphics, [right - max_w, y], style.font, size, color, &max_string);
+                }
+                ui.draw_text(graphics, [value_x, y], style.font, size, color, &value_string);
+            } else {
+                let x = self.pos[0] + self.dim[0] + gap;
+                let h = size as f64;
+                let bottom = self.pos[1] + self.dim[1] - h;
+                // The current value follows the top of the slider's pad.
+                let value_y = clamp(pad_pos[1] - h / 2.0, self.pos[1], bottom);
+                if value_y > self.pos[1] + h + gap {
+                    ui.draw_text(graphics, [x, self.pos[1]], style.font, size, color, &max_string);
+                }
+                if value_y + h + gap < bottom {
+                    ui.draw_text(graphics, [x, bottom], style.font, size, color, &min_string);
+                }
+                ui.draw_text(graphics, [x, value_y], style.font, size, color, &value_string);
+            }
         }
 
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::Slider(new_state), self.pos, self.dim);
 
+        if let (Some(event), Some(property)) = (maybe_event, self.maybe_property.as_ref()) {
+            property.set(event.value());
+        }
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
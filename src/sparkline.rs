@@ -0,0 +1,162 @@
+use color::{ Color, Colorable, Fadeable };
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use point::Point;
+use position::Positionable;
+use shape::Shapeable;
+use theme::WidgetStyle;
+use ui::Ui;
+
+/// How the samples of a sparkline are drawn.
+#[derive(Copy, Clone, Debug, PartialEq, RustcEncodable, RustcDecodable)]
+pub enum Style {
+    /// A line through each sample.
+    Line,
+    /// A bar rising from the bottom for each sample.
+    Bars,
+}
+
+/// A context on which the builder pattern can be implemented for drawing a
+/// tiny chart of recent samples, without axes or labels, i.e. for showing
+/// many live metrics compactly on a dashboard. By default it is as tall as
+/// a label, and the samples are scaled to fill its height.
+pub struct Sparkline<'a> {
+    samples: &'a [f64],
+    pos: Point,
+    dim: Dimensions,
+    variant: Style,
+    maybe_count: Option<usize>,
+    maybe_range: Option<(f64, f64)>,
+    thickness: f64,
+    style: WidgetStyle,
+}
+
+impl<'a> Sparkline<'a> {
+
+    /// Create a sparkline of the given samples, oldest first, to be built upon.
+    pub fn new(samples: &'a [f64]) -> Sparkline<'a> {
+        Sparkline {
+            samples: samples,
+            pos: [0.0, 0.0],
+            dim: [96.0, 24.0],
+            variant: Style::Line,
+            maybe_count: None,
+            maybe_range: None,
+            thickness: 1.0,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// A builder method for specifying whether the samples are drawn as a line
+    /// or as bars.
+    pub fn style(self, style: Style) -> Sparkline<'a> {
+        Sparkline { variant: style, ..self }
+    }
+
+    /// A builder method for only drawing the most recent `count` samples.
+    pub fn last(self, count: usize) -> Sparkline<'a> {
+        Sparkline { maybe_count: Some(count), ..self }
+    }
+
+    /// A builder method for specifying the values at the bottom and top of the
+    /// sparkline, rather than fitting it to the samples.
+    pub fn range(self, min: f64, max: f64) -> Sparkline<'a> {
+        Sparkline { maybe_range: Some((min, max)), ..self }
+    }
+
+    /// A builder method for specifying the thickness of the line.
+    pub fn thickness(self, thickness: f64) -> Sparkline<'a> {
+        Sparkline { thickness: thickness, ..self }
+    }
+
+}
+
+impl<'a> Colorable for Sparkline<'a> {
+    fn color(mut self, color: Color) -> Self {
+        self.style.maybe_color = Some(color);
+        self
+    }
+}
+
+impl<'a> Fadeable for Sparkline<'a> {
+    fn alpha(mut self, alpha: f32) -> Self {
+        self.style.maybe_alpha = Some(alpha);
+        self
+    }
+}
+
+impl<'a> Positionable for Sparkline<'a> {
+    fn point(mut self, pos: Point) -> Self {
+        self.pos = pos;
+        self
+    }
+}
+
+impl<'a> Shapeable for Sparkline<'a> {
+    fn get_dim(&self) -> Dimensions { self.dim }
+    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
+}
+
+impl<'a> ::draw::Drawable for Sparkline<'a> {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let len = self.samples.len();
+        let count = self.maybe_count.map_or(len, |count| ::std::cmp::min(count, len));
+        let samples = &self.samples[len - count..];
+        if samples.is_empty() { return }
+        // Sparklines are drawn in the label color unless given another.
+        let defaults = WidgetStyle { maybe_color: Some(ui.theme.label_color), ..WidgetStyle::new() };
+        let style = self.style.resolve_with(ui.theme.maybe_sparkline, defaults, &ui.theme);
+        let color = style.color;
+        ui.push_alpha(style.alpha);
+
+        // Flat samples are drawn across the middle.
+        let (min, max) = self.maybe_range.unwrap_or_else(|| {
+            samples.iter().fold((::std::f64::MAX, ::std::f64::MIN), |(min, max), &s| (min.min(s), max.max(s)))
+        });
+        let (pos, dim) = (self.pos, self.dim);
+        let y_at = |sample: f64| match max > min {
+            true => pos[1] + dim[1] * (1.0 - (sample.max(min).min(max) - min) / (max - min)),
+            false => pos[1] + dim[1] / 2.0,
+        };
+
+        match self.variant {
+            Style::Line => {
+                let mut points = ui.scratch_points();
+                let step = match count > 1 {
+                    true => dim[0] / (count - 1) as f64,
+                    false => 0.0,
+                };
+                points.extend(samples.iter().enumerate().map(|(i, &s)| [pos[0] + i as f64 * step, y_at(s)]));
+                if points.len() == 1 {
+                    let p = points[0];
+                    points.push([pos[0] + dim[0], p[1]]);
+                }
+                for w in points.windows(2) {
+                    ui.draw_line(graphics, [w[0][0], w[0][1], w[1][0], w[1][1]], self.thickness, true, color);
+                }
+                ui.recycle_points(points);
+            },
+            Style::Bars => {
+                // Bars are separated by a gap of a pixel, when there is room for one.
+                let bar_w = dim[0] / count as f64;
+                let gap = if bar_w > 2.0 { 1.0 } else { 0.0 };
+                let bottom = pos[1] + dim[1];
+                for (i, &s) in samples.iter().enumerate() {
+                    let top = match max > min {
+                        true => y_at(s),
+                        false => pos[1],
+                    };
+                    ui.draw_rectangle(graphics, [pos[0] + i as f64 * bar_w, top],
+                                      [bar_w - gap, bottom - top], color);
+                }
+            },
+        }
+
+        ui.pop_alpha();
+    }
+}
@@ -0,0 +1,359 @@
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use mouse::ButtonState::{ Down, Up };
+use point::Point;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use widget::Widget;
+
+/// Tuple / Callback params.
+pub type Idx = usize;
+
+/// The part of the closed SplitButton beneath the mouse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Part {
+    /// The main area, which fires the primary action.
+    Main,
+    /// The arrow segment on the right, which opens the menu.
+    Arrow,
+}
+
+/// Represents the state of the closed SplitButton.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DrawState {
+    Normal,
+    Highlighted(Part),
+    Clicked(Part),
+}
+
+/// Represents the state of the open menu of alternative actions.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MenuState {
+    Normal,
+    Highlighted(Idx),
+    Clicked(Idx),
+}
+
+/// Represents the state of the SplitButton widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Closed(DrawState),
+    Open(MenuState),
+}
+
+widget_fns!(SplitButton, State, Widget::SplitButton(State::Closed(DrawState::Normal)));
+
+/// What happened to the SplitButton, returned by `SplitButton::react` and
+/// given to its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The main area was clicked, firing the primary action.
+    Clicked,
+    /// The menu of alternative actions was opened.
+    Opened,
+    /// The menu was closed without an action being selected.
+    Closed,
+    /// The alternative action at the given index was selected, closing the menu.
+    Selected(Idx),
+}
+
+/// The width of the arrow segment, which is narrow relative to the button.
+fn arrow_width(dim: Dimensions) -> f64 {
+    (dim[1] * 0.75).min(dim[0] / 2.0)
+}
+
+/// The rectangle occupied by the open menu, in which neighbouring items
+/// share a frame. The menu opens beneath the button unless it doesn't fit
+/// and there is more room above.
+fn menu_rect(pos: Point, dim: Dimensions, frame_w: f64, len: usize, win_h: f64) -> (Point, Dimensions) {
+    let len = len as f64;
+    let menu_h = dim[1] * len - (len - 1.0) * frame_w;
+    let below_y = pos[1] + dim[1] - frame_w;
+    let above_y = pos[1] + frame_w - menu_h;
+    let fits_below = below_y + menu_h <= win_h;
+    match fits_below || win_h - below_y >= pos[1] + frame_w {
+        true => ([pos[0], below_y], [dim[0], menu_h]),
+        false => ([pos[0], above_y], [dim[0], menu_h]),
+    }
+}
+
+/// Determine the new State from the part of the button or the item of the
+/// menu beneath the mouse.
+fn get_new_state(maybe_part: Option<Part>,
+                 maybe_item: Option<Idx>,
+                 state: State,
+                 mouse_left: ::mouse::ButtonState) -> State {
+    use self::DrawState::{ Normal, Highlighted, Clicked };
+    match state {
+        State::Closed(draw_state) => match (maybe_part, draw_state, mouse_left) {
+            (Some(Part::Arrow), Clicked(Part::Arrow), Up) => State::Open(MenuState::Normal),
+            (Some(part), Highlighted(p), Down) if p == part => State::Closed(Clicked(part)),
+            (_, Clicked(p), Down) => State::Closed(Clicked(p)),
+            (Some(part), _, Up) => State::Closed(Highlighted(part)),
+            _ => State::Closed(Normal),
+        },
+        State::Open(menu_state) => match (maybe_item, menu_state, mouse_left) {
+            (Some(idx), MenuState::Clicked(i), Up) if i == idx => State::Closed(Normal),
+            (Some(idx), MenuState::Highlighted(_), Down) => State::Open(MenuState::Clicked(idx)),
+            (_, MenuState::Clicked(i), Down) => State::Open(MenuState::Clicked(i)),
+            (Some(idx), _, Up) => State::Open(MenuState::Highlighted(idx)),
+            (Some(_), MenuState::Normal, Down) => State::Open(MenuState::Normal),
+            // Pressing anywhere outside the menu closes it.
+            (None, _, Down) => State::Closed(Normal),
+            (None, _, Up) => State::Open(MenuState::Normal),
+        },
+    }
+}
+
+/// A button whose main area fires a primary action, with a narrow arrow
+/// segment on the right that opens a menu of alternative actions. The
+/// button is styled like a Button and the menu like a DropDownList.
+///
+/// The callback is called with `None` when the primary action fires and with
+/// the index of the alternative action when one is selected.
+pub struct SplitButton<'a, F> {
+    ui_id: UIID,
+    actions: &'a [String],
+    pos: Point,
+    dim: Dimensions,
+    maybe_label: Option<&'a str>,
+    maybe_callback: Option<F>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a, F> SplitButton<'a, F> {
+
+    /// Create a split button context to be built upon, whose menu lists the
+    /// given alternative actions.
+    pub fn new(ui_id: UIID, actions: &'a [String]) -> SplitButton<'a, F> {
+        SplitButton {
+            ui_id: ui_id,
+            actions: actions,
+            pos: [0.0, 0.0],
+            dim: [128.0, 32.0],
+            maybe_label: None,
+            maybe_callback: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+}
+
+composite_builder_impls!(SplitButton<'a, F>);
+
+impl<'a, F> Callable<F> for SplitButton<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
+        self
+    }
+}
+
+impl<'a, F> Reactable<'a, Event> for SplitButton<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a, F> Labelable<'a> for SplitButton<'a, F> {
+    fn label(mut self, text: &'a str) -> Self {
+        self.maybe_label = Some(text);
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a, F> ::draw::Drawable for SplitButton<'a, F>
+    where
+        F: FnMut(Option<Idx>) + 'a
+{
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let maybe_action = match self.update(ui, graphics) {
+            Some(Event::Clicked) => Some(None),
+            Some(Event::Selected(idx)) => Some(Some(idx)),
+            _ => None,
+        };
+        if let (Some(action), Some(callback)) = (maybe_action, self.maybe_callback.as_mut()) {
+            (*callback)(action)
+        }
+    }
+}
+
+impl<'a> SplitButton<'a, NoCallback> {
+
+    /// Draw the split button, returning what happened to it. An alternative
+    /// to `Callable::callback` for when the reaction to the action needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> SplitButton<'a, F> {
+
+    /// Update and draw the split button, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_button, &ui.theme);
+        let menu_style = self.style.resolve(ui.theme.maybe_drop_down_list, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let len = self.actions.len();
+        let frame_w = style.frame;
+        let menu_frame_w = menu_style.frame;
+        let arrow_w = arrow_width(self.dim);
+        let main_dim = [self.dim[0] - arrow_w, self.dim[1]];
+        let arrow_pos = [self.pos[0] + main_dim[0] - frame_w, self.pos[1]];
+        let arrow_dim = [arrow_w + frame_w, self.dim[1]];
+        let (menu_pos, menu_dim) = menu_rect(self.pos, self.dim, menu_frame_w, len, ui.win_h);
+        let item_step = self.dim[1] - menu_frame_w;
+
+        let maybe_part = match rectangle::is_over(self.pos, mouse.pos, self.dim) {
+            true if mouse.pos[0] >= arrow_pos[0] + frame_w && len > 0 => Some(Part::Arrow),
+            true => Some(Part::Main),
+            false => None,
+        };
+        let maybe_item = match (state, rectangle::is_over(menu_pos, mouse.pos, menu_dim)) {
+            (State::Open(_), true) if len > 0 => {
+                let idx = ((mouse.pos[1] - menu_pos[1]).max(0.0) / item_step) as Idx;
+                Some(::std::cmp::min(idx, len - 1))
+            },
+            _ => None,
+        };
+        let new_state = get_new_state(maybe_part, maybe_item, state, mouse.left);
+
+        let maybe_event = match (state, new_state) {
+            (State::Closed(DrawState::Clicked(Part::Main)), State::Closed(DrawState::Highlighted(Part::Main))) =>
+                Some(Event::Clicked),
+            (State::Closed(_), State::Open(_)) => Some(Event::Opened),
+            (State::Open(MenuState::Clicked(idx)), State::Closed(_)) => Some(Event::Selected(idx)),
+            (State::Open(_), State::Closed(_)) => Some(Event::Closed),
+            _ => None,
+        };
+
+        match (maybe_part, maybe_item) {
+            (Some(_), _) | (_, Some(_)) => ui.set_mouse_cursor(MouseCursor::Hand),
+            _ => (),
+        }
+
+        let part_state = |part: Part| match new_state {
+            State::Closed(DrawState::Highlighted(p)) if p == part => rectangle::State::Highlighted,
+            State::Closed(DrawState::Clicked(p)) if p == part => rectangle::State::Clicked,
+            State::Open(_) if part == Part::Arrow => rectangle::State::Clicked,
+            _ => rectangle::State::Normal,
+        };
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+
+        // Draw the main area and the arrow segment, which share a frame edge.
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        let main_state = part_state(Part::Main);
+        let main_fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image, main_state);
+        match self.maybe_label {
+            Some(text) => rectangle::draw_with_centered_label(
+                ui, graphics, main_state, self.pos, main_dim, maybe_frame, style.corner_radius,
+                main_fill, text, style.font, style.label_font_size, style.label_color
+            ),
+            None => rectangle::draw(
+                ui, graphics, main_state, self.pos, main_dim, maybe_frame, style.corner_radius, main_fill
+            ),
+        }
+        let arrow_state = part_state(Part::Arrow);
+        let arrow_fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image, arrow_state);
+        rectangle::draw(
+            ui, graphics, arrow_state, arrow_pos, arrow_dim, maybe_frame, style.corner_radius, arrow_fill
+        );
+        let half = arrow_w * 0.15;
+        let center = [arrow_pos[0] + (arrow_dim[0] + frame_w) / 2.0, self.pos[1] + self.dim[1] / 2.0];
+        let arrow = [
+            [center[0] - half, center[1] - half / 2.0],
+            [center[0] + half, center[1] - half / 2.0],
+            [center[0], center[1] + half / 2.0],
+        ];
+        ui.draw_polygon(graphics, &arrow, style.label_color);
+
+        // Draw the open menu of alternative actions.
+        if let State::Open(menu_state) = new_state {
+            if let Some(shadow) = menu_style.maybe_shadow {
+                rectangle::draw_shadow(ui, graphics, menu_pos, menu_dim, menu_style.corner_radius, shadow);
+            }
+            let maybe_menu_frame = match menu_frame_w > 0.0 {
+                true => Some((menu_frame_w, menu_style.frame_color)),
+                false => None,
+            };
+            for (i, action) in self.actions.iter().enumerate() {
+                let rect_state = match menu_state {
+                    MenuState::Highlighted(idx) if idx == i => rectangle::State::Highlighted,
+                    MenuState::Clicked(idx) if idx == i => rectangle::State::Clicked,
+                    _ => rectangle::State::Normal,
+                };
+                let fill = rectangle::fill(menu_style.color, menu_style.maybe_gradient,
+                                           menu_style.maybe_background_image, rect_state);
+                let item_pos = [menu_pos[0], menu_pos[1] + item_step * i as f64];
+                rectangle::draw_with_centered_label(
+                    ui, graphics, rect_state, item_pos, self.dim, maybe_menu_frame,
+                    menu_style.corner_radius, fill, action,
+                    menu_style.font, menu_style.label_font_size, menu_style.label_color
+                );
+            }
+        }
+
+        // The area covered by the split button includes the menu when it is open.
+        let (rect_pos, rect_dim) = match new_state {
+            State::Open(_) => {
+                let top = self.pos[1].min(menu_pos[1]);
+                let bottom = (self.pos[1] + self.dim[1]).max(menu_pos[1] + menu_dim[1]);
+                ([self.pos[0], top], [self.dim[0], bottom - top])
+            },
+            State::Closed(_) => (self.pos, self.dim),
+        };
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::SplitButton(new_state), rect_pos, rect_dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
@@ -0,0 +1,76 @@
+//! Statistics about the drawing of each frame, for finding which widgets
+//! are slow (see `Ui::collect_stats`).
+
+use ui::UIID;
+
+/// What was drawn during a frame and how long it took.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameStats {
+    /// The UIID of each widget set during the frame in the order it was set,
+    /// along with the time in seconds since the previous widget was set (or
+    /// since the last event was handled). This includes the time spent by
+    /// the application between setting the two.
+    pub widget_times: Vec<(UIID, f64)>,
+    /// The number of primitives drawn, including text.
+    pub primitives: usize,
+    /// The number of characters of text drawn.
+    pub glyphs: usize,
+    /// The number of times text was measured or drawn using a glyph run
+    /// that was already laid out.
+    pub glyph_run_hits: usize,
+    /// The number of glyph runs that had to be laid out.
+    pub glyph_run_misses: usize,
+    /// The number of containers re-blitted from a `RenderCache`.
+    pub render_cache_hits: usize,
+    /// The number of containers that a `RenderCache` had to redraw.
+    pub render_cache_misses: usize,
+}
+
+impl FrameStats {
+
+    /// Empty statistics for a frame that has yet to be drawn.
+    pub fn new() -> FrameStats {
+        FrameStats {
+            widget_times: Vec::new(),
+            primitives: 0,
+            glyphs: 0,
+            glyph_run_hits: 0,
+            glyph_run_misses: 0,
+            render_cache_hits: 0,
+            render_cache_misses: 0,
+        }
+    }
+
+    /// The total time spent setting widgets.
+    pub fn total_time(&self) -> f64 {
+        self.widget_times.iter().fold(0.0, |total, &(_, time)| total + time)
+    }
+
+    /// Up to `n` of the slowest widgets, slowest first.
+    pub fn slowest(&self, n: usize) -> Vec<(UIID, f64)> {
+        let mut times = self.widget_times.clone();
+        times.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(::std::cmp::Ordering::Equal));
+        times.truncate(n);
+        times
+    }
+
+    /// The proportion of glyph run lookups that didn't require laying out
+    /// the text, or `1.0` if there were none.
+    pub fn glyph_run_hit_rate(&self) -> f64 {
+        hit_rate(self.glyph_run_hits, self.glyph_run_misses)
+    }
+
+    /// The proportion of cached containers that didn't need redrawing, or
+    /// `1.0` if there were none.
+    pub fn render_cache_hit_rate(&self) -> f64 {
+        hit_rate(self.render_cache_hits, self.render_cache_misses)
+    }
+
+}
+
+fn hit_rate(hits: usize, misses: usize) -> f64 {
+    match hits + misses {
+        0 => 1.0,
+        total => hits as f64 / total as f64,
+    }
+}
@@ -0,0 +1,111 @@
+use event::Input;
+use graphics::{ DrawState, Graphics, ImageSize };
+use graphics::character::{ Character, CharacterCache };
+use label::FontSize;
+use piston::input::Button;
+use piston::input::keyboard::Key;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use theme::Theme;
+use ui::Ui;
+
+/// A texture without any data, used by the headless `Graphics`
+/// and `CharacterCache` implementations.
+#[derive(Copy, Clone, Debug)]
+pub struct MockTexture {
+    w: u32,
+    h: u32,
+}
+
+impl ImageSize for MockTexture {
+    fn get_size(&self) -> (u32, u32) {
+        (self.w, self.h)
+    }
+}
+
+/// A `Graphics` backend that draws nothing. This allows widgets to be
+/// drawn (and so updated) without a GPU.
+#[derive(Copy, Clone, Debug)]
+pub struct NoOpGraphics;
+
+impl Graphics for NoOpGraphics {
+    type Texture = MockTexture;
+
+    fn clear(&mut self, _color: [f32; 4]) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], _f: F)
+        where
+            F: FnMut(&mut FnMut(&[f32]))
+    {}
+
+    fn tri_list_uv<F>(
+        &mut self,
+        _draw_state: &DrawState,
+        _color: &[f32; 4],
+        _texture: &MockTexture,
+        _f: F
+    )
+        where
+            F: FnMut(&mut FnMut(&[f32], &[f32]))
+    {}
+}
+
+/// A `CharacterCache` that doesn't require a font file. Every glyph is an
+/// empty box whose width is a fixed ratio of the font size, making text
+/// measurements simple to predict.
+pub struct MockCharacterCache {
+    advance: f64,
+    characters: HashMap<(FontSize, char), Character<MockTexture>>,
+}
+
+impl MockCharacterCache {
+    /// Construct a MockCharacterCache whose glyphs are `advance * font_size` wide.
+    pub fn new(advance: f64) -> MockCharacterCache {
+        MockCharacterCache {
+            advance: advance,
+            characters: HashMap::new(),
+        }
+    }
+}
+
+impl CharacterCache for MockCharacterCache {
+    type Texture = MockTexture;
+
+    fn character(&mut self, font_size: FontSize, ch: char) -> &Character<MockTexture> {
+        let w = self.advance * font_size as f64;
+        let h = font_size as f64;
+        match self.characters.entry((font_size, ch)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Character {
+                offset: [0.0, h],
+                size: [w, h],
+                texture: MockTexture { w: w as u32, h: h as u32 },
+            }),
+        }
+    }
+}
+
+/// Construct a `Ui` that uses a `MockCharacterCache` (whose glyphs are half
+/// as wide as the font size) and has received a `Render` input with the given
+/// window dimensions, ready for widgets to be drawn to a `NoOpGraphics`.
+pub fn headless_ui(win_w: f64, win_h: f64) -> Ui<MockCharacterCache> {
+    let mut ui = Ui::new(MockCharacterCache::new(0.5), Theme::default());
+    ui.handle_input(Input::Render(win_w, win_h));
+    ui
+}
+
+/// Pass the given inputs to the `Ui` followed by a `Render` of the same
+/// window, after which the frame's widgets may be drawn.
+pub fn next_frame<C: CharacterCache>(ui: &mut Ui<C>, inputs: Vec<Input>) {
+    for input in inputs {
+        ui.handle_input(input);
+    }
+    let pixel_factor = ui.pixel_factor();
+    let (w, h) = (ui.win_w * pixel_factor, ui.win_h * pixel_factor);
+    ui.handle_input(Input::Render(w, h));
+}
+
+/// The inputs of pressing and releasing the given key.
+pub fn press_key(key: Key) -> Vec<Input> {
+    vec![Input::Press(Button::Keyboard(key)), Input::Release(Button::Keyboard(key))]
+}
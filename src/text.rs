@@ -0,0 +1,181 @@
+//! Utilities for measuring and laying out lines of text, shared by the
+//! text-based widgets so that custom widgets may lay out text consistently
+//! with them. Indices are character indices in logical order.
+
+use bidi;
+use color::Color;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize };
+use point::Point;
+use std::ops::Range;
+use ui::Ui;
+
+/// The byte range and color of each token in a line of text, as returned by
+/// a syntax highlighter.
+pub type Highlights = Vec<(Range<usize>, Color)>;
+
+/// The width of the given line of text.
+pub fn width<C: CharacterCache>(ui: &mut Ui<C>, font: FontId, size: FontSize, text: &str) -> f64 {
+    ui.text_width(font, size, text)
+}
+
+/// The offset from the start of the displayed line of a cursor placed before
+/// the character at the given index. Within right-to-left text the cursor
+/// sits on the right of the character.
+pub fn cursor_x<C: CharacterCache>(
+    ui: &mut Ui<C>,
+    font: FontId,
+    size: FontSize,
+    text: &str,
+    idx: usize
+) -> f64 {
+    if !bidi::has_rtl(text) {
+        return ui.glyph_run(font, size, text).x_at(idx)
+    }
+    let line = bidi::Line::new(text);
+    let run = ui.glyph_run(font, size, line.visual_text());
+    // The cursor follows the character before it, or precedes the first.
+    match idx {
+        0 => {
+            let visual_idx = line.visual_index(0);
+            if line.is_rtl(0) { run.x_at(visual_idx + 1) } else { run.x_at(visual_idx) }
+        },
+        _ => {
+            let prev = ::std::cmp::min(idx, line.len()) - 1;
+            let visual_idx = line.visual_index(prev);
+            if line.is_rtl(prev) { run.x_at(visual_idx) } else { run.x_at(visual_idx + 1) }
+        },
+    }
+}
+
+/// The index of the cursor position closest to the given offset from the
+/// start of the displayed line, along with the cursor's offset.
+pub fn closest_index<C: CharacterCache>(
+    ui: &mut Ui<C>,
+    font: FontId,
+    size: FontSize,
+    text: &str,
+    x: f64
+) -> (usize, f64) {
+    let line = bidi::Line::new(text);
+    let run = ui.glyph_run(font, size, line.visual_text());
+    if line.len() == 0 { return (0, 0.0) }
+    // Find the displayed character under the offset, clamped to the line.
+    let visual_idx = (0..run.len()).find(|&i| x < run.x_at(i + 1)).unwrap_or(run.len() - 1);
+    let logical_idx = line.logical_index(visual_idx);
+    let left_x = run.x_at(visual_idx);
+    let right_x = run.x_at(visual_idx + 1);
+    let is_left_half = x < (left_x + right_x) / 2.0;
+    match (line.is_rtl(logical_idx), is_left_half) {
+        (false, true) => (logical_idx, left_x),
+        (false, false) => (logical_idx + 1, right_x),
+        (true, true) => (logical_idx + 1, left_x),
+        (true, false) => (logical_idx, right_x),
+    }
+}
+
+/// Break the text into lines no wider than `max_width`, returning the byte
+/// range of each line. Lines are broken at newlines and, where a line is too
+/// wide, at its last whitespace (which is left out of both lines). Words
+/// wider than `max_width` are broken between characters. Lines are measured
+/// as `width` measures them, kerning included.
+pub fn wrap_ranges<C: CharacterCache>(
+    ui: &mut Ui<C>,
+    font: FontId,
+    size: FontSize,
+    text: &str,
+    max_width: f64
+) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut paragraph_start = 0;
+    for paragraph in text.split('\n') {
+        let run = ui.glyph_run(font, size, paragraph);
+        let mut line_start = paragraph_start;
+        // The offset of the line's first character within the run.
+        let mut line_x = 0.0;
+        // The byte range of the line's last whitespace and the index of the
+        // character that follows it.
+        let mut maybe_break: Option<(usize, usize, usize)> = None;
+        for (char_idx, (i, ch)) in paragraph.char_indices().enumerate() {
+            let idx = paragraph_start + i;
+            if ch.is_whitespace() {
+                maybe_break = Some((idx, idx + ch.len_utf8(), char_idx + 1));
+                continue;
+            }
+            // The line ends at the edge of this character, without the
+            // kerning between it and the next.
+            let right_x = run.x_at(char_idx) + ui.get_character_w(font, size, ch);
+            if right_x - line_x > max_width && idx > line_start {
+                match maybe_break {
+                    Some((break_start, break_end, next_idx)) if break_start > line_start => {
+                        ranges.push((line_start, break_start));
+                        line_start = break_end;
+                        line_x = run.x_at(next_idx);
+                    },
+                    _ => {
+                        ranges.push((line_start, idx));
+                        line_start = idx;
+                        line_x = run.x_at(char_idx);
+                    },
+                }
+                maybe_break = None;
+            }
+        }
+        ranges.push((line_start, paragraph_start + paragraph.len()));
+        paragraph_start += paragraph.len() + 1;
+    }
+    ranges
+}
+
+/// Draw a line of left-to-right text, coloring each token with its color
+/// and the rest of the line with `color`. Tokens are expected in order and
+/// not to overlap; any that do, or that don't fall on character boundaries,
+/// are drawn in `color`.
+pub fn draw_highlighted<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    pos: Point,
+    font: FontId,
+    size: FontSize,
+    color: Color,
+    text: &str,
+    tokens: &Highlights
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let mut runs = Vec::new();
+    let mut end = 0;
+    for &(ref range, token_color) in tokens.iter() {
+        let is_valid = range.start >= end && range.start < range.end && range.end <= text.len()
+            && text.is_char_boundary(range.start) && text.is_char_boundary(range.end);
+        if !is_valid { continue }
+        if range.start > end {
+            runs.push((end, range.start, color));
+        }
+        runs.push((range.start, range.end, token_color));
+        end = range.end;
+    }
+    if end < text.len() {
+        runs.push((end, text.len(), color));
+    }
+    for (start, end, run_color) in runs.into_iter() {
+        let x = pos[0] + width(ui, font, size, &text[..start]);
+        ui.draw_text(graphics, [x, pos[1]], font, size, run_color, &text[start..end]);
+    }
+}
+
+/// Break the text into lines no wider than `max_width` (see `wrap_ranges`).
+pub fn wrap<'a, C: CharacterCache>(
+    ui: &mut Ui<C>,
+    font: FontId,
+    size: FontSize,
+    text: &'a str,
+    max_width: f64
+) -> Vec<&'a str> {
+    wrap_ranges(ui, font, size, text, max_width).into_iter()
+        .map(|(start, end)| &text[start..end])
+        .collect()
+}
@@ -1,15 +1,20 @@
-use callback::Callable;
-use frame::Frameable;
-use color::{ Color, Colorable };
+use bidi;
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use cursor::MouseCursor;
 use dimensions::Dimensions;
-use graphics;
 use graphics::{
     Graphics,
 };
 use graphics::character::CharacterCache;
+use glyph_run::GlyphRun;
+use history::Value;
 use label;
-use label::FontSize;
+use label::{ FontId, FontSize };
+use mouse;
 use mouse::Mouse;
+use num_pad;
+use num_pad::NumPad;
 use piston::input::keyboard::Key::{
     Backspace,
     Left,
@@ -19,16 +24,18 @@ use piston::input::keyboard::Key::{
 use point::Point;
 use position::Positionable;
 use shape::Shapeable;
+use std::f64::consts::PI;
+use text;
+use text::Highlights;
+use theme::WidgetStyle;
 use rectangle;
 use num::Float;
-use clock_ticks::precise_time_s;
 use ui::{ UIID, Ui };
 use vecmath::{
     vec2_add,
     vec2_sub,
 };
 use widget::Widget;
-use std::cmp;
 
 pub type Idx = usize;
 pub type CursorX = f64;
@@ -38,6 +45,9 @@ pub type CursorX = f64;
 pub enum State {
     Capturing(Selection),
     Uncaptured(Uncaptured),
+    /// Pressed with the right or middle button, i.e. to open a context menu,
+    /// capturing with the given selection.
+    AltClicked(mouse::Button, Selection),
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -86,33 +96,99 @@ impl State {
     /// Return the associated Rectangle state.
     fn as_rectangle_state(&self) -> rectangle::State {
         match *self {
-            State::Capturing(_) => rectangle::State::Normal,
+            State::Capturing(_) | State::AltClicked(..) => rectangle::State::Normal,
             State::Uncaptured(state) => match state {
                 Uncaptured::Highlighted => rectangle::State::Highlighted,
                 Uncaptured::Normal => rectangle::State::Normal,
             }
         }
     }
+
+    /// The selection, if capturing.
+    fn selection(&self) -> Option<Selection> {
+        match *self {
+            State::Capturing(selection) | State::AltClicked(_, selection) => Some(selection),
+            State::Uncaptured(_) => None,
+        }
+    }
+
+    /// The state with the given selection, if capturing.
+    fn with_selection(self, selection: Selection) -> State {
+        match self {
+            State::AltClicked(button, _) => State::AltClicked(button, selection),
+            _ => State::Capturing(selection),
+        }
+    }
 }
 
 widget_fns!(TextBox, State, Widget::TextBox(State::Uncaptured(Uncaptured::Normal)));
 
-static TEXT_PADDING: f64 = 5f64;
+/// What happened to the TextBox, returned by `TextBox::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The text was changed by the user.
+    Edited,
+    /// Return was pressed while the TextBox was capturing the keyboard.
+    Submitted,
+    /// The right or middle button was released over the text box after
+    /// pressing it with the same, i.e. to open a context menu. The text box
+    /// captures the keyboard, keeping its selection.
+    AltClicked(mouse::Button),
+}
+
+
+/// The layout of the text, kept between frames as the TextBox's widget memory
+/// and updated as the text is edited so that the cursor may be placed without
+/// measuring the text from its start.
+struct Layout {
+    font: FontId,
+    size: FontSize,
+    /// The text as of the last edit, to detect changes made by the application.
+    text: String,
+    run: GlyphRun,
+}
+
+impl Layout {
+
+    /// Measure the given text.
+    fn new<C: CharacterCache>(ui: &mut Ui<C>, font: FontId, size: FontSize, text: &str) -> Layout {
+        Layout { font: font, size: size, text: text.to_string(), run: GlyphRun::new(ui, font, size, text) }
+    }
+
+    /// Whether or not the layout is of the given text.
+    fn matches(&self, font: FontId, size: FontSize, text: &str) -> bool {
+        self.font == font && self.size == size && self.text == text
+    }
+
+    /// Replace `removed` characters at the given character index with
+    /// `inserted`, measuring only the characters around the edit.
+    fn splice<C: CharacterCache>(&mut self, ui: &mut Ui<C>, idx: Idx, removed: usize, inserted: &str) {
+        splice_string(&mut self.text, idx, removed, inserted);
+        self.run.splice(ui, self.font, self.size, &self.text, idx, removed, inserted.chars().count());
+    }
+
+    /// The offset of the cursor at the given index from the start of the
+    /// text. Right-to-left text is displayed in a different order to that in
+    /// which it is laid out, so it is measured as a whole.
+    fn cursor_x<C: CharacterCache>(&self, ui: &mut Ui<C>, idx: usize) -> f64 {
+        match bidi::has_rtl(&self.text) {
+            true => text::cursor_x(ui, self.font, self.size, &self.text, idx),
+            false => self.run.x_at(idx),
+        }
+    }
+
+}
 
-/// Find the position of a character in a text box.
+/// Find the position of a character in a text box. Indices are logical, so
+/// within right-to-left text the cursor sits on the right of the character.
 fn cursor_position<C: CharacterCache>(ui: &mut Ui<C>,
+                 layout: &Layout,
                  mut idx: usize,
-                 mut text_x: f64,
-                 font_size: FontSize,
-                 text: &str) -> (Idx, CursorX) {
-    if idx == 0 { return (0, text_x); }
-    let text_len = text.len();
+                 text_x: f64) -> (Idx, CursorX) {
+    let text_len = layout.text.chars().count();
     if idx > text_len { idx = text_len; }
-    for (i, ch) in text.chars().enumerate() {
-        if i >= idx { break; }
-        text_x += ui.get_character(font_size, ch).width();
-    }
-    (idx, text_x)
+    (idx, text_x + layout.cursor_x(ui, idx))
 }
 
 /// Check if cursor is over the pad and if so, which
@@ -123,7 +199,7 @@ fn over_elem<C: CharacterCache>(ui: &mut Ui<C>,
              pad_pos: Point,
              pad_dim: Dimensions,
              text_pos: Point,
-             text_w: f64,
+             font: FontId,
              font_size: FontSize,
              text: &str) -> Element {
     match rectangle::is_over(pos, mouse_pos, rect_dim) {
@@ -131,7 +207,7 @@ fn over_elem<C: CharacterCache>(ui: &mut Ui<C>,
         true => match rectangle::is_over(pad_pos, mouse_pos, pad_dim) {
             false => Element::Rect,
             true => {
-                let (idx, _) = closest_idx(ui, mouse_pos, text_pos[0], text_w, font_size, text);
+                let (idx, _) = closest_idx(ui, mouse_pos, text_pos[0], font, font_size, text);
                 Element::Char(idx)
             },
         },
@@ -142,32 +218,38 @@ fn over_elem<C: CharacterCache>(ui: &mut Ui<C>,
 fn closest_idx<C: CharacterCache>(ui: &mut Ui<C>,
                mouse_pos: Point,
                text_x: f64,
-               text_w: f64,
+               font: FontId,
                font_size: FontSize,
                text: &str) -> (Idx, f64) {
-    if mouse_pos[0] <= text_x { return (0, text_x) }
-    let mut x = text_x;
-    let mut prev_x = x;
-    let mut left_x = text_x;
-    for (i, ch) in text.chars().enumerate() {
-        let character = ui.get_character(font_size, ch);
-        let char_w = character.width();
-        x += char_w;
-        let right_x = prev_x + char_w / 2.0;
-        if mouse_pos[0] > left_x && mouse_pos[0] <= right_x { return (i, prev_x) }
-        prev_x = x;
-        left_x = right_x;
-    }
-    (text.len(), text_x + text_w)
+    let (idx, x) = text::closest_index(ui, font, font_size, text, mouse_pos[0] - text_x);
+    (idx, text_x + x)
 }
 
 /// Check and return the current state of the TextBox.
 fn get_new_state(over_elem: Element, prev_state: State, mouse: Mouse) -> State {
     use mouse::ButtonState::{ Down, Up };
-    use self::State::{ Capturing, Uncaptured };
+    use self::State::{ AltClicked, Capturing, Uncaptured };
     use self::Uncaptured::{ Normal, Highlighted };
 
+    // The right and middle buttons capture the text box without moving the
+    // cursor or changing the selection, unless pressed while uncaptured.
+    match (prev_state, over_elem, mouse.left, mouse.alt_button_down()) {
+        (Capturing(prev), Element::Char(_), Up, Some(button)) |
+        (Capturing(prev), Element::Rect, Up, Some(button)) =>
+            return AltClicked(button, Selection { anchor: Anchor::None, .. prev }),
+        (Uncaptured(Highlighted), Element::Char(idx), Up, Some(button)) =>
+            return AltClicked(button, Selection::from_index(idx)),
+        (Uncaptured(Highlighted), Element::Rect, Up, Some(button)) =>
+            return AltClicked(button, Selection::from_index(0)),
+        _ => (),
+    }
+
     match prev_state {
+        State::AltClicked(button, selection) => match mouse.button(button) {
+            Down => prev_state,
+            Up => Capturing(selection),
+        },
+
         State::Capturing(prev) => match mouse.left {
             Down => match over_elem {
                 Element::Nill => if prev.anchor == Anchor::None {
@@ -212,27 +294,48 @@ fn get_new_state(over_elem: Element, prev_state: State, mouse: Mouse) -> State {
     }
 }
 
-/// Draw the text cursor.
-fn draw_cursor<B: Graphics>(
-    win_w: f64,
-    win_h: f64,
+/// Draw the text cursor, blinking if the theme says so.
+pub fn draw_cursor<B, C>(
+    ui: &mut Ui<C>,
     graphics: &mut B,
     color: Color,
     cursor_x: f64,
     pad_pos_y: f64,
     pad_h: f64
-) {
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h);
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
     let Color(color) = color.plain_contrast();
     let (r, g, b, a) = (color[0], color[1], color[2], color[3]);
-    graphics::Line::new_round([r, g, b, (a * (precise_time_s() * 2.5).sin() as f32).abs()], 0.5f64)
-        .draw(
-            [cursor_x, pad_pos_y, cursor_x, pad_pos_y + pad_h],
-            draw_state,
-            transform,
-            graphics
-        );
+    let alpha = match ui.theme.cursor_blink {
+        true => (a * (ui.time() * ui.theme.cursor_blink_rate * PI).sin() as f32).abs(),
+        false => a,
+    };
+    let width = ui.theme.cursor_width;
+    if ui.theme.cursor_blink {
+        ui.mark_damaged([cursor_x - width, pad_pos_y], [width * 2.0, pad_h]);
+    }
+    ui.draw_line(graphics, [cursor_x, pad_pos_y, cursor_x, pad_pos_y + pad_h],
+                 width, true, Color([r, g, b, alpha]));
+}
+
+/// The byte offset of the character at the given index, or the length of the
+/// string for an index at or past its end.
+fn byte_index(string: &str, idx: Idx) -> usize {
+    string.char_indices().nth(idx).map_or(string.len(), |(i, _)| i)
+}
+
+/// Replace `removed` characters of the string at the given character index
+/// with `inserted`, in place so that editing long text doesn't copy all of it.
+fn splice_string(string: &mut String, idx: Idx, removed: usize, inserted: &str) {
+    let start = byte_index(string, idx);
+    if removed > 0 {
+        let end = start + byte_index(&string[start..], removed);
+        string.drain(start..end);
+    }
+    string.insert_str(start, inserted);
 }
 
 /// A context on which the builder pattern can be implemented.
@@ -243,15 +346,38 @@ pub struct TextBox<'a, F> {
     pos: Point,
     dim: Dimensions,
     maybe_callback: Option<F>,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    maybe_highlighter: Option<Box<Fn(&str) -> Highlights + 'a>>,
+    maybe_num_pad: Option<UIID>,
+    style: WidgetStyle,
 }
 
 impl<'a, F> TextBox<'a, F> {
+    pub fn font(mut self, font: FontId) -> TextBox<'a, F> {
+        self.style.maybe_font = Some(font);
+        self
+    }
+
     pub fn font_size(self, font_size: FontSize) -> TextBox<'a, F> {
         TextBox { font_size: font_size, ..self }
     }
+
+    /// Color the text per-token, i.e. for editing code or config. The given
+    /// function is called with the text whenever it is drawn and returns the
+    /// byte range and color of each token. Right-to-left text isn't colored.
+    pub fn highlighter<H>(mut self, highlighter: H) -> TextBox<'a, F>
+        where H: Fn(&str) -> Highlights + 'a
+    {
+        self.maybe_highlighter = Some(Box::new(highlighter));
+        self
+    }
+
+    /// Pop up a `NumPad` with the given UIID beneath the text box while it
+    /// is capturing, i.e. for entering numbers on a touch screen. The keys
+    /// edit the end of the text, and Enter submits it as Return would.
+    pub fn num_pad(self, ui_id: UIID) -> TextBox<'a, F> {
+        TextBox { maybe_num_pad: Some(ui_id), ..self }
+    }
 }
 
 impl<'a, F> TextBox<'a, F> {
@@ -264,39 +390,23 @@ impl<'a, F> TextBox<'a, F> {
             pos: [0.0, 0.0],
             dim: [192.0, 48.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            maybe_highlighter: None,
+            maybe_num_pad: None,
+            style: WidgetStyle::new(),
         }
     }
 
     fn selection_rect<C: CharacterCache>
-                     (&self, ui: &mut Ui<C>, text_x: f64, start: Idx, end: Idx) ->
+                     (&self, ui: &mut Ui<C>, layout: &Layout, text_x: f64, start: Idx, end: Idx) ->
                      (Point, Dimensions) {
-        let (_, pos) = cursor_position(ui, start, text_x, self.font_size, &self.text);
-        let htext: String = self.text.chars().skip(start).take(end - start).collect();
-        let htext_w = label::width(ui, self.font_size, &htext);
-        ([pos, self.pos[1]], [htext_w, self.dim[1]])
+        let (_, start_x) = cursor_position(ui, layout, start, text_x);
+        let (_, end_x) = cursor_position(ui, layout, end, text_x);
+        ([start_x.min(end_x), self.pos[1]], [(end_x - start_x).abs(), self.dim[1]])
     }
 }
 
-impl<'a, F> Colorable for TextBox<'a, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
-    }
-}
-
-impl<'a, F> Frameable for TextBox<'a, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
-        self
-    }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
-        self
-    }
-}
+composite_builder_impls!(TextBox<'a, F>);
 
 impl<'a, F> Callable<F> for TextBox<'a, F> {
     fn callback(mut self, cb: F) -> Self {
@@ -305,150 +415,276 @@ impl<'a, F> Callable<F> for TextBox<'a, F> {
     }
 }
 
-impl<'a, F> Positionable for TextBox<'a, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+impl<'a, F> Reactable<'a, Event> for TextBox<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
 
-impl<'a, F> Shapeable for TextBox<'a, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, F> ::draw::Drawable for TextBox<'a, F>
     where
         F: FnMut(&mut String) + 'a
 {
-
     #[inline]
     fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-        let mouse = ui.get_mouse_state();
+        if let Some(Event::Submitted) = self.update(ui, graphics) {
+            let TextBox { // borrowck
+                ref mut maybe_callback,
+                ref mut text,
+                ..
+            } = *self;
+            if let Some(ref mut callback) = *maybe_callback { (*callback)(*text) }
+        }
+    }
+}
+
+impl<'a> TextBox<'a, NoCallback> {
+
+    /// Draw the text_box, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the text needs to
+    /// borrow application state. The cursor is kept within the text if the
+    /// text is changed in reaction.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, F> TextBox<'a, F> {
+
+    /// Update and draw the text_box, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_text_box, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let font = style.font;
+        // The pad beneath the text box captures the mouse from other widgets, but not from this one.
+        let mouse = match self.maybe_num_pad {
+            Some(num_pad_id) => ui.mouse_state_for(num_pad_id),
+            None => ui.get_mouse_state(),
+        };
         let state = *get_state(ui, self.ui_id);
 
+        // A change that was undone or redone is re-fired as though submitted.
+        let is_restored = match ui.take_restored(self.ui_id) {
+            Some(Value::Text(text)) => {
+                *self.text = text;
+                true
+            },
+            _ => false,
+        };
+
+        let mut layout = match ui.take_widget_memory::<Layout>(self.ui_id) {
+            Some(layout) => match layout.matches(font, self.font_size, &self.text) {
+                true => layout,
+                false => Layout::new(ui, font, self.font_size, &self.text),
+            },
+            None => Layout::new(ui, font, self.font_size, &self.text),
+        };
+
         // Rect.
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
+        let color = style.color;
+        let frame_w = style.frame;
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
+            true => Some((frame_w, style.frame_color)),
             false => None,
         };
         let pad_pos = vec2_add(self.pos, [frame_w; 2]);
         let pad_dim = vec2_sub(self.dim, [frame_w2; 2]);
-        let text_x = pad_pos[0] + TEXT_PADDING;
+        let text_w = label::width(ui, font, self.font_size, &self.text);
+        // Right-to-left text is aligned to the right of the pad.
+        let text_x = match bidi::base_direction(&self.text) {
+            bidi::Direction::LeftToRight => pad_pos[0] + ui.theme.padding,
+            bidi::Direction::RightToLeft => pad_pos[0] + pad_dim[0] - ui.theme.padding - text_w,
+        };
         let text_y = pad_pos[1] + (pad_dim[1] - self.font_size as f64) / 2.0;
         let text_pos = [text_x, text_y];
-        let text_w = label::width(ui, self.font_size, &self.text);
         let over_elem = over_elem(ui, self.pos, mouse.pos, self.dim,
-                                  pad_pos, pad_dim, text_pos, text_w,
-                                  self.font_size, &self.text);
+                                  pad_pos, pad_dim, text_pos,
+                                  font, self.font_size, &self.text);
         let mut new_state = get_new_state(over_elem, state, mouse);
+        // Pressing the pad beneath the text box keeps it capturing.
+        let num_pad_pos = [self.pos[0], self.pos[1] + self.dim[1]];
+        let num_pad_dim = [self.dim[0], self.dim[0] * 4.0 / 3.0];
+        if let (Some(_), State::Capturing(_)) = (self.maybe_num_pad, state) {
+            if rectangle::is_over(num_pad_pos, mouse.pos, num_pad_dim) {
+                new_state = state;
+            }
+        }
+        let mut maybe_event = None;
+        match over_elem {
+            Element::Nill => (),
+            _ => ui.set_mouse_cursor(MouseCursor::Text),
+        }
 
-        rectangle::draw(ui.win_w, ui.win_h, graphics, new_state.as_rectangle_state(),
-                        self.pos, self.dim, maybe_frame, color);
+        let rect_color = ui.transition_color(self.ui_id, new_state.as_rectangle_state().color(color));
+        let fill = rectangle::fill(rect_color, style.maybe_gradient, style.maybe_background_image,
+                                   new_state.as_rectangle_state());
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
 
-        if let State::Capturing(selection) = new_state {
+        if let Some(selection) = new_state.selection() {
             if selection.start != selection.end {
-                let (pos, dim) = self.selection_rect(ui, text_x, selection.start, selection.end);
-                rectangle::draw(ui.win_w, ui.win_h, graphics, new_state.as_rectangle_state(),
+                let (pos, dim) = self.selection_rect(ui, &layout, text_x, selection.start, selection.end);
+                rectangle::draw(ui, graphics, new_state.as_rectangle_state(),
                                 [pos[0], pos[1] + frame_w], [dim[0], dim[1] - frame_w2],
-                                None, color.highlighted());
+                                None, 0.0, color.highlighted());
             }
         }
 
-        ui.draw_text(graphics, text_pos, self.font_size, color.plain_contrast(), &self.text);
+        ui.push_clip(pad_pos, pad_dim);
+        let display_text = bidi::reorder(&self.text);
+        match self.maybe_highlighter {
+            // Only text displayed in its logical order can be colored by byte range.
+            Some(ref highlighter) if display_text == *self.text => {
+                let tokens = highlighter(&self.text);
+                text::draw_highlighted(ui, graphics, text_pos, font, self.font_size,
+                                       color.plain_contrast(), &self.text, &tokens);
+            },
+            _ => ui.draw_text(graphics, text_pos, font, self.font_size, color.plain_contrast(), &display_text),
+        }
+        ui.pop_clip();
 
-        if let State::Capturing(selection) = new_state {
+        if let Some(selection) = new_state.selection() {
             if selection.start == selection.end {
-            let (idx, cursor_x) = cursor_position(ui, selection.start, text_x, self.font_size, &self.text);
-            draw_cursor(ui.win_w, ui.win_h, graphics, color, cursor_x, pad_pos[1], pad_dim[1]);
+            let (idx, cursor_x) = cursor_position(ui, &layout, selection.start, text_x);
+            draw_cursor(ui, graphics, color, cursor_x, pad_pos[1], pad_dim[1]);
             let mut new_idx = idx;
-            let mut new_cursor_x = cursor_x;
-
-            // Check for entered text.
-            let entered_text = ui.get_entered_text();
-            for t in entered_text.iter() {
-                let mut entered_text_width = 0.0;
-                for ch in t[..].chars() {
-                    let c = ui.get_character(self.font_size, ch);
-                    entered_text_width += c.width();
-                }
-                if new_cursor_x + entered_text_width < pad_pos[0] + pad_dim[0] - TEXT_PADDING {
-                    new_cursor_x += entered_text_width;
-                }
-                else {
-                    break;
-                }
-                let new_text = format!("{}{}{}", &self.text[..idx], t, &self.text[idx..]);
-                *self.text = new_text;
-                new_idx += t.len();
+            // Input already handled by another widget is left be.
+            let is_input_free = ui.text_input_consumer().map_or(true, |ui_id| ui_id == self.ui_id);
+
+            // Check for entered text. The layout is updated around the new
+            // text so that kerning between the new and existing characters
+            // is taken into account, and the text is rejected if it overflows.
+            if is_input_free {
+                let text = &mut *self.text;
+                ui.with_entered_text(|ui, entered_text| {
+                    for t in entered_text.iter() {
+                        layout.splice(ui, new_idx, 0, t);
+                        let inserted = t.chars().count();
+                        if layout.run.width() >= pad_dim[0] - ui.theme.padding * 2.0 {
+                            layout.splice(ui, new_idx, inserted, "");
+                            break;
+                        }
+                        splice_string(text, new_idx, 0, t);
+                        new_idx += inserted;
+                        maybe_event = Some(Event::Edited);
+                    }
+                });
+                ui.consume_text_input(self.ui_id);
             }
 
             // Check for control keys.
-            let pressed_keys = ui.get_pressed_keys();
-            for key in pressed_keys.iter() {
-                match *key {
+            let key_count = if is_input_free { ui.pressed_keys().len() } else { 0 };
+            for i in 0..key_count {
+                let key = ui.pressed_keys()[i];
+                let text_len = self.text.chars().count();
+                match key {
                     Backspace => {
-                        if self.text.len() > 0
-                        && self.text.len() >= idx
-                        && idx > 0 {
-                            let rem_idx = idx - 1;
-                            new_cursor_x -= ui.get_character_w(
-                                self.font_size, self.text[..].char_at(rem_idx)
-                            );
-                            let new_text = format!("{}{}", &self.text[..rem_idx], &self.text[idx..]);
-                            *self.text = new_text;
+                        if text_len > 0
+                        && text_len >= new_idx
+                        && new_idx > 0 {
+                            let rem_idx = new_idx - 1;
+                            layout.splice(ui, rem_idx, 1, "");
+                            splice_string(self.text, rem_idx, 1, "");
                             new_idx = rem_idx;
+                            // A submission this frame takes precedence.
+                            maybe_event = maybe_event.or(Some(Event::Edited));
                         }
                     },
                     Left => {
                         if idx > 0 {
-                            new_cursor_x -= ui.get_character_w(
-                                self.font_size, self.text[..].char_at(idx - 1)
-                            );
                             new_idx -= 1;
                         }
                     },
                     Right => {
-                        if self.text.len() > idx {
-                            new_cursor_x += ui.get_character_w(
-                                self.font_size, self.text[..].char_at(idx)
-                            );
+                        if text_len > idx {
                             new_idx += 1;
                         }
                     },
                     Return => if self.text.len() > 0 {
-                        let TextBox { // borrowck
-                            ref mut maybe_callback,
-                            ref font_size,
-                            ref mut text,
-                            ..
-                        } = *self;
-                        match *maybe_callback {
-                            Some(ref mut callback) => {
-                                (*callback)(*text);
-                                new_idx = cmp::min(new_idx, text.len());
-                                let text = &*text;
-                                new_cursor_x = text.chars()
-                                                   // Add text_pos.x for padding
-                                                   .fold(text_pos[0], |acc, c| {
-                                    acc + ui.get_character_w(*font_size, c)
-                                });
-                            },
-                            None => (),
-                        }
+                        // The cursor is clamped to the text on the next
+                        // frame, should the text be changed in reaction.
+                        maybe_event = Some(Event::Submitted);
                     },
                     _ => (),
                 }
             }
-            new_state = State::Capturing(Selection { start: new_idx, end: new_idx, .. selection });
+            new_state = new_state.with_selection(Selection { start: new_idx, end: new_idx, .. selection });
         }}
+        if let (Some(num_pad_id), Some(_)) = (self.maybe_num_pad, new_state.selection()) {
+            let prev_len = self.text.len();
+            let num_pad_event = {
+                let mut num_pad: NumPad<NoCallback> = NumPad::new(num_pad_id, &mut *self.text)
+                    .point(num_pad_pos)
+                    .dim(num_pad_dim);
+                num_pad.react(ui, graphics)
+            };
+            // Digits that would overflow the text box are rejected, as typed text is.
+            let num_pad_event = match num_pad_event {
+                Some(num_pad::Event::Edited) if self.text.len() > prev_len
+                    && label::width(ui, font, self.font_size, &self.text) >= pad_dim[0] - ui.theme.padding * 2.0 => {
+                    self.text.truncate(prev_len);
+                    None
+                },
+                event => event,
+            };
+            match num_pad_event {
+                Some(num_pad::Event::Edited) => {
+                    new_state = new_state.with_selection(Selection::from_index(self.text.chars().count()));
+                    maybe_event = maybe_event.or(Some(Event::Edited));
+                },
+                Some(num_pad::Event::Entered) => if self.text.len() > 0 {
+                    maybe_event = Some(Event::Submitted);
+                },
+                None => (),
+            }
+        }
+        if let (State::AltClicked(button, _), State::Capturing(_)) = (state, new_state) {
+            if over_elem != Element::Nill {
+                maybe_event = maybe_event.or(Some(Event::AltClicked(button)));
+            }
+        }
+        if new_state.selection().is_some() {
+            ui.claim_text_focus(self.ui_id);
+        }
+        // The text is recorded once submitted, as a change from the text when
+        // capturing began or when the text was last submitted.
+        match (state, new_state, maybe_event) {
+            (_, _, Some(Event::Submitted)) => {
+                ui.commit_change(self.ui_id, Value::Text(self.text.clone()));
+                ui.begin_change(self.ui_id, Value::Text(self.text.clone()));
+            },
+            (State::Uncaptured(_), _, _) if new_state.selection().is_some() =>
+                ui.begin_change(self.ui_id, Value::Text(self.text.clone())),
+            _ => (),
+        }
+        if is_restored {
+            maybe_event = Some(Event::Submitted);
+        }
+        ui.set_widget_memory(self.ui_id, layout);
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::TextBox(new_state), self.pos, self.dim);
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
@@ -1,31 +1,205 @@
 
-use color::Color;
+use color::{ Color, Gradient };
+use graphics::character::CharacterCache;
+use image::NinePatch;
+use label::FontId;
+use number_format::NumberFormat;
+use rectangle::Shadow;
 use rustc_serialize::{
     json,
     Encodable,
     Decodable,
+    Decoder,
 };
 use std::error::Error;
 use std::fs::File;
 use std::path::Path;
-use std::str;
-use std::borrow::ToOwned;
 use ui::Ui;
 
 /// A data holder for style-related data.
-#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
+///
+/// Themes may be saved to and loaded from JSON files (see `Theme::save` and
+/// `Theme::load`) so that the look of an application can be changed without
+/// recompiling. Colors are written as `[r, g, b, a]` arrays.
+#[derive(Debug, Clone, RustcEncodable)]
 pub struct Theme {
     pub name: String,
     pub background_color: Color,
     pub shape_color: Color,
     pub frame_color: Color,
     pub frame_width: f64,
+    /// The radius with which the corners of widgets are rounded.
+    pub corner_radius: f64,
     pub label_color: Color,
+    /// The color in which errors are displayed (i.e. a `Form`'s invalid fields).
+    pub error_color: Color,
+    /// The color in which links are displayed (see `Link`).
+    pub link_color: Color,
     pub font_size_large: u32,
     pub font_size_medium: u32,
     pub font_size_small: u32,
-    //TODO: Add unique theme-ing for each widget.
-    //i.e. maybe_slider: Option<SliderTheme>, etc
+    /// The font used by widgets that are not given one.
+    pub font_default: FontId,
+    /// Fonts for applications to select by role (see `Ui::add_font`).
+    pub font_regular: FontId,
+    pub font_bold: FontId,
+    pub font_mono: FontId,
+    /// The icon font from which widgets draw the glyphs given via `icon`.
+    pub font_icon: FontId,
+    /// The space between a widget's edge and its content (i.e. a TextBox's text).
+    pub padding: f64,
+    /// The duration in seconds over which widget colors transition
+    /// between states. A duration of `0.0` disables the transition.
+    pub transition_duration: f64,
+    /// How numeric widgets display their values.
+    pub number_format: NumberFormat,
+    /// Whether or not the TextBox cursor blinks. A solid cursor may be
+    /// easier to see for some users.
+    pub cursor_blink: bool,
+    /// The number of times per second the TextBox cursor fades out and in.
+    pub cursor_blink_rate: f64,
+    /// The width of the TextBox cursor.
+    pub cursor_width: f64,
+    /// Whether or not scrolled lists keep moving after a flick, slowing
+    /// down with `scroll_friction`.
+    pub kinetic_scrolling: bool,
+    /// The friction with which kinetic scrolling slows down. Greater values
+    /// bring a flicked list to rest sooner.
+    pub scroll_friction: f64,
+    /// How far in pixels a list may be stretched past either end before
+    /// springing back. A limit of `0.0` stops lists dead at either end.
+    pub overscroll_limit: f64,
+    /// Defaults for each kind of widget. Where a field is `None`, the widget
+    /// falls back to the global defaults above.
+    pub maybe_button: Option<WidgetStyle>,
+    pub maybe_console: Option<WidgetStyle>,
+    pub maybe_curve_editor: Option<WidgetStyle>,
+    pub maybe_dock: Option<WidgetStyle>,
+    pub maybe_drop_down_list: Option<WidgetStyle>,
+    pub maybe_envelope_editor: Option<WidgetStyle>,
+    pub maybe_gallery: Option<WidgetStyle>,
+    pub maybe_heatmap: Option<WidgetStyle>,
+    pub maybe_label: Option<WidgetStyle>,
+    pub maybe_link: Option<WidgetStyle>,
+    pub maybe_node_editor: Option<WidgetStyle>,
+    pub maybe_num_pad: Option<WidgetStyle>,
+    pub maybe_number_dialer: Option<WidgetStyle>,
+    pub maybe_rating: Option<WidgetStyle>,
+    pub maybe_reorderable_list: Option<WidgetStyle>,
+    pub maybe_ruler: Option<WidgetStyle>,
+    pub maybe_slider: Option<WidgetStyle>,
+    pub maybe_sparkline: Option<WidgetStyle>,
+    pub maybe_text_box: Option<WidgetStyle>,
+    pub maybe_timeline: Option<WidgetStyle>,
+    pub maybe_toggle: Option<WidgetStyle>,
+    pub maybe_viewport: Option<WidgetStyle>,
+    pub maybe_xy_pad: Option<WidgetStyle>,
+}
+
+/// Default style values for a particular kind of widget. These are used in
+/// place of the `Theme`'s global values when a widget is not given its own.
+#[derive(Copy, Debug, Clone, RustcEncodable, RustcDecodable)]
+pub struct WidgetStyle {
+    pub maybe_color: Option<Color>,
+    pub maybe_frame: Option<f64>,
+    /// The frame color, also used for the track behind a Slider.
+    pub maybe_frame_color: Option<Color>,
+    pub maybe_label_color: Option<Color>,
+    pub maybe_label_font_size: Option<u32>,
+    pub maybe_font: Option<FontId>,
+    /// A gradient with which to fill the widget in place of its color.
+    pub maybe_gradient: Option<Gradient>,
+    pub maybe_corner_radius: Option<f64>,
+    pub maybe_shadow: Option<Shadow>,
+    pub maybe_background_image: Option<NinePatch>,
+    /// The amount by which the alpha of the widget's colors is multiplied.
+    pub maybe_alpha: Option<f32>,
+}
+
+/// The style with which a widget is drawn, resolved from the widget's own
+/// values, then the `WidgetStyle` for its kind in the `Theme`, and finally
+/// the `Theme`'s global values.
+#[derive(Copy, Debug, Clone)]
+pub struct Style {
+    pub color: Color,
+    pub frame: f64,
+    pub frame_color: Color,
+    pub label_color: Color,
+    pub label_font_size: u32,
+    pub font: FontId,
+    pub maybe_gradient: Option<Gradient>,
+    pub corner_radius: f64,
+    pub maybe_shadow: Option<Shadow>,
+    pub maybe_background_image: Option<NinePatch>,
+    pub alpha: f32,
+}
+
+impl WidgetStyle {
+
+    /// A style that defers every value to the global theme.
+    pub fn new() -> WidgetStyle {
+        WidgetStyle {
+            maybe_color: None,
+            maybe_frame: None,
+            maybe_frame_color: None,
+            maybe_label_color: None,
+            maybe_label_font_size: None,
+            maybe_font: None,
+            maybe_gradient: None,
+            maybe_corner_radius: None,
+            maybe_shadow: None,
+            maybe_background_image: None,
+            maybe_alpha: None,
+        }
+    }
+
+    /// Resolve this (instance) style against the style for the widget's kind
+    /// and the global values of the given `Theme`.
+    pub fn resolve(&self, maybe_kind: Option<WidgetStyle>, theme: &Theme) -> Style {
+        self.resolve_with(maybe_kind, WidgetStyle::new(), theme)
+    }
+
+    /// Resolve this (instance) style as `resolve` does, but with the given
+    /// defaults for the widget's kind taking precedence over the `Theme`'s
+    /// global values, i.e. for a kind drawn in the monospace font.
+    pub fn resolve_with(&self, maybe_kind: Option<WidgetStyle>, defaults: WidgetStyle, theme: &Theme) -> Style {
+        let kind = maybe_kind.unwrap_or(WidgetStyle::new()).or(defaults);
+        Style {
+            color: self.maybe_color.or(kind.maybe_color).unwrap_or(theme.shape_color),
+            frame: self.maybe_frame.or(kind.maybe_frame).unwrap_or(theme.frame_width),
+            frame_color: self.maybe_frame_color.or(kind.maybe_frame_color)
+                .unwrap_or(theme.frame_color),
+            label_color: self.maybe_label_color.or(kind.maybe_label_color)
+                .unwrap_or(theme.label_color),
+            label_font_size: self.maybe_label_font_size.or(kind.maybe_label_font_size)
+                .unwrap_or(theme.font_size_medium),
+            font: self.maybe_font.or(kind.maybe_font).unwrap_or(theme.font_default),
+            maybe_gradient: self.maybe_gradient.or(kind.maybe_gradient),
+            corner_radius: self.maybe_corner_radius.or(kind.maybe_corner_radius)
+                .unwrap_or(theme.corner_radius),
+            maybe_shadow: self.maybe_shadow.or(kind.maybe_shadow),
+            maybe_background_image: self.maybe_background_image.or(kind.maybe_background_image),
+            alpha: self.maybe_alpha.or(kind.maybe_alpha).unwrap_or(1.0),
+        }
+    }
+
+    /// This style with any values it leaves unset taken from `other`.
+    fn or(self, other: WidgetStyle) -> WidgetStyle {
+        WidgetStyle {
+            maybe_color: self.maybe_color.or(other.maybe_color),
+            maybe_frame: self.maybe_frame.or(other.maybe_frame),
+            maybe_frame_color: self.maybe_frame_color.or(other.maybe_frame_color),
+            maybe_label_color: self.maybe_label_color.or(other.maybe_label_color),
+            maybe_label_font_size: self.maybe_label_font_size.or(other.maybe_label_font_size),
+            maybe_font: self.maybe_font.or(other.maybe_font),
+            maybe_gradient: self.maybe_gradient.or(other.maybe_gradient),
+            maybe_corner_radius: self.maybe_corner_radius.or(other.maybe_corner_radius),
+            maybe_shadow: self.maybe_shadow.or(other.maybe_shadow),
+            maybe_background_image: self.maybe_background_image.or(other.maybe_background_image),
+            maybe_alpha: self.maybe_alpha.or(other.maybe_alpha),
+        }
+    }
+
 }
 
 impl Theme {
@@ -38,41 +212,127 @@ impl Theme {
             shape_color: Color::new(1.0, 1.0, 1.0, 1.0),
             frame_color: Color::new(0.0, 0.0, 0.0, 1.0),
             frame_width: 1.0,
+            corner_radius: 0.0,
             label_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            error_color: Color::new(0.85, 0.15, 0.15, 1.0),
+            link_color: Color::new(0.16, 0.44, 0.85, 1.0),
             font_size_large: 26,
             font_size_medium: 18,
             font_size_small: 12,
+            font_default: 0,
+            font_regular: 0,
+            font_bold: 0,
+            font_mono: 0,
+            font_icon: 0,
+            padding: 5.0,
+            transition_duration: 0.08,
+            number_format: NumberFormat::new(),
+            cursor_blink: true,
+            cursor_blink_rate: 0.8,
+            cursor_width: 1.0,
+            kinetic_scrolling: true,
+            scroll_friction: 4.0,
+            overscroll_limit: 48.0,
+            maybe_button: None,
+            maybe_console: None,
+            maybe_curve_editor: None,
+            maybe_dock: None,
+            maybe_drop_down_list: None,
+            maybe_envelope_editor: None,
+            maybe_gallery: None,
+            maybe_heatmap: None,
+            maybe_label: None,
+            maybe_link: None,
+            maybe_node_editor: None,
+            maybe_num_pad: None,
+            maybe_number_dialer: None,
+            maybe_rating: None,
+            maybe_reorderable_list: None,
+            maybe_ruler: None,
+            maybe_slider: None,
+            maybe_sparkline: None,
+            maybe_text_box: None,
+            maybe_timeline: None,
+            maybe_toggle: None,
+            maybe_viewport: None,
+            maybe_xy_pad: None,
         }
     }
 
-    /// Load a theme from file.
-    pub fn load(path: &str) -> Result<Theme, String> {
-        let mut file = match File::open(&Path::new(path)) {
-            Ok(file) => file,
-            Err(e) => return Err(format!("Failed to open file for Theme: {}", Error::description(&e))),
-        };
-        let mut contents = Vec::new();
-        if let Err(e) = ::std::io::Read::read_to_end(&mut file, &mut contents) {
-            return Err(format!("Failed to load Theme correctly: {}", Error::description(&e)));
+    /// A light theme with dark text on pale widgets.
+    pub fn light() -> Theme {
+        Theme {
+            name: "Light".to_string(),
+            background_color: Color::new(0.93, 0.93, 0.93, 1.0),
+            shape_color: Color::new(0.98, 0.98, 0.98, 1.0),
+            frame_color: Color::new(0.6, 0.6, 0.62, 1.0),
+            frame_width: 1.0,
+            label_color: Color::new(0.1, 0.1, 0.12, 1.0),
+            font_size_large: 24,
+            font_size_medium: 16,
+            font_size_small: 12,
+            maybe_label: Some(WidgetStyle {
+                maybe_color: Some(Color::new(0.1, 0.1, 0.12, 1.0)),
+                ..WidgetStyle::new()
+            }),
+            ..Theme::default()
         }
-        let json_object = match json::Json::from_str(str::from_utf8(&contents[..]).unwrap()) {
+    }
+
+    /// A dark theme with light text on muted widgets.
+    pub fn dark() -> Theme {
+        Theme {
+            name: "Dark".to_string(),
+            background_color: Color::new(0.11, 0.11, 0.12, 1.0),
+            shape_color: Color::new(0.26, 0.26, 0.28, 1.0),
+            frame_color: Color::new(0.05, 0.05, 0.06, 1.0),
+            frame_width: 1.0,
+            label_color: Color::new(0.92, 0.92, 0.92, 1.0),
+            link_color: Color::new(0.45, 0.7, 1.0, 1.0),
+            font_size_large: 24,
+            font_size_medium: 16,
+            font_size_small: 12,
+            maybe_label: Some(WidgetStyle {
+                maybe_color: Some(Color::new(0.92, 0.92, 0.92, 1.0)),
+                ..WidgetStyle::new()
+            }),
+            ..Theme::default()
+        }
+    }
+
+    /// Construct a theme from its JSON representation.
+    pub fn from_json(json_str: &str) -> Result<Theme, String> {
+        let json_object = match json::Json::from_str(json_str) {
             Ok(json_object) => json_object,
             Err(e) => return Err(format!("Failed to construct json_object from str: {}", Error::description(&e))),
         };
         let mut decoder = json::Decoder::new(json_object);
-        let theme = match Decodable::decode(&mut decoder) {
+        match Decodable::decode(&mut decoder) {
             Ok(theme) => Ok(theme),
             Err(e) => Err(format!("Failed to construct Theme from json decoder: {}", Error::description(&e))),
-        };
-        theme
+        }
     }
 
-    /// Save a theme to file.
+    /// Return the theme's JSON representation, formatted for editing by hand.
+    pub fn to_json(&self) -> Result<String, String> {
+        let mut json_string = String::new();
+        {
+            let mut encoder = json::Encoder::new_pretty(&mut json_string);
+            if let Err(e) = self.encode(&mut encoder) {
+                return Err(format!("Failed to encode Theme: {}", Error::description(&e)));
+            }
+        }
+        Ok(json_string)
+    }
+
+    /// Load a theme from a JSON file.
+    pub fn load(path: &str) -> Result<Theme, String> {
+        Theme::from_json(&try!(read_file(path)))
+    }
+
+    /// Save a theme to a JSON file.
     pub fn save(&self, path: &str) -> Result<(), String> {
-        let json_string = match json::encode(self) {
-            Ok(x) => x,
-            Err(e) => return Err(e.description().to_owned())
-        };
+        let json_string = try!(self.to_json());
         let mut file = match File::create(&Path::new(path)) {
             Ok(file) => file,
             Err(e) => return Err(format!("Failed to create a File at the given path: {}", Error::description(&e)))
@@ -85,9 +345,205 @@ impl Theme {
 
 }
 
+/// The name of each of the `Theme`'s fields in the order in which they are
+/// declared, from which the decoder takes each field's index.
+const FIELDS: &'static [&'static str] = &[
+    "name",
+    "background_color",
+    "shape_color",
+    "frame_color",
+    "frame_width",
+    "corner_radius",
+    "label_color",
+    "error_color",
+    "link_color",
+    "font_size_large",
+    "font_size_medium",
+    "font_size_small",
+    "font_default",
+    "font_regular",
+    "font_bold",
+    "font_mono",
+    "font_icon",
+    "padding",
+    "transition_duration",
+    "number_format",
+    "cursor_blink",
+    "cursor_blink_rate",
+    "cursor_width",
+    "kinetic_scrolling",
+    "scroll_friction",
+    "overscroll_limit",
+    "maybe_button",
+    "maybe_console",
+    "maybe_curve_editor",
+    "maybe_dock",
+    "maybe_drop_down_list",
+    "maybe_envelope_editor",
+    "maybe_gallery",
+    "maybe_heatmap",
+    "maybe_label",
+    "maybe_link",
+    "maybe_node_editor",
+    "maybe_num_pad",
+    "maybe_number_dialer",
+    "maybe_rating",
+    "maybe_reorderable_list",
+    "maybe_ruler",
+    "maybe_slider",
+    "maybe_sparkline",
+    "maybe_text_box",
+    "maybe_timeline",
+    "maybe_toggle",
+    "maybe_viewport",
+    "maybe_xy_pad",
+];
+
+/// Fields that were added to the `Theme` after its JSON format was first published fall back to
+/// their `Theme::default` values when missing, so that older theme files still load.
+impl Decodable for Theme {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Theme, D::Error> {
+        d.read_struct("Theme", FIELDS.len(), |d| {
+            let default = Theme::default();
+            Ok(Theme {
+                name: try!(field(d, "name")),
+                background_color: try!(field(d, "background_color")),
+                shape_color: try!(field(d, "shape_color")),
+                frame_color: try!(field(d, "frame_color")),
+                frame_width: try!(field(d, "frame_width")),
+                corner_radius: try!(field_or(d, "corner_radius", default.corner_radius)),
+                label_color: try!(field(d, "label_color")),
+                error_color: try!(field_or(d, "error_color", default.error_color)),
+                link_color: try!(field_or(d, "link_color", default.link_color)),
+                font_size_large: try!(field(d, "font_size_large")),
+                font_size_medium: try!(field(d, "font_size_medium")),
+                font_size_small: try!(field(d, "font_size_small")),
+                font_default: try!(field_or(d, "font_default", default.font_default)),
+                font_regular: try!(field_or(d, "font_regular", default.font_regular)),
+                font_bold: try!(field_or(d, "font_bold", default.font_bold)),
+                font_mono: try!(field_or(d, "font_mono", default.font_mono)),
+                font_icon: try!(field_or(d, "font_icon", default.font_icon)),
+                padding: try!(field_or(d, "padding", default.padding)),
+                transition_duration: try!(field_or(d, "transition_duration", default.transition_duration)),
+                number_format: try!(field_or(d, "number_format", default.number_format)),
+                cursor_blink: try!(field_or(d, "cursor_blink", default.cursor_blink)),
+                cursor_blink_rate: try!(field_or(d, "cursor_blink_rate", default.cursor_blink_rate)),
+                cursor_width: try!(field_or(d, "cursor_width", default.cursor_width)),
+                kinetic_scrolling: try!(field_or(d, "kinetic_scrolling", default.kinetic_scrolling)),
+                scroll_friction: try!(field_or(d, "scroll_friction", default.scroll_friction)),
+                overscroll_limit: try!(field_or(d, "overscroll_limit", default.overscroll_limit)),
+                maybe_button: try!(field(d, "maybe_button")),
+                maybe_console: try!(field(d, "maybe_console")),
+                maybe_curve_editor: try!(field(d, "maybe_curve_editor")),
+                maybe_dock: try!(field(d, "maybe_dock")),
+                maybe_drop_down_list: try!(field(d, "maybe_drop_down_list")),
+                maybe_envelope_editor: try!(field(d, "maybe_envelope_editor")),
+                maybe_gallery: try!(field(d, "maybe_gallery")),
+                maybe_heatmap: try!(field(d, "maybe_heatmap")),
+                maybe_label: try!(field(d, "maybe_label")),
+                maybe_link: try!(field(d, "maybe_link")),
+                maybe_node_editor: try!(field(d, "maybe_node_editor")),
+                maybe_num_pad: try!(field(d, "maybe_num_pad")),
+                maybe_number_dialer: try!(field(d, "maybe_number_dialer")),
+                maybe_rating: try!(field(d, "maybe_rating")),
+                maybe_reorderable_list: try!(field(d, "maybe_reorderable_list")),
+                maybe_ruler: try!(field(d, "maybe_ruler")),
+                maybe_slider: try!(field(d, "maybe_slider")),
+                maybe_sparkline: try!(field(d, "maybe_sparkline")),
+                maybe_text_box: try!(field(d, "maybe_text_box")),
+                maybe_timeline: try!(field(d, "maybe_timeline")),
+                maybe_toggle: try!(field(d, "maybe_toggle")),
+                maybe_viewport: try!(field(d, "maybe_viewport")),
+                maybe_xy_pad: try!(field(d, "maybe_xy_pad")),
+            })
+        })
+    }
+}
+
+/// The index of the named field within `FIELDS`.
+fn field_idx(name: &str) -> usize {
+    match FIELDS.iter().position(|&field| field == name) {
+        Some(idx) => idx,
+        None => panic!("The Theme field \"{}\" is missing from FIELDS.", name),
+    }
+}
+
+/// Decode the given struct field.
+fn field<D: Decoder, T: Decodable>(d: &mut D, name: &str) -> Result<T, D::Error> {
+    d.read_struct_field(name, field_idx(name), Decodable::decode)
+}
+
+/// Decode the given struct field if it is present, otherwise return the given default.
+fn field_or<D: Decoder, T: Decodable>(d: &mut D, name: &str, default: T) -> Result<T, D::Error> {
+    let maybe: Option<T> = try!(field(d, name));
+    Ok(maybe.unwrap_or(default))
+}
+
+/// Read the whole of the file at the given path into a String.
+fn read_file(path: &str) -> Result<String, String> {
+    let mut file = match File::open(&Path::new(path)) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("Failed to open file for Theme: {}", Error::description(&e))),
+    };
+    let mut contents = Vec::new();
+    if let Err(e) = ::std::io::Read::read_to_end(&mut file, &mut contents) {
+        return Err(format!("Failed to load Theme correctly: {}", Error::description(&e)));
+    }
+    match String::from_utf8(contents) {
+        Ok(contents) => Ok(contents),
+        Err(e) => Err(format!("Theme file is not valid UTF-8: {}", Error::description(&e))),
+    }
+}
+
+/// Polls a theme file for changes so that it may be reloaded while the
+/// application is running (see `Ui::watch_theme`).
+#[derive(Clone, Debug)]
+pub struct ThemeWatcher {
+    path: String,
+    maybe_prev_contents: Option<String>,
+    last_poll: f64,
+    /// The minimum number of seconds between reads of the file.
+    pub interval: f64,
+}
+
+impl ThemeWatcher {
+
+    /// Construct a watcher for the theme file at the given path.
+    pub fn new(path: &str) -> ThemeWatcher {
+        ThemeWatcher {
+            path: path.to_string(),
+            maybe_prev_contents: None,
+            last_poll: ::std::f64::NEG_INFINITY,
+            interval: 0.25,
+        }
+    }
+
+    /// The path of the watched file.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Read the file if `interval` has passed since it was last read. Returns
+    /// the newly loaded theme (or the reason it failed to load) if the file's
+    /// contents have changed since the last read, including the first read.
+    pub fn poll(&mut self, time: f64) -> Option<Result<Theme, String>> {
+        if time - self.last_poll < self.interval { return None }
+        self.last_poll = time;
+        let contents = match read_file(&self.path) {
+            Ok(contents) => contents,
+            // The file may be briefly missing while an editor saves it.
+            Err(_) => return None,
+        };
+        if self.maybe_prev_contents.as_ref() == Some(&contents) { return None }
+        let result = Theme::from_json(&contents);
+        self.maybe_prev_contents = Some(contents);
+        Some(result)
+    }
+
+}
 
 /// A trait to make it easier to generically access the UIC on different widget contexts.
-pub trait Themeable<C> {
+pub trait Themeable<C: CharacterCache> {
     /// Return a reference to the UiContext.
     fn get_theme(&self) -> &Ui<C>;
     /// Return a reference to the UiContext.
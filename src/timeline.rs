@@ -0,0 +1,521 @@
+use callback::{ NoCallback, Reactable };
+use color::Color;
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use label::{ FontId, FontSize, Labelable };
+use mouse::ButtonState::{ Down, Up };
+use point::Point;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::{ clamp, tick_precision, tick_step };
+use widget::Widget;
+
+/// The width in pixels of the grabbable area at either end of a clip.
+const EDGE_WIDTH: f64 = 6.0;
+
+/// The least spacing in pixels between labelled ticks on the ruler.
+const MIN_TICK_SPACING: f64 = 64.0;
+
+/// The factor by which each step of the mouse wheel zooms.
+const ZOOM_STEP: f64 = 1.1;
+
+/// The distance in pixels panned by each step of horizontal scrolling.
+const PAN_STEP: f64 = 20.0;
+
+/// The least and greatest number of pixels per unit of time.
+const MIN_SCALE: f64 = 1.0e-3;
+const MAX_SCALE: f64 = 1.0e4;
+
+/// A block of time on one of the timeline's tracks, i.e. an audio region or
+/// an animation clip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip {
+    /// The index of the track on which the clip lies.
+    pub track: usize,
+    pub start: f64,
+    pub duration: f64,
+    pub label: String,
+}
+
+impl Clip {
+    /// A clip on the given track, beginning at `start`.
+    pub fn new(track: usize, start: f64, duration: f64, label: &str) -> Clip {
+        Clip { track: track, start: start, duration: duration, label: label.to_string() }
+    }
+
+    /// The time at which the clip ends.
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
+/// The part of the timeline that is visible.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct View {
+    /// The time at the left edge of the tracks.
+    pub start: f64,
+    /// The number of pixels per unit of time. Zero until the view is first
+    /// fitted to the clips.
+    pub scale: f64,
+}
+
+/// Either end of a clip.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Edge {
+    Start,
+    End,
+}
+
+/// The elements of the Timeline beneath the mouse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Element {
+    /// The time ruler above the tracks.
+    Ruler,
+    /// The background of the tracks.
+    Tracks,
+    /// The clip at the given index.
+    Clip(usize),
+    /// An end of the clip at the given index.
+    ClipEdge(usize, Edge),
+}
+
+/// What the mouse is doing to the Timeline.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    Highlighted(Element),
+    /// The clip is being moved, having been grabbed at the given time after
+    /// its start.
+    Moving(usize, f64),
+    /// An end of the clip is being dragged.
+    Resizing(usize, Edge),
+    /// The view is being panned, with the mouse last at the given x.
+    Panning(f64),
+    /// The ruler is being pressed to move the playhead.
+    Seeking,
+}
+
+/// Represents the state of the Timeline widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    pub view: View,
+    pub interaction: Interaction,
+}
+
+widget_fns!(Timeline, State, Widget::Timeline(State {
+    view: View { start: 0.0, scale: 0.0 },
+    interaction: Interaction::Normal,
+}));
+
+/// What happened to the Timeline, returned by `Timeline::react` and given
+/// to its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The clip at the given index was pressed.
+    Selected(usize),
+    /// The background of the tracks was pressed.
+    Deselected,
+    /// The clip is being moved to a new time or track.
+    Moved(usize),
+    /// One end of the clip is being dragged.
+    Resized(usize),
+    /// The clip was released after being moved or resized.
+    Released(usize),
+    /// The ruler was pressed or dragged at the given time.
+    Seek(f64),
+}
+
+/// A horizontally zoomable and pannable timeline of clips on multiple
+/// tracks, beneath a time ruler. Clips are moved by dragging them, even
+/// onto another track, and resized by dragging either end. The mouse
+/// wheel zooms about the mouse, horizontal scrolling or dragging the
+/// background pans, and pressing the ruler seeks. The clips are changed in
+/// place, and what happened is reported via `on_event` or `react`. A label
+/// is drawn in the corner above the track names.
+pub struct Timeline<'a> {
+    ui_id: UIID,
+    tracks: &'a [String],
+    clips: &'a mut Vec<Clip>,
+    maybe_selected: Option<usize>,
+    maybe_playhead: Option<f64>,
+    maybe_snap: Option<f64>,
+    maybe_clip_color: Option<Color>,
+    track_height: f64,
+    header_width: f64,
+    maybe_label: Option<&'a str>,
+    pos: Point,
+    dim: Dimensions,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a> Timeline<'a> {
+
+    /// Create a timeline context to be built upon, with a track for each of
+    /// the given track names.
+    pub fn new(ui_id: UIID, tracks: &'a [String], clips: &'a mut Vec<Clip>) -> Timeline<'a> {
+        Timeline {
+            ui_id: ui_id,
+            tracks: tracks,
+            clips: clips,
+            maybe_selected: None,
+            maybe_playhead: None,
+            maybe_snap: None,
+            maybe_clip_color: None,
+            track_height: 32.0,
+            header_width: 80.0,
+            maybe_label: None,
+            pos: [0.0, 0.0],
+            dim: [512.0, 160.0],
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// Highlight the clip at the given index as selected.
+    pub fn selected(self, maybe_idx: Option<usize>) -> Timeline<'a> {
+        Timeline { maybe_selected: maybe_idx, ..self }
+    }
+
+    /// Draw the playhead at the given time.
+    pub fn playhead(self, time: f64) -> Timeline<'a> {
+        Timeline { maybe_playhead: Some(time), ..self }
+    }
+
+    /// Snap the times to which clips are moved and resized to multiples of
+    /// the given step.
+    pub fn snap(self, step: f64) -> Timeline<'a> {
+        Timeline { maybe_snap: Some(step), ..self }
+    }
+
+    /// The color of the clips. Defaults to the frame color.
+    pub fn clip_color(self, color: Color) -> Timeline<'a> {
+        Timeline { maybe_clip_color: Some(color), ..self }
+    }
+
+    /// The height of each track.
+    pub fn track_height(self, height: f64) -> Timeline<'a> {
+        Timeline { track_height: height, ..self }
+    }
+
+    /// The width of the column of track names to the left of the tracks.
+    pub fn header_width(self, width: f64) -> Timeline<'a> {
+        Timeline { header_width: width, ..self }
+    }
+
+}
+
+composite_builder_impls!(Timeline<'a>);
+
+impl<'a> Reactable<'a, Event> for Timeline<'a> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a> Labelable<'a> for Timeline<'a> {
+    fn label(mut self, text: &'a str) -> Self {
+        self.maybe_label = Some(text);
+        self
+    }
+
+    fn label_color(mut self, color: Color) -> Self {
+        self.style.maybe_label_color = Some(color);
+        self
+    }
+
+    fn label_font_size(mut self, size: FontSize) -> Self {
+        self.style.maybe_label_font_size = Some(size);
+        self
+    }
+
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
+        self
+    }
+}
+
+impl<'a> ::draw::Drawable for Timeline<'a> {
+    fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics);
+    }
+}
+
+impl<'a> Timeline<'a> {
+
+    /// Draw the timeline, returning what happened to it.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+    /// Snap the given time to the snapping step, if there is one.
+    fn snapped(&self, time: f64) -> f64 {
+        match self.maybe_snap {
+            Some(step) if step > 0.0 => (time / step).round() * step,
+            _ => time,
+        }
+    }
+
+    /// Update and draw the timeline, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_timeline, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let frame_w = style.frame;
+        let font_size = style.label_font_size;
+        let padding = ui.theme.padding;
+
+        // The header column on the left, the ruler along the top and the tracks beneath.
+        let inner_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let inner_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+        let header_w = self.header_width.min(inner_dim[0]);
+        let ruler_h = (font_size as f64 + padding * 2.0).min(inner_dim[1]);
+        let lanes_pos = [inner_pos[0] + header_w, inner_pos[1] + ruler_h];
+        let lanes_dim = [inner_dim[0] - header_w, inner_dim[1] - ruler_h];
+        let ruler_pos = [lanes_pos[0], inner_pos[1]];
+        let ruler_dim = [lanes_dim[0], ruler_h];
+        let track_h = self.track_height;
+        let num_tracks = self.tracks.len();
+
+        // The view is first fitted to the clips.
+        let mut view = state.view;
+        if view.scale <= 0.0 {
+            let end = self.clips.iter().fold(1.0f64, |end, clip| end.max(clip.end()));
+            view = View { start: 0.0, scale: clamp(lanes_dim[0] / end, MIN_SCALE, MAX_SCALE) };
+        }
+
+        // The wheel zooms about the time beneath the mouse and horizontal
+        // scrolling pans.
+        let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
+        if is_over && (mouse.scroll[0] != 0.0 || mouse.scroll[1] != 0.0) {
+            let mouse_time = view.start + (mouse.pos[0] - lanes_pos[0]) / view.scale;
+            let scale = clamp(view.scale * ZOOM_STEP.powf(mouse.scroll[1]), MIN_SCALE, MAX_SCALE);
+            let start = mouse_time - (mouse.pos[0] - lanes_pos[0]) / scale;
+            view = View { start: (start - mouse.scroll[0] * PAN_STEP / scale).max(0.0), scale: scale };
+        }
+        // Panning applies from the next frame, as the view is borrowed below.
+        let mut new_view = view;
+        let time_at = |x: f64| view.start + (x - lanes_pos[0]) / view.scale;
+        let x_at = |time: f64| lanes_pos[0] + (time - view.start) * view.scale;
+        let clip_rect = |clip: &Clip| -> (Point, Dimensions) {
+            ([x_at(clip.start), lanes_pos[1] + clip.track as f64 * track_h + 1.0],
+             [clip.duration * view.scale, track_h - 2.0])
+        };
+
+        // The topmost clip beneath the mouse is the last drawn.
+        let is_over_elem = match is_over {
+            false => None,
+            true if rectangle::is_over(ruler_pos, mouse.pos, ruler_dim) => Some(Element::Ruler),
+            true if rectangle::is_over(lanes_pos, mouse.pos, lanes_dim) => {
+                let maybe_clip = self.clips.iter().enumerate().rev().find(|&(_, clip)| {
+                    let (pos, dim) = clip_rect(clip);
+                    clip.track < num_tracks && rectangle::is_over(pos, mouse.pos, dim)
+                }).map(|(i, clip)| {
+                    let (pos, dim) = clip_rect(clip);
+                    let has_edges = dim[0] > EDGE_WIDTH * 3.0;
+                    if has_edges && mouse.pos[0] < pos[0] + EDGE_WIDTH { Element::ClipEdge(i, Edge::Start) }
+                    else if has_edges && mouse.pos[0] > pos[0] + dim[0] - EDGE_WIDTH { Element::ClipEdge(i, Edge::End) }
+                    else { Element::Clip(i) }
+                });
+                Some(maybe_clip.unwrap_or(Element::Tracks))
+            },
+            true => None,
+        };
+
+        // Apply the mouse to the clips and the view.
+        let mouse_time = time_at(mouse.pos[0]);
+        let len = self.clips.len();
+        let mut maybe_event = None;
+        let interaction = match (state.interaction, mouse.left) {
+            (Interaction::Moving(idx, _), Up) | (Interaction::Resizing(idx, _), Up) => {
+                if idx < len { maybe_event = Some(Event::Released(idx)); }
+                is_over_elem.map_or(Interaction::Normal, Interaction::Highlighted)
+            },
+            (_, Up) => is_over_elem.map_or(Interaction::Normal, Interaction::Highlighted),
+            (Interaction::Highlighted(elem), Down) => match elem {
+                Element::Clip(idx) if idx < len => {
+                    maybe_event = Some(Event::Selected(idx));
+                    Interaction::Moving(idx, mouse_time - self.clips[idx].start)
+                },
+                Element::ClipEdge(idx, edge) if idx < len => {
+                    maybe_event = Some(Event::Selected(idx));
+                    Interaction::Resizing(idx, edge)
+                },
+                Element::Tracks => {
+                    maybe_event = Some(Event::Deselected);
+                    Interaction::Panning(mouse.pos[0])
+                },
+                Element::Ruler => {
+                    maybe_event = Some(Event::Seek(mouse_time.max(0.0)));
+                    Interaction::Seeking
+                },
+                _ => Interaction::Normal,
+            },
+            (Interaction::Moving(idx, grab), Down) if idx < len => {
+                let start = self.snapped(mouse_time - grab).max(0.0);
+                let track = match num_tracks {
+                    0 => 0,
+                    _ => clamp(((mouse.pos[1] - lanes_pos[1]) / track_h).floor(), 0.0,
+                               num_tracks as f64 - 1.0) as usize,
+                };
+                if self.clips[idx].start != start || self.clips[idx].track != track {
+                    self.clips[idx].start = start;
+                    self.clips[idx].track = track;
+                    maybe_event = Some(Event::Moved(idx));
+                }
+                Interaction::Moving(idx, grab)
+            },
+            (Interaction::Resizing(idx, edge), Down) if idx < len => {
+                // A clip can't be resized to less than a pixel.
+                let min_duration = 1.0 / view.scale;
+                let (start, end) = (self.clips[idx].start, self.clips[idx].end());
+                let (new_start, new_end) = match edge {
+                    Edge::Start => (clamp(self.snapped(mouse_time), 0.0, end - min_duration), end),
+                    Edge::End => (start, self.snapped(mouse_time).max(start + min_duration)),
+                };
+                if new_start != start || new_end != end {
+                    self.clips[idx].start = new_start;
+                    self.clips[idx].duration = new_end - new_start;
+                    maybe_event = Some(Event::Resized(idx));
+                }
+                Interaction::Resizing(idx, edge)
+            },
+            (Interaction::Panning(last_x), Down) => {
+                new_view.start = (view.start - (mouse.pos[0] - last_x) / view.scale).max(0.0);
+                Interaction::Panning(mouse.pos[0])
+            },
+            (Interaction::Seeking, Down) => {
+                if ui.mouse_moved() {
+                    maybe_event = Some(Event::Seek(time_at(mouse.pos[0]).max(0.0)));
+                }
+                Interaction::Seeking
+            },
+            (_, Down) => Interaction::Normal,
+        };
+        let new_state = State { view: new_view, interaction: interaction };
+
+        match interaction {
+            Interaction::Highlighted(Element::ClipEdge(_, _)) | Interaction::Resizing(_, _) =>
+                ui.set_mouse_cursor(MouseCursor::ResizeHorizontal),
+            Interaction::Highlighted(Element::Clip(_)) | Interaction::Moving(_, _) |
+            Interaction::Panning(_) => ui.set_mouse_cursor(MouseCursor::Move),
+            _ => (),
+        }
+
+        // Draw the background, with a line between each track.
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        let fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
+        let line_color = style.color.plain_contrast();
+        let divider_color = line_color.fade(0.15);
+        ui.push_clip(inner_pos, inner_dim);
+        for i in 0..num_tracks {
+            let y = lanes_pos[1] + i as f64 * track_h;
+            let text_pos = [inner_pos[0] + padding, y + (track_h - font_size as f64) / 2.0];
+            ui.draw_text(graphics, text_pos, style.font, font_size, style.label_color, &self.tracks[i]);
+            ui.draw_line(graphics, [inner_pos[0], y + track_h, inner_pos[0] + inner_dim[0], y + track_h],
+                         1.0, false, divider_color);
+        }
+        ui.draw_line(graphics, [lanes_pos[0], inner_pos[1], lanes_pos[0], inner_pos[1] + inner_dim[1]],
+                     1.0, false, divider_color);
+        ui.pop_clip();
+        if let Some(label) = self.maybe_label {
+            ui.push_clip(inner_pos, [header_w, ruler_h]);
+            let text_pos = [inner_pos[0] + padding, inner_pos[1] + (ruler_h - font_size as f64) / 2.0];
+            ui.draw_text(graphics, text_pos, style.font, font_size, style.label_color, label);
+            ui.pop_clip();
+        }
+
+        // Draw the ruler, labelling each step with minor ticks between.
+        let ruler_color = rectangle::State::Highlighted.color(style.color);
+        ui.draw_rectangle(graphics, ruler_pos, ruler_dim, ruler_color);
+        ui.push_clip(ruler_pos, ruler_dim);
+        let step = tick_step(MIN_TICK_SPACING, view.scale);
+        let precision = tick_precision(step);
+        let ruler_bottom = ruler_pos[1] + ruler_dim[1];
+        let first_tick = (view.start / step).floor() as i64;
+        let last_tick = (time_at(ruler_pos[0] + ruler_dim[0]) / step).ceil() as i64;
+        for tick in first_tick..last_tick + 1 {
+            let time = tick as f64 * step;
+            let x = x_at(time);
+            ui.draw_line(graphics, [x, ruler_pos[1], x, ruler_bottom], 1.0, false, line_color);
+            let text = ui.theme.number_format.format(time, precision);
+            ui.draw_text(graphics, [x + 2.0, ruler_pos[1] + padding / 2.0], style.font,
+                         font_size, style.label_color, &text);
+            for minor in 1..5 {
+                let x = x_at(time + step * minor as f64 / 5.0);
+                ui.draw_line(graphics, [x, ruler_bottom - ruler_h / 4.0, x, ruler_bottom], 1.0, false, line_color);
+            }
+        }
+        ui.pop_clip();
+
+        // Draw the clips, with their labels clipped to them.
+        let clip_color = self.maybe_clip_color.unwrap_or(style.frame_color);
+        ui.push_clip(lanes_pos, lanes_dim);
+        for (i, clip) in self.clips.iter().enumerate() {
+            if clip.track >= num_tracks { continue }
+            let (pos, dim) = clip_rect(clip);
+            if pos[0] + dim[0] < lanes_pos[0] || pos[0] > lanes_pos[0] + lanes_dim[0] { continue }
+            let rect_state = match interaction {
+                Interaction::Moving(idx, _) | Interaction::Resizing(idx, _) if idx == i =>
+                    rectangle::State::Clicked,
+                _ if self.maybe_selected == Some(i) => rectangle::State::Clicked,
+                Interaction::Highlighted(Element::Clip(idx)) |
+                Interaction::Highlighted(Element::ClipEdge(idx, _)) if idx == i =>
+                    rectangle::State::Highlighted,
+                _ => rectangle::State::Normal,
+            };
+            let color = rect_state.color(clip_color);
+            rectangle::draw(ui, graphics, rectangle::State::Normal, pos, dim,
+                            Some((1.0, line_color.fade(0.5))), style.corner_radius, color);
+            ui.push_clip(pos, dim);
+            let text_pos = [pos[0] + padding, pos[1] + (dim[1] - font_size as f64) / 2.0];
+            ui.draw_text(graphics, text_pos, style.font, font_size, color.plain_contrast(), &clip.label);
+            ui.pop_clip();
+        }
+        ui.pop_clip();
+
+        // Draw the playhead across the ruler and tracks.
+        if let Some(time) = self.maybe_playhead {
+            let x = x_at(time);
+            if x >= lanes_pos[0] && x <= lanes_pos[0] + lanes_dim[0] {
+                ui.draw_line(graphics, [x, ruler_pos[1], x, lanes_pos[1] + lanes_dim[1]],
+                             2.0, false, line_color);
+            }
+        }
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Timeline(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
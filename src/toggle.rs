@@ -1,24 +1,29 @@
-use callback::Callable;
-use frame::Frameable;
-use color::{ Color, Colorable };
-use label::{ FontSize, Labelable };
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use label::{ FontId, FontSize, Labelable };
 use dimensions::Dimensions;
+use mouse;
 use mouse::Mouse;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use property::{ Bindable, Property };
+use theme;
+use theme::WidgetStyle;
 use rectangle;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use animation;
+use history::Value;
 use ui::{ UIID, Ui };
 use widget::Widget;
 
 /// Represents the state of the Toggle widget.
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum State {
     Normal,
     Highlighted,
     Clicked,
+    /// Pressed with the right or middle button.
+    AltClicked(mouse::Button),
 }
 
 impl State {
@@ -27,25 +32,72 @@ impl State {
         match self {
             &State::Normal => rectangle::State::Normal,
             &State::Highlighted => rectangle::State::Highlighted,
-            &State::Clicked => rectangle::State::Clicked,
+            &State::Clicked | &State::AltClicked(_) => rectangle::State::Clicked,
         }
     }
 }
 
 widget_fns!(Toggle, State, Widget::Toggle(State::Normal));
 
+/// How the Toggle is drawn.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Style {
+    /// A rectangle whose color is dimmed while the toggle is off.
+    Flat,
+    /// A pill-shaped track with a knob that slides to the right while the
+    /// toggle is on, as in a settings screen. The label is drawn to the
+    /// right of the track.
+    Switch,
+}
+
+/// The knob of a switch, which slides smoothly between its off (`0.0`) and
+/// on (`1.0`) positions. Kept as the toggle's widget memory.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Knob {
+    from: f64,
+    to: f64,
+    /// The time at which the knob began sliding.
+    start: f64,
+}
+
+impl Knob {
+    /// The position of the knob at the given time.
+    fn position_at(&self, time: f64, duration: f64) -> f64 {
+        let perc = animation::ease_in_out(animation::progress(self.start, duration, time));
+        self.from + (self.to - self.from) * perc
+    }
+}
+
+/// What happened to the Toggle, returned by `Toggle::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The mouse was pressed over the toggle.
+    Pressed,
+    /// The mouse was released over the toggle after pressing it, giving the
+    /// toggle's new value.
+    Toggled(bool),
+    /// The right or middle button was released over the toggle after
+    /// pressing it with the same, i.e. to open a context menu. The value is
+    /// left as it is.
+    AltClicked(mouse::Button),
+}
+
 /// Check the current state of the button.
 fn get_new_state(is_over: bool,
                  prev: State,
                  mouse: Mouse) -> State {
     use mouse::ButtonState::{Down, Up};
-    use self::State::{Normal, Highlighted, Clicked};
-    match (is_over, prev, mouse.left) {
-        (true,  Normal,  Down) => Normal,
-        (true,  _,       Down) => Clicked,
-        (true,  _,       Up)   => Highlighted,
-        (false, Clicked, Down) => Clicked,
-        _                      => Normal,
+    use self::State::{Normal, Highlighted, Clicked, AltClicked};
+    match (is_over, prev, mouse.left, mouse.alt_button_down()) {
+        (true,  Normal,        Down, _)       => Normal,
+        (true,  Normal,        Up,   Some(_)) => Normal,
+        (_,     AltClicked(b), Up,   _) if mouse.button(b) == Down => AltClicked(b),
+        (true,  Highlighted,   Up,   Some(b)) => AltClicked(b),
+        (true,  _,             Down, _)       => Clicked,
+        (true,  _,             Up,   _)       => Highlighted,
+        (false, Clicked,       Down, _)       => Clicked,
+        _                                     => Normal,
     }
 }
 
@@ -55,13 +107,12 @@ pub struct Toggle<'a, F> {
     pos: Point,
     dim: Dimensions,
     maybe_callback: Option<F>,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    maybe_property: Option<Property<bool>>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
     value: bool,
+    variant: Style,
 }
 
 impl<'a, F> Toggle<'a, F> {
@@ -73,39 +124,42 @@ impl<'a, F> Toggle<'a, F> {
             pos: [0.0, 0.0],
             dim: [64.0, 64.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            maybe_property: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
             value: value,
+            variant: Style::Flat,
         }
     }
 
+    /// Set how the toggle is drawn.
+    pub fn style(self, style: Style) -> Toggle<'a, F> {
+        Toggle { variant: style, ..self }
+    }
+
 }
 
-impl<'a, F> Colorable for Toggle<'a, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
+composite_builder_impls!(Toggle<'a, F>);
+
+impl<'a, F> Callable<F> for Toggle<'a, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
         self
     }
 }
 
-impl<'a, F> Frameable for Toggle<'a, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
-        self
-    }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
+impl<'a, F> Bindable<bool> for Toggle<'a, F> {
+    fn bind(mut self, property: &Property<bool>) -> Self {
+        self.value = property.get();
+        self.maybe_property = Some(property.clone());
         self
     }
 }
 
-impl<'a, F> Callable<F> for Toggle<'a, F> {
-    fn callback(mut self, cb: F) -> Self {
-        self.maybe_callback = Some(cb);
+impl<'a, F> Reactable<'a, Event> for Toggle<'a, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
@@ -118,35 +172,114 @@ impl<'a, F> Labelable<'a> for Toggle<'a, F>
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
         self
     }
-}
 
-impl<'a, F> Positionable for Toggle<'a, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, F> Shapeable for Toggle<'a, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, F> ::draw::Drawable for Toggle<'a, F> where F: FnMut(bool) + 'a {
     fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
+        if let Some(Event::Toggled(new_value)) = self.update(ui, graphics) {
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(new_value) }
+        }
+    }
+}
+
+impl<'a> Toggle<'a, NoCallback> {
+
+    /// Draw the toggle, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the click needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+/// Draw the toggle as a switch whose knob slides towards the given value.
+fn draw_switch<B, C>(
+    ui: &mut Ui<C>,
+    graphics: &mut B,
+    ui_id: UIID,
+    pos: Point,
+    dim: Dimensions,
+    value: bool,
+    style: &theme::Style,
+    state: State,
+    maybe_label: Option<&str>
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    let time = ui.time();
+    let duration = ui.theme.transition_duration;
+    let target = if value { 1.0 } else { 0.0 };
+    let knob = match ui.widget_memory::<Knob>(ui_id).cloned() {
+        Some(knob) if knob.to == target => knob,
+        Some(knob) => Knob { from: knob.position_at(time, duration), to: target, start: time },
+        None => Knob { from: target, to: target, start: time },
+    };
+    ui.set_widget_memory(ui_id, knob);
+    let perc = knob.position_at(time, duration);
+    // The knob moves without the toggle's state changing.
+    if perc != target {
+        ui.mark_damaged(pos, dim);
+    }
+
+    let off_color = style.color * Color::new(0.1, 0.1, 0.1, 1.0);
+    let track_color = state.as_rectangle_state().color(off_color.interpolate(style.color, perc as f32));
+    let radius = dim[1] / 2.0;
+    let maybe_frame = match style.frame > 0.0 {
+        true => Some((style.frame, style.frame_color)),
+        false => None,
+    };
+    if let Some(shadow) = style.maybe_shadow {
+        rectangle::draw_shadow(ui, graphics, pos, dim, radius, shadow);
+    }
+    rectangle::draw(ui, graphics, rectangle::State::Normal, pos, dim, maybe_frame, radius, track_color);
+
+    let inset = style.frame + dim[1] * 0.1;
+    let knob_d = (dim[1] - inset * 2.0).max(0.0);
+    let knob_x = pos[0] + inset + (dim[0] - knob_d - inset * 2.0).max(0.0) * perc;
+    ui.draw_ellipse(graphics, [knob_x, pos[1] + inset], [knob_d, knob_d], track_color.plain_contrast());
+
+    if let Some(text) = maybe_label {
+        let size = style.label_font_size;
+        let text_pos = [pos[0] + dim[0] + ui.theme.padding, pos[1] + (dim[1] - size as f64) / 2.0];
+        ui.draw_text(graphics, text_pos, style.font, size, style.label_color, text);
+    }
+}
+
+impl<'a, F> Toggle<'a, F> {
+
+    /// Update and draw the toggle, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_toggle, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let color = style.color;
         let color = match self.value {
             true => color,
             false => color * Color::new(0.1, 0.1, 0.1, 1.0)
@@ -155,40 +288,79 @@ impl<'a, F> ::draw::Drawable for Toggle<'a, F> where F: FnMut(bool) + 'a {
         let mouse = ui.get_mouse_state();
         let is_over = rectangle::is_over(self.pos, mouse.pos, self.dim);
         let new_state = get_new_state(is_over, state, mouse);
-        let rect_state = new_state.as_rectangle_state();
-        match self.maybe_callback {
-            Some(ref mut callback) => {
-                match (is_over, state, new_state) {
-                    (true, State::Clicked, State::Highlighted) =>
-                        (*callback)(match self.value { true => false, false => true }),
-                    _ => (),
-                }
-            }, None => (),
-        }
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
-        let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
-            false => None,
+        let maybe_event = match (is_over, state, new_state) {
+            (true, State::Highlighted, State::Clicked) => Some(Event::Pressed),
+            (true, State::Clicked, State::Highlighted) => {
+                ui.record_change(self.ui_id, Value::Bool(self.value), Value::Bool(!self.value));
+                Some(Event::Toggled(!self.value))
+            },
+            (true, State::AltClicked(b), State::Highlighted) => Some(Event::AltClicked(b)),
+            _ => None,
         };
-        match self.maybe_label {
-            None => {
-                rectangle::draw(
-                    ui.win_w, ui.win_h, graphics, rect_state, self.pos,
-                    self.dim, maybe_frame, color
-                )
+        // A change that was undone or redone is re-fired as though clicked.
+        let maybe_event = match ui.take_restored(self.ui_id) {
+            Some(Value::Bool(value)) => Some(Event::Toggled(value)),
+            _ => maybe_event,
+        };
+        match self.variant {
+            Style::Flat => {
+                // The state's color is applied via the Ui's color transition,
+                // so the rectangle itself is drawn in its Normal state.
+                let color = ui.transition_color(self.ui_id, new_state.as_rectangle_state().color(color));
+                let rect_state = rectangle::State::Normal;
+                let frame_w = style.frame;
+                let maybe_frame = match frame_w > 0.0 {
+                    true => Some((frame_w, style.frame_color)),
+                    false => None,
+                };
+                let maybe_gradient = match self.value {
+                    true => style.maybe_gradient,
+                    false => style.maybe_gradient.map(|g| g.map(|c| c * Color::new(0.1, 0.1, 0.1, 1.0))),
+                };
+                let fill = rectangle::fill(color, maybe_gradient, style.maybe_background_image,
+                                           new_state.as_rectangle_state());
+                if let Some(shadow) = style.maybe_shadow {
+                    rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+                }
+                match self.maybe_label {
+                    None => {
+                        rectangle::draw(
+                            ui, graphics, rect_state, self.pos,
+                            self.dim, maybe_frame, style.corner_radius, fill
+                        )
+                    },
+                    Some(text) => {
+                        let text_color = style.label_color;
+                        let size = style.label_font_size;
+                        rectangle::draw_with_centered_label(
+                            ui, graphics, rect_state,
+                            self.pos, self.dim, maybe_frame, style.corner_radius, fill,
+                            text, style.font, size, text_color
+                        )
+                    },
+                }
             },
-            Some(text) => {
-                let text_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
-                let size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
-                rectangle::draw_with_centered_label(
-                    ui.win_w, ui.win_h, graphics, ui, rect_state,
-                    self.pos, self.dim, maybe_frame, color,
-                    text, size, text_color
-                )
+            Style::Switch => {
+                // The knob heads for the new value as soon as the toggle is clicked.
+                let value = match maybe_event {
+                    Some(Event::Toggled(value)) => value,
+                    _ => self.value,
+                };
+                draw_switch(ui, graphics, self.ui_id, self.pos, self.dim, value, &style,
+                            new_state, self.maybe_label);
             },
         }
 
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::Toggle(new_state), self.pos, self.dim);
 
+        if let (Some(Event::Toggled(value)), Some(property)) = (maybe_event, self.maybe_property.as_ref()) {
+            property.set(value);
+        }
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
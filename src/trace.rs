@@ -0,0 +1,19 @@
+//! An optional hook through which the `Ui` reports how it interprets input
+//! (see `Ui::set_tracer`), i.e. for finding out why a button never fires.
+
+use event::Input;
+use ui::UIID;
+use widget::Widget;
+
+/// Something that happened within the `Ui`, passed to its tracer.
+#[derive(Clone, Debug)]
+pub enum TraceEvent {
+    /// The `Ui` received an input.
+    Input(Input),
+    /// The state of the widget with the given UIID changed as it was set.
+    Transition {
+        ui_id: UIID,
+        from: Widget,
+        to: Widget,
+    },
+}
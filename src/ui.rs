@@ -1,10 +1,32 @@
-use std::iter::repeat;
+use animation::{ ColorTransition, ThemeTransition };
+use arena::ScratchArena;
+use std::any::Any;
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::{ HashMap, HashSet };
+use std::collections::hash_map::Entry;
+use std::hash::{ Hasher, SipHasher };
+use std::io::{ Read, Write };
+use std::rc::Rc;
 use Color;
+use clipboard::{ Clipboard, LocalClipboard };
+use clock_ticks::precise_time_s;
+use clock::Clock;
+use cursor::MouseCursor;
+use color::{ Gradient, GradientDirection };
+use deferred::Deferred;
+use drag::{ Drag, Preview };
+use dock::DockLayout;
 use dimensions::Dimensions;
+use event::{ Input, Record };
+use glyph_run::{ GlyphRun, KerningTable };
+use image::{ ImageId, ImageMap };
 use graphics;
 use graphics::Graphics;
 use graphics::character::{ Character, CharacterCache };
-use label::FontSize;
+use history::{ Change, History, Value };
+use label::{ FontId, FontSize };
+use locale::Locale;
 use mouse::{
     ButtonState,
     Mouse,
@@ -13,13 +35,21 @@ use piston::input;
 use piston::event::{
     GenericEvent,
     MouseCursorEvent,
+    MouseScrollEvent,
     PressEvent,
     ReleaseEvent,
     RenderEvent,
     TextEvent,
 };
 use point::Point;
-use theme::Theme;
+use persist::{ self, SavedState };
+use polygon;
+use primitive::Primitive;
+use rectangle;
+use shortcuts::{ ActionId, Shortcut, Shortcuts };
+use stats::FrameStats;
+use theme::{ Theme, ThemeWatcher };
+use trace::TraceEvent;
 use widget;
 use widget::Widget;
 
@@ -28,23 +58,233 @@ use widget::Widget;
 /// track of it's state.
 pub type UIID = u64;
 
+/// Glyph runs grouped by font and size and keyed by their text, so that they
+/// may be looked up by a `&str` without allocating.
+type GlyphRuns = HashMap<(FontId, FontSize), HashMap<String, Rc<GlyphRun>>>;
+
+/// The width of each line of text measured, keyed by its font and size.
+type TextWidths = HashMap<(FontId, FontSize), HashMap<String, f64>>;
+
+/// The greatest number of text widths that are remembered before the cache
+/// is emptied, so that text that changes every frame doesn't grow it forever.
+const MAX_CACHED_TEXT_WIDTHS: usize = 4096;
+
+/// The maximum number of bands with which a gradient is rendered.
+const MAX_GRADIENT_BANDS: usize = 128;
+
+/// The position given for the mouse while it is over an area captured by
+/// another widget, far outside of any window.
+const OUTSIDE_WINDOW: Point = [-1.0e6, -1.0e6];
+
+/// The seconds of mouse movement over which the mouse's velocity is measured.
+const MOUSE_HISTORY_DURATION: f64 = 0.1;
+
+/// The number of vertex coordinates passed to the backend at a time when
+/// rendering a batch of rectangles (a whole number of rectangles, each of
+/// which is two triangles).
+const RECTANGLE_BATCH_CHUNK_LEN: usize = graphics::BACK_END_MAX_VERTEX_COUNT / 6 * 12;
+
+/// A problem detected while updating the widgets. Rather than panicking, the
+/// `Ui` recovers from these and lists them in `Ui::diagnostics`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Diagnostic {
+    /// A widget was requested with a UIID that already belongs to a widget of
+    /// a different kind. The stored state was reset to the requested kind's
+    /// default (check that no UIID is used by more than one widget).
+    VariantConflict {
+        ui_id: UIID,
+        existing: &'static str,
+        requested: &'static str,
+    },
+    /// The same UIID was set twice within a single frame, first by a widget
+    /// of kind `first` and then by one of kind `second`. Only detected in
+    /// debug builds.
+    DuplicateUIID {
+        ui_id: UIID,
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+/// Solid rectangles of a single color that may be rendered in one draw call.
+struct RectangleBatch {
+    color: Color,
+    /// The rectangle to which the batch is clipped.
+    maybe_clip: Option<(Point, Dimensions)>,
+    rects: Vec<[f64; 4]>,
+}
+
 /// UiContext retains the state of all widgets and
 /// data relevant to the draw_widget functions.
-pub struct Ui<C> {
-    data: Vec<(Widget, widget::Placing)>,
+///
+/// Each window should have its own `Ui`, which should be passed only the
+/// events that belong to that window. Several `Ui`s may share a single
+/// glyph cache (see `Ui::new_shared`), and their fonts and theme as well
+/// (see `Ui::new_sharing`).
+pub struct Ui<C>
+    where
+        C: CharacterCache
+{
+    /// The state and placing of each widget, keyed by UIID so that sparse
+    /// and large UIIDs cost no more than small ones.
+    data: HashMap<UIID, (Widget, widget::Placing)>,
     pub theme: Theme,
+    /// The textures that widgets may display, referred to by `ImageId`.
+    pub image_map: ImageMap<<C as CharacterCache>::Texture>,
     pub mouse: Mouse,
     pub keys_just_pressed: Vec<input::keyboard::Key>,
     pub keys_just_released: Vec<input::keyboard::Key>,
+    /// The keys that are currently held down.
+    keys_held: Vec<input::keyboard::Key>,
     pub text_just_entered: Vec<String>,
-    glyph_cache: C,
+    /// The glyph cache for each font, indexed by `FontId`. The first is the
+    /// glyph cache given when the `Ui` was constructed.
+    fonts: Vec<Rc<RefCell<C>>>,
     prev_event_was_render: bool,
-    /// Window width.
+    /// Window width in DPI-independent units.
     pub win_w: f64,
-    /// Window height.
+    /// Window height in DPI-independent units.
     pub win_h: f64,
+    /// The ratio of physical pixels to DPI-independent units.
+    dpi_factor: f64,
+    /// The factor by which the whole interface is zoomed.
+    scale: f64,
     /// The UIID of the widget drawn previously.
     prev_uiid: u64,
+    /// The color transitions of widgets currently animating between states.
+    color_transitions: HashMap<UIID, ColorTransition>,
+    /// The UIIDs of the widgets set during the current frame in the order they were set.
+    updated_widgets: Vec<UIID>,
+    /// The UIIDs of the widgets set during the previous frame.
+    prev_updated_widgets: Vec<UIID>,
+    /// The UIIDs of widgets that were set during the last frame but not the one before.
+    appeared_widgets: Vec<UIID>,
+    /// The UIIDs of widgets that were set during the frame before last but not the last.
+    disappeared_widgets: Vec<UIID>,
+    /// Arbitrary state stored by custom widgets alongside the built-in widget state.
+    user_states: HashMap<UIID, Box<Any>>,
+    /// State kept between frames by widgets for their own use. This is kept
+    /// apart from the user state so that neither may clobber the other.
+    widget_memory: HashMap<UIID, Box<Any>>,
+    /// Whether or not the debug overlay should be drawn.
+    debug: bool,
+    /// The recording of inputs, if recording is in progress.
+    maybe_record: Option<Record>,
+    /// The clock consulted for the current time, which may be paused, set
+    /// or mocked.
+    pub clock: Clock,
+    /// The primitives drawn so far, if the Ui is capturing primitives.
+    maybe_primitives: Option<Vec<Primitive>>,
+    /// The problems detected so far during the current frame.
+    diagnostics: Vec<Diagnostic>,
+    /// The kind of each widget set so far during the current frame (debug builds only).
+    frame_widget_kinds: HashMap<UIID, &'static str>,
+    /// The theme last set via `set_theme` or `fade_to_theme`, shared with
+    /// the `Ui`s of other windows constructed via `new_sharing`.
+    shared_theme: Rc<RefCell<SharedTheme>>,
+    /// The generation of the shared theme last applied to `theme`.
+    theme_generation: u64,
+    /// The crossfade between themes, if one is in progress.
+    maybe_theme_transition: Option<ThemeTransition>,
+    /// Reloads the theme from a file when it changes, if watching.
+    maybe_theme_watcher: Option<ThemeWatcher>,
+    /// The reason the watched theme file last failed to load, if it did.
+    maybe_theme_error: Option<String>,
+    /// The rectangles to which rendering is currently clipped. Each is the
+    /// intersection of the rectangle pushed and the one beneath it.
+    clip_stack: Vec<(Point, Dimensions)>,
+    /// The position of the mouse during the previous frame.
+    prev_mouse_pos: Point,
+    /// The time and position of each movement of the mouse within the last
+    /// `MOUSE_HISTORY_DURATION` seconds, oldest first, preceded by the last
+    /// movement before them (where the mouse was as they began).
+    mouse_history: Vec<(f64, Point)>,
+    /// Incremented whenever all cached renderings become invalid.
+    render_cache_generation: u64,
+    /// Whether or not solid rectangles are batched by color.
+    batch_rectangles: bool,
+    /// The batches of rectangles waiting to be rendered, in the order in
+    /// which they must be rendered.
+    rectangle_batches: Vec<RectangleBatch>,
+    /// The glyph runs measured or used so far during the current frame.
+    glyph_runs: GlyphRuns,
+    /// The glyph runs measured or used during the previous frame. Those that
+    /// aren't used again during the current frame are discarded.
+    prev_glyph_runs: GlyphRuns,
+    /// The width of each line of text measured. Unlike the glyph runs these
+    /// are kept until the fonts change.
+    text_widths: TextWidths,
+    /// The kerning table for each font that has one.
+    kerning: HashMap<FontId, KerningTable>,
+    /// The fonts to fall back to, in priority order, for characters missing from each font.
+    font_fallbacks: HashMap<FontId, Vec<FontId>>,
+    /// The ranges of characters covered by each font for which they are known.
+    font_coverage: HashMap<FontId, Vec<(char, char)>>,
+    /// The font chosen by `font_for_char` for each character drawn in a font
+    /// with fallbacks, so that the fonts needn't be searched again.
+    char_fonts: HashMap<(FontId, FontSize, char), FontId>,
+    /// The clipboard used for copying and pasting text.
+    clipboard: Box<Clipboard>,
+    /// The closures to be called with the `Ui` once the frame's widgets have been drawn.
+    deferred: Deferred<C>,
+    /// The payload being dragged between widgets, if any.
+    maybe_drag: Option<Drag>,
+    /// The key combinations bound to the application's actions.
+    pub shortcuts: Shortcuts,
+    /// The actions whose shortcuts were pressed since the last frame.
+    triggered_actions: Vec<ActionId>,
+    /// The widget that was taking text input during the previous frame.
+    maybe_text_focus: Option<UIID>,
+    /// The widget that has claimed text input so far during the current frame.
+    maybe_next_text_focus: Option<UIID>,
+    /// The widget that captured the mouse over an area during the previous frame.
+    maybe_mouse_capture: Option<(UIID, Point, Dimensions)>,
+    /// The widget that has captured the mouse so far during the current frame.
+    maybe_next_mouse_capture: Option<(UIID, Point, Dimensions)>,
+    /// The changes made via widgets, if undo and redo are enabled.
+    maybe_history: Option<History>,
+    /// The translations of displayed strings and the current language.
+    pub locale: Locale,
+    /// The latest value, scroll offset and layout of each widget, saved by
+    /// `Ui::save_state`.
+    persisted: SavedState,
+    /// The state loaded by `Ui::load_state` that has yet to be restored by
+    /// the widgets.
+    loaded: SavedState,
+    /// The mouse cursor requested by the widgets drawn so far this frame.
+    mouse_cursor: MouseCursor,
+    /// The alpha by which colors are currently multiplied, for each group
+    /// of widgets being faded. Each is the product of the alpha pushed and
+    /// the one beneath it.
+    alpha_stack: Vec<f32>,
+    /// The parent of each widget that has been given one, i.e. the parts of
+    /// a composite widget.
+    parents: HashMap<UIID, UIID>,
+    /// The statistics of the frame being drawn, if collecting statistics.
+    maybe_stats: Option<FrameStats>,
+    /// The statistics of the last frame drawn while collecting statistics.
+    maybe_prev_stats: Option<FrameStats>,
+    /// The system time at which the last widget was set or event was
+    /// handled, from which the next widget is timed.
+    stats_mark: f64,
+    /// Receives every input and widget state transition, if tracing.
+    maybe_tracer: Option<Box<FnMut(&TraceEvent)>>,
+    /// Reusable buffers for the temporary strings and point lists built by
+    /// widgets while drawing.
+    scratch: ScratchArena,
+    /// The widget that handled the frame's keys and text, if any.
+    maybe_text_consumer: Option<UIID>,
+    /// The union of the rectangles that changed during the current frame.
+    maybe_damage: Option<(Point, Dimensions)>,
+    /// Whether or not the whole window changed during the current frame.
+    is_fully_damaged: bool,
+    /// The widgets whose state changed as they were set this frame.
+    changed_widgets: HashSet<UIID>,
+    /// Hashes everything drawn since the last widget was set, while tracking
+    /// damage.
+    maybe_content_hasher: Option<ContentHasher>,
+    /// The hash of what each widget drew when it was last set.
+    content_hashes: HashMap<UIID, u64>,
 }
 
 impl<C> Ui<C>
@@ -54,82 +294,300 @@ impl<C> Ui<C>
 
     /// Constructor for a UiContext.
     pub fn new(glyph_cache: C, theme: Theme) -> Ui<C> {
+        Ui::new_shared(Rc::new(RefCell::new(glyph_cache)), theme)
+    }
+
+    /// Constructor for a UiContext whose glyph cache may be shared with
+    /// the `Ui`s of other windows.
+    pub fn new_shared(glyph_cache: Rc<RefCell<C>>, theme: Theme) -> Ui<C> {
+        let shared_theme = SharedTheme { theme: theme.clone(), duration: 0.0, generation: 0 };
         Ui {
-            data: repeat((widget::Widget::NoWidget, widget::Placing::NoPlace)).take(512).collect(),
+            data: HashMap::new(),
             theme: theme,
+            image_map: ImageMap::new(),
             mouse: Mouse::new([0.0, 0.0], ButtonState::Up, ButtonState::Up, ButtonState::Up),
             keys_just_pressed: Vec::with_capacity(10),
             keys_just_released: Vec::with_capacity(10),
+            keys_held: Vec::new(),
             text_just_entered: Vec::with_capacity(10),
-            glyph_cache: glyph_cache,
+            fonts: vec![glyph_cache],
             prev_event_was_render: false,
             win_w: 0.0,
             win_h: 0.0,
+            dpi_factor: 1.0,
+            scale: 1.0,
             prev_uiid: 0,
+            color_transitions: HashMap::new(),
+            updated_widgets: Vec::new(),
+            prev_updated_widgets: Vec::new(),
+            appeared_widgets: Vec::new(),
+            disappeared_widgets: Vec::new(),
+            user_states: HashMap::new(),
+            widget_memory: HashMap::new(),
+            debug: false,
+            maybe_record: None,
+            clock: Clock::new(),
+            maybe_primitives: None,
+            diagnostics: Vec::new(),
+            frame_widget_kinds: HashMap::new(),
+            shared_theme: Rc::new(RefCell::new(shared_theme)),
+            theme_generation: 0,
+            maybe_theme_transition: None,
+            maybe_theme_watcher: None,
+            maybe_theme_error: None,
+            clip_stack: Vec::new(),
+            prev_mouse_pos: [0.0, 0.0],
+            mouse_history: Vec::new(),
+            render_cache_generation: 0,
+            batch_rectangles: false,
+            rectangle_batches: Vec::new(),
+            glyph_runs: HashMap::new(),
+            prev_glyph_runs: HashMap::new(),
+            text_widths: HashMap::new(),
+            kerning: HashMap::new(),
+            font_fallbacks: HashMap::new(),
+            font_coverage: HashMap::new(),
+            char_fonts: HashMap::new(),
+            clipboard: Box::new(LocalClipboard::new()),
+            deferred: Deferred::new(),
+            maybe_drag: None,
+            shortcuts: Shortcuts::new(),
+            triggered_actions: Vec::new(),
+            maybe_text_focus: None,
+            maybe_next_text_focus: None,
+            maybe_mouse_capture: None,
+            maybe_next_mouse_capture: None,
+            maybe_history: None,
+            locale: Locale::new("en"),
+            persisted: SavedState::new(),
+            loaded: SavedState::new(),
+            mouse_cursor: MouseCursor::Arrow,
+            alpha_stack: Vec::new(),
+            parents: HashMap::new(),
+            maybe_stats: None,
+            maybe_prev_stats: None,
+            stats_mark: 0.0,
+            maybe_tracer: None,
+            scratch: ScratchArena::new(),
+            maybe_text_consumer: None,
+            maybe_damage: None,
+            is_fully_damaged: true,
+            changed_widgets: HashSet::new(),
+            maybe_content_hasher: None,
+            content_hashes: HashMap::new(),
         }
     }
 
+    /// Construct a new `Ui` for another window which shares this `Ui`'s
+    /// fonts and theme. A theme set on any of the sharing `Ui`s via
+    /// `set_theme` or `fade_to_theme` is applied to all of them, while
+    /// changes made directly to a `Ui`'s `theme` field remain its own.
+    pub fn new_sharing(&self) -> Ui<C> {
+        let (theme, generation) = {
+            let shared = self.shared_theme.borrow();
+            (shared.theme.clone(), shared.generation)
+        };
+        let mut ui = Ui::new_shared(self.fonts[0].clone(), theme);
+        ui.fonts = self.fonts.clone();
+        ui.shared_theme = self.shared_theme.clone();
+        ui.theme_generation = generation;
+        ui
+    }
+
     /// Handle game events and update the state.
     pub fn handle_event<E: GenericEvent + ::std::fmt::Debug>(&mut self, event: &E) {
-        if self.prev_event_was_render {
-            self.flush_input();
-            self.prev_event_was_render = false;
-        }
+        let mut maybe_input = None;
         event.render(|args| {
-            self.win_w = args.width as f64;
-            self.win_h = args.height as f64;
-            self.prev_event_was_render = true;
+            maybe_input = Some(Input::Render(args.width as f64, args.height as f64));
         });
         event.mouse_cursor(|x, y| {
-            self.mouse.pos = [x, y];
+            maybe_input = Some(Input::MouseCursor(x, y));
+        });
+        event.mouse_scroll(|x, y| {
+            maybe_input = Some(Input::MouseScroll(x, y));
         });
         event.press(|button_type| {
-            use piston::input::Button;
-            use piston::input::MouseButton::Left;
-
-            match button_type {
-                Button::Mouse(button) => {
-                    *match button {
-                        Left => &mut self.mouse.left,
-                        _/*input::mouse::Right*/ => &mut self.mouse.right,
-                        //Middle => &mut self.mouse.middle,
-                    } = ButtonState::Down;
-                },
-                Button::Keyboard(key) => self.keys_just_pressed.push(key),
-            }
+            maybe_input = Some(Input::Press(button_type));
         });
         event.release(|button_type| {
-            use piston::input::Button;
-            use piston::input::MouseButton::Left;
-
-            match button_type {
-                Button::Mouse(button) => {
-                    *match button {
-                        Left => &mut self.mouse.left,
-                        _/*input::mouse::Right*/ => &mut self.mouse.right,
-                        //Middle => &mut self.mouse.middle,
-                    } = ButtonState::Up;
-                },
-                Button::Keyboard(key) => self.keys_just_released.push(key),
-            }
+            maybe_input = Some(Input::Release(button_type));
         });
         event.text(|text| {
-            self.text_just_entered.push(text.to_string())
+            maybe_input = Some(Input::Text(text.to_string()));
         });
+        match maybe_input {
+            Some(input) => self.handle_input(input),
+            None => self.flush_if_rendered(),
+        }
+        if self.maybe_stats.is_some() {
+            self.stats_mark = precise_time_s();
+        }
     }
 
-    /// Return a reference to a `Character` from the GlyphCache.
-    pub fn get_character(
-        &mut self,
-        size: FontSize,
-        ch: char
-    ) -> &Character<<C as CharacterCache>::Texture> {
-        self.glyph_cache.character(size, ch)
+    /// Call the given function with a mutable reference to the glyph cache
+    /// of the given font. As the glyph cache may be changed, any text
+    /// measured with it is measured again.
+    pub fn with_glyph_cache<F, R>(&mut self, font: FontId, f: F) -> R
+        where
+            F: FnOnce(&mut C) -> R
+    {
+        let glyph_cache = self.font_glyph_cache(font).clone();
+        let result = f(&mut *glyph_cache.borrow_mut());
+        self.glyph_runs.clear();
+        self.prev_glyph_runs.clear();
+        self.text_widths.clear();
+        self.char_fonts.clear();
+        result
+    }
+
+    /// Return a copy of a `Character` from the glyph cache given on
+    /// construction, at the given size in physical pixels.
+    #[deprecated(note = "the glyph cache may be shared between `Ui`s; use `Ui::with_glyph_cache` \
+                         or `Ui::get_character_w`")]
+    pub fn get_character(&mut self, size: FontSize, ch: char) -> Character<<C as CharacterCache>::Texture>
+        where
+            Character<<C as CharacterCache>::Texture>: Clone
+    {
+        self.fonts[0].borrow_mut().character(size, ch).clone()
+    }
+
+    /// Return the width of a 'Character' in DPI-independent units. If the
+    /// character is missing from the font, its fallback font is measured.
+    pub fn get_character_w(&mut self, font: FontId, size: FontSize, ch: char) -> f64 {
+        let pixel_factor = self.pixel_factor();
+        let scaled_size = self.scaled_font_size(size);
+        let font = self.font_for_char(font, size, ch);
+        self.font_glyph_cache(font).borrow_mut().character(scaled_size, ch).width() / pixel_factor
+    }
+
+    /// Rasterize the given characters at the given size into the glyph cache
+    /// of the theme's default font ahead of time, i.e. at startup, so that
+    /// the first frame to draw them doesn't stall. Characters missing from
+    /// the font are loaded from its fallbacks. Glyphs are rasterized at the
+    /// current pixel factor, so should be preloaded again after it changes.
+    pub fn preload_glyphs(&mut self, size: FontSize, chars: &str) {
+        let font = self.theme.font_default;
+        self.preload_font_glyphs(font, size, chars);
+    }
+
+    /// Like `preload_glyphs`, but for the given font.
+    pub fn preload_font_glyphs(&mut self, font: FontId, size: FontSize, chars: &str) {
+        let scaled_size = self.scaled_font_size(size);
+        for ch in chars.chars() {
+            let font = self.font_for_char(font, size, ch);
+            self.font_glyph_cache(font).borrow_mut().character(scaled_size, ch);
+        }
+    }
+
+    /// Set the fonts, in priority order, with which to draw characters that
+    /// are missing from the given font (i.e. CJK characters, symbols or emoji).
+    pub fn set_font_fallbacks(&mut self, font: FontId, fallbacks: Vec<FontId>) {
+        self.font_fallbacks.insert(font, fallbacks);
+        self.glyph_runs.clear();
+        self.prev_glyph_runs.clear();
+        self.text_widths.clear();
+        self.char_fonts.clear();
+    }
+
+    /// Set the inclusive ranges of characters that the given font contains.
+    /// The `CharacterCache` can't report whether a glyph exists, so without
+    /// coverage a character is only considered missing if its glyph is empty.
+    pub fn set_font_coverage(&mut self, font: FontId, ranges: Vec<(char, char)>) {
+        self.font_coverage.insert(font, ranges);
+        self.glyph_runs.clear();
+        self.prev_glyph_runs.clear();
+        self.text_widths.clear();
+        self.char_fonts.clear();
+    }
+
+    /// The font with which the given character is drawn: the given font if
+    /// it contains the character, otherwise the first of its fallbacks that
+    /// does. If none do, the given font is used.
+    pub fn font_for_char(&mut self, font: FontId, size: FontSize, ch: char) -> FontId {
+        // Without fallbacks there's nothing to choose between.
+        if self.font_fallbacks.get(&font).map_or(true, |fallbacks| fallbacks.is_empty()) {
+            return font;
+        }
+        if let Some(&chosen) = self.char_fonts.get(&(font, size, ch)) {
+            return chosen;
+        }
+        let chosen = match self.has_glyph(font, size, ch) {
+            true => font,
+            false => {
+                let fallbacks = self.font_fallbacks[&font].clone();
+                fallbacks.into_iter().find(|&fallback| self.has_glyph(fallback, size, ch)).unwrap_or(font)
+            },
+        };
+        self.char_fonts.insert((font, size, ch), chosen);
+        chosen
+    }
+
+    /// Whether or not the given font contains the given character.
+    fn has_glyph(&mut self, font: FontId, size: FontSize, ch: char) -> bool {
+        if let Some(ranges) = self.font_coverage.get(&font) {
+            return ranges.iter().any(|&(start, end)| start <= ch && ch <= end)
+        }
+        if ch.is_whitespace() { return true }
+        let scaled_size = self.scaled_font_size(size);
+        let glyph_cache = self.font_glyph_cache(font);
+        let mut glyph_cache = glyph_cache.borrow_mut();
+        let character = glyph_cache.character(scaled_size, ch);
+        character.width() > 0.0 || character.size[0] > 0.0
+    }
+
+    /// Return the layout of the given line of text, measuring it only if it
+    /// wasn't measured during the current or previous frame.
+    pub fn glyph_run(&mut self, font: FontId, size: FontSize, text: &str) -> Rc<GlyphRun> {
+        // The text is only copied for the key when it's first measured.
+        if let Some(run) = self.glyph_runs.get(&(font, size)).and_then(|runs| runs.get(text)) {
+            if let Some(ref mut stats) = self.maybe_stats { stats.glyph_run_hits += 1 }
+            return run.clone();
+        }
+        let maybe_prev_run = self.prev_glyph_runs.get_mut(&(font, size)).and_then(|runs| runs.remove(text));
+        let run = match maybe_prev_run {
+            Some(run) => {
+                if let Some(ref mut stats) = self.maybe_stats { stats.glyph_run_hits += 1 }
+                run
+            },
+            None => {
+                if let Some(ref mut stats) = self.maybe_stats { stats.glyph_run_misses += 1 }
+                Rc::new(GlyphRun::new(self, font, size, text))
+            },
+        };
+        self.glyph_runs.entry((font, size)).or_insert_with(HashMap::new).insert(text.to_string(), run.clone());
+        run
     }
 
-    /// Return the width of a 'Character'.
-    pub fn get_character_w(&mut self, size: FontSize, ch: char) -> f64 {
-        self.get_character(size, ch).width()
+    /// The width of the given line of text, measuring it only if it hasn't
+    /// been measured since the fonts last changed.
+    pub fn text_width(&mut self, font: FontId, size: FontSize, text: &str) -> f64 {
+        if let Some(&width) = self.text_widths.get(&(font, size)).and_then(|widths| widths.get(text)) {
+            return width;
+        }
+        let width = self.glyph_run(font, size, text).width();
+        let num_widths = self.text_widths.values().fold(0, |total, widths| total + widths.len());
+        if num_widths >= MAX_CACHED_TEXT_WIDTHS {
+            self.text_widths.clear();
+        }
+        self.text_widths.entry((font, size)).or_insert_with(HashMap::new).insert(text.to_string(), width);
+        width
+    }
+
+    /// Set the kerning table for the given font, which is then applied
+    /// wherever text in the font is measured or drawn.
+    pub fn set_kerning(&mut self, font: FontId, table: KerningTable) {
+        self.kerning.insert(font, table);
+        self.glyph_runs.clear();
+        self.prev_glyph_runs.clear();
+        self.text_widths.clear();
+    }
+
+    /// The adjustment to the advance between the given pair of characters.
+    pub fn kerning(&self, font: FontId, size: FontSize, left: char, right: char) -> f64 {
+        match self.kerning.get(&font) {
+            Some(table) => table.get(&(left, right)).map_or(0.0, |&kern| kern * size as f64),
+            None => 0.0,
+        }
     }
 
     /// Flush all stored keys.
@@ -137,6 +595,9 @@ impl<C> Ui<C>
         self.keys_just_pressed.clear();
         self.keys_just_released.clear();
         self.text_just_entered.clear();
+        self.maybe_text_consumer = None;
+        self.triggered_actions.clear();
+        self.mouse.scroll = [0.0, 0.0];
     }
 
     /// Draws text
@@ -144,6 +605,409 @@ impl<C> Ui<C>
         &mut self,
         graphics: &mut B,
         pos: Point,
+        font: FontId,
+        size: FontSize,
+        color: Color,
+        text: &str
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let color = color.fade(self.alpha());
+        if let Some(ref mut hasher) = self.maybe_content_hasher {
+            hasher.add_text(pos, font, size, color, text);
+        }
+        if let Some(ref mut stats) = self.maybe_stats {
+            stats.primitives += 1;
+            stats.glyphs += text.chars().count();
+        }
+        if let Some(ref mut primitives) = self.maybe_primitives {
+            primitives.push(Primitive::Text {
+                pos: pos,
+                font: font,
+                size: size,
+                color: color,
+                text: text.to_string(),
+            });
+            return
+        }
+        self.render_text(graphics, pos, font, size, color, text);
+    }
+
+    /// Draw a filled rectangle.
+    pub fn draw_rectangle<B>(&mut self, graphics: &mut B, pos: Point, dim: Dimensions, color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_primitive(graphics, Primitive::Rectangle { pos: pos, dim: dim, color: color });
+    }
+
+    /// Draw a filled rectangle whose corners are rounded with the given radius.
+    pub fn draw_rounded_rectangle<B>(
+        &mut self,
+        graphics: &mut B,
+        pos: Point,
+        dim: Dimensions,
+        radius: f64,
+        color: Color
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        if radius <= 0.0 { return self.draw_rectangle(graphics, pos, dim, color) }
+        self.draw_primitive(graphics, Primitive::RoundedRectangle {
+            pos: pos,
+            dim: dim,
+            radius: radius,
+            color: color,
+        });
+    }
+
+    /// Draw a straight line between the points `[x1, y1, x2, y2]`.
+    pub fn draw_line<B>(&mut self, graphics: &mut B, line: [f64; 4], width: f64, round: bool, color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_primitive(graphics, Primitive::Line {
+            line: line,
+            width: width,
+            round: round,
+            color: color,
+        });
+    }
+
+    /// Draw a filled ellipse within the given rectangle.
+    pub fn draw_ellipse<B>(&mut self, graphics: &mut B, pos: Point, dim: Dimensions, color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_primitive(graphics, Primitive::Ellipse { pos: pos, dim: dim, color: color });
+    }
+
+    /// Draw a filled polygon with the given vertices.
+    pub fn draw_polygon<B>(&mut self, graphics: &mut B, points: &[Point], color: Color)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_primitive(graphics, Primitive::Polygon { points: points.to_vec(), color: color });
+    }
+
+    /// Draw a rectangle filled with a gradient.
+    pub fn draw_gradient<B>(&mut self, graphics: &mut B, pos: Point, dim: Dimensions, gradient: Gradient)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_primitive(graphics, Primitive::Gradient { pos: pos, dim: dim, gradient: gradient });
+    }
+
+    /// Clip everything drawn until the matching `pop_clip` to the given
+    /// rectangle (and to any rectangle to which drawing is already clipped).
+    /// This allows containers to cut off children that overflow their bounds.
+    /// While capturing primitives the clip is captured rather than scissored,
+    /// though `Ui::clip_rect` still reflects it.
+    pub fn push_clip(&mut self, pos: Point, dim: Dimensions) {
+        if let Some(ref mut primitives) = self.maybe_primitives {
+            primitives.push(Primitive::PushClip { pos: pos, dim: dim });
+        }
+        self.push_clip_rect(pos, dim);
+    }
+
+    /// Stop clipping to the rectangle given by the last `push_clip`.
+    pub fn pop_clip(&mut self) {
+        if let Some(ref mut primitives) = self.maybe_primitives {
+            primitives.push(Primitive::PopClip);
+        }
+        self.clip_stack.pop();
+    }
+
+    /// The rectangle to which drawing is currently clipped, if any.
+    pub fn clip_rect(&self) -> Option<(Point, Dimensions)> {
+        self.clip_stack.last().map(|&rect| rect)
+    }
+
+    /// Push the intersection of the given rectangle and the current clip
+    /// rectangle onto the clip stack.
+    fn push_clip_rect(&mut self, pos: Point, dim: Dimensions) {
+        let rect = match self.clip_stack.last() {
+            Some(&(clip_pos, clip_dim)) => {
+                let left = pos[0].max(clip_pos[0]);
+                let top = pos[1].max(clip_pos[1]);
+                let right = (pos[0] + dim[0]).min(clip_pos[0] + clip_dim[0]);
+                let bottom = (pos[1] + dim[1]).min(clip_pos[1] + clip_dim[1]);
+                ([left, top], [(right - left).max(0.0), (bottom - top).max(0.0)])
+            },
+            None => (pos, dim),
+        };
+        self.clip_stack.push(rect);
+    }
+
+    /// Multiply the alpha of every color drawn with by the given amount until
+    /// the matching `pop_alpha`, i.e. to fade a group of widgets in or out.
+    pub fn push_alpha(&mut self, alpha: f32) {
+        let alpha = self.alpha() * alpha;
+        self.alpha_stack.push(alpha);
+    }
+
+    /// Stop multiplying colors by the alpha given by the last `push_alpha`.
+    pub fn pop_alpha(&mut self) {
+        self.alpha_stack.pop();
+    }
+
+    /// The amount by which the alpha of every color drawn with is currently multiplied.
+    pub fn alpha(&self) -> f32 {
+        self.alpha_stack.last().map_or(1.0, |&alpha| alpha)
+    }
+
+    /// The `DrawState` with which primitives are rendered, scissored to the
+    /// current clip rectangle. The scissor is given in physical pixels from
+    /// the bottom-left of the window.
+    fn draw_state(&self) -> graphics::DrawState {
+        self.clipped_draw_state(self.clip_rect())
+    }
+
+    /// The `DrawState` scissored to the given clip rectangle.
+    fn clipped_draw_state(&self, maybe_clip: Option<(Point, Dimensions)>) -> graphics::DrawState {
+        let draw_state = graphics::default_draw_state().clone();
+        match maybe_clip {
+            Some((pos, dim)) => {
+                // Scissor rectangles are measured from the bottom left of the
+                // window, and any part beyond its left or bottom edge is cut off.
+                let pixel_factor = self.pixel_factor();
+                let x = pos[0] * pixel_factor;
+                let y = (self.win_h - pos[1] - dim[1]) * pixel_factor;
+                let w = (dim[0] * pixel_factor + x.min(0.0)).max(0.0);
+                let h = (dim[1] * pixel_factor + y.min(0.0)).max(0.0);
+                draw_state.scissor(x.max(0.0) as u16, y.max(0.0) as u16, w as u16, h as u16)
+            },
+            None => draw_state,
+        }
+    }
+
+    /// Draw the part of the image within `src_rect` (or the whole image if
+    /// `None`) stretched over the given rectangle, optionally tinted with the
+    /// given color.
+    pub fn draw_image<B>(
+        &mut self,
+        graphics: &mut B,
+        id: ImageId,
+        maybe_src_rect: Option<[i32; 4]>,
+        pos: Point,
+        dim: Dimensions,
+        maybe_color: Option<Color>
+    )
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_primitive(graphics, Primitive::Image {
+            id: id,
+            maybe_src_rect: maybe_src_rect,
+            pos: pos,
+            dim: dim,
+            maybe_color: maybe_color,
+        });
+    }
+
+    /// Draw the given primitive, or add it to the captured primitives if the
+    /// `Ui` is capturing primitives.
+    pub fn draw_primitive<B>(&mut self, graphics: &mut B, primitive: Primitive)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let alpha = self.alpha();
+        let primitive = if alpha < 1.0 { primitive.faded(alpha) } else { primitive };
+        if let Some(ref mut hasher) = self.maybe_content_hasher {
+            hasher.add_primitive(&primitive);
+        }
+        if let Some(ref mut stats) = self.maybe_stats { stats.primitives += 1 }
+        if let Some(ref mut primitives) = self.maybe_primitives {
+            primitives.push(primitive);
+            return
+        }
+        self.render_primitive(graphics, &primitive);
+    }
+
+    /// Render the given (i.e. previously captured) primitives to the
+    /// `Graphics` backend, regardless of whether or not the `Ui` is capturing.
+    pub fn render_primitives<B>(&mut self, graphics: &mut B, primitives: &[Primitive])
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        for primitive in primitives.iter() {
+            self.render_primitive(graphics, primitive);
+        }
+    }
+
+    /// Render a single primitive to the `Graphics` backend.
+    fn render_primitive<B>(&mut self, graphics: &mut B, primitive: &Primitive)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        // Batched rectangles must be rendered before anything drawn after them.
+        match *primitive {
+            Primitive::Rectangle { .. } | Primitive::PushClip { .. } | Primitive::PopClip => (),
+            _ => self.flush_rectangles(graphics),
+        }
+        let draw_state = &self.draw_state();
+        let transform = graphics::abs_transform(self.win_w, self.win_h);
+        match *primitive {
+            Primitive::Rectangle { pos, dim, color } => {
+                if self.batch_rectangles {
+                    self.batch_rectangle(pos, dim, color);
+                } else {
+                    graphics::Rectangle::new(color.0)
+                        .draw([pos[0], pos[1], dim[0], dim[1]], draw_state, transform, graphics);
+                }
+            },
+            Primitive::RoundedRectangle { pos, dim, radius, color } => {
+                let radius = radius.min(dim[0].min(dim[1]) / 2.0);
+                graphics::Rectangle::new_round(color.0, radius)
+                    .draw([pos[0], pos[1], dim[0], dim[1]], draw_state, transform, graphics);
+            },
+            Primitive::Line { line, width, round, color } => {
+                let shape = if round { graphics::Line::new_round(color.0, 0.5 * width) }
+                            else { graphics::Line::new(color.0, 0.5 * width) };
+                shape.draw(line, draw_state, transform, graphics);
+            },
+            Primitive::Ellipse { pos, dim, color } => {
+                graphics::Ellipse::new(color.0)
+                    .draw([pos[0], pos[1], dim[0], dim[1]], draw_state, transform, graphics);
+            },
+            Primitive::Polygon { ref points, color } => {
+                let shape = graphics::Polygon::new(color.0);
+                for triangle in polygon::triangulate(points).iter() {
+                    shape.draw(triangle, draw_state, transform, graphics);
+                }
+            },
+            Primitive::Gradient { pos, dim, gradient } => {
+                self.render_gradient(graphics, pos, dim, gradient);
+            },
+            Primitive::Text { pos, font, size, color, ref text } => {
+                self.render_text(graphics, pos, font, size, color, text);
+            },
+            Primitive::Image { id, maybe_src_rect, pos, dim, maybe_color } => {
+                if let Some(texture) = self.image_map.get(id) {
+                    let mut image = graphics::Image::new().rect([pos[0], pos[1], dim[0], dim[1]]);
+                    if let Some(src_rect) = maybe_src_rect {
+                        image = image.src_rect(src_rect);
+                    }
+                    if let Some(color) = maybe_color {
+                        image = image.color(color.0);
+                    }
+                    image.draw(texture, draw_state, transform, graphics);
+                }
+            },
+            Primitive::PushClip { pos, dim } => self.push_clip_rect(pos, dim),
+            Primitive::PopClip => { self.clip_stack.pop(); },
+        }
+    }
+
+    /// Add a rectangle to the latest batch of its color to which it can be
+    /// added without rendering it beneath anything that was drawn after it.
+    fn batch_rectangle(&mut self, pos: Point, dim: Dimensions, color: Color) {
+        let rect = [pos[0], pos[1], dim[0], dim[1]];
+        let maybe_clip = self.clip_rect();
+        let overlaps = |other: &[f64; 4]| {
+            other[0] < rect[0] + rect[2] && rect[0] < other[0] + other[2]
+                && other[1] < rect[1] + rect[3] && rect[1] < other[1] + other[3]
+        };
+        let mut maybe_idx = None;
+        for (i, batch) in self.rectangle_batches.iter().enumerate().rev() {
+            if batch.color.0 == color.0 && batch.maybe_clip == maybe_clip {
+                maybe_idx = Some(i);
+                break;
+            }
+            if batch.rects.iter().any(&overlaps) { break }
+        }
+        match maybe_idx {
+            Some(idx) => self.rectangle_batches[idx].rects.push(rect),
+            None => self.rectangle_batches.push(RectangleBatch {
+                color: color,
+                maybe_clip: maybe_clip,
+                rects: vec![rect],
+            }),
+        }
+    }
+
+    /// Render all batched rectangles, one draw call per batch. When batching
+    /// is enabled, this must be called once all widgets have been drawn for
+    /// the frame (it is called automatically before any other kind of
+    /// primitive is rendered).
+    pub fn flush_rectangles<B>(&mut self, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        if self.rectangle_batches.is_empty() { return }
+        let transform = graphics::abs_transform(self.win_w, self.win_h);
+        let batches = ::std::mem::replace(&mut self.rectangle_batches, Vec::new());
+        for batch in batches.iter() {
+            let draw_state = &self.clipped_draw_state(batch.maybe_clip);
+            let vertices: Vec<f32> = batch.rects.iter()
+                .flat_map(|&rect| {
+                    graphics::triangulation::rect_tri_list_xy(transform, rect).to_vec().into_iter()
+                })
+                .collect();
+            graphics.tri_list(draw_state, &batch.color.0, |f| {
+                for chunk in vertices.chunks(RECTANGLE_BATCH_CHUNK_LEN) {
+                    f(chunk);
+                }
+            });
+        }
+    }
+
+    /// Render a gradient as a series of bands of interpolated color, as the
+    /// `Graphics` backend only supports a single color per triangle list.
+    fn render_gradient<B>(&mut self, graphics: &mut B, pos: Point, dim: Dimensions, gradient: Gradient)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        // Roughly one band for every two physical pixels.
+        let num_bands = |extent: f64, pixel_factor: f64| {
+            cmp::max(1, cmp::min((extent * pixel_factor / 2.0) as usize, MAX_GRADIENT_BANDS))
+        };
+        let draw_state = &self.draw_state();
+        let transform = graphics::abs_transform(self.win_w, self.win_h);
+        match gradient {
+            Gradient::Linear(a, b, direction) => {
+                let extent = match direction {
+                    GradientDirection::Horizontal => dim[0],
+                    GradientDirection::Vertical => dim[1],
+                };
+                let n = num_bands(extent, self.pixel_factor());
+                let band = extent / n as f64;
+                for i in 0..n {
+                    let color = a.interpolate(b, (i as f32 + 0.5) / n as f32);
+                    let offset = band * i as f64;
+                    let rect = match direction {
+                        GradientDirection::Horizontal => [pos[0] + offset, pos[1], band, dim[1]],
+                        GradientDirection::Vertical => [pos[0], pos[1] + offset, dim[0], band],
+                    };
+                    graphics::Rectangle::new(color.0).draw(rect, draw_state, transform, graphics);
+                }
+            },
+            Gradient::Radial(inner, outer) => {
+                // The corners beyond the outermost ellipse take the outer color,
+                // then concentric ellipses from the outer edge towards the center.
+                let rect = [pos[0], pos[1], dim[0], dim[1]];
+                graphics::Rectangle::new(outer.0).draw(rect, draw_state, transform, graphics);
+                let n = num_bands(dim[0].max(dim[1]) / 2.0, self.pixel_factor());
+                let center = [pos[0] + dim[0] / 2.0, pos[1] + dim[1] / 2.0];
+                for i in 0..n {
+                    let perc = 1.0 - i as f64 / n as f64;
+                    let color = inner.interpolate(outer, perc as f32);
+                    let (w, h) = (dim[0] * perc, dim[1] * perc);
+                    let rect = [center[0] - w / 2.0, center[1] - h / 2.0, w, h];
+                    graphics::Ellipse::new(color.0).draw(rect, draw_state, transform, graphics);
+                }
+            },
+        }
+    }
+
+    /// Render text directly to the `Graphics` backend.
+    fn render_text<B>(
+        &mut self,
+        graphics: &mut B,
+        pos: Point,
+        font: FontId,
         size: FontSize,
         color: Color,
         text: &str
@@ -155,88 +1019,1617 @@ impl<C> Ui<C>
         use graphics::Transformed;
         use num::Float;
 
+        // Glyphs are rasterized at the physical font size and scaled back
+        // down so that text remains crisp on high DPI displays.
         let Color(col) = color;
-        let draw_state = graphics::default_draw_state();
+        let pixel_factor = self.pixel_factor();
+        let draw_state = &self.draw_state();
         let transform = graphics::abs_transform(self.win_w, self.win_h)
-                        .trans(pos[0].ceil(), pos[1].ceil() + size as f64);
-        Text::colored(col, size).draw(
+                        .trans(pos[0].ceil(), pos[1].ceil() + size as f64)
+                        .scale(1.0 / pixel_factor, 1.0 / pixel_factor);
+        let scaled_size = self.scaled_font_size(size);
+
+        // Kerned text and text in fonts with fallbacks is drawn a glyph at a
+        // time at the offsets of its run, each in the font that contains it.
+        if self.kerning.contains_key(&font) || self.font_fallbacks.contains_key(&font) {
+            let run = self.glyph_run(font, size, text);
+            let mut glyph = String::with_capacity(4);
+            for (i, ch) in text.chars().enumerate() {
+                glyph.clear();
+                glyph.push(ch);
+                let glyph_font = self.font_for_char(font, size, ch);
+                let mut glyph_cache = self.font_glyph_cache(glyph_font).borrow_mut();
+                let transform = transform.trans(run.x_at(i) * pixel_factor, 0.0);
+                Text::colored(col, scaled_size).draw(
+                    &glyph,
+                    &mut *glyph_cache,
+                    draw_state,
+                    transform,
+                    graphics
+                );
+            }
+            return
+        }
+
+        let mut glyph_cache = self.font_glyph_cache(font).borrow_mut();
+        Text::colored(col, scaled_size).draw(
             text,
-            &mut self.glyph_cache,
+            &mut *glyph_cache,
             draw_state,
             transform,
             graphics
         );
     }
 
-}
-
-impl<C> Ui<C> {
-    /// Return the current mouse state.
-    pub fn get_mouse_state(&self) -> Mouse {
-        self.mouse
+    /// Draw a texture (i.e. a cached rendering) stretched over the given
+    /// rectangle. Textures can't be captured, so this always renders directly.
+    pub fn draw_texture<B>(&mut self, graphics: &mut B, pos: Point, dim: Dimensions, texture: &B::Texture)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.flush_rectangles(graphics);
+        let draw_state = &self.draw_state();
+        let transform = graphics::abs_transform(self.win_w, self.win_h);
+        graphics::Image::new()
+            .rect([pos[0], pos[1], dim[0], dim[1]])
+            .draw(texture, draw_state, transform, graphics);
     }
 
-    /// Return the vector of recently pressed keys.
-    pub fn get_pressed_keys(&self) -> Vec<input::keyboard::Key> {
-        self.keys_just_pressed.clone()
+    /// Finish drawing the frame. This should be called once all widgets have
+    /// been drawn: it renders any batched rectangles, followed by the preview
+    /// of the drag in progress and, if enabled, the debug overlay.
+    pub fn draw<B>(&mut self, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.flush_rectangles(graphics);
+        self.draw_drag_preview(graphics);
+        if self.debug {
+            self.draw_debug_overlay(graphics);
+        }
     }
 
-    /// Return the vector of recently entered text.
-    pub fn get_entered_text(&self) -> Vec<String> {
-        self.text_just_entered.clone()
+    /// Draw the preview of the drag in progress, centered beneath the cursor.
+    fn draw_drag_preview<B>(&mut self, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let preview = match self.maybe_drag {
+            Some(ref drag) => drag.preview().clone(),
+            None => return,
+        };
+        let mouse_pos = self.mouse.pos;
+        let centered = |dim: Dimensions| [mouse_pos[0] - dim[0] / 2.0, mouse_pos[1] - dim[1] / 2.0];
+        match preview {
+            Preview::Nothing => (),
+            Preview::Rectangle(dim, color) => self.draw_rectangle(graphics, centered(dim), dim, color),
+            Preview::Text(text) => {
+                let font = self.theme.font_default;
+                let size = self.theme.font_size_medium;
+                let color = self.theme.label_color;
+                let dim = [self.glyph_run(font, size, &text).width(), size as f64];
+                self.draw_text(graphics, centered(dim), font, size, color, &text);
+            },
+            Preview::Image(id, dim) => self.draw_image(graphics, id, None, centered(dim), dim, None),
+        }
     }
 
-    /// Return a mutable reference to the widget that matches the given ui_id
-    pub fn get_widget(&mut self, ui_id: UIID, default: Widget) -> &mut Widget {
-        let ui_id_idx = ui_id as usize;
-        if self.data.len() > ui_id_idx {
-            match &mut self.data[ui_id_idx] {
-                &mut (widget::Widget::NoWidget, _) => {
-                    match &mut self.data[ui_id_idx] {
-                        &mut (ref mut widget, _) => {
-                            *widget = default; widget
-                        }
-                    }
-                },
-                _ => {
-                    match &mut self.data[ui_id_idx] {
-                        &mut (ref mut widget, _) => widget
-                    }
-                },
+    /// Draw the debug overlay. Every widget set during the frame is outlined
+    /// and labelled with its UIID and state, while the widget currently under
+    /// the mouse is highlighted. If statistics are being collected, those of
+    /// the last frame are listed in the top left corner along with its
+    /// slowest widgets.
+    fn draw_debug_overlay<B>(&mut self, graphics: &mut B)
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let outline_color = Color::new(1.0, 0.0, 1.0, 1.0);
+        let highlight_color = Color::new(1.0, 0.0, 1.0, 0.25);
+        let font = self.theme.font_default;
+        let font_size = self.theme.font_size_small;
+        let mouse_pos = self.mouse.pos;
+        let over = self.widget_under(mouse_pos);
+        let updated_widgets = self.updated_widgets.clone();
+        for &ui_id in updated_widgets.iter() {
+            let (x, y, w, h) = match self.get_placing(ui_id) {
+                widget::Placing::Place(x, y, w, h) => (x, y, w, h),
+                widget::Placing::NoPlace => continue,
+            };
+            if over == Some(ui_id) {
+                self.draw_rectangle(graphics, [x, y], [w, h], highlight_color);
             }
-        } else {
-            if ui_id_idx >= self.data.len() {
-                let num_to_push = ui_id_idx - self.data.len();
-                let mut vec: Vec<(widget::Widget, widget::Placing)> = repeat((widget::Widget::NoWidget, widget::Placing::NoPlace)).take(num_to_push).collect();
-                vec.push((default, widget::Placing::NoPlace));
-                self.data.extend(vec.into_iter());
-            } else {
-                self.data[ui_id_idx] = (default, widget::Placing::NoPlace);
+            self.draw_line(graphics, [x, y, x + w, y], 1.0, false, outline_color);
+            self.draw_line(graphics, [x + w, y, x + w, y + h], 1.0, false, outline_color);
+            self.draw_line(graphics, [x + w, y + h, x, y + h], 1.0, false, outline_color);
+            self.draw_line(graphics, [x, y + h, x, y], 1.0, false, outline_color);
+            let text = format!("{}: {:?}", ui_id, self.data[&ui_id].0);
+            self.draw_text(graphics, [x, y - font_size as f64], font, font_size, outline_color, &text);
+        }
+        if let Some(stats) = self.maybe_prev_stats.clone() {
+            let mut lines = vec![
+                format!("widgets: {} in {:.2}ms", stats.widget_times.len(), stats.total_time() * 1000.0),
+                format!("primitives: {}, glyphs: {}", stats.primitives, stats.glyphs),
+                format!("glyph run hits: {:.0}%", stats.glyph_run_hit_rate() * 100.0),
+                format!("render cache hits: {:.0}%", stats.render_cache_hit_rate() * 100.0),
+            ];
+            for &(ui_id, time) in stats.slowest(5).iter() {
+                lines.push(format!("{}: {:.2}ms", ui_id, time * 1000.0));
             }
-            match &mut self.data[ui_id_idx] {
-                &mut (ref mut widget, _) => widget,
+            let line_h = font_size as f64 + 2.0;
+            for (i, line) in lines.iter().enumerate() {
+                let pos = [4.0, 4.0 + i as f64 * line_h];
+                self.draw_text(graphics, pos, font, font_size, outline_color, line);
             }
         }
     }
 
-    /// Set the Placing for a particular widget.
-    pub fn set_place(&mut self, ui_id: UIID, pos: Point, dim: Dimensions) {
-        match &mut self.data[ui_id as usize] {
-            &mut (_, ref mut placing) => {
-                *placing = widget::Placing::Place(pos[0], pos[1], dim[0], dim[1])
+}
+
+impl<C> Ui<C>
+    where
+        C: CharacterCache
+{
+    /// Update the state with the given input.
+    pub fn handle_input(&mut self, input: Input) {
+        self.flush_if_rendered();
+        let time = self.time();
+        if let Some(ref mut record) = self.maybe_record {
+            record.push(time, input.clone());
+        }
+        if let Some(ref mut tracer) = self.maybe_tracer {
+            tracer(&TraceEvent::Input(input.clone()));
+        }
+        let pixel_factor = self.pixel_factor();
+        match input {
+            Input::Render(w, h) => {
+                let (win_w, win_h) = (w / pixel_factor, h / pixel_factor);
+                if win_w != self.win_w || win_h != self.win_h {
+                    self.invalidate_render_cache();
+                }
+                self.win_w = win_w;
+                self.win_h = win_h;
+                self.prev_event_was_render = true;
+            },
+            Input::MouseCursor(x, y) => {
+                self.mouse.pos = [x / pixel_factor, y / pixel_factor];
+                let time = self.time();
+                let num_old = self.mouse_history.iter()
+                    .take_while(|&&(t, _)| time - t > MOUSE_HISTORY_DURATION)
+                    .count();
+                if num_old > 1 {
+                    self.mouse_history.drain(..num_old - 1);
+                }
+                self.mouse_history.push((time, self.mouse.pos));
+            },
+            Input::MouseScroll(x, y) => {
+                self.mouse.scroll[0] += x;
+                self.mouse.scroll[1] += y;
+            },
+            Input::Press(button_type) => {
+                use piston::input::Button;
+                use piston::input::MouseButton::{ Left, Middle, Right };
+
+                match button_type {
+                    Button::Mouse(button) => {
+                        match button {
+                            Left => self.mouse.left = ButtonState::Down,
+                            Middle => self.mouse.middle = ButtonState::Down,
+                            Right => self.mouse.right = ButtonState::Down,
+                            // The extra buttons of some mice aren't tracked.
+                            _ => (),
+                        }
+                    },
+                    Button::Keyboard(key) => {
+                        if !self.keys_held.contains(&key) {
+                            self.keys_held.push(key);
+                        }
+                        self.keys_just_pressed.push(key);
+                        self.trigger_shortcut(key);
+                    },
+                }
+            },
+            Input::Release(button_type) => {
+                use piston::input::Button;
+                use piston::input::MouseButton::{ Left, Middle, Right };
+
+                match button_type {
+                    Button::Mouse(button) => {
+                        match button {
+                            Left => self.mouse.left = ButtonState::Up,
+                            Middle => self.mouse.middle = ButtonState::Up,
+                            Right => self.mouse.right = ButtonState::Up,
+                            // The extra buttons of some mice aren't tracked.
+                            _ => (),
+                        }
+                    },
+                    Button::Keyboard(key) => {
+                        self.keys_held.retain(|held| *held != key);
+                        self.keys_just_released.push(key);
+                    },
+                }
+            },
+            Input::Text(text) => self.text_just_entered.push(text),
+        }
+    }
+
+    /// If the previous input was a render, the frame is complete, so flush
+    /// the input and update the widget lifecycles.
+    fn flush_if_rendered(&mut self) {
+        if self.prev_event_was_render {
+            self.run_deferred();
+            // A drag that wasn't dropped on a target ends with the frame in
+            // which the mouse was released.
+            if let ButtonState::Up = self.mouse.left {
+                self.maybe_drag = None;
+            }
+            self.flush_input();
+            self.update_widget_lifecycles();
+            self.diagnostics.clear();
+            self.frame_widget_kinds.clear();
+            self.clip_stack.clear();
+            self.alpha_stack.clear();
+            self.maybe_text_focus = self.maybe_next_text_focus.take();
+            self.maybe_mouse_capture = self.maybe_next_mouse_capture.take();
+            self.prev_mouse_pos = self.mouse.pos;
+            self.mouse_cursor = MouseCursor::Arrow;
+            if let Some(stats) = self.maybe_stats.take() {
+                self.maybe_prev_stats = Some(stats);
+                self.maybe_stats = Some(FrameStats::new());
             }
+            self.prev_glyph_runs = ::std::mem::replace(&mut self.glyph_runs, HashMap::new());
+            self.scratch.reset();
+            self.maybe_damage = None;
+            self.is_fully_damaged = false;
+            self.changed_widgets.clear();
+            if self.maybe_content_hasher.is_some() {
+                self.maybe_content_hasher = Some(ContentHasher::new());
+            }
+            self.poll_theme_watcher();
+            self.update_theme();
+            self.prev_event_was_render = false;
         }
-        self.prev_uiid = ui_id;
     }
 
-    /// Get the UIID of the previous widget.
-    pub fn get_prev_uiid(&self) -> UIID { self.prev_uiid }
+    /// A handle to the queue of closures to be called with the `Ui` once the
+    /// frame's widgets have been drawn. Widget callbacks may capture the
+    /// handle in order to react to the widget by changing the `Ui` (see
+    /// `Deferred`).
+    pub fn deferred(&self) -> Deferred<C> {
+        self.deferred.clone()
+    }
 
-    /// Get the Placing for a particular widget.
-    pub fn get_placing(&self, ui_id: UIID) -> widget::Placing {
-        if ui_id as usize >= self.data.len() { widget::Placing::NoPlace }
-        else {
-            match self.data[ui_id as usize] { (_, ref placing) => *placing }
+    /// Queue the given closure to be called with the `Ui` once the frame's
+    /// widgets have been drawn.
+    pub fn defer<F>(&mut self, callback: F)
+        where
+            C: 'static,
+            F: FnOnce(&mut Ui<C>) + 'static
+    {
+        self.deferred.push(callback);
+    }
+
+    /// Call the closures queued during the frame. This happens automatically
+    /// before the next frame's input is handled, though may be done sooner
+    /// by calling this once the frame's widgets have been drawn.
+    pub fn run_deferred(&mut self) {
+        let deferred = self.deferred.clone();
+        deferred.run(self);
+    }
+
+    /// Trigger the action bound to the given key and the modifiers currently
+    /// held, unless text is being entered and the shortcut could be typing.
+    fn trigger_shortcut(&mut self, key: input::keyboard::Key) {
+        use piston::input::keyboard::Key::{ LAlt, LCtrl, LGui, LShift, RAlt, RCtrl, RGui, RShift };
+        let shortcut = Shortcut {
+            key: key,
+            ctrl: self.is_key_held(LCtrl) || self.is_key_held(RCtrl),
+            shift: self.is_key_held(LShift) || self.is_key_held(RShift),
+            alt: self.is_key_held(LAlt) || self.is_key_held(RAlt),
+            gui: self.is_key_held(LGui) || self.is_key_held(RGui),
+        };
+        if self.maybe_text_focus.is_some() && !shortcut.is_allowed_while_typing() {
+            return
         }
+        if let Some(action) = self.shortcuts.action_for(shortcut) {
+            self.triggered_actions.push(action);
+        }
+    }
+
+    /// The actions whose shortcuts were pressed since the last frame, in the
+    /// order in which they were pressed.
+    pub fn triggered_actions(&self) -> &[ActionId] {
+        &self.triggered_actions[..]
+    }
+
+    /// Whether or not the given action's shortcut was pressed since the last frame.
+    pub fn was_triggered(&self, action: ActionId) -> bool {
+        self.triggered_actions.contains(&action)
     }
+
+    /// Called by widgets that want a particular mouse cursor while hovered.
+    /// The request of the widget drawn last (i.e. the topmost) is kept.
+    pub fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
+        self.mouse_cursor = cursor;
+    }
+
+    /// The mouse cursor requested by the widgets during the current frame,
+    /// for the window code to apply once the widgets are drawn.
+    pub fn mouse_cursor(&self) -> MouseCursor {
+        self.mouse_cursor
+    }
+
+    /// Called each frame by widgets that are taking text input, so that
+    /// shortcuts which could be typing aren't triggered meanwhile.
+    pub fn claim_text_focus(&mut self, ui_id: UIID) {
+        self.maybe_next_text_focus = Some(ui_id);
+    }
+
+    /// The widget that is taking text input, if any.
+    pub fn text_focus(&self) -> Option<UIID> {
+        self.maybe_next_text_focus.or(self.maybe_text_focus)
+    }
+
+    /// The translation of the given key for the current language, or the key
+    /// itself if it hasn't been translated (see `Locale`).
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.locale.tr(key)
+    }
+
+    /// Switch the language in which translated strings are displayed.
+    pub fn set_language(&mut self, language: &str) {
+        self.locale.set_language(language);
+    }
+
+    /// Begin recording the values committed via widgets so that the changes
+    /// may be undone and redone, remembering up to `max_len` changes.
+    pub fn enable_history(&mut self, max_len: usize) {
+        self.maybe_history = Some(History::new(max_len));
+    }
+
+    /// Stop recording changes and forget those recorded.
+    pub fn disable_history(&mut self) {
+        self.maybe_history = None;
+    }
+
+    /// The changes recorded, if history is enabled.
+    pub fn history(&self) -> Option<&History> {
+        self.maybe_history.as_ref()
+    }
+
+    /// The changes recorded, if history is enabled.
+    pub fn history_mut(&mut self) -> Option<&mut History> {
+        self.maybe_history.as_mut()
+    }
+
+    /// Undo the latest change, returning `false` if there was none. The
+    /// widget that made the change re-fires its event with the previous
+    /// value the next time it is drawn.
+    pub fn undo(&mut self) -> bool {
+        self.maybe_history.as_mut().map_or(false, |history| history.undo())
+    }
+
+    /// Redo the latest change that was undone, returning `false` if there
+    /// was none. The widget that made the change re-fires its event with the
+    /// value the next time it is drawn.
+    pub fn redo(&mut self) -> bool {
+        self.maybe_history.as_mut().map_or(false, |history| history.redo())
+    }
+
+    /// Remember the widget's value at the beginning of a change, if history
+    /// is enabled.
+    pub fn begin_change(&mut self, ui_id: UIID, before: Value) {
+        if let Some(ref mut history) = self.maybe_history {
+            history.begin(ui_id, before);
+        }
+    }
+
+    /// Record the change begun by the widget now that its value has been
+    /// committed, if history is enabled.
+    pub fn commit_change(&mut self, ui_id: UIID, after: Value) {
+        self.persist_value(ui_id, after.clone());
+        if let Some(ref mut history) = self.maybe_history {
+            history.commit(ui_id, after);
+        }
+    }
+
+    /// Record a change made by the widget all at once, if history is enabled.
+    pub fn record_change(&mut self, ui_id: UIID, before: Value, after: Value) {
+        self.persist_value(ui_id, after.clone());
+        if let Some(ref mut history) = self.maybe_history {
+            history.push(Change { ui_id: ui_id, before: before, after: after });
+        }
+    }
+
+    /// Take the value that the widget should restore, if a change it made
+    /// was undone or redone since it was last drawn, or a value was loaded
+    /// for it via `Ui::load_state`.
+    pub fn take_restored(&mut self, ui_id: UIID) -> Option<Value> {
+        if let Some(value) = self.loaded.values.remove(&ui_id) {
+            return Some(value)
+        }
+        match self.maybe_history {
+            Some(ref mut history) => history.take_restored(ui_id),
+            None => None,
+        }
+    }
+
+    /// Remember the value committed via the widget so that it's saved by
+    /// `Ui::save_state`.
+    pub fn persist_value(&mut self, ui_id: UIID, value: Value) {
+        self.persisted.values.insert(ui_id, value);
+    }
+
+    /// Remember how far the widget is scrolled so that it's saved by
+    /// `Ui::save_state`.
+    pub fn persist_scroll(&mut self, ui_id: UIID, offset: f64) {
+        self.persisted.scroll_offsets.insert(ui_id, offset);
+    }
+
+    /// The scroll offset loaded for the widget via `Ui::load_state`, if it
+    /// has yet to be restored, i.e. for a list that is only scrolled once
+    /// opened.
+    pub fn restored_scroll(&self, ui_id: UIID) -> Option<f64> {
+        self.loaded.scroll_offsets.get(&ui_id).cloned()
+    }
+
+    /// Take the scroll offset loaded for the widget via `Ui::load_state`, if
+    /// it has yet to be restored.
+    pub fn take_restored_scroll(&mut self, ui_id: UIID) -> Option<f64> {
+        self.loaded.scroll_offsets.remove(&ui_id)
+    }
+
+    /// Remember the layout of the `Dock` so that it's saved by `Ui::save_state`.
+    pub fn persist_dock_layout(&mut self, ui_id: UIID, layout: &DockLayout) {
+        self.persisted.dock_layouts.insert(ui_id, layout.clone());
+    }
+
+    /// Take the layout loaded for the `Dock` via `Ui::load_state`, if it has
+    /// yet to be restored.
+    pub fn take_restored_dock_layout(&mut self, ui_id: UIID) -> Option<DockLayout> {
+        self.loaded.dock_layouts.remove(&ui_id)
+    }
+
+    /// Write the latest value committed via each widget, along with the
+    /// scroll offsets and dock layouts, to the writer as JSON (see the
+    /// `persist` module).
+    pub fn save_state<W: Write>(&self, writer: &mut W) -> Result<(), String> {
+        persist::save(&self.persisted, writer)
+    }
+
+    /// Read the state written by `Ui::save_state` from the reader. Each
+    /// widget restores its saved state the next time it's drawn, re-firing
+    /// its event with its saved value.
+    pub fn load_state<R: Read>(&mut self, reader: &mut R) -> Result<(), String> {
+        let state = try!(persist::load(reader));
+        self.persisted.extend(state.clone());
+        self.loaded = state;
+        Ok(())
+    }
+
+    /// Begin dragging the given payload from the widget with the given UIID,
+    /// drawing the given preview beneath the cursor until the mouse is
+    /// released. Drags may only be started while the left mouse button is
+    /// down, and replace any drag already in progress.
+    pub fn start_drag<T: Any>(&mut self, source: UIID, payload: T, preview: Preview) {
+        if let ButtonState::Down = self.mouse.left {
+            self.maybe_drag = Some(Drag::new(source, payload, preview));
+        }
+    }
+
+    /// The drag in progress, if any. Drop targets may use this to highlight
+    /// themselves when a payload they accept is being dragged.
+    pub fn dragging(&self) -> Option<&Drag> {
+        self.maybe_drag.as_ref()
+    }
+
+    /// Abandon the drag in progress, if any.
+    pub fn cancel_drag(&mut self) {
+        self.maybe_drag = None;
+    }
+
+    /// Declare the given rectangle a target onto which payloads of type `T`
+    /// may be dropped. Returns whether or not such a payload is being dragged
+    /// over the rectangle. If the payload is dropped on it, the drag ends and
+    /// the payload is given to `on_drop`.
+    pub fn drop_target<T, F>(&mut self, pos: Point, dim: Dimensions, on_drop: F) -> bool
+        where
+            T: Any,
+            F: FnOnce(T)
+    {
+        let is_dragging_t = match self.maybe_drag {
+            Some(ref drag) => drag.payload::<T>().is_some(),
+            None => false,
+        };
+        if !is_dragging_t || !rectangle::is_over(pos, self.mouse.pos, dim) {
+            return false
+        }
+        if let ButtonState::Up = self.mouse.left {
+            let drag = self.maybe_drag.take().unwrap();
+            if let Ok(payload) = drag.into_payload().downcast::<T>() {
+                on_drop(*payload);
+            }
+        }
+        true
+    }
+
+    /// Replace the theme at the beginning of the next frame, along with that
+    /// of every `Ui` sharing it.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.fade_to_theme(theme, 0.0);
+    }
+
+    /// Crossfade the theme's colors to those of the given theme over
+    /// `duration` seconds, beginning at the next frame. Changes made directly
+    /// to `ui.theme` while the crossfade is in progress will be overwritten.
+    /// Every `Ui` sharing the theme crossfades to it as well.
+    pub fn fade_to_theme(&mut self, theme: Theme, duration: f64) {
+        let mut shared = self.shared_theme.borrow_mut();
+        shared.theme = theme;
+        shared.duration = duration;
+        shared.generation += 1;
+    }
+
+    /// Load the theme from the file at the given path and reload it whenever
+    /// the file changes. Intended for use during development so that the
+    /// look of an application may be tweaked while it runs.
+    pub fn watch_theme(&mut self, path: &str) {
+        self.maybe_theme_watcher = Some(ThemeWatcher::new(path));
+        self.maybe_theme_error = None;
+    }
+
+    /// Stop watching the theme file, keeping the current theme.
+    pub fn unwatch_theme(&mut self) {
+        self.maybe_theme_watcher = None;
+        self.maybe_theme_error = None;
+    }
+
+    /// The reason the watched theme file failed to load, if its latest
+    /// contents could not be loaded. The previous theme remains in use.
+    pub fn theme_error(&self) -> Option<&str> {
+        self.maybe_theme_error.as_ref().map(|e| &e[..])
+    }
+
+    /// Check the watched theme file for changes.
+    fn poll_theme_watcher(&mut self) {
+        let time = self.time();
+        let maybe_result = match self.maybe_theme_watcher {
+            Some(ref mut watcher) => watcher.poll(time),
+            None => return,
+        };
+        match maybe_result {
+            Some(Ok(theme)) => {
+                self.maybe_theme_error = None;
+                self.set_theme(theme);
+            },
+            Some(Err(e)) => self.maybe_theme_error = Some(e),
+            None => (),
+        }
+    }
+
+    /// Apply any theme newly set on this or a sharing `Ui`, and step the
+    /// crossfade between themes.
+    fn update_theme(&mut self) {
+        let time = self.time();
+        let maybe_pending = {
+            let shared = self.shared_theme.borrow();
+            match shared.generation != self.theme_generation {
+                true => Some((shared.theme.clone(), shared.duration, shared.generation)),
+                false => None,
+            }
+        };
+        if let Some((theme, duration, generation)) = maybe_pending {
+            self.theme_generation = generation;
+            // Begin from wherever any current crossfade has reached.
+            let from = ::std::mem::replace(&mut self.theme, Theme::default());
+            self.maybe_theme_transition = Some(ThemeTransition::new(from, theme, time, duration));
+        }
+        let is_finished = match self.maybe_theme_transition {
+            Some(ref transition) => {
+                self.theme = transition.theme_at(time);
+                transition.is_finished(time)
+            },
+            None => return,
+        };
+        self.invalidate_render_cache();
+        if is_finished {
+            if let Some(transition) = self.maybe_theme_transition.take() {
+                self.theme = transition.target().clone();
+            }
+        }
+    }
+
+    /// Begin recording all inputs passed to the `Ui`, discarding any
+    /// recording already in progress.
+    pub fn start_recording(&mut self) {
+        self.maybe_record = Some(Record::new(self.time()));
+    }
+
+    /// Stop recording inputs and return the recording, if there was one.
+    pub fn stop_recording(&mut self) -> Option<Record> {
+        self.maybe_record.take()
+    }
+
+    /// Return the current time in seconds according to the `Ui`'s clock.
+    /// This is the mock time if one has been set, otherwise the clock's
+    /// time (the precise system time unless given another source).
+    pub fn time(&self) -> f64 {
+        self.clock.now()
+    }
+
+    /// Set a mock time to be returned by `Ui::time` in place of the clock's
+    /// time (i.e. for deterministic playback and testing), or `None` to
+    /// return to using the clock's time.
+    pub fn set_mock_time(&mut self, maybe_time: Option<f64>) {
+        self.clock.set_mock_time(maybe_time);
+    }
+
+    /// The mock time returned by `Ui::time`, if one has been set.
+    pub fn mock_time(&self) -> Option<f64> {
+        self.clock.mock_time()
+    }
+
+    /// Set whether or not widgets should capture the primitives they draw
+    /// (retrievable via `Ui::take_primitives`) rather than drawing them to
+    /// the `Graphics` backend.
+    pub fn capture_primitives(&mut self, capture: bool) {
+        match (capture, self.maybe_primitives.is_some()) {
+            (true, false) => self.maybe_primitives = Some(Vec::new()),
+            (false, true) => self.maybe_primitives = None,
+            _ => (),
+        }
+    }
+
+    /// Whether or not the `Ui` is capturing primitives.
+    pub fn is_capturing_primitives(&self) -> bool {
+        self.maybe_primitives.is_some()
+    }
+
+    /// Take all primitives captured so far, leaving capturing enabled.
+    pub fn take_primitives(&mut self) -> Vec<Primitive> {
+        match self.maybe_primitives {
+            Some(ref mut primitives) => ::std::mem::replace(primitives, Vec::new()),
+            None => Vec::new(),
+        }
+    }
+
+    /// Enable or disable the debug overlay drawn by `Ui::draw`.
+    pub fn debug(&mut self, enabled: bool) {
+        self.debug = enabled;
+    }
+
+    /// Whether or not the debug overlay is enabled.
+    pub fn is_debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Enable or disable the collection of statistics about the drawing of
+    /// each frame, retrievable via `Ui::frame_stats` and shown by the debug
+    /// overlay. Collecting statistics has a small cost of its own.
+    pub fn collect_stats(&mut self, enabled: bool) {
+        match (enabled, self.maybe_stats.is_some()) {
+            (true, false) => {
+                self.maybe_stats = Some(FrameStats::new());
+                self.stats_mark = precise_time_s();
+            },
+            (false, true) => {
+                self.maybe_stats = None;
+                self.maybe_prev_stats = None;
+            },
+            _ => (),
+        }
+    }
+
+    /// Whether or not statistics are being collected.
+    pub fn is_collecting_stats(&self) -> bool {
+        self.maybe_stats.is_some()
+    }
+
+    /// Enable or disable the tracking of the region damaged each frame,
+    /// retrievable via `Ui::damage_region`. Tracking hashes everything drawn
+    /// so that widgets whose drawing changed are noticed, which has a small
+    /// cost of its own.
+    pub fn track_damage(&mut self, enabled: bool) {
+        match (enabled, self.maybe_content_hasher.is_some()) {
+            (true, false) => {
+                self.maybe_content_hasher = Some(ContentHasher::new());
+                self.is_fully_damaged = true;
+            },
+            (false, true) => {
+                self.maybe_content_hasher = None;
+                self.content_hashes.clear();
+            },
+            _ => (),
+        }
+    }
+
+    /// Whether or not the damaged region is being tracked.
+    pub fn is_tracking_damage(&self) -> bool {
+        self.maybe_content_hasher.is_some()
+    }
+
+    /// The statistics of the last complete frame, if collecting statistics.
+    pub fn frame_stats(&self) -> Option<&FrameStats> {
+        self.maybe_prev_stats.as_ref()
+    }
+
+    /// The statistics of the frame being drawn, if collecting statistics,
+    /// i.e. for a `RenderCache` to record its hits and misses.
+    pub fn current_frame_stats_mut(&mut self) -> Option<&mut FrameStats> {
+        self.maybe_stats.as_mut()
+    }
+
+    /// Pass every input received and every change to the state of a widget to
+    /// the given closure, replacing any previous tracer.
+    ///
+    /// ```ignore
+    /// ui.set_tracer(|event| println!("{:?}", event));
+    /// ```
+    pub fn set_tracer<F>(&mut self, tracer: F) where F: FnMut(&TraceEvent) + 'static {
+        self.maybe_tracer = Some(Box::new(tracer));
+    }
+
+    /// Stop tracing.
+    pub fn remove_tracer(&mut self) {
+        self.maybe_tracer = None;
+    }
+
+    /// Note the transition of a widget's state if the state changed, passing
+    /// it to the tracer and marking the widget as damaged once it is placed.
+    /// Called by widgets as their new state is set.
+    pub fn record_transition(&mut self, ui_id: UIID, from: Widget, to: Widget) {
+        if from == to { return }
+        self.changed_widgets.insert(ui_id);
+        if let Some(ref mut tracer) = self.maybe_tracer {
+            tracer(&TraceEvent::Transition { ui_id: ui_id, from: from, to: to });
+        }
+    }
+
+    /// Enable or disable batching of solid rectangles. When enabled, the
+    /// rectangles drawn during a frame are collected and rendered with a
+    /// single draw call per color, which greatly reduces the overhead of
+    /// drawing many widgets. `Ui::draw` (or `Ui::flush_rectangles`) must
+    /// then be called once all widgets have been drawn.
+    pub fn batch_rectangles(&mut self, enabled: bool) {
+        self.batch_rectangles = enabled;
+    }
+
+    /// Whether or not solid rectangles are being batched.
+    pub fn is_batching_rectangles(&self) -> bool {
+        self.batch_rectangles
+    }
+
+    /// Return a handle to the glyph cache given on construction so that it may be shared.
+    pub fn glyph_cache(&self) -> Rc<RefCell<C>> {
+        self.fonts[0].clone()
+    }
+
+    /// Add a font by its glyph cache, returning the `FontId` with which
+    /// widgets may select it.
+    pub fn add_font(&mut self, glyph_cache: C) -> FontId {
+        self.add_shared_font(Rc::new(RefCell::new(glyph_cache)))
+    }
+
+    /// Add a font whose glyph cache may be shared with other `Ui`s.
+    pub fn add_shared_font(&mut self, glyph_cache: Rc<RefCell<C>>) -> FontId {
+        self.fonts.push(glyph_cache);
+        self.fonts.len() - 1
+    }
+
+    /// Return a handle to the glyph cache of the given font, if there is one.
+    pub fn font(&self, font: FontId) -> Option<Rc<RefCell<C>>> {
+        self.fonts.get(font).cloned()
+    }
+
+    /// The glyph cache for the given font, falling back to the first font if
+    /// there is no font with the given `FontId`.
+    fn font_glyph_cache(&self, font: FontId) -> &Rc<RefCell<C>> {
+        self.fonts.get(font).unwrap_or(&self.fonts[0])
+    }
+
+    /// Return the ratio of physical pixels to DPI-independent units.
+    pub fn dpi_factor(&self) -> f64 {
+        self.dpi_factor
+    }
+
+    /// Set the ratio of physical pixels to DPI-independent units. All widget
+    /// positions, dimensions, frame widths, font sizes and mouse coordinates
+    /// are given in DPI-independent units and scaled by this factor. Backends
+    /// should update this whenever the window moves to a monitor with a
+    /// different scale factor.
+    pub fn set_dpi_factor(&mut self, dpi_factor: f64) {
+        assert!(dpi_factor > 0.0, "The DPI factor must be greater than zero.");
+        let scale = self.scale;
+        self.set_pixel_factor(dpi_factor, scale);
+    }
+
+    /// Return the factor by which the whole interface is zoomed.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Zoom the whole interface by the given factor, independently of the
+    /// DPI factor, i.e. in reaction to Ctrl+= and Ctrl+- shortcuts. Widget
+    /// positions, dimensions and font sizes are scaled along with the window
+    /// and mouse coordinates, so that hit testing is unaffected.
+    pub fn set_scale(&mut self, scale: f64) {
+        assert!(scale > 0.0, "The scale must be greater than zero.");
+        let dpi_factor = self.dpi_factor;
+        self.set_pixel_factor(dpi_factor, scale);
+    }
+
+    /// Return the ratio of physical pixels to the units in which widgets are
+    /// laid out, which is the DPI factor multiplied by the scale.
+    pub fn pixel_factor(&self) -> f64 {
+        self.dpi_factor * self.scale
+    }
+
+    /// Set the DPI factor and scale, converting the window dimensions and
+    /// mouse position to the new units.
+    fn set_pixel_factor(&mut self, dpi_factor: f64, scale: f64) {
+        let ratio = self.pixel_factor() / (dpi_factor * scale);
+        self.win_w = self.win_w * ratio;
+        self.win_h = self.win_h * ratio;
+        self.mouse.pos = [self.mouse.pos[0] * ratio, self.mouse.pos[1] * ratio];
+        self.prev_mouse_pos = self.mouse.pos;
+        self.mouse_history.clear();
+        self.dpi_factor = dpi_factor;
+        self.scale = scale;
+        self.invalidate_render_cache();
+        // Glyph widths are rounded at the physical size, so remeasure.
+        self.glyph_runs.clear();
+        self.prev_glyph_runs.clear();
+        self.text_widths.clear();
+        self.char_fonts.clear();
+    }
+
+    /// Return the font size at which glyphs should be rasterized for the given
+    /// DPI-independent font size.
+    pub fn scaled_font_size(&self, size: FontSize) -> FontSize {
+        (size as f64 * self.pixel_factor()).round() as FontSize
+    }
+
+    /// Return the current mouse state. While the mouse is over an area
+    /// captured by a widget (see `Ui::capture_mouse`), it is given as though
+    /// it were outside of the window, so that widgets beneath don't react.
+    pub fn get_mouse_state(&self) -> Mouse {
+        match self.maybe_mouse_capture {
+            Some((_, pos, dim)) if rectangle::is_over(pos, self.mouse.pos, dim) =>
+                Mouse { pos: OUTSIDE_WINDOW, ..self.mouse },
+            _ => self.mouse,
+        }
+    }
+
+    /// Return the mouse state as given to the widget with the given UIID,
+    /// which sees the mouse over the area it has captured.
+    pub fn mouse_state_for(&self, ui_id: UIID) -> Mouse {
+        match self.maybe_mouse_capture {
+            Some((capturer, _, _)) if capturer == ui_id => self.mouse,
+            _ => self.get_mouse_state(),
+        }
+    }
+
+    /// Called each frame by a widget drawn over others (i.e. a popup) to
+    /// capture the mouse while it is over the given area. As widgets beneath
+    /// may be set before it, the capture takes effect from the next frame.
+    pub fn capture_mouse(&mut self, ui_id: UIID, pos: Point, dim: Dimensions) {
+        self.maybe_next_mouse_capture = Some((ui_id, pos, dim));
+    }
+
+    /// Whether or not the mouse has moved since the previous frame.
+    pub fn mouse_moved(&self) -> bool {
+        self.mouse.pos != self.prev_mouse_pos
+    }
+
+    /// The time and position of each movement of the mouse within the last
+    /// tenth of a second, oldest first, i.e. for recognising gestures.
+    pub fn mouse_history(&self) -> &[(f64, Point)] {
+        let time = self.time();
+        let start = self.mouse_history.iter()
+            .position(|&(t, _)| time - t <= MOUSE_HISTORY_DURATION)
+            .unwrap_or(self.mouse_history.len());
+        &self.mouse_history[start..]
+    }
+
+    /// The velocity of the mouse in pixels per second, averaged over its
+    /// movements within the last tenth of a second, i.e. for throwing
+    /// something dragged or flicking a list into kinetic scrolling. A mouse
+    /// that hasn't moved in that time is at rest.
+    pub fn mouse_velocity(&self) -> [f64; 2] {
+        let time = self.time();
+        let start = match self.mouse_history.iter().position(|&(t, _)| time - t <= MOUSE_HISTORY_DURATION) {
+            Some(start) => start,
+            None => return [0.0, 0.0],
+        };
+        // The mouse was where it last moved to before the window as the
+        // window began, so the first movement within it is measured too.
+        let (first_t, first_pos) = match start {
+            0 => self.mouse_history[0],
+            _ => (time - MOUSE_HISTORY_DURATION, self.mouse_history[start - 1].1),
+        };
+        let (last_t, last_pos) = self.mouse_history[self.mouse_history.len() - 1];
+        // Measure up to now, so that a mouse slowing to a stop slows too.
+        let dt = (time - first_t).max(last_t - first_t);
+        match dt > 0.0 {
+            true => [(last_pos[0] - first_pos[0]) / dt, (last_pos[1] - first_pos[1]) / dt],
+            false => [0.0, 0.0],
+        }
+    }
+
+    /// Whether or not the given key is currently held down.
+    pub fn is_key_held(&self, key: input::keyboard::Key) -> bool {
+        self.keys_held.contains(&key)
+    }
+
+    /// Whether or not the shortcut modifier (Ctrl, or Cmd on OS X) is held down.
+    pub fn is_shortcut_modifier_held(&self) -> bool {
+        use piston::input::keyboard::Key::{ LCtrl, RCtrl, LGui, RGui };
+        self.keys_held.iter().any(|&key| key == LCtrl || key == RCtrl || key == LGui || key == RGui)
+    }
+
+    /// Whether or not either Ctrl key is held down, i.e. to temporarily
+    /// disable snapping for finer control.
+    pub fn is_ctrl_held(&self) -> bool {
+        use piston::input::keyboard::Key::{ LCtrl, RCtrl };
+        self.is_key_held(LCtrl) || self.is_key_held(RCtrl)
+    }
+
+    /// Use the given clipboard (i.e. the system clipboard) for copying and
+    /// pasting text in place of the clipboard local to the `Ui`.
+    pub fn set_clipboard(&mut self, clipboard: Box<Clipboard>) {
+        self.clipboard = clipboard;
+    }
+
+    /// The clipboard used for copying and pasting text.
+    pub fn clipboard(&mut self) -> &mut Clipboard {
+        &mut *self.clipboard
+    }
+
+    /// Return a copy of the keys pressed since the last frame. Prefer
+    /// `Ui::pressed_keys`, which doesn't copy them.
+    pub fn get_pressed_keys(&self) -> Vec<input::keyboard::Key> {
+        self.keys_just_pressed.clone()
+    }
+
+    /// The keys pressed since the last frame.
+    pub fn pressed_keys(&self) -> &[input::keyboard::Key] {
+        &self.keys_just_pressed[..]
+    }
+
+    /// The text entered since the last frame.
+    pub fn entered_text(&self) -> &[String] {
+        &self.text_just_entered[..]
+    }
+
+    /// Call the given function with the `Ui` and the text entered since the
+    /// last frame, for widgets that must change the `Ui` as they handle each
+    /// piece of text. The text is lent rather than copied.
+    pub fn with_entered_text<F, R>(&mut self, f: F) -> R where F: FnOnce(&mut Ui<C>, &[String]) -> R {
+        let entered_text = ::std::mem::replace(&mut self.text_just_entered, Vec::new());
+        let result = f(self, &entered_text[..]);
+        self.text_just_entered = entered_text;
+        result
+    }
+
+    /// Mark the keys pressed and text entered since the last frame as handled
+    /// by the widget with the given UIID, so that the widgets set after it
+    /// may leave them be.
+    pub fn consume_text_input(&mut self, ui_id: UIID) {
+        self.maybe_text_consumer = Some(ui_id);
+    }
+
+    /// The widget that handled the keys pressed and text entered since the
+    /// last frame, if one has done so.
+    pub fn text_input_consumer(&self) -> Option<UIID> {
+        self.maybe_text_consumer
+    }
+
+    /// An empty string for temporary use while drawing, reusing the
+    /// allocation of one given back via `Ui::recycle_string`.
+    pub fn scratch_string(&mut self) -> String {
+        self.scratch.string()
+    }
+
+    /// Give back a string taken via `Ui::scratch_string` once it is no
+    /// longer needed.
+    pub fn recycle_string(&mut self, string: String) {
+        self.scratch.give_string(string);
+    }
+
+    /// An empty list of points for temporary use while drawing, reusing the
+    /// allocation of one given back via `Ui::recycle_points`.
+    pub fn scratch_points(&mut self) -> Vec<Point> {
+        self.scratch.points()
+    }
+
+    /// Give back a list of points taken via `Ui::scratch_points` once it is
+    /// no longer needed.
+    pub fn recycle_points(&mut self, points: Vec<Point>) {
+        self.scratch.give_points(points);
+    }
+
+    /// Return a copy of the text entered since the last frame. Prefer
+    /// `Ui::entered_text`, which doesn't copy it.
+    pub fn get_entered_text(&self) -> Vec<String> {
+        self.text_just_entered.clone()
+    }
+
+    /// Return a mutable reference to the widget that matches the given ui_id
+    pub fn get_widget(&mut self, ui_id: UIID, default: Widget) -> &mut Widget {
+        let existing = self.widget_slot(ui_id).0;
+        match existing {
+            Widget::NoWidget => self.widget_slot(ui_id).0 = default,
+            _ if !existing.matches(&default) => {
+                self.diagnostics.push(Diagnostic::VariantConflict {
+                    ui_id: ui_id,
+                    existing: existing.name(),
+                    requested: default.name(),
+                });
+                self.widget_slot(ui_id).0 = default;
+            },
+            _ => (),
+        }
+        &mut self.widget_slot(ui_id).0
+    }
+
+    /// Return the widget associated with the given UIID, initialising it with
+    /// `default` if there is none. Unlike `get_widget`, a widget of a different
+    /// kind is left untouched and the conflict is returned as an `Err`.
+    pub fn try_get_widget(&mut self, ui_id: UIID, default: Widget) -> Result<&mut Widget, Diagnostic> {
+        let existing = self.widget_slot(ui_id).0;
+        match existing {
+            Widget::NoWidget => self.widget_slot(ui_id).0 = default,
+            _ if !existing.matches(&default) => return Err(Diagnostic::VariantConflict {
+                ui_id: ui_id,
+                existing: existing.name(),
+                requested: default.name(),
+            }),
+            _ => (),
+        }
+        Ok(&mut self.widget_slot(ui_id).0)
+    }
+
+    /// The slot in `data` for the given UIID, inserting an empty one if there is none.
+    fn widget_slot(&mut self, ui_id: UIID) -> &mut (Widget, widget::Placing) {
+        match self.data.entry(ui_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert((Widget::NoWidget, widget::Placing::NoPlace)),
+        }
+    }
+
+    /// The number of widgets for which the `Ui` is storing state.
+    pub fn widget_count(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The number of widgets for which the `Ui` may store state without
+    /// reallocating.
+    pub fn widget_capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Reserve room for the state of at least `additional` more widgets,
+    /// i.e. before setting a large number of widgets for the first time.
+    pub fn reserve_widgets(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    /// The problems (such as UIID conflicts) that have been detected and
+    /// recovered from so far during the current frame.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics[..]
+    }
+
+    /// Compare the widgets set during the frame that just finished with those
+    /// set during the frame before it to determine which appeared and which
+    /// disappeared. Everything kept for a widget that disappeared is freed,
+    /// so a widget that reappears begins again from its default state.
+    fn update_widget_lifecycles(&mut self) {
+        let prev: HashSet<UIID> = self.prev_updated_widgets.iter().cloned().collect();
+        let current: HashSet<UIID> = self.updated_widgets.iter().cloned().collect();
+        self.appeared_widgets = self.updated_widgets.iter()
+            .filter(|ui_id| !prev.contains(*ui_id)).cloned().collect();
+        self.disappeared_widgets = self.prev_updated_widgets.iter()
+            .filter(|ui_id| !current.contains(*ui_id)).cloned().collect();
+        for ui_id in self.disappeared_widgets.iter() {
+            self.color_transitions.remove(ui_id);
+            self.parents.remove(ui_id);
+            self.content_hashes.remove(ui_id);
+            self.user_states.remove(ui_id);
+            self.widget_memory.remove(ui_id);
+            self.data.remove(ui_id);
+        }
+        self.prev_updated_widgets = ::std::mem::replace(&mut self.updated_widgets, Vec::new());
+    }
+
+    /// The UIIDs of the widgets that were set during the last frame but not
+    /// the frame before it, in the order in which they were set.
+    pub fn widgets_appeared(&self) -> &[UIID] {
+        &self.appeared_widgets[..]
+    }
+
+    /// The UIIDs of the widgets that were set during the frame before last
+    /// but not during the last frame.
+    pub fn widgets_disappeared(&self) -> &[UIID] {
+        &self.disappeared_widgets[..]
+    }
+
+    /// Whether or not the widget with the given UIID was set during the last
+    /// frame or has been set so far during the current frame.
+    pub fn is_widget_alive(&self, ui_id: UIID) -> bool {
+        self.updated_widgets.contains(&ui_id) || self.prev_updated_widgets.contains(&ui_id)
+    }
+
+    /// Make the widget a child of the given parent, i.e. a part of a
+    /// composite widget. The relationship lasts until the child disappears.
+    pub fn set_parent(&mut self, child: UIID, parent: UIID) {
+        self.parents.insert(child, parent);
+    }
+
+    /// The parent of the widget, if it has been given one.
+    pub fn parent_of(&self, ui_id: UIID) -> Option<UIID> {
+        self.parents.get(&ui_id).cloned()
+    }
+
+    /// The UIIDs of the widget's children, in ascending order.
+    pub fn children_of(&self, ui_id: UIID) -> Vec<UIID> {
+        let mut children: Vec<UIID> = self.parents.iter()
+            .filter(|&(_, &parent)| parent == ui_id)
+            .map(|(&child, _)| child)
+            .collect();
+        children.sort();
+        children
+    }
+
+    /// Whether or not widget `a` is the parent of widget `b`, or of one of
+    /// `b`'s ancestors.
+    pub fn is_ancestor(&self, a: UIID, b: UIID) -> bool {
+        let mut maybe_parent = self.parent_of(b);
+        while let Some(parent) = maybe_parent {
+            if parent == a { return true }
+            maybe_parent = self.parent_of(parent);
+        }
+        false
+    }
+
+    /// The UIIDs of the living widgets (see `Ui::is_widget_alive`) whose
+    /// placing intersects the given rectangle, in ascending order.
+    pub fn widgets_intersecting(&self, pos: Point, dim: Dimensions) -> Vec<UIID> {
+        let alive: HashSet<UIID> = self.updated_widgets.iter()
+            .chain(self.prev_updated_widgets.iter())
+            .cloned()
+            .collect();
+        let mut ui_ids: Vec<UIID> = self.data.iter().filter_map(|(&ui_id, &(_, ref placing))| {
+            match *placing {
+                widget::Placing::Place(x, y, w, h) if alive.contains(&ui_id)
+                    && x < pos[0] + dim[0] && pos[0] < x + w
+                    && y < pos[1] + dim[1] && pos[1] < y + h => Some(ui_id),
+                _ => None,
+            }
+        }).collect();
+        ui_ids.sort();
+        ui_ids
+    }
+
+    /// Return a mutable reference to the user state of type `T` stored for the
+    /// widget with the given UIID. If there is no state or the stored state is
+    /// of a different type, it will be replaced with the given `default`.
+    pub fn get_user_state<T: Any>(&mut self, ui_id: UIID, default: T) -> &mut T {
+        let is_t = match self.user_states.get(&ui_id) {
+            Some(state) => (**state).is::<T>(),
+            None => false,
+        };
+        if !is_t {
+            self.user_states.insert(ui_id, Box::new(default));
+        }
+        self.user_states.get_mut(&ui_id).unwrap().downcast_mut::<T>().unwrap()
+    }
+
+    /// Return a reference to the user state stored for the given UIID if there
+    /// is some and it is of type `T`.
+    pub fn user_state<T: Any>(&self, ui_id: UIID) -> Option<&T> {
+        match self.user_states.get(&ui_id) {
+            Some(state) => (**state).downcast_ref::<T>(),
+            None => None,
+        }
+    }
+
+    /// Store the given user state for the widget with the given UIID,
+    /// replacing any previously stored state.
+    pub fn set_user_state<T: Any>(&mut self, ui_id: UIID, state: T) {
+        self.user_states.insert(ui_id, Box::new(state));
+    }
+
+    /// Remove and return the user state stored for the given UIID.
+    pub fn remove_user_state(&mut self, ui_id: UIID) -> Option<Box<Any>> {
+        self.user_states.remove(&ui_id)
+    }
+
+    /// Return a reference to the memory kept by the widget with the given UIID
+    /// if there is some and it is of type `T`.
+    ///
+    /// Widget memory is for a widget's implementation (including `CustomWidget`s)
+    /// and is kept separately from the user state.
+    pub fn widget_memory<T: Any>(&self, ui_id: UIID) -> Option<&T> {
+        match self.widget_memory.get(&ui_id) {
+            Some(memory) => (**memory).downcast_ref::<T>(),
+            None => None,
+        }
+    }
+
+    /// Store the memory of the widget with the given UIID, replacing any that
+    /// was previously stored.
+    pub fn set_widget_memory<T: Any>(&mut self, ui_id: UIID, memory: T) {
+        self.widget_memory.insert(ui_id, Box::new(memory));
+    }
+
+    /// Remove and return the memory of the widget with the given UIID if there
+    /// is some and it is of type `T`.
+    pub fn take_widget_memory<T: Any>(&mut self, ui_id: UIID) -> Option<T> {
+        match self.widget_memory.remove(&ui_id).map(|memory| memory.downcast::<T>()) {
+            Some(Ok(memory)) => Some(*memory),
+            _ => None,
+        }
+    }
+
+    /// Set the Placing for a particular widget.
+    pub fn set_place(&mut self, ui_id: UIID, pos: Point, dim: Dimensions) {
+        // Custom widgets may be placed without having been given a `Widget` variant.
+        let new_placing = widget::Placing::Place(pos[0], pos[1], dim[0], dim[1]);
+        let (kind, prev_placing) = match self.widget_slot(ui_id) {
+            &mut (ref widget, ref mut placing) => {
+                let prev_placing = ::std::mem::replace(placing, new_placing);
+                (widget.name(), prev_placing)
+            }
+        };
+        // Whatever was drawn since the previous widget was set is taken to
+        // have been drawn by this one, so that a change in its value, text or
+        // style damages it even where its state is unchanged.
+        let is_content_changed = match self.maybe_content_hasher.take() {
+            Some(hasher) => {
+                let content_hash = hasher.finish();
+                self.maybe_content_hasher = Some(ContentHasher::new());
+                self.content_hashes.insert(ui_id, content_hash) != Some(content_hash)
+            },
+            None => false,
+        };
+        // A widget that moved must be redrawn where it was as well as where it is.
+        let time = self.time();
+        let is_changed = self.changed_widgets.remove(&ui_id)
+            || is_content_changed
+            || self.color_transitions.get(&ui_id).map_or(false, |t| !t.is_finished(time));
+        if prev_placing != new_placing {
+            if let widget::Placing::Place(x, y, w, h) = prev_placing {
+                self.mark_damaged([x, y], [w, h]);
+            }
+            self.mark_damaged(pos, dim);
+        } else if is_changed {
+            self.mark_damaged(pos, dim);
+        }
+        if cfg!(debug_assertions) {
+            match self.frame_widget_kinds.entry(ui_id) {
+                Entry::Occupied(entry) => self.diagnostics.push(Diagnostic::DuplicateUIID {
+                    ui_id: ui_id,
+                    first: *entry.get(),
+                    second: kind,
+                }),
+                Entry::Vacant(entry) => { entry.insert(kind); },
+            }
+        }
+        self.prev_uiid = ui_id;
+        self.updated_widgets.push(ui_id);
+        if let Some(ref mut stats) = self.maybe_stats {
+            let now = precise_time_s();
+            stats.widget_times.push((ui_id, now - self.stats_mark));
+            self.stats_mark = now;
+        }
+    }
+
+    /// The number of widgets set so far during the current frame.
+    pub fn updated_widget_count(&self) -> usize {
+        self.updated_widgets.len()
+    }
+
+    /// The UIIDs of the widgets set during the current frame, beginning with
+    /// the widget at the given index (see `updated_widget_count`).
+    pub fn widgets_updated_since(&self, idx: usize) -> &[UIID] {
+        &self.updated_widgets[cmp::min(idx, self.updated_widgets.len())..]
+    }
+
+    /// Treat the given widgets as having been set during the current frame
+    /// without updating them, i.e. when drawing them from a `RenderCache`.
+    pub fn keep_widgets_alive(&mut self, ui_ids: &[UIID]) {
+        self.updated_widgets.extend(ui_ids.iter().cloned());
+        if let Some(&ui_id) = ui_ids.last() {
+            self.prev_uiid = ui_id;
+        }
+    }
+
+    /// The generation of the render cache. Cached renderings from an earlier
+    /// generation are out of date.
+    pub fn render_cache_generation(&self) -> u64 {
+        self.render_cache_generation
+    }
+
+    /// Invalidate every cached rendering so that all containers are redrawn.
+    pub fn invalidate_render_cache(&mut self) {
+        self.render_cache_generation += 1;
+        self.is_fully_damaged = true;
+    }
+
+    /// Mark the given rectangle as needing to be redrawn this frame. Widgets
+    /// are marked automatically when their state, placement or drawing
+    /// changes, so this is for changes drawn outside of any widget's area,
+    /// e.g. a popup drawn beyond the widget that opened it.
+    pub fn mark_damaged(&mut self, pos: Point, dim: Dimensions) {
+        if dim[0] <= 0.0 || dim[1] <= 0.0 { return }
+        self.maybe_damage = Some(match self.maybe_damage {
+            Some((d_pos, d_dim)) => union_rect(d_pos, d_dim, pos, dim),
+            None => (pos, dim),
+        });
+    }
+
+    /// The region of the window that changed since the last frame, as the
+    /// position and dimensions of the smallest rectangle containing every
+    /// change, or `None` if nothing changed. Backends that support partial
+    /// presentation may redraw and present only this region. Should be
+    /// called once every widget has been set for the frame, as widgets that
+    /// were set last frame but not yet this frame are considered removed.
+    /// Unless damage is being tracked (see `Ui::track_damage`), the whole
+    /// window is reported.
+    pub fn damage_region(&self) -> Option<(Point, Dimensions)> {
+        if self.is_fully_damaged || self.maybe_content_hasher.is_none() {
+            return Some(([0.0, 0.0], [self.win_w, self.win_h]));
+        }
+        let current: HashSet<UIID> = self.updated_widgets.iter().cloned().collect();
+        self.prev_updated_widgets.iter()
+            .filter(|ui_id| !current.contains(*ui_id))
+            .filter_map(|ui_id| match self.data.get(ui_id) {
+                Some(&(_, widget::Placing::Place(x, y, w, h))) => Some(([x, y], [w, h])),
+                _ => None,
+            })
+            .fold(self.maybe_damage, |maybe_damage, (pos, dim)| Some(match maybe_damage {
+                Some((d_pos, d_dim)) => union_rect(d_pos, d_dim, pos, dim),
+                None => (pos, dim),
+            }))
+    }
+
+    /// Whether or not anything changed since the last frame.
+    pub fn is_damaged(&self) -> bool {
+        self.damage_region().is_some()
+    }
+
+    /// Whether or not the widgets within the given rectangle may respond to
+    /// the current input and so must be updated rather than drawn from a cache.
+    pub fn needs_redraw(&self, pos: Point, dim: Dimensions) -> bool {
+        let time = self.time();
+        rectangle::is_over(pos, self.mouse.pos, dim)
+            || rectangle::is_over(pos, self.prev_mouse_pos, dim)
+            || !self.keys_just_pressed.is_empty()
+            || !self.keys_just_released.is_empty()
+            || !self.text_just_entered.is_empty()
+            || self.color_transitions.values().any(|t| !t.is_finished(time))
+    }
+
+    /// Return the color with which the widget should be drawn this frame
+    /// while transitioning towards the given target color. The transition
+    /// lasts for the Theme's `transition_duration`.
+    pub fn transition_color(&mut self, ui_id: UIID, target: Color) -> Color {
+        let time = self.time();
+        let duration = self.theme.transition_duration;
+        if duration <= 0.0 { return target }
+        if !self.color_transitions.contains_key(&ui_id) {
+            self.color_transitions.insert(ui_id, ColorTransition::new(target, time));
+        }
+        let transition = self.color_transitions.get_mut(&ui_id).unwrap();
+        transition.retarget(target, time, duration);
+        transition.color_at(time)
+    }
+
+    /// Return the UIID of the topmost widget under the given point, that is
+    /// the most recently drawn widget whose rectangle contains the point.
+    /// Widgets set during the current frame are checked before those set
+    /// during the previous frame.
+    pub fn widget_under(&self, point: Point) -> Option<UIID> {
+        let is_over = |ui_id: &&UIID| match self.get_placing(**ui_id) {
+            widget::Placing::Place(x, y, w, h) => rectangle::is_over([x, y], point, [w, h]),
+            widget::Placing::NoPlace => false,
+        };
+        match self.updated_widgets.iter().rev().find(&is_over) {
+            Some(&ui_id) => Some(ui_id),
+            None => self.prev_updated_widgets.iter().rev().find(&is_over).map(|&ui_id| ui_id),
+        }
+    }
+
+    /// Return the position and dimensions of the widget with the given UIID
+    /// if it has been placed.
+    pub fn placing_of(&self, ui_id: UIID) -> Option<(Point, Dimensions)> {
+        match self.get_placing(ui_id) {
+            widget::Placing::Place(x, y, w, h) => Some(([x, y], [w, h])),
+            widget::Placing::NoPlace => None,
+        }
+    }
+
+    /// Return an iterator over the UIID, state, position and dimensions of
+    /// every widget that has been placed, in ascending order of UIID.
+    pub fn widgets(&self) -> Widgets {
+        let mut ui_ids: Vec<UIID> = self.data.keys().cloned().collect();
+        ui_ids.sort();
+        Widgets { data: &self.data, ui_ids: ui_ids.into_iter() }
+    }
+
+    /// Get the UIID of the previous widget.
+    pub fn get_prev_uiid(&self) -> UIID { self.prev_uiid }
+
+    /// Get the Placing for a particular widget.
+    pub fn get_placing(&self, ui_id: UIID) -> widget::Placing {
+        self.data.get(&ui_id).map_or(widget::Placing::NoPlace, |&(_, placing)| placing)
+    }
+}
+
+/// An iterator over the UIID, state, position and dimensions of
+/// every widget that has been placed within a `Ui`.
+pub struct Widgets<'a> {
+    data: &'a HashMap<UIID, (Widget, widget::Placing)>,
+    ui_ids: ::std::vec::IntoIter<UIID>,
+}
+
+impl<'a> Iterator for Widgets<'a> {
+    type Item = (UIID, Widget, Point, Dimensions);
+    fn next(&mut self) -> Option<(UIID, Widget, Point, Dimensions)> {
+        loop {
+            let ui_id = match self.ui_ids.next() {
+                Some(ui_id) => ui_id,
+                None => return None,
+            };
+            if let (widget, widget::Placing::Place(x, y, w, h)) = self.data[&ui_id] {
+                return Some((ui_id, widget, [x, y], [w, h]));
+            }
+        }
+    }
+}
+
+/// A theme shared between several `Ui`s, along with the duration over which
+/// to crossfade to it and the number of times it has been set, by which each
+/// `Ui` notices that it has changed.
+struct SharedTheme {
+    theme: Theme,
+    duration: f64,
+    generation: u64,
+}
+
+/// Hashes whatever is drawn, so that a widget's drawing may be compared with
+/// that of the previous frame without keeping it. Positions and colors are
+/// hashed to a precision far finer than can be seen, as floats aren't `Hash`.
+struct ContentHasher(SipHasher);
+
+impl ContentHasher {
+    fn new() -> ContentHasher {
+        ContentHasher(SipHasher::new())
+    }
+
+    fn add_f64(&mut self, value: f64) {
+        self.0.write_i64((value * 1024.0).round() as i64);
+    }
+
+    fn add_point(&mut self, point: Point) {
+        self.add_f64(point[0]);
+        self.add_f64(point[1]);
+    }
+
+    fn add_color(&mut self, color: Color) {
+        for &component in color.0.iter() {
+            self.0.write_i32((component * 65536.0).round() as i32);
+        }
+    }
+
+    fn add_gradient(&mut self, gradient: Gradient) {
+        match gradient {
+            Gradient::Linear(a, b, direction) => {
+                self.0.write_u8(match direction {
+                    GradientDirection::Horizontal => 0,
+                    GradientDirection::Vertical => 1,
+                });
+                self.add_color(a);
+                self.add_color(b);
+            },
+            Gradient::Radial(a, b) => {
+                self.0.write_u8(2);
+                self.add_color(a);
+                self.add_color(b);
+            },
+        }
+    }
+
+    fn add_text(&mut self, pos: Point, font: FontId, size: FontSize, color: Color, text: &str) {
+        self.add_point(pos);
+        self.0.write_usize(font);
+        self.0.write_u32(size);
+        self.add_color(color);
+        self.0.write_usize(text.len());
+        self.0.write(text.as_bytes());
+    }
+
+    /// Add the given primitive to the hash, preceded by its kind.
+    fn add_primitive(&mut self, primitive: &Primitive) {
+        match *primitive {
+            Primitive::Rectangle { pos, dim, color } => {
+                self.0.write_u8(0);
+                self.add_point(pos);
+                self.add_point(dim);
+                self.add_color(color);
+            },
+            Primitive::RoundedRectangle { pos, dim, radius, color } => {
+                self.0.write_u8(1);
+                self.add_point(pos);
+                self.add_point(dim);
+                self.add_f64(radius);
+                self.add_color(color);
+            },
+            Primitive::Line { line, width, round, color } => {
+                self.0.write_u8(2);
+                self.add_point([line[0], line[1]]);
+                self.add_point([line[2], line[3]]);
+                self.add_f64(width);
+                self.0.write_u8(round as u8);
+                self.add_color(color);
+            },
+            Primitive::Ellipse { pos, dim, color } => {
+                self.0.write_u8(3);
+                self.add_point(pos);
+                self.add_point(dim);
+                self.add_color(color);
+            },
+            Primitive::Polygon { ref points, color } => {
+                self.0.write_u8(4);
+                self.0.write_usize(points.len());
+                for &point in points.iter() {
+                    self.add_point(point);
+                }
+                self.add_color(color);
+            },
+            Primitive::Gradient { pos, dim, gradient } => {
+                self.0.write_u8(5);
+                self.add_point(pos);
+                self.add_point(dim);
+                self.add_gradient(gradient);
+            },
+            Primitive::Text { pos, font, size, color, ref text } => {
+                self.0.write_u8(6);
+                self.add_text(pos, font, size, color, text);
+            },
+            Primitive::Image { id, maybe_src_rect, pos, dim, maybe_color } => {
+                self.0.write_u8(7);
+                self.0.write_usize(id);
+                if let Some(src_rect) = maybe_src_rect {
+                    for &edge in src_rect.iter() {
+                        self.0.write_i32(edge);
+                    }
+                }
+                self.add_point(pos);
+                self.add_point(dim);
+                if let Some(color) = maybe_color {
+                    self.add_color(color);
+                }
+            },
+            Primitive::PushClip { pos, dim } => {
+                self.0.write_u8(8);
+                self.add_point(pos);
+                self.add_point(dim);
+            },
+            Primitive::PopClip => self.0.write_u8(9),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+/// The smallest rectangle containing both of the given rectangles.
+fn union_rect(a_pos: Point, a_dim: Dimensions, b_pos: Point, b_dim: Dimensions) -> (Point, Dimensions) {
+    let left = a_pos[0].min(b_pos[0]);
+    let top = a_pos[1].min(b_pos[1]);
+    let right = (a_pos[0] + a_dim[0]).max(b_pos[0] + b_dim[0]);
+    let bottom = (a_pos[1] + a_dim[1]).max(b_pos[1] + b_dim[1]);
+    ([left, top], [right - left, bottom - top])
 }
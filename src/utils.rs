@@ -11,6 +11,32 @@ pub fn clampf32(f: f32) -> f32 {
     if f < 0f32 { 0f32 } else if f > 1f32 { 1f32 } else { f }
 }
 
+/// Snap the value to the nearest multiple of `step` from `min`, keeping it
+/// within `min` and `max`.
+pub fn snap<T: Float + PartialOrd>(value: T, min: T, max: T, step: T) -> T {
+    if step.to_f64().unwrap() <= 0.0 { return value }
+    let snapped = min + ((value - min) / step).round() * step;
+    if min < max { clamp(snapped, min, max) } else { clamp(snapped, max, min) }
+}
+
+/// The distance between labelled ticks on a scale of `scale` pixels to each
+/// unit, a one, two or five times a power of ten, such that the ticks are at
+/// least `min_spacing` pixels apart.
+pub fn tick_step(min_spacing: f64, scale: f64) -> f64 {
+    let min_step = min_spacing / scale;
+    let magnitude = 10.0f64.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].iter().map(|m| m * magnitude).find(|&step| step >= min_step)
+        .unwrap_or(magnitude * 10.0)
+}
+
+/// The number of decimal places needed to label ticks the given step apart.
+pub fn tick_precision(step: f64) -> usize {
+    match step < 1.0 {
+        true => (-step.log10().floor()) as usize,
+        false => 0,
+    }
+}
+
 /// Compare two f64s and return an Ordering.
 pub fn compare_f64s(a: f64, b: f64) -> Ordering {
     if a > b { Greater }
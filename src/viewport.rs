@@ -0,0 +1,244 @@
+use callback::Reactable;
+use cursor::MouseCursor;
+use dimensions::Dimensions;
+use graphics::Graphics;
+use graphics::character::CharacterCache;
+use mouse::ButtonState::Down;
+use point::Point;
+use rectangle;
+use theme::WidgetStyle;
+use ui::{ UIID, Ui };
+use utils::clamp;
+use widget::Widget;
+
+/// The factor by which each step of the mouse wheel zooms.
+const ZOOM_STEP: f64 = 1.1;
+
+/// The mapping between positions on a viewport's canvas and on the screen.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Transform {
+    /// The screen position of the viewport's top left, within its frame.
+    pub origin: Point,
+    /// The canvas position shown at the origin.
+    pub offset: Point,
+    /// The number of screen pixels to each unit of the canvas.
+    pub zoom: f64,
+}
+
+impl Transform {
+
+    /// The screen position of the given canvas position.
+    pub fn to_screen(&self, p: Point) -> Point {
+        [self.origin[0] + (p[0] - self.offset[0]) * self.zoom,
+         self.origin[1] + (p[1] - self.offset[1]) * self.zoom]
+    }
+
+    /// The canvas position at the given screen position.
+    pub fn to_canvas(&self, p: Point) -> Point {
+        [self.offset[0] + (p[0] - self.origin[0]) / self.zoom,
+         self.offset[1] + (p[1] - self.origin[1]) / self.zoom]
+    }
+
+    /// The size on screen of the given size on the canvas.
+    pub fn to_screen_dim(&self, dim: Dimensions) -> Dimensions {
+        [dim[0] * self.zoom, dim[1] * self.zoom]
+    }
+
+    /// The size on the canvas of the given size on screen.
+    pub fn to_canvas_dim(&self, dim: Dimensions) -> Dimensions {
+        [dim[0] / self.zoom, dim[1] / self.zoom]
+    }
+
+}
+
+/// What the mouse is doing to the Viewport.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interaction {
+    Normal,
+    /// The canvas is being panned with the middle button, with the mouse
+    /// last at the given position.
+    Panning(Point),
+}
+
+/// Represents the state of the Viewport widget.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct State {
+    /// The canvas position shown at the top left of the viewport.
+    pub offset: Point,
+    pub zoom: f64,
+    pub interaction: Interaction,
+}
+
+widget_fns!(Viewport, State, Widget::Viewport(State {
+    offset: [0.0, 0.0],
+    zoom: 1.0,
+    interaction: Interaction::Normal,
+}));
+
+/// What happened to the Viewport, returned by `Viewport::react` and given to
+/// its `on_event` closure.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event {
+    /// The canvas was dragged to show another part of it.
+    Panned(Transform),
+    /// The canvas was zoomed about the mouse.
+    Zoomed(Transform),
+}
+
+/// A window onto a canvas which may be panned and zoomed, i.e. for building
+/// map or diagram editors. The mouse wheel zooms toward the mouse and
+/// dragging with the middle button pans.
+///
+/// The viewport's children are drawn by the closure given to `react`, which
+/// is given the viewport's `Transform` with which to place them on screen,
+/// and drawing is clipped to the viewport. The pan and zoom may also be set
+/// by the application with `offset` and `zoom`, i.e. to center on a
+/// selection.
+pub struct Viewport<'a> {
+    ui_id: UIID,
+    maybe_offset: Option<Point>,
+    maybe_zoom: Option<f64>,
+    zoom_range: (f64, f64),
+    pos: Point,
+    dim: Dimensions,
+    maybe_on_event: Option<Box<FnMut(Event) + 'a>>,
+    style: WidgetStyle,
+}
+
+impl<'a> Viewport<'a> {
+
+    /// Create a viewport context to be built upon.
+    pub fn new(ui_id: UIID) -> Viewport<'a> {
+        Viewport {
+            ui_id: ui_id,
+            maybe_offset: None,
+            maybe_zoom: None,
+            zoom_range: (0.1, 10.0),
+            pos: [0.0, 0.0],
+            dim: [256.0, 256.0],
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
+        }
+    }
+
+    /// Show the given canvas position at the top left of the viewport.
+    pub fn offset(self, offset: Point) -> Viewport<'a> {
+        Viewport { maybe_offset: Some(offset), ..self }
+    }
+
+    /// Set the number of screen pixels to each unit of the canvas.
+    pub fn zoom(self, zoom: f64) -> Viewport<'a> {
+        Viewport { maybe_zoom: Some(zoom), ..self }
+    }
+
+    /// The least and greatest zoom to which the wheel may zoom.
+    pub fn zoom_range(self, min: f64, max: f64) -> Viewport<'a> {
+        Viewport { zoom_range: (min, max), ..self }
+    }
+
+    /// The viewport's current transform, i.e. for placing widgets over the
+    /// canvas outside of `react`, or for a `Ruler` alongside it.
+    pub fn transform<C: CharacterCache>(&self, ui: &mut Ui<C>) -> Transform {
+        let style = self.style.resolve(ui.theme.maybe_viewport, &ui.theme);
+        let state = *get_state(ui, self.ui_id);
+        Transform {
+            origin: [self.pos[0] + style.frame, self.pos[1] + style.frame],
+            offset: self.maybe_offset.unwrap_or(state.offset),
+            zoom: self.maybe_zoom.unwrap_or(state.zoom),
+        }
+    }
+
+}
+
+composite_builder_impls!(Viewport<'a>);
+
+impl<'a> Reactable<'a, Event> for Viewport<'a> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
+        self
+    }
+}
+
+impl<'a> Viewport<'a> {
+
+    /// Update and draw the viewport, calling `draw_children` with its
+    /// transform to draw whatever is on the canvas, and return how the view
+    /// changed.
+    pub fn react<B, C, F>(&mut self, ui: &mut Ui<C>, graphics: &mut B, mut draw_children: F) -> Option<Event>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache,
+            F: FnMut(&mut Ui<C>, &mut B, Transform)
+    {
+        let style = self.style.resolve(ui.theme.maybe_viewport, &ui.theme);
+        ui.push_alpha(style.alpha);
+        let state = *get_state(ui, self.ui_id);
+        let mouse = ui.get_mouse_state();
+        let frame_w = style.frame;
+        let inner_pos = [self.pos[0] + frame_w, self.pos[1] + frame_w];
+        let inner_dim = [self.dim[0] - frame_w * 2.0, self.dim[1] - frame_w * 2.0];
+        let is_over = rectangle::is_over(inner_pos, mouse.pos, inner_dim);
+        let mut transform = Transform {
+            origin: inner_pos,
+            offset: self.maybe_offset.unwrap_or(state.offset),
+            zoom: self.maybe_zoom.unwrap_or(state.zoom),
+        };
+
+        // The wheel zooms about the canvas position beneath the mouse.
+        let mut maybe_event = None;
+        if is_over && mouse.scroll[1] != 0.0 {
+            let anchor = transform.to_canvas(mouse.pos);
+            let (min_zoom, max_zoom) = self.zoom_range;
+            let zoom = clamp(transform.zoom * ZOOM_STEP.powf(mouse.scroll[1]), min_zoom, max_zoom);
+            if zoom != transform.zoom {
+                transform.offset = [anchor[0] - (mouse.pos[0] - inner_pos[0]) / zoom,
+                                    anchor[1] - (mouse.pos[1] - inner_pos[1]) / zoom];
+                transform.zoom = zoom;
+                maybe_event = Some(Event::Zoomed(transform));
+            }
+        }
+
+        // Dragging with the middle button moves the canvas with the mouse.
+        let interaction = match (is_over, state.interaction, mouse.middle) {
+            (_, Interaction::Panning(last), Down) => {
+                if mouse.pos != last {
+                    transform.offset = [transform.offset[0] - (mouse.pos[0] - last[0]) / transform.zoom,
+                                        transform.offset[1] - (mouse.pos[1] - last[1]) / transform.zoom];
+                    maybe_event = Some(Event::Panned(transform));
+                }
+                Interaction::Panning(mouse.pos)
+            },
+            (true, Interaction::Normal, Down) => Interaction::Panning(mouse.pos),
+            _ => Interaction::Normal,
+        };
+        if let Interaction::Panning(_) = interaction {
+            ui.set_mouse_cursor(MouseCursor::Move);
+        }
+        let new_state = State { offset: transform.offset, zoom: transform.zoom, interaction: interaction };
+
+        // Draw the background, and the children clipped within the frame.
+        let maybe_frame = match frame_w > 0.0 {
+            true => Some((frame_w, style.frame_color)),
+            false => None,
+        };
+        let fill = rectangle::fill(style.color, style.maybe_gradient, style.maybe_background_image,
+                                   rectangle::State::Normal);
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal,
+                        self.pos, self.dim, maybe_frame, style.corner_radius, fill);
+        ui.push_clip(inner_pos, inner_dim);
+        draw_children(ui, graphics, transform);
+        ui.pop_clip();
+
+        ui.pop_alpha();
+        set_state(ui, self.ui_id, Widget::Viewport(new_state), self.pos, self.dim);
+
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
+    }
+
+}
@@ -1,16 +1,30 @@
 
 use button;
+use console;
+use curve_editor;
 use drop_down_list;
 use envelope_editor;
+use gallery;
+use heatmap;
+use label;
+use link;
+use node_editor;
+use num_pad;
 use number_dialer;
+use rating;
+use reorderable_list;
+use ruler;
 use slider;
+use split_button;
 use text_box;
+use timeline;
 use toggle;
+use viewport;
 use xy_pad;
 
 /// Represents the placement of the widget including
 /// x / y position, width and height.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Placing {
     Place(f64, f64, f64, f64), // (x, y, w, h)
     NoPlace,
@@ -45,16 +59,30 @@ impl Placing {
 
 /// Algebraic widget type for storing in ui_context
 /// and for ease of state-matching.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Widget {
     NoWidget,
     Button(button::State),
+    Console(console::State),
+    CurveEditor(curve_editor::State),
     DropDownList(drop_down_list::State),
     EnvelopeEditor(envelope_editor::State),
+    Gallery(gallery::State),
+    Heatmap(heatmap::State),
+    Label(label::State),
+    Link(link::State),
+    NodeEditor(node_editor::State),
     NumberDialer(number_dialer::State),
+    NumPad(num_pad::State),
+    Rating(rating::State),
+    ReorderableList(reorderable_list::State),
+    Ruler(ruler::State),
     Slider(slider::State),
+    SplitButton(split_button::State),
     TextBox(text_box::State),
+    Timeline(timeline::State),
     Toggle(toggle::State),
+    Viewport(viewport::State),
     XYPad(xy_pad::State),
 }
 
@@ -63,14 +91,57 @@ impl Widget {
         match (self, other) {
             (&Widget::NoWidget, &Widget::NoWidget) => true,
             (&Widget::Button(_), &Widget::Button(_)) => true,
+            (&Widget::Console(_), &Widget::Console(_)) => true,
+            (&Widget::CurveEditor(_), &Widget::CurveEditor(_)) => true,
             (&Widget::DropDownList(_), &Widget::DropDownList(_)) => true,
             (&Widget::EnvelopeEditor(_), &Widget::EnvelopeEditor(_)) => true,
+            (&Widget::Gallery(_), &Widget::Gallery(_)) => true,
+            (&Widget::Heatmap(_), &Widget::Heatmap(_)) => true,
+            (&Widget::Label(_), &Widget::Label(_)) => true,
+            (&Widget::Link(_), &Widget::Link(_)) => true,
+            (&Widget::NodeEditor(_), &Widget::NodeEditor(_)) => true,
             (&Widget::NumberDialer(_), &Widget::NumberDialer(_)) => true,
+            (&Widget::NumPad(_), &Widget::NumPad(_)) => true,
+            (&Widget::Rating(_), &Widget::Rating(_)) => true,
+            (&Widget::ReorderableList(_), &Widget::ReorderableList(_)) => true,
+            (&Widget::Ruler(_), &Widget::Ruler(_)) => true,
             (&Widget::Slider(_), &Widget::Slider(_)) => true,
+            (&Widget::SplitButton(_), &Widget::SplitButton(_)) => true,
             (&Widget::TextBox(_), &Widget::TextBox(_)) => true,
+            (&Widget::Timeline(_), &Widget::Timeline(_)) => true,
             (&Widget::Toggle(_), &Widget::Toggle(_)) => true,
+            (&Widget::Viewport(_), &Widget::Viewport(_)) => true,
             (&Widget::XYPad(_), &Widget::XYPad(_)) => true,
             _ => false
         }
     }
+
+    /// The name of the widget kind, used when describing UIID conflicts.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Widget::NoWidget => "NoWidget",
+            Widget::Button(_) => "Button",
+            Widget::Console(_) => "Console",
+            Widget::CurveEditor(_) => "CurveEditor",
+            Widget::DropDownList(_) => "DropDownList",
+            Widget::EnvelopeEditor(_) => "EnvelopeEditor",
+            Widget::Gallery(_) => "Gallery",
+            Widget::Heatmap(_) => "Heatmap",
+            Widget::Label(_) => "Label",
+            Widget::Link(_) => "Link",
+            Widget::NodeEditor(_) => "NodeEditor",
+            Widget::NumberDialer(_) => "NumberDialer",
+            Widget::NumPad(_) => "NumPad",
+            Widget::Rating(_) => "Rating",
+            Widget::ReorderableList(_) => "ReorderableList",
+            Widget::Ruler(_) => "Ruler",
+            Widget::Slider(_) => "Slider",
+            Widget::SplitButton(_) => "SplitButton",
+            Widget::TextBox(_) => "TextBox",
+            Widget::Timeline(_) => "Timeline",
+            Widget::Toggle(_) => "Toggle",
+            Widget::Viewport(_) => "Viewport",
+            Widget::XYPad(_) => "XYPad",
+        }
+    }
 }
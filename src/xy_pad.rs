@@ -1,25 +1,27 @@
 use num::{ Float, ToPrimitive, FromPrimitive };
-use callback::Callable;
-use frame::Frameable;
-use color::{ Color, Colorable };
-use label::{ FontSize, Labelable };
+use callback::{ Callable, NoCallback, Reactable };
+use color::Color;
+use cursor::MouseCursor;
+use label::{ FontId, FontSize, Labelable };
 use dimensions::Dimensions;
-use graphics;
 use graphics::Graphics;
 use graphics::character::CharacterCache;
+use history::Value;
 use label;
+use mouse;
 use mouse::Mouse;
 use point::Point;
-use position::Positionable;
-use shape::Shapeable;
+use theme::WidgetStyle;
 use rectangle;
 use rectangle::{
     Corner
 };
+use scale::Scale;
 use ui::{ UIID, Ui };
 use utils::{
     clamp,
     map_range,
+    snap,
     val_to_string,
 };
 use vecmath::{
@@ -34,6 +36,8 @@ pub enum State {
     Normal,
     Highlighted,
     Clicked,
+    /// Pressed with the right or middle button.
+    AltClicked(mouse::Button),
 }
 
 impl State {
@@ -42,7 +46,7 @@ impl State {
         match self {
             &State::Normal => rectangle::State::Normal,
             &State::Highlighted => rectangle::State::Highlighted,
-            &State::Clicked => rectangle::State::Clicked,
+            &State::Clicked | &State::AltClicked(_) => rectangle::State::Clicked,
         }
     }
 }
@@ -54,46 +58,64 @@ fn get_new_state(is_over: bool,
                  prev: State,
                  mouse: Mouse) -> State {
     use mouse::ButtonState::{Down, Up};
-    use self::State::{Normal, Highlighted, Clicked};
-    match (is_over, prev, mouse.left) {
-        (true,  Normal,  Down) => Normal,
-        (true,  _,       Down) => Clicked,
-        (true,  _,       Up)   => Highlighted,
-        (false, Clicked, Down) => Clicked,
-        _                      => Normal,
+    use self::State::{Normal, Highlighted, Clicked, AltClicked};
+    match (is_over, prev, mouse.left, mouse.alt_button_down()) {
+        (true,  Normal,        Down, _)       => Normal,
+        (true,  Normal,        Up,   Some(_)) => Normal,
+        (_,     AltClicked(b), Up,   _) if mouse.button(b) == Down => AltClicked(b),
+        (true,  Highlighted,   Up,   Some(b)) => AltClicked(b),
+        (true,  _,             Down, _)       => Clicked,
+        (true,  _,             Up,   _)       => Highlighted,
+        (false, Clicked,       Down, _)       => Clicked,
+        _                                     => Normal,
     }
 }
 
 /// Draw the crosshair.
-fn draw_crosshair<B: Graphics>(
-    win_w: f64,
-    win_h: f64,
+fn draw_crosshair<B, C>(
+    ui: &mut Ui<C>,
     graphics: &mut B,
     pos: Point,
     line_width: f64,
     vert_x: f64, hori_y: f64,
     pad_dim: Dimensions,
     color: Color
-) {
-    let draw_state = graphics::default_draw_state();
-    let transform = graphics::abs_transform(win_w, win_h);
-    let Color(col) = color;
-    let line = graphics::Line::new(col, 0.5 * line_width);
-    line.draw(
-        [vert_x, pos[1], vert_x, pos[1] + pad_dim[1]],
-        draw_state,
-        transform,
-        graphics
-    );
-    line.draw(
-        [pos[0], hori_y, pos[0] + pad_dim[0], hori_y],
-        draw_state,
-        transform,
-        graphics
-    );
+)
+    where
+        B: Graphics<Texture = <C as CharacterCache>::Texture>,
+        C: CharacterCache
+{
+    ui.draw_line(graphics, [vert_x, pos[1], vert_x, pos[1] + pad_dim[1]], line_width, false, color);
+    ui.draw_line(graphics, [pos[0], hori_y, pos[0] + pad_dim[0], hori_y], line_width, false, color);
 }
 
 
+/// What happened to the XYPad, returned by `XYPad::react` and given to its
+/// `on_event` closure along with the pad's values.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Event<X, Y> {
+    /// The mouse was pressed over the pad.
+    Pressed(X, Y),
+    /// The values are changing while the mouse is held.
+    Dragging(X, Y),
+    /// The mouse was released, committing the values.
+    Released(X, Y),
+    /// The right or middle button was released over the pad after pressing
+    /// it with the same, i.e. to open a context menu. The values are left
+    /// unchanged.
+    AltClicked(mouse::Button, X, Y),
+}
+
+impl<X: Copy, Y: Copy> Event<X, Y> {
+    /// The values of the xy_pad when the event occurred.
+    pub fn values(&self) -> (X, Y) {
+        match *self {
+            Event::Pressed(x, y) | Event::Dragging(x, y) | Event::Released(x, y) |
+            Event::AltClicked(_, x, y) => (x, y),
+        }
+    }
+}
+
 /// A context on which the builder pattern can be implemented.
 pub struct XYPad<'a, X, Y, F> {
     ui_id: UIID,
@@ -104,12 +126,12 @@ pub struct XYPad<'a, X, Y, F> {
     pos: Point,
     dim: Dimensions,
     maybe_callback: Option<F>,
-    maybe_color: Option<Color>,
-    maybe_frame: Option<f64>,
-    maybe_frame_color: Option<Color>,
+    maybe_on_event: Option<Box<FnMut(Event<X, Y>) + 'a>>,
+    style: WidgetStyle,
     maybe_label: Option<&'a str>,
-    maybe_label_color: Option<Color>,
-    maybe_label_font_size: Option<u32>,
+    x_scale: Scale,
+    y_scale: Scale,
+    maybe_step: Option<(X, Y)>,
 }
 
 impl <'a, X, Y, F> XYPad<'a, X, Y, F> {
@@ -121,6 +143,32 @@ impl <'a, X, Y, F> XYPad<'a, X, Y, F> {
     pub fn value_font_size(self, size: FontSize) -> XYPad<'a, X, Y, F> {
         XYPad { font_size: size, ..self }
     }
+    /// Skew the mapping from the pad's width to the x value (see `Scale::Skew`).
+    #[inline]
+    pub fn skew_x(self, skew: f64) -> XYPad<'a, X, Y, F> {
+        XYPad { x_scale: Scale::Skew(skew), ..self }
+    }
+    /// Skew the mapping from the pad's height to the y value (see `Scale::Skew`).
+    #[inline]
+    pub fn skew_y(self, skew: f64) -> XYPad<'a, X, Y, F> {
+        XYPad { y_scale: Scale::Skew(skew), ..self }
+    }
+    /// Spread the x values logarithmically. The x range must be positive.
+    #[inline]
+    pub fn log_scale_x(self) -> XYPad<'a, X, Y, F> {
+        XYPad { x_scale: Scale::Logarithmic, ..self }
+    }
+    /// Spread the y values logarithmically. The y range must be positive.
+    #[inline]
+    pub fn log_scale_y(self) -> XYPad<'a, X, Y, F> {
+        XYPad { y_scale: Scale::Logarithmic, ..self }
+    }
+    /// Snap the dragged values to multiples of the given steps from their
+    /// mins. Snapping is disabled while Ctrl is held, allowing for finer control.
+    #[inline]
+    pub fn step(self, x_step: X, y_step: Y) -> XYPad<'a, X, Y, F> {
+        XYPad { maybe_step: Some((x_step, y_step)), ..self }
+    }
 }
 
 impl<'a, X, Y, F> XYPad<'a, X, Y, F> {
@@ -137,37 +185,28 @@ impl<'a, X, Y, F> XYPad<'a, X, Y, F> {
             pos: [0.0, 0.0],
             dim: [128.0, 128.0],
             maybe_callback: None,
-            maybe_color: None,
-            maybe_frame: None,
-            maybe_frame_color: None,
+            maybe_on_event: None,
+            style: WidgetStyle::new(),
             maybe_label: None,
-            maybe_label_color: None,
-            maybe_label_font_size: None,
+            x_scale: Scale::Linear,
+            y_scale: Scale::Linear,
+            maybe_step: None,
         }
     }
 }
 
-impl<'a, X, Y, F> Colorable for XYPad<'a, X, Y, F> {
-    fn color(mut self, color: Color) -> Self {
-        self.maybe_color = Some(color);
-        self
-    }
-}
+composite_builder_impls!(XYPad<'a, X, Y, F>);
 
-impl<'a, X, Y, F> Frameable for XYPad<'a, X, Y, F> {
-    fn frame(mut self, width: f64) -> Self {
-        self.maybe_frame = Some(width);
-        self
-    }
-    fn frame_color(mut self, color: Color) -> Self {
-        self.maybe_frame_color = Some(color);
+impl<'a, X, Y, F> Callable<F> for XYPad<'a, X, Y, F> {
+    fn callback(mut self, cb: F) -> Self {
+        self.maybe_callback = Some(cb);
         self
     }
 }
 
-impl<'a, X, Y, F> Callable<F> for XYPad<'a, X, Y, F> {
-    fn callback(mut self, cb: F) -> Self {
-        self.maybe_callback = Some(cb);
+impl<'a, X, Y, F> Reactable<'a, Event<X, Y>> for XYPad<'a, X, Y, F> {
+    fn on_event<G>(mut self, on_event: G) -> Self where G: FnMut(Event<X, Y>) + 'a {
+        self.maybe_on_event = Some(Box::new(on_event));
         self
     }
 }
@@ -180,114 +219,181 @@ impl<'a, X, Y, F> Labelable<'a> for XYPad<'a, X, Y, F>
     }
 
     fn label_color(mut self, color: Color) -> Self {
-        self.maybe_label_color = Some(color);
+        self.style.maybe_label_color = Some(color);
         self
     }
 
     fn label_font_size(mut self, size: FontSize) -> Self {
-        self.maybe_label_font_size = Some(size);
+        self.style.maybe_label_font_size = Some(size);
         self
     }
-}
 
-impl<'a, X, Y, F> Positionable for XYPad<'a, X, Y, F> {
-    fn point(mut self, pos: Point) -> Self {
-        self.pos = pos;
+    fn label_font(mut self, font: FontId) -> Self {
+        self.style.maybe_font = Some(font);
         self
     }
 }
 
-impl<'a, X, Y, F> Shapeable for XYPad<'a, X, Y, F> {
-    fn get_dim(&self) -> Dimensions { self.dim }
-    fn dim(mut self, dim: Dimensions) -> Self { self.dim = dim; self }
-}
-
 impl<'a, X, Y, F> ::draw::Drawable for XYPad<'a, X, Y, F>
     where
         X: Float + ToPrimitive + FromPrimitive + ToString,
         Y: Float + ToPrimitive + FromPrimitive + ToString,
         F: FnMut(X, Y) + 'a
 {
-
     fn draw<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B)
         where
             B: Graphics<Texture = <C as CharacterCache>::Texture>,
             C: CharacterCache
     {
+        if let Some(event) = self.update(ui, graphics) {
+            let (new_x, new_y) = event.values();
+            if let Some(ref mut callback) = self.maybe_callback { (*callback)(new_x, new_y) }
+        }
+    }
+}
 
+impl<'a, X, Y> XYPad<'a, X, Y, NoCallback>
+    where
+        X: Float + ToPrimitive + FromPrimitive + ToString,
+        Y: Float + ToPrimitive + FromPrimitive + ToString
+{
+
+    /// Draw the xy_pad, returning what happened to it. An alternative to
+    /// `Callable::callback` for when the reaction to the change needs to
+    /// borrow application state.
+    pub fn react<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event<X, Y>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        self.update(ui, graphics)
+    }
+
+}
+
+impl<'a, X, Y, F> XYPad<'a, X, Y, F>
+    where
+        X: Float + ToPrimitive + FromPrimitive + ToString,
+        Y: Float + ToPrimitive + FromPrimitive + ToString
+{
+
+    /// Update and draw the xy_pad, returning what happened to it.
+    fn update<B, C>(&mut self, ui: &mut Ui<C>, graphics: &mut B) -> Option<Event<X, Y>>
+        where
+            B: Graphics<Texture = <C as CharacterCache>::Texture>,
+            C: CharacterCache
+    {
+        let style = self.style.resolve(ui.theme.maybe_xy_pad, &ui.theme);
+        ui.push_alpha(style.alpha);
         // Init.
         let state = *get_state(ui, self.ui_id);
         let mouse = ui.get_mouse_state();
-        let frame_w = self.maybe_frame.unwrap_or(ui.theme.frame_width);
+        let frame_w = style.frame;
         let frame_w2 = frame_w * 2.0;
         let maybe_frame = match frame_w > 0.0 {
-            true => Some((frame_w, self.maybe_frame_color.unwrap_or(ui.theme.frame_color))),
+            true => Some((frame_w, style.frame_color)),
             false => None,
         };
         let pad_dim = vec2_sub(self.dim, [frame_w2; 2]);
         let pad_pos = vec2_add(self.pos, [frame_w, frame_w]);
         let is_over_pad = rectangle::is_over(pad_pos, mouse.pos, pad_dim);
         let new_state = get_new_state(is_over_pad, state, mouse);
+        if is_over_pad {
+            ui.set_mouse_cursor(MouseCursor::Crosshair);
+        }
 
-        // Determine new values.
+        // Determine new values, snapping them to the step if there is one.
+        let maybe_step = if ui.is_ctrl_held() { None } else { self.maybe_step };
         let (new_x, new_y) = match (is_over_pad, new_state) {
-            (_, State::Normal) | (_, State::Highlighted) => (self.x, self.y),
+            (_, State::Normal) | (_, State::Highlighted) | (_, State::AltClicked(_)) => (self.x, self.y),
             (_, State::Clicked) => {
                 let temp_x = clamp(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]);
                 let temp_y = clamp(mouse.pos[1], pad_pos[1], pad_pos[1] + pad_dim[1]);
-                (map_range(temp_x - self.pos[0], pad_dim[0], 0.0, self.min_x, self.max_x),
-                 map_range(temp_y - self.pos[1], pad_dim[1], 0.0, self.min_y, self.max_y))
+                let x_perc = map_range(temp_x - self.pos[0], pad_dim[0], 0.0, 0.0, 1.0);
+                let y_perc = map_range(temp_y - self.pos[1], pad_dim[1], 0.0, 0.0, 1.0);
+                let new_x = self.x_scale.value_from_perc(x_perc, self.min_x, self.max_x);
+                let new_y = self.y_scale.value_from_perc(y_perc, self.min_y, self.max_y);
+                match maybe_step {
+                    Some((x_step, y_step)) => (snap(new_x, self.min_x, self.max_x, x_step),
+                                               snap(new_y, self.min_y, self.max_y, y_step)),
+                    None => (new_x, new_y),
+                }
             }
         };
 
-        // Callback if value is changed or the pad is clicked/released.
-        match self.maybe_callback {
-            Some(ref mut callback) => {
-                if self.x != new_x || self.y != new_y { (*callback)(new_x, new_y) }
-                else {
-                    match (state, new_state) {
-                        (State::Highlighted, State::Clicked)
-                        | (State::Clicked, State::Highlighted) => (*callback)(new_x, new_y),
-                        _ => (),
-                    }
-                }
+        // React if value is changed or the pad is clicked/released.
+        let maybe_event = match (state, new_state) {
+            (State::Highlighted, State::Clicked) => Some(Event::Pressed(new_x, new_y)),
+            (State::Clicked, State::Highlighted)
+            | (State::Clicked, State::Normal) => Some(Event::Released(new_x, new_y)),
+            (State::AltClicked(b), State::Highlighted) => Some(Event::AltClicked(b, new_x, new_y)),
+            _ if self.x != new_x || self.y != new_y => Some(Event::Dragging(new_x, new_y)),
+            _ => None,
+        };
+
+        // The change is recorded once released, and a change that was undone
+        // or redone is re-fired as though released.
+        match maybe_event {
+            Some(Event::Pressed(_, _)) => {
+                let before = Value::Point(self.x.to_f64().unwrap(), self.y.to_f64().unwrap());
+                ui.begin_change(self.ui_id, before);
             },
-            None => (),
+            Some(Event::Released(x, y)) =>
+                ui.commit_change(self.ui_id, Value::Point(x.to_f64().unwrap(), y.to_f64().unwrap())),
+            _ => (),
         }
+        let maybe_event = match ui.take_restored(self.ui_id) {
+            Some(Value::Point(x, y)) => match (FromPrimitive::from_f64(x), FromPrimitive::from_f64(y)) {
+                (Some(x), Some(y)) => Some(Event::Released(x, y)),
+                _ => maybe_event,
+            },
+            _ => maybe_event,
+        };
 
         // Draw.
-        let rect_state = new_state.as_rectangle_state();
-        let color = self.maybe_color.unwrap_or(ui.theme.shape_color);
-        rectangle::draw(ui.win_w, ui.win_h, graphics, rect_state, self.pos,
-                        self.dim, maybe_frame, color);
-        let (vert_x, hori_y) = match (is_over_pad, new_state) {
-            (_, State::Normal) | (_, State::Highlighted) =>
-                (pad_pos[0] + map_range(new_x, self.min_x, self.max_x, pad_dim[0], 0.0),
-                 pad_pos[1] + map_range(new_y, self.min_y, self.max_y, pad_dim[1], 0.0)),
-            (_, State::Clicked) =>
+        let color = style.color;
+        let rect_color = ui.transition_color(self.ui_id, new_state.as_rectangle_state().color(color));
+        let fill = rectangle::fill(rect_color, style.maybe_gradient, style.maybe_background_image,
+                                   new_state.as_rectangle_state());
+        if let Some(shadow) = style.maybe_shadow {
+            rectangle::draw_shadow(ui, graphics, self.pos, self.dim, style.corner_radius, shadow);
+        }
+        rectangle::draw(ui, graphics, rectangle::State::Normal, self.pos,
+                        self.dim, maybe_frame, style.corner_radius, fill);
+        // While snapping, the crosshair shows the snapped values rather than the mouse.
+        let (vert_x, hori_y) = match (new_state, maybe_step) {
+            (State::Clicked, None) =>
                 (clamp(mouse.pos[0], pad_pos[0], pad_pos[0] + pad_dim[0]),
                  clamp(mouse.pos[1], pad_pos[1], pad_pos[1] + pad_dim[1])),
+            _ =>
+                (pad_pos[0] + map_range(self.x_scale.perc_from_value(new_x, self.min_x, self.max_x),
+                                        0.0, 1.0, pad_dim[0], 0.0),
+                 pad_pos[1] + map_range(self.y_scale.perc_from_value(new_y, self.min_y, self.max_y),
+                                        0.0, 1.0, pad_dim[1], 0.0)),
         };
         // Crosshair.
-        draw_crosshair(ui.win_w, ui.win_h, graphics, pad_pos, self.line_width,
+        draw_crosshair(ui, graphics, pad_pos, self.line_width,
                        vert_x, hori_y, pad_dim, color.plain_contrast());
         // Label.
         if let Some(l_text) = self.maybe_label {
-            let l_color = self.maybe_label_color.unwrap_or(ui.theme.label_color);
-            let l_size = self.maybe_label_font_size.unwrap_or(ui.theme.font_size_medium);
-            let l_w = label::width(ui, l_size, l_text);
+            let l_color = style.label_color;
+            let l_size = style.label_font_size;
+            let l_w = label::width(ui, style.font, l_size, l_text);
             let l_x = pad_pos[0] + (pad_dim[0] - l_w) / 2.0;
             let l_y = pad_pos[1] + (pad_dim[1] - l_size as f64) / 2.0;
             let l_pos = [l_x, l_y];
-            ui.draw_text(graphics, l_pos, l_size, l_color, l_text);
+            ui.draw_text(graphics, l_pos, style.font, l_size, l_color, l_text);
         }
         // xy value string.
         let x_string = val_to_string(self.x, self.max_x,
                                      self.max_x - self.min_x, self.dim[0] as usize);
         let y_string = val_to_string(self.y, self.max_y,
                                      self.max_y - self.min_y, self.dim[1] as usize);
-        let xy_string = format!("{}, {}", x_string, y_string);
-        let xy_string_w = label::width(ui, self.font_size, &xy_string);
+        let mut xy_string = ui.scratch_string();
+        xy_string.push_str(&x_string);
+        xy_string.push_str(", ");
+        xy_string.push_str(&y_string);
+        let xy_string_w = label::width(ui, style.font, self.font_size, &xy_string);
         let xy_string_pos = {
             match rectangle::corner(pad_pos, [vert_x, hori_y], pad_dim) {
                 Corner::TopLeft => [vert_x, hori_y],
@@ -296,10 +402,17 @@ impl<'a, X, Y, F> ::draw::Drawable for XYPad<'a, X, Y, F>
                 Corner::BottomRight => [vert_x - xy_string_w, hori_y - self.font_size as f64],
             }
         };
-        ui.draw_text(graphics, xy_string_pos, self.font_size,
+        ui.draw_text(graphics, xy_string_pos, style.font, self.font_size,
                     color.plain_contrast(), &xy_string);
+        ui.recycle_string(xy_string);
 
+        ui.pop_alpha();
         set_state(ui, self.ui_id, Widget::XYPad(new_state), self.pos, self.dim);
 
+        if let (Some(event), Some(on_event)) = (maybe_event, self.maybe_on_event.as_mut()) {
+            on_event(event);
+        }
+        maybe_event
     }
+
 }
@@ -0,0 +1,50 @@
+extern crate conrod;
+
+use conrod::arena::ScratchArena;
+
+#[test]
+fn reuses_the_allocation_of_buffers_given_back() {
+    let mut arena = ScratchArena::new();
+    let mut string = arena.string();
+    string.push_str("reused");
+    string.reserve(100);
+    arena.give_string(string);
+    let string = arena.string();
+    assert!(string.is_empty());
+    assert!(string.capacity() >= 100);
+
+    let mut points = arena.points();
+    points.push([1.0, 2.0]);
+    arena.give_points(points);
+    let points = arena.points();
+    assert!(points.is_empty());
+    assert!(points.capacity() >= 1);
+}
+
+#[test]
+fn lets_go_of_buffers_that_grew_too_large() {
+    let mut arena = ScratchArena::new();
+    let mut string = arena.string();
+    string.reserve(1 << 16);
+    arena.give_string(string);
+    assert_eq!(arena.string().capacity(), 0);
+}
+
+#[test]
+fn keeps_only_as_many_buffers_as_were_used_at_once() {
+    let mut arena = ScratchArena::new();
+    // Two strings in use at once during the first frame.
+    let (mut a, mut b) = (arena.string(), arena.string());
+    a.reserve(10);
+    b.reserve(10);
+    arena.give_string(a);
+    arena.give_string(b);
+    arena.reset();
+    // Only one during the second.
+    let c = arena.string();
+    arena.give_string(c);
+    arena.reset();
+    let (d, e) = (arena.string(), arena.string());
+    assert!(d.capacity() >= 10);
+    assert_eq!(e.capacity(), 0);
+}
@@ -0,0 +1,50 @@
+extern crate conrod;
+
+use conrod::bidi::{ self, Direction, Line };
+
+#[test]
+fn leaves_left_to_right_text_alone() {
+    assert_eq!(bidi::reorder("abc 123"), "abc 123");
+    assert_eq!(bidi::base_direction("123 abc"), Direction::LeftToRight);
+}
+
+#[test]
+fn reverses_right_to_left_text() {
+    assert_eq!(bidi::reorder("שלום"), "םולש");
+    assert_eq!(bidi::base_direction("שלום abc"), Direction::RightToLeft);
+}
+
+#[test]
+fn keeps_the_order_of_numbers_within_right_to_left_text() {
+    assert_eq!(bidi::reorder("שלום 123 עולם"), "םלוע 123 םולש");
+    assert_eq!(bidi::reorder("מחיר 1,234.50"), "1,234.50 ריחמ");
+}
+
+#[test]
+fn displays_right_to_left_text_within_left_to_right_text_reversed() {
+    assert_eq!(bidi::reorder("abc שלום def"), "abc םולש def");
+    // A number following the right-to-left text is displayed to its left.
+    assert_eq!(bidi::reorder("abc שלום 123"), "abc 123 םולש");
+}
+
+#[test]
+fn maps_between_logical_and_visual_indices() {
+    let line = Line::new("ab שלום");
+    assert_eq!(line.len(), 7);
+    for logical_idx in 0..line.len() {
+        assert_eq!(line.logical_index(line.visual_index(logical_idx)), logical_idx);
+    }
+    assert_eq!(line.visual_index(0), 0);
+    assert_eq!(line.visual_index(3), 6);
+    assert!(!line.is_rtl(0));
+    assert!(line.is_rtl(3));
+}
+
+#[test]
+fn displays_digits_left_to_right() {
+    let line = Line::new("שלום 42");
+    assert!(line.is_rtl(0));
+    assert!(!line.is_rtl(5));
+    assert!(!line.is_rtl(6));
+    assert_eq!(line.visual_text(), "42 םולש");
+}
@@ -0,0 +1,21 @@
+extern crate conrod;
+
+use conrod::primitive::Primitive;
+use conrod::testing::headless_ui;
+
+#[test]
+fn clips_while_capturing_primitives() {
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.capture_primitives(true);
+    ui.push_clip([0.0, 0.0], [100.0, 100.0]);
+    ui.push_clip([50.0, 50.0], [100.0, 100.0]);
+    assert_eq!(ui.clip_rect(), Some(([50.0, 50.0], [50.0, 50.0])));
+    ui.pop_clip();
+    assert_eq!(ui.clip_rect(), Some(([0.0, 0.0], [100.0, 100.0])));
+    ui.pop_clip();
+    assert_eq!(ui.clip_rect(), None);
+    let primitives = ui.take_primitives();
+    assert_eq!(primitives.len(), 4);
+    assert_eq!(primitives[0], Primitive::PushClip { pos: [0.0, 0.0], dim: [100.0, 100.0] });
+    assert_eq!(primitives[3], Primitive::PopClip);
+}
@@ -0,0 +1,65 @@
+extern crate conrod;
+
+use conrod::Color;
+
+fn assert_close(a: Color, b: Color) {
+    for (x, y) in a.0.iter().zip(b.0.iter()) {
+        assert!((x - y).abs() < 1e-4, "{:?} is not close to {:?}", a, b);
+    }
+}
+
+#[test]
+fn constructs_colors_from_hsl() {
+    assert_close(Color::hsl(0.0, 1.0, 0.5), Color::new(1.0, 0.0, 0.0, 1.0));
+    assert_close(Color::hsl(120.0, 1.0, 0.5), Color::new(0.0, 1.0, 0.0, 1.0));
+    assert_close(Color::hsl(240.0, 1.0, 0.5), Color::new(0.0, 0.0, 1.0, 1.0));
+    assert_close(Color::hsla(60.0, 0.0, 0.25, 0.5), Color::new(0.25, 0.25, 0.25, 0.5));
+}
+
+#[test]
+fn wraps_the_hue_and_clamps_saturation_and_lightness() {
+    assert_close(Color::hsl(-120.0, 1.0, 0.5), Color::hsl(240.0, 1.0, 0.5));
+    assert_close(Color::hsl(480.0, 1.0, 0.5), Color::hsl(120.0, 1.0, 0.5));
+    assert_close(Color::hsl(0.0, 2.0, 1.5), Color::new(1.0, 1.0, 1.0, 1.0));
+}
+
+#[test]
+fn converts_colors_to_hsla() {
+    let (h, s, l, a) = Color::new(1.0, 0.0, 1.0, 0.5).to_hsla();
+    assert!((h - 300.0).abs() < 1e-3 && (s - 1.0).abs() < 1e-4 && (l - 0.5).abs() < 1e-4);
+    assert_eq!(a, 0.5);
+    assert_eq!(Color::new(0.5, 0.5, 0.5, 1.0).to_hsla(), (0.0, 0.0, 0.5, 1.0));
+}
+
+#[test]
+fn round_trips_through_hsla() {
+    let colors = [
+        Color::new(0.2, 0.4, 0.6, 0.8),
+        Color::new(0.9, 0.1, 0.3, 1.0),
+        Color::new(0.05, 0.7, 0.2, 0.0),
+    ];
+    for &color in colors.iter() {
+        let (h, s, l, a) = color.to_hsla();
+        assert_close(Color::hsla(h, s, l, a), color);
+    }
+}
+
+#[test]
+fn constructs_colors_from_hex() {
+    assert_eq!(Color::from_hex("#1e90ff").unwrap().to_32_bit(), [30, 144, 255, 255]);
+    assert_eq!(Color::from_hex("FF000080").unwrap().to_32_bit(), [255, 0, 0, 128]);
+}
+
+#[test]
+fn rejects_invalid_hex() {
+    assert!(Color::from_hex("#12345").is_err());
+    assert!(Color::from_hex("#1234").is_err());
+    assert!(Color::from_hex("#zzzzzz").is_err());
+}
+
+#[test]
+fn round_trips_through_hex() {
+    let color = Color::from_hex("#336699CC").unwrap();
+    assert_eq!(color.to_hex(), "#336699CC");
+    assert_eq!(Color::from_hex(&color.to_hex()).unwrap(), color);
+}
@@ -0,0 +1,93 @@
+extern crate conrod;
+extern crate piston;
+
+use conrod::{ Console, NoCallback, Positionable, Shapeable, Ui };
+use conrod::console::Event;
+use conrod::event::Input;
+use conrod::testing::{ headless_ui, next_frame, press_key, MockCharacterCache, NoOpGraphics };
+use piston::input::Button;
+use piston::input::keyboard::Key;
+use piston::input::MouseButton;
+
+const CONSOLE: u64 = 0;
+
+fn draw_console(ui: &mut Ui<MockCharacterCache>, input: &mut String) -> Option<Event> {
+    let lines: Vec<String> = Vec::new();
+    Console::<NoCallback>::new(CONSOLE, &lines, input)
+        .point([0.0, 0.0])
+        .dim([300.0, 200.0])
+        .react(ui, &mut NoOpGraphics)
+}
+
+/// Click the console so that it takes the keyboard, then submit each of the given commands.
+fn capture_and_submit(ui: &mut Ui<MockCharacterCache>, input: &mut String, commands: &[&str]) {
+    draw_console(ui, input);
+    next_frame(ui, vec![Input::MouseCursor(150.0, 100.0)]);
+    draw_console(ui, input);
+    next_frame(ui, vec![Input::Press(Button::Mouse(MouseButton::Left))]);
+    draw_console(ui, input);
+    next_frame(ui, vec![Input::Release(Button::Mouse(MouseButton::Left))]);
+    draw_console(ui, input);
+    for command in commands.iter() {
+        next_frame(ui, vec![Input::Text(command.to_string())]);
+        draw_console(ui, input);
+        next_frame(ui, press_key(Key::Return));
+        assert_eq!(draw_console(ui, input), Some(Event::Submitted(command.to_string())));
+    }
+}
+
+#[test]
+fn recalls_history_with_up_and_down() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let mut input = String::new();
+    capture_and_submit(&mut ui, &mut input, &["first", "second"]);
+
+    next_frame(&mut ui, vec![Input::Text("dra".to_string())]);
+    draw_console(&mut ui, &mut input);
+    next_frame(&mut ui, press_key(Key::Up));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "second");
+    next_frame(&mut ui, press_key(Key::Up));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "first");
+    next_frame(&mut ui, press_key(Key::Down));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "second");
+    // Moving past the newest command returns to what was being typed.
+    next_frame(&mut ui, press_key(Key::Down));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "dra");
+}
+
+#[test]
+fn moving_the_cursor_keeps_the_recalled_command() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let mut input = String::new();
+    capture_and_submit(&mut ui, &mut input, &["first", "second"]);
+
+    next_frame(&mut ui, press_key(Key::Up));
+    draw_console(&mut ui, &mut input);
+    next_frame(&mut ui, press_key(Key::Left));
+    draw_console(&mut ui, &mut input);
+    next_frame(&mut ui, press_key(Key::Up));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "first");
+}
+
+#[test]
+fn typing_over_a_recalled_command_makes_it_the_draft() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let mut input = String::new();
+    capture_and_submit(&mut ui, &mut input, &["first", "second"]);
+
+    next_frame(&mut ui, press_key(Key::Up));
+    draw_console(&mut ui, &mut input);
+    next_frame(&mut ui, vec![Input::Text("!".to_string())]);
+    draw_console(&mut ui, &mut input);
+    next_frame(&mut ui, press_key(Key::Up));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "second");
+    next_frame(&mut ui, press_key(Key::Down));
+    draw_console(&mut ui, &mut input);
+    assert_eq!(input, "second!");
+}
@@ -0,0 +1,53 @@
+extern crate conrod;
+
+use conrod::{ NoCallback, Positionable, Shapeable, Slider, Ui };
+use conrod::testing::{ headless_ui, next_frame, MockCharacterCache, NoOpGraphics };
+
+const SLIDER: u64 = 0;
+
+/// Begin the next frame a second after the last, so that any transitions
+/// begun in the last frame have finished.
+fn next_later_frame(ui: &mut Ui<MockCharacterCache>) {
+    let time = ui.time();
+    ui.set_mock_time(Some(time + 1.0));
+    next_frame(ui, vec![]);
+}
+
+fn draw_slider(ui: &mut Ui<MockCharacterCache>, value: f32) {
+    Slider::<f32, NoCallback>::new(SLIDER, value, 0.0, 1.0)
+        .point([50.0, 50.0])
+        .dim([200.0, 30.0])
+        .react(ui, &mut NoOpGraphics);
+}
+
+#[test]
+fn damages_a_widget_whose_value_changed() {
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.track_damage(true);
+    ui.set_mock_time(Some(0.0));
+    draw_slider(&mut ui, 0.2);
+
+    next_later_frame(&mut ui);
+    draw_slider(&mut ui, 0.2);
+    assert!(!ui.is_damaged());
+
+    // The application changes the value without the slider's state changing.
+    next_later_frame(&mut ui);
+    draw_slider(&mut ui, 0.7);
+    assert_eq!(ui.damage_region(), Some(([50.0, 50.0], [200.0, 30.0])));
+
+    next_later_frame(&mut ui);
+    draw_slider(&mut ui, 0.7);
+    assert!(!ui.is_damaged());
+}
+
+#[test]
+fn damages_the_whole_window_unless_tracking() {
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.set_mock_time(Some(0.0));
+    draw_slider(&mut ui, 0.2);
+
+    next_later_frame(&mut ui);
+    draw_slider(&mut ui, 0.2);
+    assert_eq!(ui.damage_region(), Some(([0.0, 0.0], [400.0, 300.0])));
+}
@@ -0,0 +1,98 @@
+extern crate conrod;
+extern crate piston;
+
+use conrod::{ NoCallback, Positionable, Shapeable, Ui, XYPad };
+use conrod::event::Input;
+use conrod::history::{ Change, History, Value };
+use conrod::testing::{ headless_ui, next_frame, MockCharacterCache, NoOpGraphics };
+use conrod::xy_pad::Event;
+use piston::input::Button;
+use piston::input::MouseButton;
+
+fn change(ui_id: u64, before: f64, after: f64) -> Change {
+    Change { ui_id: ui_id, before: Value::Number(before), after: Value::Number(after) }
+}
+
+#[test]
+fn undoes_and_redoes_changes_in_order() {
+    let mut history = History::new(10);
+    history.push(change(0, 1.0, 2.0));
+    history.push(change(1, 3.0, 4.0));
+    assert!(history.undo());
+    assert_eq!(history.take_restored(1), Some(Value::Number(3.0)));
+    assert!(history.undo());
+    assert_eq!(history.take_restored(0), Some(Value::Number(1.0)));
+    assert!(!history.undo());
+    assert!(history.redo());
+    assert_eq!(history.take_restored(0), Some(Value::Number(2.0)));
+    assert_eq!(history.take_restored(0), None);
+}
+
+#[test]
+fn forgets_what_was_undone_once_a_change_is_made() {
+    let mut history = History::new(10);
+    history.push(change(0, 1.0, 2.0));
+    history.undo();
+    history.push(change(0, 1.0, 5.0));
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn ignores_changes_that_leave_the_value_alone() {
+    let mut history = History::new(10);
+    history.begin(0, Value::Number(1.0));
+    history.commit(0, Value::Number(1.0));
+    history.commit(1, Value::Number(2.0));
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn remembers_at_most_max_len_changes() {
+    let mut history = History::new(2);
+    for i in 0..3 {
+        history.push(change(0, i as f64, i as f64 + 1.0));
+    }
+    assert!(history.undo());
+    assert!(history.undo());
+    assert!(!history.undo());
+    assert_eq!(history.take_restored(0), Some(Value::Number(1.0)));
+}
+
+/// Draw the pad, applying any values it reports.
+fn draw_pad(ui: &mut Ui<MockCharacterCache>, xy: &mut (f64, f64)) -> Option<Event<f64, f64>> {
+    let maybe_event = XYPad::<f64, f64, NoCallback>::new(0, xy.0, 0.0, 1.0, xy.1, 0.0, 1.0)
+        .point([0.0, 0.0])
+        .dim([100.0, 100.0])
+        .react(ui, &mut NoOpGraphics);
+    if let Some(event) = maybe_event {
+        *xy = event.values();
+    }
+    maybe_event
+}
+
+#[test]
+fn undoes_the_values_released_via_an_xy_pad() {
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.enable_history(10);
+    let mut xy = (0.25, 0.75);
+    draw_pad(&mut ui, &mut xy);
+    next_frame(&mut ui, vec![Input::MouseCursor(50.0, 50.0)]);
+    draw_pad(&mut ui, &mut xy);
+    next_frame(&mut ui, vec![Input::Press(Button::Mouse(MouseButton::Left))]);
+    draw_pad(&mut ui, &mut xy);
+    next_frame(&mut ui, vec![Input::MouseCursor(80.0, 40.0)]);
+    draw_pad(&mut ui, &mut xy);
+    next_frame(&mut ui, vec![Input::Release(Button::Mouse(MouseButton::Left))]);
+    let released = draw_pad(&mut ui, &mut xy);
+    match released {
+        Some(Event::Released(x, y)) => assert!(x != 0.25 && y != 0.75),
+        event => panic!("Expected the pad to be released, not {:?}", event),
+    }
+    next_frame(&mut ui, vec![]);
+
+    assert!(ui.undo());
+    assert_eq!(draw_pad(&mut ui, &mut xy), Some(Event::Released(0.25, 0.75)));
+    next_frame(&mut ui, vec![]);
+    assert!(ui.redo());
+    assert_eq!(draw_pad(&mut ui, &mut xy), released);
+}
@@ -0,0 +1,45 @@
+extern crate conrod;
+
+use conrod::{ NoCallback, Positionable, Shapeable, Slider, Ui };
+use conrod::testing::{ headless_ui, next_frame, MockCharacterCache, NoOpGraphics };
+
+const SLIDER: u64 = 0;
+
+fn draw_slider(ui: &mut Ui<MockCharacterCache>) {
+    Slider::<f32, NoCallback>::new(SLIDER, 0.5, 0.0, 1.0)
+        .point([50.0, 50.0])
+        .dim([200.0, 30.0])
+        .react(ui, &mut NoOpGraphics);
+}
+
+#[test]
+fn reports_widgets_appearing_and_disappearing() {
+    let mut ui = headless_ui(400.0, 300.0);
+    draw_slider(&mut ui);
+    next_frame(&mut ui, vec![]);
+    assert_eq!(ui.widgets_appeared(), &[SLIDER]);
+    draw_slider(&mut ui);
+    next_frame(&mut ui, vec![]);
+    assert!(ui.widgets_appeared().is_empty());
+    assert!(ui.widgets_disappeared().is_empty());
+    next_frame(&mut ui, vec![]);
+    assert_eq!(ui.widgets_disappeared(), &[SLIDER]);
+}
+
+#[test]
+fn frees_the_state_of_widgets_that_disappear() {
+    let mut ui = headless_ui(400.0, 300.0);
+    draw_slider(&mut ui);
+    ui.set_user_state(SLIDER, 1u32);
+    ui.set_widget_memory(SLIDER, 2u32);
+    next_frame(&mut ui, vec![]);
+    draw_slider(&mut ui);
+    next_frame(&mut ui, vec![]);
+    assert_eq!(ui.user_state::<u32>(SLIDER), Some(&1));
+    assert_eq!(ui.widget_memory::<u32>(SLIDER), Some(&2));
+
+    // The slider isn't drawn during this frame.
+    next_frame(&mut ui, vec![]);
+    assert_eq!(ui.user_state::<u32>(SLIDER), None);
+    assert_eq!(ui.widget_memory::<u32>(SLIDER), None);
+}
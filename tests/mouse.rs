@@ -0,0 +1,47 @@
+extern crate conrod;
+
+use conrod::Ui;
+use conrod::event::Input;
+use conrod::testing::{ headless_ui, MockCharacterCache };
+
+/// Move the mouse to the given position at the given time.
+fn move_mouse(ui: &mut Ui<MockCharacterCache>, time: f64, x: f64, y: f64) {
+    ui.set_mock_time(Some(time));
+    ui.handle_input(Input::MouseCursor(x, y));
+}
+
+fn assert_velocity(ui: &Ui<MockCharacterCache>, expected: [f64; 2]) {
+    let velocity = ui.mouse_velocity();
+    assert!((velocity[0] - expected[0]).abs() < 1.0e-6 && (velocity[1] - expected[1]).abs() < 1.0e-6,
+            "{:?} != {:?}", velocity, expected);
+}
+
+#[test]
+fn measures_a_single_movement_from_where_the_mouse_rested() {
+    let mut ui = headless_ui(400.0, 300.0);
+    move_mouse(&mut ui, 0.0, 0.0, 0.0);
+    move_mouse(&mut ui, 1.0, 10.0, 0.0);
+    ui.set_mock_time(Some(1.05));
+    assert_velocity(&ui, [100.0, 0.0]);
+}
+
+#[test]
+fn averages_the_movements_within_a_tenth_of_a_second() {
+    let mut ui = headless_ui(400.0, 300.0);
+    move_mouse(&mut ui, 0.0, 0.0, 0.0);
+    move_mouse(&mut ui, 0.5, 0.0, 10.0);
+    move_mouse(&mut ui, 1.0, 0.0, 20.0);
+    move_mouse(&mut ui, 1.05, 0.0, 30.0);
+    move_mouse(&mut ui, 1.1, 0.0, 40.0);
+    ui.set_mock_time(Some(1.12));
+    assert_velocity(&ui, [0.0, 200.0]);
+}
+
+#[test]
+fn is_at_rest_once_the_mouse_stops() {
+    let mut ui = headless_ui(400.0, 300.0);
+    move_mouse(&mut ui, 0.0, 0.0, 0.0);
+    move_mouse(&mut ui, 0.05, 10.0, 10.0);
+    ui.set_mock_time(Some(1.0));
+    assert_velocity(&ui, [0.0, 0.0]);
+}
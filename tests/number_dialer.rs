@@ -0,0 +1,46 @@
+extern crate conrod;
+extern crate piston;
+
+use conrod::{ Labelable, NoCallback, NumberDialer, NumberFormat, Positionable, Shapeable, Ui };
+use conrod::number_dialer::Event;
+use conrod::event::Input;
+use conrod::testing::{ headless_ui, next_frame, MockCharacterCache, NoOpGraphics };
+use piston::input::Button;
+use piston::input::MouseButton;
+
+const DIALER: u64 = 0;
+
+/// Draw a dialer whose digits are grouped as "1,234". With a font size of 20
+/// each slot is 15 wide, so the five slots begin at x = 62.5.
+fn draw_dialer(ui: &mut Ui<MockCharacterCache>, value: f32) -> Option<Event<f32>> {
+    NumberDialer::<f32, NoCallback>::new(DIALER, value, 0.0, 9999.0, 0)
+        .number_format(NumberFormat::new().thousands_separator(','))
+        .label_font_size(20)
+        .point([0.0, 0.0])
+        .dim([200.0, 40.0])
+        .react(ui, &mut NoOpGraphics)
+}
+
+/// Press the slot at the given x position and drag upwards, returning the
+/// event of the last frame.
+fn dial_up(ui: &mut Ui<MockCharacterCache>, x: f64) -> Option<Event<f32>> {
+    draw_dialer(ui, 1234.0);
+    next_frame(ui, vec![Input::MouseCursor(x, 20.0)]);
+    draw_dialer(ui, 1234.0);
+    next_frame(ui, vec![Input::Press(Button::Mouse(MouseButton::Left))]);
+    draw_dialer(ui, 1234.0);
+    next_frame(ui, vec![Input::MouseCursor(x, 10.0)]);
+    draw_dialer(ui, 1234.0)
+}
+
+#[test]
+fn dials_the_digit_after_a_thousands_separator() {
+    let mut ui = headless_ui(400.0, 300.0);
+    assert_eq!(dial_up(&mut ui, 100.0), Some(Event::Dragging(1334.0)));
+}
+
+#[test]
+fn ignores_dialing_a_thousands_separator() {
+    let mut ui = headless_ui(400.0, 300.0);
+    assert_eq!(dial_up(&mut ui, 85.0), None);
+}
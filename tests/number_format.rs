@@ -0,0 +1,34 @@
+extern crate conrod;
+
+use conrod::NumberFormat;
+
+#[test]
+fn leaves_plain_numbers_alone() {
+    let format = NumberFormat::new();
+    assert_eq!(format.localize("1234.5"), "1234.5");
+    assert_eq!(format.localize("-42"), "-42");
+}
+
+#[test]
+fn groups_the_integer_digits() {
+    let format = NumberFormat::new().thousands_separator(',');
+    assert_eq!(format.localize("1"), "1");
+    assert_eq!(format.localize("123"), "123");
+    assert_eq!(format.localize("1234"), "1,234");
+    assert_eq!(format.localize("1234567.891"), "1,234,567.891");
+    assert_eq!(format.localize("-123456"), "-123,456");
+}
+
+#[test]
+fn replaces_the_decimal_separator_and_appends_the_unit() {
+    let format = NumberFormat::new().decimal_separator(',').thousands_separator('.').unit(" Hz");
+    assert_eq!(format.localize("1234.5"), "1.234,5 Hz");
+    assert_eq!(format.localize("0.25"), "0,25 Hz");
+}
+
+#[test]
+fn formats_with_its_own_precision_before_the_default() {
+    assert_eq!(NumberFormat::new().format(3.14159, 2), "3.14");
+    assert_eq!(NumberFormat::new().precision(0).format(2.5, 2), "2");
+    assert_eq!(NumberFormat::new().thousands_separator(',').format(-9876.5, 1), "-9,876.5");
+}
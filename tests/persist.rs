@@ -0,0 +1,65 @@
+extern crate conrod;
+
+use conrod::dock::{ DockLayout, DockNode, Floating };
+use conrod::history::Value;
+use conrod::persist::{ self, SavedState };
+use conrod::testing::headless_ui;
+use std::io::Cursor;
+
+fn layout() -> DockLayout {
+    DockLayout {
+        maybe_root: Some(DockNode::Tabs(vec![0, 1], 1)),
+        floating: vec![Floating { panel: 2, pos: [10.0, 20.0], dim: [100.0, 50.0] }],
+    }
+}
+
+#[test]
+fn round_trips_everything_saved() {
+    let mut state = SavedState::new();
+    state.values.insert(1, Value::Number(0.25));
+    state.values.insert(2, Value::Text("hello".to_string()));
+    state.scroll_offsets.insert(3, 42.5);
+    state.dock_layouts.insert(4, layout());
+    let mut bytes = Vec::new();
+    persist::save(&state, &mut bytes).unwrap();
+    let loaded = persist::load(&mut Cursor::new(bytes)).unwrap();
+    assert_eq!(loaded.values, state.values);
+    assert_eq!(loaded.scroll_offsets, state.scroll_offsets);
+    assert_eq!(loaded.dock_layouts, state.dock_layouts);
+}
+
+#[test]
+fn saves_the_same_output_each_time() {
+    let mut state = SavedState::new();
+    for id in 0..16 {
+        state.scroll_offsets.insert(id, id as f64);
+    }
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    persist::save(&state, &mut first).unwrap();
+    persist::save(&state.clone(), &mut second).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn restores_what_the_ui_saved() {
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.persist_value(1, Value::Bool(true));
+    ui.persist_scroll(2, 12.0);
+    ui.persist_dock_layout(3, &layout());
+    let mut bytes = Vec::new();
+    ui.save_state(&mut bytes).unwrap();
+
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.load_state(&mut Cursor::new(bytes)).unwrap();
+    assert_eq!(ui.take_restored(1), Some(Value::Bool(true)));
+    assert_eq!(ui.restored_scroll(2), Some(12.0));
+    assert_eq!(ui.take_restored_scroll(2), Some(12.0));
+    assert_eq!(ui.take_restored_scroll(2), None);
+    assert_eq!(ui.take_restored_dock_layout(3), Some(layout()));
+}
+
+#[test]
+fn fails_to_load_anything_else() {
+    assert!(persist::load(&mut Cursor::new(b"not json".to_vec())).is_err());
+}
@@ -0,0 +1,46 @@
+extern crate conrod;
+
+use conrod::event::{ Input, Playback, Record };
+use conrod::testing::headless_ui;
+
+#[test]
+fn mocks_the_recorded_times_and_restores_the_clock() {
+    let mut ui = headless_ui(400.0, 300.0);
+    ui.set_mock_time(Some(100.0));
+    let record = Record {
+        start_time: 0.0,
+        inputs: vec![
+            (0.5, Input::MouseCursor(10.0, 10.0)),
+            (1.0, Input::Render(400.0, 300.0)),
+            (2.0, Input::Render(400.0, 300.0)),
+        ],
+    };
+    let mut playback = Playback::new(record);
+
+    assert!(playback.next_frame(&mut ui));
+    assert_eq!(ui.time(), 1.0);
+    assert_eq!(ui.get_mouse_state().pos, [10.0, 10.0]);
+    assert!(playback.next_frame(&mut ui));
+    assert_eq!(ui.time(), 2.0);
+    assert!(!playback.next_frame(&mut ui));
+    assert_eq!(ui.time(), 100.0);
+}
+
+#[test]
+fn restores_the_clock_when_stopped_early() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let record = Record {
+        start_time: 0.0,
+        inputs: vec![
+            (1.0, Input::Render(400.0, 300.0)),
+            (2.0, Input::Render(400.0, 300.0)),
+        ],
+    };
+    let mut playback = Playback::new(record);
+
+    assert!(playback.next_frame(&mut ui));
+    assert_eq!(ui.mock_time(), Some(1.0));
+    playback.stop(&mut ui);
+    assert!(playback.is_finished());
+    assert_eq!(ui.mock_time(), None);
+}
@@ -0,0 +1,37 @@
+extern crate conrod;
+
+use conrod::Point;
+use conrod::polygon::triangulate;
+
+/// The total area of the given triangles.
+fn area(triangles: &[[Point; 3]]) -> f64 {
+    triangles.iter().fold(0.0, |total, t| {
+        let (a, b, c) = (t[0], t[1], t[2]);
+        total + ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs() / 2.0
+    })
+}
+
+#[test]
+fn triangulates_a_concave_polygon() {
+    let l_shape = [[0.0, 0.0], [20.0, 0.0], [20.0, 10.0], [10.0, 10.0], [10.0, 20.0], [0.0, 20.0]];
+    let triangles = triangulate(&l_shape);
+    assert_eq!(triangles.len(), 4);
+    assert_eq!(area(&triangles), 300.0);
+}
+
+#[test]
+fn triangulates_a_polygon_with_collinear_points() {
+    let square = [
+        [0.0, 0.0], [5.0, 0.0], [10.0, 0.0], [10.0, 5.0],
+        [10.0, 10.0], [5.0, 10.0], [0.0, 10.0], [0.0, 5.0],
+    ];
+    let triangles = triangulate(&square);
+    assert_eq!(area(&triangles), 100.0);
+    assert!(triangles.iter().all(|t| area(&[*t]) > 0.0));
+}
+
+#[test]
+fn triangulates_a_polygon_whose_first_point_is_repeated() {
+    let closed = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]];
+    assert_eq!(area(&triangulate(&closed)), 100.0);
+}
@@ -0,0 +1,28 @@
+extern crate conrod;
+
+use conrod::{ NoCallback, Positionable, Shapeable, Slider, Ui };
+use conrod::event::Input;
+use conrod::slider::Event;
+use conrod::testing::{ headless_ui, next_frame, MockCharacterCache, NoOpGraphics };
+
+const SLIDER: u64 = 0;
+
+fn draw_skewed_slider(ui: &mut Ui<MockCharacterCache>, value: f32) -> Option<Event<f32>> {
+    Slider::<f32, NoCallback>::new(SLIDER, value, 0.0, 1.0)
+        .skew(2.5)
+        .point([50.0, 50.0])
+        .dim([200.0, 30.0])
+        .react(ui, &mut NoOpGraphics)
+}
+
+#[test]
+fn leaves_a_skewed_value_alone_unless_dragged() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let value = 0.3;
+    assert_eq!(draw_skewed_slider(&mut ui, value), None);
+    next_frame(&mut ui, vec![Input::MouseCursor(150.0, 65.0)]);
+    for _ in 0..3 {
+        assert_eq!(draw_skewed_slider(&mut ui, value), None);
+        next_frame(&mut ui, vec![]);
+    }
+}
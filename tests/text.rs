@@ -0,0 +1,35 @@
+extern crate conrod;
+
+use conrod::text;
+use conrod::testing::headless_ui;
+use std::collections::HashMap;
+
+// With a font size of 20 each character of the mock glyph cache is 10 wide.
+
+#[test]
+fn wraps_at_newlines_and_whitespace() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let font = ui.theme.font_default;
+    assert_eq!(text::wrap_ranges(&mut ui, font, 20, "ab cd", 30.0), vec![(0, 2), (3, 5)]);
+    assert_eq!(text::wrap_ranges(&mut ui, font, 20, "ab\ncd", 100.0), vec![(0, 2), (3, 5)]);
+    assert_eq!(text::wrap_ranges(&mut ui, font, 20, "ab cd", 50.0), vec![(0, 5)]);
+}
+
+#[test]
+fn breaks_words_wider_than_a_line() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let font = ui.theme.font_default;
+    assert_eq!(text::wrap_ranges(&mut ui, font, 20, "abcdef", 30.0), vec![(0, 3), (3, 6)]);
+}
+
+#[test]
+fn wraps_kerned_text_as_it_is_measured() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let font = ui.theme.font_default;
+    let mut kerning = HashMap::new();
+    kerning.insert(('A', 'V'), -0.25);
+    ui.set_kerning(font, kerning);
+    // Each "AVAV" is 30 wide once kerned, so neither word is broken.
+    assert_eq!(text::width(&mut ui, font, 20, "AVAV"), 30.0);
+    assert_eq!(text::wrap_ranges(&mut ui, font, 20, "AVAV AVAV", 30.0), vec![(0, 4), (5, 9)]);
+}
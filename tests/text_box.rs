@@ -0,0 +1,68 @@
+extern crate conrod;
+extern crate piston;
+
+use conrod::{ NoCallback, Positionable, Shapeable, TextBox, Ui };
+use conrod::event::Input;
+use conrod::testing::{ headless_ui, next_frame, press_key, MockCharacterCache, NoOpGraphics };
+use piston::input::Button;
+use piston::input::keyboard::Key;
+use piston::input::MouseButton;
+
+const TEXT_BOX: u64 = 0;
+
+fn draw_text_box(ui: &mut Ui<MockCharacterCache>, text: &mut String) {
+    TextBox::<NoCallback>::new(TEXT_BOX, text)
+        .font_size(20)
+        .point([0.0, 0.0])
+        .dim([200.0, 40.0])
+        .react(ui, &mut NoOpGraphics);
+}
+
+/// Click the text box so that it captures the keyboard, with the cursor at
+/// the end of its (empty) text.
+fn capture(ui: &mut Ui<MockCharacterCache>, text: &mut String) {
+    draw_text_box(ui, text);
+    next_frame(ui, vec![Input::MouseCursor(100.0, 20.0)]);
+    draw_text_box(ui, text);
+    next_frame(ui, vec![Input::Press(Button::Mouse(MouseButton::Left))]);
+    draw_text_box(ui, text);
+    next_frame(ui, vec![Input::Release(Button::Mouse(MouseButton::Left))]);
+    draw_text_box(ui, text);
+}
+
+#[test]
+fn edits_multibyte_text() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let mut text = String::new();
+    capture(&mut ui, &mut text);
+
+    next_frame(&mut ui, vec![Input::Text("né".to_string()), Input::Text("€".to_string())]);
+    draw_text_box(&mut ui, &mut text);
+    assert_eq!(text, "né€");
+
+    // Step back over the "€" and remove the "é" before it.
+    next_frame(&mut ui, press_key(Key::Left));
+    draw_text_box(&mut ui, &mut text);
+    next_frame(&mut ui, press_key(Key::Backspace));
+    draw_text_box(&mut ui, &mut text);
+    assert_eq!(text, "n€");
+
+    next_frame(&mut ui, vec![Input::Text("ü".to_string())]);
+    draw_text_box(&mut ui, &mut text);
+    assert_eq!(text, "nü€");
+}
+
+#[test]
+fn keeps_the_cursor_within_text_shortened_by_the_application() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let mut text = String::new();
+    capture(&mut ui, &mut text);
+
+    next_frame(&mut ui, vec![Input::Text("äöü".to_string())]);
+    draw_text_box(&mut ui, &mut text);
+    text = "ß".to_string();
+
+    next_frame(&mut ui, vec![Input::Text("!".to_string())]);
+    draw_text_box(&mut ui, &mut text);
+    assert_eq!(text, "ß!");
+}
@@ -0,0 +1,22 @@
+extern crate conrod;
+
+use conrod::testing::{ headless_ui, MockCharacterCache };
+
+#[test]
+fn measures_each_text_separately() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let font = ui.theme.font_default;
+    assert_eq!(ui.text_width(font, 20, "ab"), 20.0);
+    assert_eq!(ui.text_width(font, 20, "abcd"), 40.0);
+    assert_eq!(ui.text_width(font, 10, "abcd"), 20.0);
+    assert_eq!(ui.text_width(font, 20, "ab"), 20.0);
+}
+
+#[test]
+fn remeasures_text_once_the_glyph_cache_changes() {
+    let mut ui = headless_ui(400.0, 300.0);
+    let font = ui.theme.font_default;
+    assert_eq!(ui.text_width(font, 20, "abcd"), 40.0);
+    ui.with_glyph_cache(font, |glyph_cache| *glyph_cache = MockCharacterCache::new(1.0));
+    assert_eq!(ui.text_width(font, 20, "abcd"), 80.0);
+}
@@ -0,0 +1,73 @@
+extern crate conrod;
+extern crate rustc_serialize;
+
+use conrod::Theme;
+use rustc_serialize::json::Json;
+
+/// The fields written by the first version of the theme's JSON format.
+const ORIGINAL_FIELDS: &'static [&'static str] = &[
+    "name",
+    "background_color",
+    "shape_color",
+    "frame_color",
+    "frame_width",
+    "label_color",
+    "font_size_large",
+    "font_size_medium",
+    "font_size_small",
+];
+
+#[test]
+fn round_trips_through_json() {
+    let mut theme = Theme::default();
+    theme.name = "Round Trip".to_string();
+    theme.padding = 12.0;
+    theme.cursor_blink = false;
+    let decoded = Theme::from_json(&theme.to_json().unwrap()).unwrap();
+    assert_eq!(decoded.name, "Round Trip");
+    assert_eq!(decoded.padding, 12.0);
+    assert_eq!(decoded.cursor_blink, false);
+}
+
+#[test]
+fn decodes_every_field_it_encodes() {
+    // Decoding a field missing from the decoder's list of fields panics, and
+    // any field it doesn't decode would be lost from the re-encoded JSON.
+    let json = Theme::default().to_json().unwrap();
+    let decoded = Theme::from_json(&json).unwrap();
+    assert_eq!(Json::from_str(&decoded.to_json().unwrap()).unwrap(), Json::from_str(&json).unwrap());
+}
+
+#[test]
+fn loads_json_without_the_newer_fields() {
+    let mut theme = Theme::default();
+    theme.name = "Old Theme".to_string();
+    theme.frame_width = 3.0;
+    let json = Json::from_str(&theme.to_json().unwrap()).unwrap();
+    let mut object = json.as_object().unwrap().clone();
+    let newer: Vec<String> = object.keys()
+        .filter(|key| !ORIGINAL_FIELDS.contains(&&key[..]))
+        .cloned()
+        .collect();
+    for key in newer.iter() {
+        object.remove(key);
+    }
+
+    let decoded = Theme::from_json(&Json::Object(object).to_string()).unwrap();
+    let default = Theme::default();
+    assert_eq!(decoded.name, "Old Theme");
+    assert_eq!(decoded.frame_width, 3.0);
+    assert_eq!(decoded.padding, default.padding);
+    assert_eq!(decoded.corner_radius, default.corner_radius);
+    assert_eq!(decoded.cursor_blink_rate, default.cursor_blink_rate);
+    assert_eq!(decoded.scroll_friction, default.scroll_friction);
+    assert!(decoded.maybe_button.is_none());
+}
+
+#[test]
+fn rejects_json_without_the_original_fields() {
+    let json = Theme::default().to_json().unwrap();
+    let mut object = Json::from_str(&json).unwrap().as_object().unwrap().clone();
+    object.remove("label_color");
+    assert!(Theme::from_json(&Json::Object(object).to_string()).is_err());
+}